@@ -0,0 +1,278 @@
+use std::{cell::RefCell, collections::HashMap, future::Future};
+
+use crate::{error, WebFetch};
+#[cfg(feature = "reqwest")]
+use crate::throttle;
+
+/// Async counterpart of `WebFetch`, for library users running inside an async runtime.
+///
+/// The scrapers stay blocking, use [`scrape`] to run them over an `AsyncWebFetch`.
+pub trait AsyncWebFetch {
+    fn fetch(&self, url: &str) -> impl Future<Output = Result<String, error::Error>> + Send;
+
+    /// Fetch `url` sending the given extra headers (API keys, ...).
+    fn fetch_with_headers(
+        &self,
+        url: &str,
+        _headers: &[(&str, &str)],
+    ) -> impl Future<Output = Result<String, error::Error>> + Send {
+        return self.fetch(url);
+    }
+}
+
+/// `AsyncWebFetch` over the network, sharing the connections between requests.
+#[cfg(feature = "reqwest")]
+#[derive(Clone, Default)]
+pub struct AsyncHttpReqwest {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "reqwest")]
+impl AsyncHttpReqwest {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl AsyncWebFetch for AsyncHttpReqwest {
+    async fn fetch(&self, url: &str) -> Result<String, error::Error> {
+        return self.fetch_with_headers(url, &[]).await;
+    }
+
+    /// Throttled (HTTP 429) requests are retried after the wait requested by the server,
+    /// only the calling task waits meanwhile.
+    async fn fetch_with_headers(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<String, error::Error> {
+        let mut retries = 0;
+
+        loop {
+            let mut request = self.client.get(url);
+
+            for (name, value) in headers {
+                request = request.header(*name, *value);
+            }
+
+            let response = request.send().await?;
+
+            if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+                && retries < throttle::MAX_THROTTLED_RETRIES
+            {
+                let wait = response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|retry_after| retry_after.to_str().ok())
+                    .and_then(|retry_after| {
+                        throttle::parse_retry_after(retry_after, chrono::Utc::now())
+                    })
+                    .unwrap_or(throttle::DEFAULT_RETRY_AFTER);
+
+                throttle::record_pause(wait);
+                tokio::time::sleep(wait).await;
+                retries += 1;
+
+                continue;
+            }
+
+            return Ok(response.error_for_status()?.text().await?);
+        }
+    }
+}
+
+/// URL and headers of a page requested by a scraper.
+type PageRequest = (String, Vec<(String, String)>);
+
+/// Pages already fetched by [`scrape`], served to the blocking scrapers as a `WebFetch`.
+/// Requesting any other page records it as missing and fails.
+#[derive(Default)]
+pub struct Pages {
+    pages: HashMap<String, String>,
+    missing: RefCell<Option<PageRequest>>,
+}
+
+impl WebFetch for Pages {
+    fn fetch(&self, url: &str) -> Result<String, error::Error> {
+        return self.fetch_with_headers(url, &[]);
+    }
+
+    fn fetch_with_headers(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<String, error::Error> {
+        if let Some(page) = self.pages.get(url) {
+            return Ok(page.clone());
+        }
+
+        self.missing.borrow_mut().get_or_insert_with(|| {
+            (
+                url.to_string(),
+                headers
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+            )
+        });
+
+        return Err(error::Error::General(format!(
+            "{} has not been fetched yet",
+            url
+        )));
+    }
+}
+
+/// Run a blocking scraper (any `fetch_*` function) over an `AsyncWebFetch`.
+///
+/// Every page the scraper requests is fetched asynchronously, the scraper being run again
+/// until all of its pages are available. Parsing happens on the calling task, it is cheap
+/// compared to the requests.
+///
+/// ```no_run
+/// use mitre_cli::{
+///     async_fetch::{scrape, AsyncWebFetch},
+///     attack::groups,
+/// };
+///
+/// // `AsyncHttpReqwest::new()` with the `reqwest` feature.
+/// # async fn run(web_client: &impl AsyncWebFetch) -> Result<(), mitre_cli::error::Error> {
+/// let group = scrape(web_client, |pages| groups::fetch_group("G0018", pages)).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn scrape<T, A, F>(web_client: &A, scraper: F) -> Result<T, error::Error>
+where
+    A: AsyncWebFetch,
+    F: Fn(&Pages) -> Result<T, error::Error>,
+{
+    let mut pages = Pages::default();
+
+    loop {
+        let (url, headers) = {
+            let scraped = scraper(&pages);
+
+            match pages.missing.take() {
+                Some(missing) => missing,
+                None => return scraped,
+            }
+        };
+        let headers = headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect::<Vec<(&str, &str)>>();
+
+        let page = web_client.fetch_with_headers(&url, &headers).await?;
+        pages.pages.insert(url, page);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        pin::pin,
+        sync::Mutex,
+        task::{Context, Poll, Waker},
+    };
+
+    use super::*;
+    use crate::{attack::groups, fakers::FakeHttpReqwest, nvd};
+
+    /// Drive futures that never wait, like the fakes below.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut context = Context::from_waker(Waker::noop());
+
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    /// Fake answering every URL with the blocking fake, recording the requested URLs.
+    struct FakeAsyncReqwest {
+        inner: FakeHttpReqwest,
+        requested: Mutex<Vec<String>>,
+    }
+
+    impl FakeAsyncReqwest {
+        fn new(inner: FakeHttpReqwest) -> Self {
+            return Self {
+                inner,
+                requested: Mutex::default(),
+            };
+        }
+    }
+
+    impl AsyncWebFetch for FakeAsyncReqwest {
+        async fn fetch(&self, url: &str) -> Result<String, error::Error> {
+            self.requested.lock().unwrap().push(url.to_string());
+
+            return WebFetch::fetch(&self.inner, url);
+        }
+    }
+
+    #[test]
+    fn test_scrape_single_page() -> Result<(), error::Error> {
+        let fake_reqwest = FakeAsyncReqwest::new(FakeHttpReqwest::default().set_success_response(
+            include_str!("attack/html/attck/groups/admin_338.html").to_string(),
+        ));
+
+        let group = block_on(scrape(&fake_reqwest, |pages| {
+            groups::fetch_group("G0018", pages)
+        }))?;
+
+        assert_eq!(group.id, "G0018");
+        assert_eq!(
+            *fake_reqwest.requested.lock().unwrap(),
+            vec![groups::group_url("G0018")]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scrape_several_pages() -> Result<(), error::Error> {
+        let fake_reqwest = FakeAsyncReqwest::new(
+            FakeHttpReqwest::default()
+                .set_success_response(include_str!("nvd/json/cve_2012_0158.json").to_string()),
+        );
+        let cve_ids = vec![String::from("CVE-2012-0158"), String::from("cve-2014-6352")];
+
+        let cves = block_on(scrape(&fake_reqwest, |pages| {
+            nvd::fetch_cves(&cve_ids, Some("key"), pages)
+        }))?;
+
+        assert_eq!(cves.0.len(), 2);
+        assert_eq!(fake_reqwest.requested.lock().unwrap().len(), 2);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[test]
+    fn test_scrape_is_send() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let web_client = AsyncHttpReqwest::new();
+        assert_send(&scrape(&web_client, |pages| {
+            groups::fetch_group("G0018", pages)
+        }));
+    }
+
+    #[test]
+    fn test_scrape_request_error() {
+        let fake_reqwest = FakeAsyncReqwest::new(
+            FakeHttpReqwest::default()
+                .set_error_response(error::Error::Request(String::from("Timeout"))),
+        );
+
+        let scraped = block_on(scrape(&fake_reqwest, |pages| {
+            groups::fetch_group("G0018", pages)
+        }));
+
+        assert!(matches!(scraped, Err(error::Error::Request(_))));
+    }
+}
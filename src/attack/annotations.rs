@@ -0,0 +1,232 @@
+use std::{collections::BTreeMap, path::PathBuf, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error, storage};
+
+/// File of the annotations, under the mitre_cli data directory.
+pub const ANNOTATIONS_FILE_NAME: &'static str = "annotations.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImplementationStatus {
+    IMPLEMENTED,
+    PLANNED,
+    NA,
+}
+
+impl FromStr for ImplementationStatus {
+    type Err = error::Error;
+
+    fn from_str(status_str: &str) -> Result<Self, Self::Err> {
+        match status_str {
+            "implemented" => Ok(Self::IMPLEMENTED),
+            "planned" => Ok(Self::PLANNED),
+            "na" => Ok(Self::NA),
+            _ => Err(error::Error::InvalidValue(format!(
+                "{} is not a valid implementation status (implemented, planned, na)",
+                status_str
+            ))),
+        }
+    }
+}
+
+impl Into<&'static str> for ImplementationStatus {
+    fn into(self) -> &'static str {
+        match self {
+            Self::IMPLEMENTED => "implemented",
+            Self::PLANNED => "planned",
+            Self::NA => "na",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MitigationAnnotation {
+    pub status: ImplementationStatus,
+}
+
+/// Local, user-provided data attached to ATT&CK entities.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Annotations {
+    #[serde(default)]
+    pub mitigations: BTreeMap<String, MitigationAnnotation>,
+    /// Free text notes by entity ID (any entity type).
+    #[serde(default)]
+    pub notes: BTreeMap<String, Vec<String>>,
+}
+
+/// Note matching a search term.
+#[derive(Debug, Default, Serialize)]
+pub struct NoteMatchRow {
+    pub id: String,
+    pub name: String,
+    pub note: String,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for NoteMatchRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(self.name))
+            .add_cell(comfy_table::Cell::new(self.note));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct NoteMatchesTable(pub Vec<NoteMatchRow>);
+
+impl NoteMatchesTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+}
+
+impl IntoIterator for NoteMatchesTable {
+    type Item = NoteMatchRow;
+    type IntoIter = std::vec::IntoIter<NoteMatchRow>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.0.into_iter();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for NoteMatchesTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&["ID", "Name", "Note"]));
+
+        for row in self {
+            table.add_row(row);
+        }
+
+        return table;
+    }
+}
+
+impl Annotations {
+    fn path() -> Result<PathBuf, error::Error> {
+        return storage::data_path(ANNOTATIONS_FILE_NAME);
+    }
+
+    pub fn load() -> Result<Self, error::Error> {
+        return Ok(storage::load_json(&Self::path()?)?.unwrap_or_default());
+    }
+
+    pub fn save(&self) -> Result<(), error::Error> {
+        return storage::save_json(&Self::path()?, self);
+    }
+
+    pub fn set_mitigation_status(&mut self, mitigation_id: &str, status: ImplementationStatus) {
+        self.mitigations.insert(
+            mitigation_id.to_uppercase(),
+            MitigationAnnotation { status },
+        );
+    }
+
+    pub fn mitigation_status(&self, mitigation_id: &str) -> Option<ImplementationStatus> {
+        return self
+            .mitigations
+            .get(&mitigation_id.to_uppercase())
+            .map(|annotation| annotation.status);
+    }
+
+    pub fn add_note(&mut self, entity_id: &str, note: &str) {
+        self.notes
+            .entry(entity_id.to_uppercase())
+            .or_default()
+            .push(note.to_string());
+    }
+
+    pub fn entity_notes(&self, entity_id: &str) -> &[String] {
+        return self
+            .notes
+            .get(&entity_id.to_uppercase())
+            .map_or(&[], Vec::as_slice);
+    }
+
+    /// Notes containing every word of `term` (case insensitive), names left for the caller
+    /// to resolve.
+    pub fn search_notes(&self, term: &str) -> NoteMatchesTable {
+        let words = term
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect::<Vec<String>>();
+
+        return NoteMatchesTable(
+            self.notes
+                .iter()
+                .flat_map(|(entity_id, notes)| notes.iter().map(move |note| (entity_id, note)))
+                .filter(|(_, note)| {
+                    let note = note.to_lowercase();
+                    !words.is_empty() && words.iter().all(|word| note.contains(word))
+                })
+                .map(|(entity_id, note)| NoteMatchRow {
+                    id: entity_id.clone(),
+                    note: note.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_implementation_status() -> Result<(), error::Error> {
+        assert_eq!(
+            ImplementationStatus::from_str("implemented")?,
+            ImplementationStatus::IMPLEMENTED
+        );
+        assert_eq!(
+            ImplementationStatus::from_str("planned")?,
+            ImplementationStatus::PLANNED
+        );
+        assert_eq!(
+            ImplementationStatus::from_str("na")?,
+            ImplementationStatus::NA
+        );
+        assert!(matches!(
+            ImplementationStatus::from_str("done"),
+            Err(error::Error::InvalidValue(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mitigation_status_lookup_is_case_insensitive() {
+        let mut annotations = Annotations::default();
+        annotations.set_mitigation_status("m1052", ImplementationStatus::PLANNED);
+
+        assert_eq!(
+            annotations.mitigation_status("M1052"),
+            Some(ImplementationStatus::PLANNED)
+        );
+        assert_eq!(annotations.mitigation_status("M1036"), None);
+    }
+
+    #[test]
+    fn test_search_notes() {
+        let mut annotations = Annotations::default();
+        annotations.add_note("t1190", "Seen against the VPN appliance in 2023");
+        annotations.add_note("T1190", "Patched edge devices");
+        annotations.add_note("G0018", "Tracked by the CTI team");
+
+        assert_eq!(annotations.entity_notes("T1190").len(), 2);
+        assert!(annotations.entity_notes("T1003").is_empty());
+
+        let matches = annotations.search_notes("vpn APPLIANCE");
+        assert_eq!(matches.0.len(), 1);
+        assert_eq!(matches.0[0].id, "T1190");
+        assert!(annotations.search_notes("vpn team").is_empty());
+        assert!(annotations.search_notes("  ").is_empty());
+    }
+}
@@ -0,0 +1,165 @@
+use std::{
+    fs::{self, File},
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::{error, storage};
+
+use super::cache::Cache;
+
+/// Files of the cache directory, relative to it, the temporary files of interrupted writes
+/// left out.
+fn cache_files(dir: &Path, relative_dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let relative_path = relative_dir.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            cache_files(&entry.path(), &relative_path, files)?;
+        } else if !entry.file_name().to_string_lossy().ends_with(".tmp") {
+            files.push(relative_path);
+        }
+    }
+
+    return Ok(());
+}
+
+/// Write the whole cache (listings, entities, versions, manifest and spooled pages) into a
+/// single tar archive compressed with zstd (`.tar.zst`) at `out`, to hand it to machines
+/// without network access. Returns the number of archived files.
+pub fn export_cache(cache: &Cache, out: &Path) -> Result<usize, error::Error> {
+    if cache.manifest()?.is_none() {
+        return Err(error::Error::General(String::from(
+            "No sync manifest in the local cache, run 'attack sync' first",
+        )));
+    }
+
+    let mut files: Vec<PathBuf> = Vec::new();
+    cache_files(cache.root(), Path::new(""), &mut files)?;
+    files.sort();
+
+    let mut archive = tar::Builder::new(zstd::Encoder::new(Vec::new(), 0)?);
+    archive.mode(tar::HeaderMode::Deterministic);
+
+    for file in &files {
+        archive.append_path_with_name(cache.root().join(file), file)?;
+    }
+    storage::write_atomic(out, archive.into_inner()?.finish()?)?;
+
+    return Ok(files.len());
+}
+
+/// Replace the cache with the content of an archive written by [`export_cache`]. The archive
+/// is extracted next to the cache first, so an invalid archive leaves the cache untouched.
+/// Returns the number of extracted files.
+pub fn import_cache(cache: &Cache, archive_path: &Path) -> Result<usize, error::Error> {
+    let mut archive = tar::Archive::new(zstd::Decoder::new(File::open(archive_path)?)?);
+    let staging_root = cache.root().with_extension("import");
+    let _ = fs::remove_dir_all(&staging_root);
+
+    let extracted = extract(&mut archive, &staging_root).and_then(|extracted| {
+        if Cache::with_root(staging_root.clone()).manifest()?.is_none() {
+            return Err(error::Error::Parse(format!(
+                "{} is not a cache archive of 'attack cache export' (no sync manifest)",
+                archive_path.display()
+            )));
+        }
+
+        return Ok(extracted);
+    });
+
+    let extracted = match extracted {
+        Ok(extracted) => extracted,
+        Err(err) => {
+            let _ = fs::remove_dir_all(&staging_root);
+
+            return Err(err);
+        }
+    };
+
+    let previous_root = cache.root().with_extension("previous");
+    let _ = fs::remove_dir_all(&previous_root);
+    if cache.root().exists() {
+        fs::rename(cache.root(), &previous_root)?;
+    }
+    fs::rename(&staging_root, cache.root())?;
+    let _ = fs::remove_dir_all(&previous_root);
+
+    return Ok(extracted);
+}
+
+/// Extract the files and directories of the archive under `root`, failing on the paths
+/// leaving it and on the other entries (links...).
+fn extract(archive: &mut tar::Archive<impl Read>, root: &Path) -> Result<usize, error::Error> {
+    let mut extracted = 0;
+    fs::create_dir_all(root)?;
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_type = entry.header().entry_type();
+
+        if !entry_type.is_file() && !entry_type.is_dir() {
+            return Err(error::Error::Parse(format!(
+                "Unsupported entry in the archive: {}",
+                entry.path()?.display()
+            )));
+        }
+
+        if !entry.unpack_in(root)? {
+            return Err(error::Error::Parse(format!(
+                "Unsafe path in the archive: {}",
+                entry.path()?.display()
+            )));
+        }
+
+        if entry_type.is_file() {
+            extracted += 1;
+        }
+    }
+
+    return Ok(extracted);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack::{cache::EntityKind, provenance::SyncManifest, techniques::Technique};
+
+    #[test]
+    fn test_export_import_cache() -> Result<(), error::Error> {
+        let dir = std::env::temp_dir().join("mitre_cli_archive");
+        let _ = fs::remove_dir_all(&dir);
+        let cache = Cache::with_root(dir.join("attack"));
+        cache.save_entity(
+            EntityKind::TECHNIQUE,
+            "T1548",
+            &Technique {
+                id: String::from("T1548"),
+                ..Default::default()
+            },
+        )?;
+        let archive_path = dir.join("cache.tar.zst");
+
+        assert!(export_cache(&cache, &archive_path).is_err());
+        cache.save_manifest(&SyncManifest::from_cache(
+            &cache,
+            "2024-05-01T10:00:00.000Z",
+        )?)?;
+        assert_eq!(export_cache(&cache, &archive_path)?, 2);
+
+        let imported = Cache::with_root(dir.join("imported"));
+        imported.save_attack_version("v14")?;
+        assert_eq!(import_cache(&imported, &archive_path)?, 2);
+        assert_eq!(imported.attack_version()?, None);
+        assert_eq!(imported.entity_ids(EntityKind::TECHNIQUE)?, vec!["T1548"]);
+
+        fs::write(&archive_path, "Not a tar.zst archive")?;
+        assert!(import_cache(&imported, &archive_path).is_err());
+        assert_eq!(imported.entity_ids(EntityKind::TECHNIQUE)?, vec!["T1548"]);
+
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+}
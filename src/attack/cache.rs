@@ -0,0 +1,768 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::SystemTime,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{error, metrics, storage};
+
+use super::{
+    data_sources::{DataSource, DataSourcesTable},
+    dataset,
+    groups::{Group, GroupsTable},
+    mitigations::{Mitigation, MitigationTable},
+    provenance::SyncManifest,
+    software::{Software, SoftwareTable},
+    tactics::{Tactic, TacticsTable},
+    techniques::{Technique, TechniquesTable},
+};
+
+/// Directory of the cache, under the mitre_cli cache directory.
+pub const CACHE_DIR_NAME: &'static str = "attack";
+const INDEX_FILE_STEM: &'static str = "index";
+const ATTACK_VERSION_FILE_NAME: &'static str = "attack_version.json";
+const VERSIONS_DIR_NAME: &'static str = "versions";
+const MANIFEST_FILE_NAME: &'static str = "manifest.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityKind {
+    TACTIC,
+    TECHNIQUE,
+    MITIGATION,
+    SOFTWARE,
+    GROUP,
+    DATASOURCE,
+}
+
+impl EntityKind {
+    pub const ALL: [EntityKind; 6] = [
+        Self::TACTIC,
+        Self::TECHNIQUE,
+        Self::MITIGATION,
+        Self::SOFTWARE,
+        Self::GROUP,
+        Self::DATASOURCE,
+    ];
+
+    /// Entity type of an ATT&CK ID (e.g. TA0001, T1059.001, M1036, S0002, G0018, DS0009).
+    pub fn from_id(id: &str) -> Option<Self> {
+        let id = id.to_uppercase();
+        let (prefix, number) = id.split_at(id.find(|c: char| c.is_ascii_digit())?);
+
+        if !number
+            .split('.')
+            .all(|part| part.chars().all(|c| c.is_ascii_digit()))
+        {
+            return None;
+        }
+
+        return match prefix {
+            "TA" => Some(Self::TACTIC),
+            "T" => Some(Self::TECHNIQUE),
+            "M" => Some(Self::MITIGATION),
+            "S" => Some(Self::SOFTWARE),
+            "G" => Some(Self::GROUP),
+            "DS" => Some(Self::DATASOURCE),
+            _ => None,
+        };
+    }
+
+    /// Whether the entity listing is split by domain (enterprise, mobile, ics).
+    pub fn has_domain(&self) -> bool {
+        return matches!(self, Self::TACTIC | Self::TECHNIQUE | Self::MITIGATION);
+    }
+}
+
+impl FromStr for EntityKind {
+    type Err = error::Error;
+
+    fn from_str(kind_str: &str) -> Result<Self, Self::Err> {
+        match kind_str {
+            "tactics" => Ok(Self::TACTIC),
+            "techniques" => Ok(Self::TECHNIQUE),
+            "mitigations" => Ok(Self::MITIGATION),
+            "software" => Ok(Self::SOFTWARE),
+            "groups" => Ok(Self::GROUP),
+            "data-sources" => Ok(Self::DATASOURCE),
+            _ => Err(error::Error::InvalidValue(format!(
+                "{} is not a valid entity type (tactics, techniques, mitigations, software, groups, data-sources)",
+                kind_str
+            ))),
+        }
+    }
+}
+
+impl Into<&'static str> for EntityKind {
+    fn into(self) -> &'static str {
+        match self {
+            Self::TACTIC => "tactics",
+            Self::TECHNIQUE => "techniques",
+            Self::MITIGATION => "mitigations",
+            Self::SOFTWARE => "software",
+            Self::GROUP => "groups",
+            Self::DATASOURCE => "data-sources",
+        }
+    }
+}
+
+/// Domains the tactics, techniques and mitigations listings are split by.
+pub const DOMAINS: [&'static str; 3] = ["enterprise", "mobile", "ics"];
+
+/// Version metadata of a synced entity page.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityVersion {
+    /// Version shown in the entity card (e.g. 1.2).
+    pub version: Option<String>,
+    /// Last Modified date of the entity card, as YYYY-MM-DD.
+    pub modified: Option<String>,
+    /// ATT&CK release the page was synced from.
+    pub attack_version: Option<String>,
+}
+
+/// Offline copy of the scraped ATT&CK entities.
+///
+/// Every entity type has its own directory holding the listings (`<domain>.json`, or
+/// `index.json` for entities without domain) and one `<ID>.json` file per described entity.
+pub struct Cache {
+    root: PathBuf,
+}
+
+impl Cache {
+    /// Cache stored under the mitre_cli cache directory.
+    pub fn open() -> Result<Self, error::Error> {
+        return Ok(Self::with_root(storage::cache_path(CACHE_DIR_NAME)?));
+    }
+
+    pub fn with_root(root: PathBuf) -> Self {
+        return Self { root };
+    }
+
+    pub fn root(&self) -> &Path {
+        return &self.root;
+    }
+
+    fn kind_dir(&self, kind: EntityKind) -> PathBuf {
+        let dir_name: &str = kind.into();
+
+        return self.root.join(dir_name);
+    }
+
+    pub fn listing_path(&self, kind: EntityKind, domain: Option<&str>) -> PathBuf {
+        return self
+            .kind_dir(kind)
+            .join(format!("{}.json", domain.unwrap_or(INDEX_FILE_STEM)));
+    }
+
+    pub fn entity_path(&self, kind: EntityKind, id: &str) -> PathBuf {
+        return self
+            .kind_dir(kind)
+            .join(format!("{}.json", id.to_uppercase()));
+    }
+
+    pub fn load_listing<T: DeserializeOwned>(
+        &self,
+        kind: EntityKind,
+        domain: Option<&str>,
+    ) -> Result<Option<T>, error::Error> {
+        return storage::load_json(&self.listing_path(kind, domain));
+    }
+
+    pub fn save_listing<T: Serialize>(
+        &self,
+        kind: EntityKind,
+        domain: Option<&str>,
+        listing: &T,
+    ) -> Result<(), error::Error> {
+        return storage::save_json(&self.listing_path(kind, domain), listing);
+    }
+
+    pub fn load_entity<T: DeserializeOwned>(
+        &self,
+        kind: EntityKind,
+        id: &str,
+    ) -> Result<Option<T>, error::Error> {
+        return storage::load_json(&self.entity_path(kind, id));
+    }
+
+    pub fn save_entity<T: Serialize>(
+        &self,
+        kind: EntityKind,
+        id: &str,
+        entity: &T,
+    ) -> Result<(), error::Error> {
+        return storage::save_json(&self.entity_path(kind, id), entity);
+    }
+
+    /// ATT&CK release the cache was last synced from, when known.
+    pub fn attack_version(&self) -> Result<Option<String>, error::Error> {
+        return storage::load_json(&self.root.join(ATTACK_VERSION_FILE_NAME));
+    }
+
+    pub fn save_attack_version(&self, attack_version: &str) -> Result<(), error::Error> {
+        return storage::save_json(&self.root.join(ATTACK_VERSION_FILE_NAME), &attack_version);
+    }
+
+    /// Manifest of the last sync, `None` if the cache was never synced.
+    pub fn manifest(&self) -> Result<Option<SyncManifest>, error::Error> {
+        return storage::load_json(&self.root.join(MANIFEST_FILE_NAME));
+    }
+
+    pub fn save_manifest(&self, manifest: &SyncManifest) -> Result<(), error::Error> {
+        return storage::save_json(&self.root.join(MANIFEST_FILE_NAME), manifest);
+    }
+
+    /// Versions are kept apart from the entities, one file per entity so parallel syncs
+    /// never write the same file.
+    fn entity_version_path(&self, kind: EntityKind, id: &str) -> PathBuf {
+        let dir_name: &str = kind.into();
+
+        return self
+            .root
+            .join(VERSIONS_DIR_NAME)
+            .join(dir_name)
+            .join(format!("{}.json", id.to_uppercase()));
+    }
+
+    /// Version metadata recorded when the entity was last synced.
+    pub fn entity_version(
+        &self,
+        kind: EntityKind,
+        id: &str,
+    ) -> Result<Option<EntityVersion>, error::Error> {
+        return storage::load_json(&self.entity_version_path(kind, id));
+    }
+
+    pub fn save_entity_version(
+        &self,
+        kind: EntityKind,
+        id: &str,
+        version: &EntityVersion,
+    ) -> Result<(), error::Error> {
+        return storage::save_json(&self.entity_version_path(kind, id), version);
+    }
+
+    /// Last time a listing or entity of the given type was written, `None` if none is cached.
+    pub fn synced_at(&self, kind: EntityKind) -> Result<Option<SystemTime>, error::Error> {
+        let kind_dir = self.kind_dir(kind);
+
+        if !kind_dir.exists() {
+            return Ok(None);
+        }
+
+        return Ok(fs::read_dir(kind_dir)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| {
+                entry
+                    .metadata()
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+            })
+            .max());
+    }
+
+    /// Load the listing from the cache, falling back to the embedded dataset and then to
+    /// `fetch` if it has not been synced.
+    pub fn listing_or_fetch<T, F>(
+        &self,
+        kind: EntityKind,
+        domain: Option<&str>,
+        fetch: F,
+    ) -> Result<T, error::Error>
+    where
+        T: DeserializeOwned,
+        F: FnOnce() -> Result<T, error::Error>,
+    {
+        let embedded_listing = || dataset::embedded()?.listing(kind, domain).cloned();
+
+        return match self.load_listing(kind, domain)? {
+            Some(listing) => {
+                metrics::record_cache_hit();
+                Ok(listing)
+            }
+            None => match embedded_listing() {
+                Some(listing) => {
+                    metrics::record_cache_hit();
+                    Ok(serde_json::from_value(listing)?)
+                }
+                None => {
+                    metrics::record_cache_miss();
+                    fetch()
+                }
+            },
+        };
+    }
+
+    /// Load the entity from the cache, falling back to the embedded dataset and then to
+    /// `fetch` if it has not been synced. Keeping the citations (`set_keep_citations`), the
+    /// entity is always fetched, the cached texts having them stripped.
+    pub fn entity_or_fetch<T, F>(
+        &self,
+        kind: EntityKind,
+        id: &str,
+        fetch: F,
+    ) -> Result<T, error::Error>
+    where
+        T: DeserializeOwned,
+        F: FnOnce() -> Result<T, error::Error>,
+    {
+        let embedded_entity = || dataset::embedded()?.entity(kind, id).cloned();
+
+        if crate::keeps_citations() {
+            log::debug!("Fetching {} to keep its citations", id);
+            metrics::record_cache_miss();
+            return fetch();
+        }
+
+        return match self.load_entity(kind, id)? {
+            Some(entity) => {
+                metrics::record_cache_hit();
+                Ok(entity)
+            }
+            None => match embedded_entity() {
+                Some(entity) => {
+                    metrics::record_cache_hit();
+                    Ok(serde_json::from_value(entity)?)
+                }
+                None => {
+                    log::debug!("{} not cached, fetching it", id);
+                    metrics::record_cache_miss();
+                    fetch()
+                }
+            },
+        };
+    }
+
+    /// IDs listed by the cached listing (sub-techniques included), `None` if not synced yet.
+    pub fn listing_ids(
+        &self,
+        kind: EntityKind,
+        domain: Option<&str>,
+    ) -> Result<Option<Vec<String>>, error::Error> {
+        return Ok(match kind {
+            EntityKind::TACTIC => self
+                .load_listing::<TacticsTable>(kind, domain)?
+                .map(|listing| listing.ids()),
+            EntityKind::TECHNIQUE => self
+                .load_listing::<TechniquesTable>(kind, domain)?
+                .map(|listing| listing.ids()),
+            EntityKind::MITIGATION => self
+                .load_listing::<MitigationTable>(kind, domain)?
+                .map(|listing| listing.ids()),
+            EntityKind::SOFTWARE => self
+                .load_listing::<SoftwareTable>(kind, domain)?
+                .map(|listing| listing.ids()),
+            EntityKind::GROUP => self
+                .load_listing::<GroupsTable>(kind, domain)?
+                .map(|listing| listing.ids()),
+            EntityKind::DATASOURCE => self
+                .load_listing::<DataSourcesTable>(kind, domain)?
+                .map(|listing| listing.ids()),
+        });
+    }
+
+    /// (ID, name) of the entities listed by the cached listings of every domain, `None` if
+    /// none is synced yet.
+    pub fn listing_names(
+        &self,
+        kind: EntityKind,
+    ) -> Result<Option<Vec<(String, String)>>, error::Error> {
+        let domains: Vec<Option<&str>> = if kind.has_domain() {
+            DOMAINS.iter().map(|domain| Some(*domain)).collect()
+        } else {
+            vec![None]
+        };
+        let mut listing_names: Option<Vec<(String, String)>> = None;
+
+        for domain in domains {
+            let names = match kind {
+                EntityKind::TACTIC => self
+                    .load_listing::<TacticsTable>(kind, domain)?
+                    .map(|listing| listing.names()),
+                EntityKind::TECHNIQUE => self
+                    .load_listing::<TechniquesTable>(kind, domain)?
+                    .map(|listing| listing.names()),
+                EntityKind::MITIGATION => self
+                    .load_listing::<MitigationTable>(kind, domain)?
+                    .map(|listing| listing.names()),
+                EntityKind::SOFTWARE => self
+                    .load_listing::<SoftwareTable>(kind, domain)?
+                    .map(|listing| listing.names()),
+                EntityKind::GROUP => self
+                    .load_listing::<GroupsTable>(kind, domain)?
+                    .map(|listing| listing.names()),
+                EntityKind::DATASOURCE => self
+                    .load_listing::<DataSourcesTable>(kind, domain)?
+                    .map(|listing| listing.names()),
+            };
+
+            if let Some(names) = names {
+                listing_names.get_or_insert_with(Vec::new).extend(names);
+            }
+        }
+
+        return Ok(listing_names);
+    }
+
+    /// Domain the entity belongs to: the cached domain listing including it or, when not
+    /// synced yet, the ICS ID ranges (TA01xx, T0xxx, M0xxx). `None` for entities without
+    /// domain or not found.
+    pub fn entity_domain(
+        &self,
+        kind: EntityKind,
+        id: &str,
+    ) -> Result<Option<String>, error::Error> {
+        if !kind.has_domain() {
+            return Ok(None);
+        }
+
+        let id = id.to_uppercase();
+
+        for domain in DOMAINS {
+            if let Some(listing_ids) = self.listing_ids(kind, Some(domain))? {
+                if listing_ids
+                    .iter()
+                    .any(|listed_id| listed_id.to_uppercase() == id)
+                {
+                    return Ok(Some(domain.to_string()));
+                }
+            }
+        }
+
+        return Ok(is_ics_id(kind, &id).then(|| String::from("ics")));
+    }
+
+    /// IDs of the cached listing without a cached detail file.
+    pub fn missing_ids(
+        &self,
+        kind: EntityKind,
+        domain: Option<&str>,
+    ) -> Result<Vec<String>, error::Error> {
+        let listing_ids = match self.listing_ids(kind, domain)? {
+            Some(listing_ids) => listing_ids,
+            None => {
+                let kind_name: &str = kind.into();
+
+                return Err(error::Error::General(format!(
+                    "No {} listing cached, run 'attack sync {}' first",
+                    kind_name, kind_name
+                )));
+            }
+        };
+        let cached_ids = self.entity_ids(kind)?;
+
+        return Ok(listing_ids
+            .into_iter()
+            .filter(|id| !cached_ids.contains(&id.to_uppercase()))
+            .collect());
+    }
+
+    /// IDs of the entities of the given type with a cached detail file.
+    pub fn entity_ids(&self, kind: EntityKind) -> Result<Vec<String>, error::Error> {
+        let kind_dir = self.kind_dir(kind);
+
+        if !kind_dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut ids = fs::read_dir(kind_dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let path = entry.path();
+
+                if path.extension()? != "json" {
+                    return None;
+                }
+
+                let stem = path.file_stem()?.to_str()?;
+
+                if is_entity_file_stem(stem) {
+                    Some(stem.to_string())
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<String>>();
+        ids.sort();
+
+        return Ok(ids);
+    }
+
+    /// Load every cached entity detail of the given type.
+    pub fn load_entities<T: DeserializeOwned>(
+        &self,
+        kind: EntityKind,
+    ) -> Result<Vec<T>, error::Error> {
+        let mut entities = Vec::new();
+
+        for id in self.entity_ids(kind)? {
+            if let Some(entity) = self.load_entity(kind, &id)? {
+                entities.push(entity);
+            }
+        }
+
+        return Ok(entities);
+    }
+
+    /// Remove the cached detail file of the entity, returning whether it was cached.
+    pub fn remove_entity(&self, kind: EntityKind, id: &str) -> Result<bool, error::Error> {
+        let path = self.entity_path(kind, id);
+
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        fs::remove_file(path)?;
+
+        let version_path = self.entity_version_path(kind, id);
+
+        if version_path.exists() {
+            fs::remove_file(version_path)?;
+        }
+
+        return Ok(true);
+    }
+
+    /// Load every cached entity detail.
+    pub fn snapshot(&self) -> Result<Snapshot, error::Error> {
+        return Ok(Snapshot {
+            tactics: self.load_entities(EntityKind::TACTIC)?,
+            techniques: self.load_entities(EntityKind::TECHNIQUE)?,
+            mitigations: self.load_entities(EntityKind::MITIGATION)?,
+            software: self.load_entities(EntityKind::SOFTWARE)?,
+            groups: self.load_entities(EntityKind::GROUP)?,
+            data_sources: self.load_entities(EntityKind::DATASOURCE)?,
+        });
+    }
+}
+
+/// Whether the ID falls within the ICS ranges, which do not overlap the enterprise and
+/// mobile ones.
+fn is_ics_id(kind: EntityKind, id: &str) -> bool {
+    return match kind {
+        EntityKind::TACTIC => id.starts_with("TA01"),
+        EntityKind::TECHNIQUE => id.starts_with("T0"),
+        EntityKind::MITIGATION => id.starts_with("M0"),
+        _ => false,
+    };
+}
+
+/// Entity files are named after the (uppercase) ATT&CK ID, listings after the lowercase domain.
+fn is_entity_file_stem(stem: &str) -> bool {
+    return stem
+        .chars()
+        .next()
+        .is_some_and(|first_char| first_char.is_ascii_uppercase());
+}
+
+/// Every entity detail available in the cache.
+#[derive(Debug, Default)]
+pub struct Snapshot {
+    pub tactics: Vec<Tactic>,
+    pub techniques: Vec<Technique>,
+    pub mitigations: Vec<Mitigation>,
+    pub software: Vec<Software>,
+    pub groups: Vec<Group>,
+    pub data_sources: Vec<DataSource>,
+}
+
+impl Snapshot {
+    pub fn is_empty(&self) -> bool {
+        return self.tactics.is_empty()
+            && self.techniques.is_empty()
+            && self.mitigations.is_empty()
+            && self.software.is_empty()
+            && self.groups.is_empty()
+            && self.data_sources.is_empty();
+    }
+}
+
+/// Empty cache in the temporary directory, `name` being unique across the test modules
+/// (prefixed by the module name, e.g. `sync_listing`).
+#[cfg(test)]
+pub(crate) fn test_cache(name: &str) -> Cache {
+    let root = std::env::temp_dir().join(format!("mitre_cli_{}", name));
+    let _ = fs::remove_dir_all(&root);
+
+    return Cache::with_root(root);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack::techniques::{fetch_technique, fetch_techniques, Domain};
+    use crate::fakers::FakeHttpReqwest;
+
+    const TEST_TECHNIQUE_ID: &'static str = "T1134.004";
+
+    #[test]
+    fn test_entity_roundtrip() -> Result<(), error::Error> {
+        let cache = test_cache("cache_entity_roundtrip");
+        let fake_reqwest = FakeHttpReqwest::default().set_success_response(
+            include_str!("html/attck/techniques/enterprise_parent_pid_spoofing.html").to_string(),
+        );
+        let technique = fetch_technique(TEST_TECHNIQUE_ID, &fake_reqwest)?;
+
+        cache.save_entity(EntityKind::TECHNIQUE, TEST_TECHNIQUE_ID, &technique)?;
+        let cached_technique: Technique = cache
+            .load_entity(EntityKind::TECHNIQUE, TEST_TECHNIQUE_ID)?
+            .unwrap();
+
+        assert_eq!(cached_technique.name, technique.name);
+        assert_eq!(
+            cached_technique.procedures.unwrap().0.len(),
+            technique.procedures.unwrap().0.len()
+        );
+        assert_eq!(
+            cache.entity_ids(EntityKind::TECHNIQUE)?,
+            vec![TEST_TECHNIQUE_ID]
+        );
+        assert_eq!(cache.snapshot()?.techniques.len(), 1);
+
+        fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_listings_are_not_entities() -> Result<(), error::Error> {
+        let cache = test_cache("cache_listings");
+
+        cache.save_listing(
+            EntityKind::TECHNIQUE,
+            Some("enterprise"),
+            &TechniquesTable::default(),
+        )?;
+
+        assert!(cache.entity_ids(EntityKind::TECHNIQUE)?.is_empty());
+        assert!(cache
+            .load_listing::<TechniquesTable>(EntityKind::TECHNIQUE, Some("enterprise"))?
+            .is_some());
+
+        fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_ids() -> Result<(), error::Error> {
+        let cache = test_cache("cache_missing");
+        let fake_reqwest = FakeHttpReqwest::default()
+            .set_success_response(include_str!("html/attck/techniques/mobile.html").to_string());
+        let listing = fetch_techniques(Domain::MOBILE, &fake_reqwest)?;
+        let listing_ids = listing.ids();
+
+        assert!(cache
+            .missing_ids(EntityKind::TECHNIQUE, Some("mobile"))
+            .is_err());
+
+        cache.save_listing(EntityKind::TECHNIQUE, Some("mobile"), &listing)?;
+        cache.save_entity(
+            EntityKind::TECHNIQUE,
+            &listing_ids[0],
+            &Technique::default(),
+        )?;
+
+        let missing_ids = cache.missing_ids(EntityKind::TECHNIQUE, Some("mobile"))?;
+
+        assert_eq!(missing_ids.len(), listing_ids.len() - 1);
+        assert!(!missing_ids.contains(&listing_ids[0]));
+
+        fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_entity_kind_from_id() {
+        assert_eq!(EntityKind::from_id("TA0001"), Some(EntityKind::TACTIC));
+        assert_eq!(
+            EntityKind::from_id("t1059.001"),
+            Some(EntityKind::TECHNIQUE)
+        );
+        assert_eq!(EntityKind::from_id("M1036"), Some(EntityKind::MITIGATION));
+        assert_eq!(EntityKind::from_id("DS0009"), Some(EntityKind::DATASOURCE));
+        assert_eq!(EntityKind::from_id("CVE-2021-44228"), None);
+        assert_eq!(EntityKind::from_id("G00x8"), None);
+        assert_eq!(EntityKind::from_id("apt29"), None);
+    }
+
+    #[test]
+    fn test_entity_domain() -> Result<(), error::Error> {
+        let cache = test_cache("cache_entity_domain");
+        let fake_reqwest = FakeHttpReqwest::default()
+            .set_success_response(include_str!("html/attck/techniques/ics.html").to_string());
+        let listing = fetch_techniques(Domain::ICS, &fake_reqwest)?;
+        let ics_id = listing.ids()[0].clone();
+
+        cache.save_listing(EntityKind::TECHNIQUE, Some("ics"), &listing)?;
+
+        assert_eq!(
+            cache.entity_domain(EntityKind::TECHNIQUE, &ics_id.to_lowercase())?,
+            Some(String::from("ics"))
+        );
+        assert_eq!(cache.entity_domain(EntityKind::TECHNIQUE, "T1610")?, None);
+        assert_eq!(cache.entity_domain(EntityKind::GROUP, "G0018")?, None);
+
+        fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_entity_domain_from_ics_ranges() -> Result<(), error::Error> {
+        let cache = test_cache("cache_entity_domain_ranges");
+
+        assert_eq!(
+            cache.entity_domain(EntityKind::TACTIC, "TA0108")?,
+            Some(String::from("ics"))
+        );
+        assert_eq!(
+            cache.entity_domain(EntityKind::MITIGATION, "M0801")?,
+            Some(String::from("ics"))
+        );
+        assert_eq!(cache.entity_domain(EntityKind::TACTIC, "TA0001")?, None);
+        assert_eq!(cache.entity_domain(EntityKind::MITIGATION, "M1036")?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_metadata() -> Result<(), error::Error> {
+        let cache = test_cache("cache_sync_metadata");
+
+        assert_eq!(cache.attack_version()?, None);
+        assert_eq!(cache.synced_at(EntityKind::TECHNIQUE)?, None);
+
+        cache.save_attack_version("v15.1")?;
+        cache.save_listing(
+            EntityKind::TECHNIQUE,
+            Some("enterprise"),
+            &TechniquesTable::default(),
+        )?;
+
+        assert_eq!(cache.attack_version()?, Some(String::from("v15.1")));
+        assert!(cache.synced_at(EntityKind::TECHNIQUE)?.is_some());
+        assert!(cache.synced_at(EntityKind::GROUP)?.is_none());
+
+        fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch_on_cache_miss() -> Result<(), error::Error> {
+        let cache = test_cache("cache_cache_miss");
+        let listing: TechniquesTable =
+            cache.listing_or_fetch(EntityKind::TECHNIQUE, Some("ics"), || {
+                Ok(TechniquesTable::default())
+            })?;
+
+        assert!(listing.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,409 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error;
+
+/// CALDERA default objective ("Default objective"), used by the stockpile adversaries.
+const DEFAULT_OBJECTIVE_ID: &'static str = "495a9828-cab1-44dd-a0ca-66e58177d8cc";
+
+/// Ability as returned by the CALDERA REST API (`GET /api/v2/abilities`).
+#[derive(Debug, Default, Deserialize)]
+pub struct Ability {
+    pub ability_id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub technique_id: String,
+}
+
+/// Load the abilities exported from a CALDERA server (JSON), or the ability files of a
+/// CALDERA abilities directory (e.g. `plugins/stockpile/data/abilities`).
+pub fn load_abilities(path: &Path) -> Result<Vec<Ability>, error::Error> {
+    if path.is_dir() {
+        let mut abilities = Vec::new();
+        load_abilities_dir(path, &mut abilities)?;
+
+        return Ok(abilities);
+    }
+
+    return Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?);
+}
+
+fn load_abilities_dir(dir: &Path, abilities: &mut Vec<Ability>) -> Result<(), error::Error> {
+    let mut entries = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, std::io::Error>>()?;
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            load_abilities_dir(&path, abilities)?;
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "yml" || extension == "yaml")
+        {
+            abilities.extend(parse_ability_yaml(&std::fs::read_to_string(&path)?));
+        }
+    }
+
+    return Ok(());
+}
+
+/// Abilities of a CALDERA ability file. Only the ability ID, name and ATT&CK technique ID
+/// are read, the files being plain YAML lists of mappings.
+pub fn parse_ability_yaml(content: &str) -> Vec<Ability> {
+    let mut abilities: Vec<Ability> = Vec::new();
+    // Indentation of the ability keys, nested mappings (technique, executors) are deeper.
+    let mut key_indent = 0;
+
+    for line in content.lines() {
+        let indent = line.len() - line.trim_start().len();
+        let mut entry = line.trim();
+
+        if let Some(item) = entry.strip_prefix("- ") {
+            if indent == 0 {
+                abilities.push(Ability::default());
+                key_indent = indent + 2;
+            }
+            entry = item.trim_start();
+        }
+
+        let (key, value) = match entry.split_once(':') {
+            Some((key, value)) => (key.trim(), yaml_value(value)),
+            None => continue,
+        };
+        let ability = match abilities.last_mut() {
+            Some(ability) => ability,
+            None => continue,
+        };
+
+        match key {
+            "id" if indent <= key_indent && ability.ability_id.is_empty() => {
+                ability.ability_id = value
+            }
+            "name" if indent <= key_indent && ability.name.is_empty() => ability.name = value,
+            "attack_id" => ability.technique_id = value,
+            _ => {}
+        }
+    }
+
+    return abilities
+        .into_iter()
+        .filter(|ability| !ability.ability_id.is_empty())
+        .collect();
+}
+
+fn yaml_value(value: &str) -> String {
+    return value
+        .trim()
+        .trim_matches(|c| c == '\'' || c == '"')
+        .to_string();
+}
+
+/// Abilities emulating the technique, those of the parent technique for sub-techniques
+/// without abilities of their own.
+pub fn technique_abilities<'a>(technique_id: &str, abilities: &'a [Ability]) -> Vec<&'a Ability> {
+    let technique_abilities = abilities
+        .iter()
+        .filter(|ability| ability.technique_id.eq_ignore_ascii_case(technique_id))
+        .collect::<Vec<&Ability>>();
+
+    return match technique_id.split_once('.') {
+        Some((parent_id, _)) if technique_abilities.is_empty() => abilities
+            .iter()
+            .filter(|ability| ability.technique_id.eq_ignore_ascii_case(parent_id))
+            .collect(),
+        _ => technique_abilities,
+    };
+}
+
+/// CALDERA adversary profile emulating a set of techniques.
+#[derive(Debug, Default)]
+pub struct AdversaryProfile {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// (ability ID, technique ID) in the order the abilities will run.
+    pub atomic_ordering: Vec<(String, String)>,
+    /// Techniques without a matching ability.
+    pub unresolved: Vec<String>,
+}
+
+impl AdversaryProfile {
+    /// Profile running one ability per technique. Sub-techniques without an ability of
+    /// their own fall back to an ability of their parent technique.
+    pub fn build(
+        name: &str,
+        description: &str,
+        technique_ids: &[String],
+        abilities: &[Ability],
+    ) -> Self {
+        let mut profile = Self {
+            id: Uuid::new_v5(&Uuid::NAMESPACE_OID, name.as_bytes()).to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            ..Default::default()
+        };
+
+        for technique_id in technique_ids {
+            let technique_id = technique_id.to_uppercase();
+
+            match technique_abilities(&technique_id, abilities).first() {
+                Some(ability)
+                    if !profile
+                        .atomic_ordering
+                        .iter()
+                        .any(|(ability_id, _)| *ability_id == ability.ability_id) =>
+                {
+                    profile
+                        .atomic_ordering
+                        .push((ability.ability_id.clone(), technique_id));
+                }
+                Some(_) => {}
+                None => profile.unresolved.push(technique_id),
+            }
+        }
+
+        return profile;
+    }
+
+    /// Profile in the YAML layout of the CALDERA adversaries, unresolved techniques are
+    /// listed as comments so they can be filled in by hand.
+    pub fn to_yaml(&self) -> String {
+        let mut yaml = format!(
+            "id: {}\nname: {}\ndescription: {}\nobjective: {}\natomic_ordering:{}\n",
+            self.id,
+            yaml_string(&self.name),
+            yaml_string(&self.description),
+            DEFAULT_OBJECTIVE_ID,
+            if self.atomic_ordering.is_empty() {
+                " []"
+            } else {
+                ""
+            }
+        );
+
+        for (ability_id, technique_id) in &self.atomic_ordering {
+            yaml.push_str(&format!("  - {}  # {}\n", ability_id, technique_id));
+        }
+
+        if !self.unresolved.is_empty() {
+            yaml.push_str("# Techniques without a matching ability:\n");
+
+            for technique_id in &self.unresolved {
+                yaml.push_str(&format!("#  - {}\n", technique_id));
+            }
+        }
+
+        return yaml;
+    }
+}
+
+/// Technique of the emulated set and the abilities already emulating it.
+#[derive(Debug, Default, Serialize)]
+pub struct EmulationRow {
+    pub id: String,
+    pub abilities: Vec<String>,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for EmulationRow {
+    fn into(self) -> comfy_table::Row {
+        let status = if self.abilities.is_empty() {
+            "Needs ability"
+        } else {
+            "Emulated"
+        };
+
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(status))
+            .add_cell(comfy_table::Cell::new(self.abilities.join("\n")));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct EmulationCoverage(pub Vec<EmulationRow>);
+
+impl EmulationCoverage {
+    /// Match every technique against the abilities inventory.
+    pub fn build(technique_ids: &[String], abilities: &[Ability]) -> Self {
+        return Self(
+            technique_ids
+                .iter()
+                .map(|technique_id| EmulationRow {
+                    id: technique_id.to_uppercase(),
+                    abilities: technique_abilities(technique_id, abilities)
+                        .into_iter()
+                        .map(|ability| format!("{} ({})", ability.name, ability.ability_id))
+                        .collect(),
+                })
+                .collect(),
+        );
+    }
+
+    pub fn emulated(&self) -> usize {
+        return self
+            .0
+            .iter()
+            .filter(|row| !row.abilities.is_empty())
+            .count();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.0.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+}
+
+impl IntoIterator for EmulationCoverage {
+    type Item = EmulationRow;
+    type IntoIter = std::vec::IntoIter<EmulationRow>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.0.into_iter();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for EmulationCoverage {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&["ID", "Status", "Abilities"]));
+
+        for row in self {
+            table.add_row(row);
+        }
+
+        return table;
+    }
+}
+
+/// Double quoted YAML scalar, JSON string escaping is valid YAML.
+fn yaml_string(value: &str) -> String {
+    return serde_json::Value::from(value).to_string();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_abilities() -> Vec<Ability> {
+        return serde_json::from_str(
+            r#"[
+                {"ability_id": "c0da588f-79f0-4263-8998-7496b1a40596", "name": "Identify active user", "technique_id": "T1033"},
+                {"ability_id": "3b5db901-2cb8-4df7-8043-c4628a6a5d5a", "name": "Find local users", "technique_id": "T1087.001"},
+                {"ability_id": "9a30740d-3aa8-4c23-8efa-d51215e8a5b9", "name": "Scan WIFI networks", "technique_id": "T1016"}
+            ]"#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_profile_resolves_abilities() {
+        let technique_ids = vec![
+            String::from("t1033"),
+            String::from("T1087.001"),
+            String::from("T1016.001"),
+            String::from("T1003"),
+        ];
+        let profile = AdversaryProfile::build("APT3", "", &technique_ids, &test_abilities());
+
+        assert_eq!(profile.atomic_ordering.len(), 3);
+        assert_eq!(profile.atomic_ordering[0].1, "T1033");
+        assert_eq!(
+            profile.atomic_ordering[2].0,
+            "9a30740d-3aa8-4c23-8efa-d51215e8a5b9"
+        );
+        assert_eq!(profile.unresolved, vec!["T1003"]);
+        assert_eq!(
+            profile.id,
+            AdversaryProfile::build("APT3", "", &[], &[]).id,
+            "Profile IDs should be stable"
+        );
+    }
+
+    #[test]
+    fn test_parse_ability_yaml() {
+        let abilities = parse_ability_yaml(
+            r#"---
+
+- id: c0da588f-79f0-4263-8998-7496b1a40596
+  name: Identify active user
+  description: Find user running agent
+  tactic: discovery
+  technique:
+    attack_id: T1033
+    name: "System Owner/User Discovery"
+  platforms:
+    darwin:
+      sh:
+        command: whoami
+"#,
+        );
+
+        assert_eq!(abilities.len(), 1);
+        assert_eq!(
+            abilities[0].ability_id,
+            "c0da588f-79f0-4263-8998-7496b1a40596"
+        );
+        assert_eq!(abilities[0].name, "Identify active user");
+        assert_eq!(abilities[0].technique_id, "T1033");
+    }
+
+    #[test]
+    fn test_load_abilities_dir() -> Result<(), error::Error> {
+        let root = std::env::temp_dir().join("mitre_cli_caldera_abilities");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("discovery"))?;
+        std::fs::write(
+            root.join("discovery").join("c0da588f.yml"),
+            "- id: c0da588f\n  name: Identify active user\n  technique:\n    attack_id: T1033\n",
+        )?;
+        std::fs::write(root.join("README.md"), "- id: not-an-ability\n")?;
+
+        let abilities = load_abilities(&root)?;
+        assert_eq!(abilities.len(), 1);
+
+        let coverage = EmulationCoverage::build(
+            &[String::from("T1033.001"), String::from("T1003")],
+            &abilities,
+        );
+        assert_eq!(coverage.emulated(), 1);
+        assert_eq!(
+            coverage.0[0].abilities,
+            vec!["Identify active user (c0da588f)"]
+        );
+        assert!(coverage.0[1].abilities.is_empty());
+
+        std::fs::remove_dir_all(&root)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_profile_yaml() {
+        let profile = AdversaryProfile::build(
+            "APT3",
+            "China-based \"threat\" group",
+            &[String::from("T1033"), String::from("T1003")],
+            &test_abilities(),
+        );
+        let yaml = profile.to_yaml();
+
+        assert!(yaml.contains("name: \"APT3\"\n"));
+        assert!(yaml.contains("description: \"China-based \\\"threat\\\" group\"\n"));
+        assert!(yaml.contains("  - c0da588f-79f0-4263-8998-7496b1a40596  # T1033\n"));
+        assert!(yaml.contains("#  - T1003\n"));
+        assert!(AdversaryProfile::build("APT3", "", &[], &[])
+            .to_yaml()
+            .contains("atomic_ordering: []\n"));
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::error;
+
+use super::{
+    cache::{EntityKind, DOMAINS},
+    dataset::Dataset,
+    techniques::TechniquesTable,
+};
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct ChangelogEntry {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct RenamedTechnique {
+    pub id: String,
+    pub previous_name: String,
+    pub name: String,
+}
+
+/// Technique changes between two ATT&CK releases, from their listings.
+#[derive(Debug, Default, Serialize)]
+pub struct Changelog {
+    pub from: String,
+    pub to: String,
+    pub new_techniques: Vec<ChangelogEntry>,
+    /// Techniques no longer listed, ATT&CK deprecating (or revoking) them.
+    pub deprecated: Vec<ChangelogEntry>,
+    pub renamed: Vec<RenamedTechnique>,
+    pub description_changes: Vec<ChangelogEntry>,
+    /// Domains whose techniques are listed in only one of the datasets (e.g. not synced into
+    /// the local cache), left out of the comparison.
+    pub skipped_domains: Vec<String>,
+}
+
+/// (name, description) of the techniques and sub-techniques of the `domains` listings, by ID.
+fn listed_techniques(
+    dataset: &Dataset,
+    domains: &[&str],
+) -> Result<BTreeMap<String, (String, String)>, error::Error> {
+    let mut techniques = BTreeMap::new();
+
+    for &domain in domains {
+        let listing = match dataset.listing(EntityKind::TECHNIQUE, Some(domain)) {
+            Some(listing) => serde_json::from_value::<TechniquesTable>(listing.clone())?,
+            None => continue,
+        };
+
+        for technique in listing {
+            for sub_technique in technique.sub_techniques.iter().flatten() {
+                techniques.insert(
+                    format!("{}{}", technique.id, sub_technique.id),
+                    (
+                        format!("{}: {}", technique.name, sub_technique.name),
+                        sub_technique.description.clone(),
+                    ),
+                );
+            }
+
+            techniques.insert(technique.id, (technique.name, technique.description));
+        }
+    }
+
+    return Ok(techniques);
+}
+
+impl Changelog {
+    pub fn between(from: &Dataset, to: &Dataset) -> Result<Self, error::Error> {
+        let is_listed = |dataset: &Dataset, domain: &str| {
+            return dataset
+                .listing(EntityKind::TECHNIQUE, Some(domain))
+                .is_some();
+        };
+        let (domains, skipped_domains): (Vec<&str>, Vec<&str>) = DOMAINS
+            .into_iter()
+            .filter(|domain| is_listed(from, domain) || is_listed(to, domain))
+            .partition(|domain| is_listed(from, domain) && is_listed(to, domain));
+        let (from_techniques, to_techniques) = (
+            listed_techniques(from, &domains)?,
+            listed_techniques(to, &domains)?,
+        );
+        let mut changelog = Self {
+            from: from.release.clone(),
+            to: to.release.clone(),
+            skipped_domains: skipped_domains.into_iter().map(String::from).collect(),
+            ..Default::default()
+        };
+
+        for (id, (name, description)) in &to_techniques {
+            let entry = ChangelogEntry {
+                id: id.clone(),
+                name: name.clone(),
+            };
+
+            match from_techniques.get(id) {
+                None => changelog.new_techniques.push(entry),
+                Some((previous_name, previous_description)) => {
+                    if previous_name != name {
+                        changelog.renamed.push(RenamedTechnique {
+                            id: id.clone(),
+                            previous_name: previous_name.clone(),
+                            name: name.clone(),
+                        });
+                    }
+
+                    if previous_description != description {
+                        changelog.description_changes.push(entry);
+                    }
+                }
+            }
+        }
+
+        changelog.deprecated = from_techniques
+            .into_iter()
+            .filter(|(id, _)| !to_techniques.contains_key(id))
+            .map(|(id, (name, _))| ChangelogEntry { id, name })
+            .collect();
+
+        return Ok(changelog);
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let entries = |entries: &[ChangelogEntry]| {
+            return entries
+                .iter()
+                .map(|entry| format!("- {} {}", entry.id, entry.name))
+                .collect::<Vec<String>>();
+        };
+        let sections = [
+            ("New techniques", entries(&self.new_techniques)),
+            ("Deprecated techniques", entries(&self.deprecated)),
+            (
+                "Renamed techniques",
+                self.renamed
+                    .iter()
+                    .map(|renamed| {
+                        format!(
+                            "- {} {} -> {}",
+                            renamed.id, renamed.previous_name, renamed.name
+                        )
+                    })
+                    .collect(),
+            ),
+            ("Description changes", entries(&self.description_changes)),
+        ];
+        let mut markdown = format!("# ATT&CK changelog {} -> {}\n", self.from, self.to);
+
+        if !self.skipped_domains.is_empty() {
+            markdown.push_str(&format!(
+                "\nSkipped domains, listed in only one release: {}\n",
+                self.skipped_domains.join(", ")
+            ));
+        }
+
+        for (title, lines) in sections {
+            markdown.push_str(&format!("\n## {} ({})\n\n", title, lines.len()));
+
+            if lines.is_empty() {
+                markdown.push_str("None\n");
+            } else {
+                markdown.push_str(&(lines.join("\n") + "\n"));
+            }
+        }
+
+        return markdown;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dataset(release: &str, techniques: serde_json::Value) -> Result<Dataset, error::Error> {
+        return Ok(serde_json::from_value(serde_json::json!({
+            "release": release,
+            "listings": [{"kind": "techniques", "domain": "enterprise", "listing": techniques}],
+            "entities": []
+        }))?);
+    }
+
+    #[test]
+    fn test_changelog_skips_unshared_domains() -> Result<(), error::Error> {
+        let from: Dataset = serde_json::from_value(serde_json::json!({
+            "release": "v14",
+            "listings": [
+                {"kind": "techniques", "domain": "enterprise", "listing": []},
+                {"kind": "techniques", "domain": "mobile", "listing": [
+                    {"id": "T1398", "name": "Boot or Logon Initialization Scripts",
+                     "description": "Scripts", "sub_techniques": null}
+                ]}
+            ],
+            "entities": []
+        }))?;
+        let to = dataset("local cache", serde_json::json!([]))?;
+
+        let changelog = Changelog::between(&from, &to)?;
+
+        assert!(changelog.deprecated.is_empty());
+        assert_eq!(changelog.skipped_domains, vec!["mobile"]);
+        assert!(changelog
+            .to_markdown()
+            .contains("Skipped domains, listed in only one release: mobile\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_changelog_between_releases() -> Result<(), error::Error> {
+        let from = dataset(
+            "v13",
+            serde_json::json!([
+                {"id": "T1059", "name": "Command and Scripting Interpreter", "description": "Scripts",
+                 "sub_techniques": [{"id": ".001", "name": "PowerShell", "description": "PS"}]},
+                {"id": "T1064", "name": "Scripting", "description": "Old", "sub_techniques": null},
+                {"id": "T1610", "name": "Deploy a Container", "description": "Containers",
+                 "sub_techniques": null}
+            ]),
+        )?;
+        let to = dataset(
+            "v14",
+            serde_json::json!([
+                {"id": "T1059", "name": "Command and Scripting Interpreter",
+                 "description": "Interpreters",
+                 "sub_techniques": [{"id": ".001", "name": "PowerShell", "description": "PS"},
+                                    {"id": ".010", "name": "AutoHotKey", "description": "AHK"}]},
+                {"id": "T1610", "name": "Deploy Container", "description": "Containers",
+                 "sub_techniques": null}
+            ]),
+        )?;
+
+        let changelog = Changelog::between(&from, &to)?;
+
+        assert_eq!(
+            changelog.new_techniques,
+            vec![ChangelogEntry {
+                id: String::from("T1059.010"),
+                name: String::from("Command and Scripting Interpreter: AutoHotKey"),
+            }]
+        );
+        assert_eq!(changelog.deprecated[0].id, "T1064");
+        assert_eq!(
+            changelog.renamed,
+            vec![RenamedTechnique {
+                id: String::from("T1610"),
+                previous_name: String::from("Deploy a Container"),
+                name: String::from("Deploy Container"),
+            }]
+        );
+        assert_eq!(changelog.description_changes.len(), 1);
+        assert_eq!(changelog.description_changes[0].id, "T1059");
+
+        let markdown = changelog.to_markdown();
+        assert!(markdown.starts_with("# ATT&CK changelog v13 -> v14\n"));
+        assert!(markdown.contains(
+            "## Renamed techniques (1)\n\n- T1610 Deploy a Container -> Deploy Container\n"
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,285 @@
+use serde::Serialize;
+
+use super::software::Software;
+
+/// Compared software, with the techniques no other compared software uses.
+#[derive(Debug, Default, Serialize)]
+pub struct ComparedSoftware {
+    pub id: String,
+    pub name: String,
+    pub techniques: usize,
+    pub unique_techniques: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SharedTechniqueRow {
+    pub id: String,
+    pub name: String,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for SharedTechniqueRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(self.name));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SharedTechniquesTable(pub Vec<SharedTechniqueRow>);
+
+impl SharedTechniquesTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for SharedTechniquesTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&["ID", "Name"]));
+
+        for technique in self.0 {
+            table.add_row(technique);
+        }
+
+        return table;
+    }
+}
+
+/// Group using some of the compared software.
+#[derive(Debug, Default, Serialize)]
+pub struct GroupUsageRow {
+    pub id: String,
+    pub name: String,
+    /// IDs of the compared software used by the group.
+    pub software: Vec<String>,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for GroupUsageRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(self.name))
+            .add_cell(comfy_table::Cell::new(self.software.join(", ")));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct GroupUsageTable(pub Vec<GroupUsageRow>);
+
+impl GroupUsageTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for GroupUsageTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&["ID", "Name", "Software used"]));
+
+        for group in self.0 {
+            table.add_row(group);
+        }
+
+        return table;
+    }
+}
+
+/// Techniques shared by the compared software and the groups using each of them, to reason
+/// about tool substitution.
+#[derive(Debug, Default, Serialize)]
+pub struct SoftwareComparison {
+    pub software: Vec<ComparedSoftware>,
+    /// Techniques (sub-techniques included) used by every compared software.
+    pub shared_techniques: SharedTechniquesTable,
+    /// Groups using the compared software, those using most of them first.
+    pub groups: GroupUsageTable,
+}
+
+impl SoftwareComparison {
+    pub fn build(software: &[Software]) -> Self {
+        let technique_names: Vec<Vec<(String, String)>> = software
+            .iter()
+            .map(|software| {
+                software
+                    .techniques
+                    .as_ref()
+                    .map_or_else(Vec::new, |techniques| techniques.names())
+            })
+            .collect();
+        let is_used_by = |names: &[(String, String)], technique_id: &str| {
+            return names.iter().any(|(id, _)| id == technique_id);
+        };
+
+        let shared_techniques = technique_names
+            .first()
+            .map_or_else(Vec::new, |first_names| {
+                first_names
+                    .iter()
+                    .filter(|(id, _)| technique_names.iter().all(|names| is_used_by(names, id)))
+                    .map(|(id, name)| SharedTechniqueRow {
+                        id: id.clone(),
+                        name: name.clone(),
+                    })
+                    .collect()
+            });
+
+        let compared_software = software
+            .iter()
+            .zip(&technique_names)
+            .enumerate()
+            .map(|(inx, (software, names))| ComparedSoftware {
+                id: software.id.clone(),
+                name: software.name.clone(),
+                techniques: names.len(),
+                unique_techniques: names
+                    .iter()
+                    .filter(|(id, _)| {
+                        technique_names
+                            .iter()
+                            .enumerate()
+                            .all(|(other_inx, other_names)| {
+                                other_inx == inx || !is_used_by(other_names, id)
+                            })
+                    })
+                    .map(|(id, _)| id.clone())
+                    .collect(),
+            })
+            .collect();
+
+        let mut groups: Vec<GroupUsageRow> = Vec::new();
+
+        for software in software {
+            for group in software.groups.iter().flat_map(|groups| groups.0.iter()) {
+                match groups.iter_mut().find(|usage| usage.id == group.id) {
+                    Some(usage) => usage.software.push(software.id.clone()),
+                    None => groups.push(GroupUsageRow {
+                        id: group.id.clone(),
+                        name: group.name.clone(),
+                        software: vec![software.id.clone()],
+                    }),
+                }
+            }
+        }
+
+        groups.sort_by(|a, b| {
+            b.software
+                .len()
+                .cmp(&a.software.len())
+                .then(a.id.cmp(&b.id))
+        });
+
+        return Self {
+            software: compared_software,
+            shared_techniques: SharedTechniquesTable(shared_techniques),
+            groups: GroupUsageTable(groups),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack::{
+        software::{AssocGroupsRow, AssocGroupsTable},
+        techniques::domain::{DomainSubTechniqueRow, DomainTechniqueRow, DomainTechniquesTable},
+    };
+
+    fn software(id: &str, techniques: &[(&str, &[&str])], groups: &[&str]) -> Software {
+        return Software {
+            id: String::from(id),
+            name: format!("Software {}", id),
+            techniques: Some(DomainTechniquesTable(
+                techniques
+                    .iter()
+                    .map(|(technique_id, sub_ids)| DomainTechniqueRow {
+                        id: technique_id.to_string(),
+                        name: format!("Technique {}", technique_id),
+                        sub_techniques: Some(
+                            sub_ids
+                                .iter()
+                                .map(|sub_id| DomainSubTechniqueRow {
+                                    id: sub_id.to_string(),
+                                    name: format!("Sub-technique {}", sub_id),
+                                    ..Default::default()
+                                })
+                                .collect(),
+                        ),
+                        ..Default::default()
+                    })
+                    .collect(),
+            )),
+            groups: Some(AssocGroupsTable(
+                groups
+                    .iter()
+                    .map(|group_id| AssocGroupsRow {
+                        id: group_id.to_string(),
+                        name: format!("Group {}", group_id),
+                    })
+                    .collect(),
+            )),
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn test_compare_software() {
+        let comparison = SoftwareComparison::build(&[
+            software(
+                "S0002",
+                &[("T1003", &[".001", ".004"]), ("T1134", &[])],
+                &["G0016", "G0007"],
+            ),
+            software(
+                "S0029",
+                &[("T1003", &[".001"]), ("T1021", &[".002"])],
+                &["G0007", "G0050"],
+            ),
+        ]);
+
+        assert_eq!(
+            comparison
+                .shared_techniques
+                .0
+                .iter()
+                .map(|technique| (technique.id.as_str(), technique.name.as_str()))
+                .collect::<Vec<(&str, &str)>>(),
+            vec![
+                ("T1003", "Technique T1003"),
+                ("T1003.001", "Technique T1003: Sub-technique .001")
+            ]
+        );
+        assert_eq!(comparison.software[0].techniques, 4);
+        assert_eq!(
+            comparison.software[0].unique_techniques,
+            vec!["T1003.004", "T1134"]
+        );
+        assert_eq!(
+            comparison.software[1].unique_techniques,
+            vec!["T1021", "T1021.002"]
+        );
+        assert_eq!(
+            comparison
+                .groups
+                .0
+                .iter()
+                .map(|group| (group.id.as_str(), group.software.join(",")))
+                .collect::<Vec<(&str, String)>>(),
+            vec![
+                ("G0007", String::from("S0002,S0029")),
+                ("G0016", String::from("S0002")),
+                ("G0050", String::from("S0029"))
+            ]
+        );
+    }
+}
@@ -0,0 +1,425 @@
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::{error, WebFetch};
+
+use super::{
+    cache::{Cache, EntityKind, DOMAINS},
+    data_sources::DataSource,
+    groups::Group,
+    mitigations::Mitigation,
+    software::Software,
+    sync::{self, SyncSummary},
+    tactics::Tactic,
+    techniques::Technique,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrphanReason {
+    /// The parent technique has no cached detail file.
+    ORPHANED,
+    /// The cached listings include the parent technique but not the sub-technique anymore.
+    UNLISTED,
+}
+
+impl Into<&'static str> for OrphanReason {
+    fn into(self) -> &'static str {
+        match self {
+            Self::ORPHANED => "Missing parent",
+            Self::UNLISTED => "Unlisted",
+        }
+    }
+}
+
+/// Cached sub-technique inconsistent with its parent technique.
+#[derive(Debug, Serialize)]
+pub struct OrphanRow {
+    pub id: String,
+    pub parent_id: String,
+    pub reason: OrphanReason,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for OrphanRow {
+    fn into(self) -> comfy_table::Row {
+        let reason: &str = self.reason.into();
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(self.parent_id))
+            .add_cell(comfy_table::Cell::new(reason));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct OrphansTable(pub Vec<OrphanRow>);
+
+impl OrphansTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+
+    /// Parent techniques without a cached detail file, deduplicated.
+    pub fn missing_parents(&self) -> Vec<String> {
+        let mut parent_ids: Vec<String> = self
+            .0
+            .iter()
+            .filter(|orphan| orphan.reason == OrphanReason::ORPHANED)
+            .map(|orphan| orphan.parent_id.clone())
+            .collect();
+        parent_ids.dedup();
+
+        return parent_ids;
+    }
+}
+
+impl IntoIterator for OrphansTable {
+    type Item = OrphanRow;
+    type IntoIter = std::vec::IntoIter<OrphanRow>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.0.into_iter();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for OrphansTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&["ID", "Parent", "Reason"]));
+
+        for orphan in self.0 {
+            table.add_row(orphan);
+        }
+
+        return table;
+    }
+}
+
+/// Cached sub-techniques whose parent technique is not cached, or that the cached listings
+/// no longer include while still listing their parent.
+pub fn find_orphans(cache: &Cache) -> Result<OrphansTable, error::Error> {
+    let mut listed_ids: Option<Vec<String>> = None;
+
+    for domain in DOMAINS {
+        if let Some(domain_ids) = cache.listing_ids(EntityKind::TECHNIQUE, Some(domain))? {
+            listed_ids
+                .get_or_insert_with(Vec::new)
+                .extend(domain_ids.iter().map(|id| id.to_uppercase()));
+        }
+    }
+
+    let cached_ids = cache.entity_ids(EntityKind::TECHNIQUE)?;
+    let mut orphans = OrphansTable::default();
+
+    for id in &cached_ids {
+        let parent_id = match id.split_once('.') {
+            Some((parent_id, _)) => parent_id.to_string(),
+            None => continue,
+        };
+
+        let reason = if !cached_ids.contains(&parent_id) {
+            OrphanReason::ORPHANED
+        } else if listed_ids
+            .as_ref()
+            .is_some_and(|listed_ids| listed_ids.contains(&parent_id) && !listed_ids.contains(id))
+        {
+            OrphanReason::UNLISTED
+        } else {
+            continue;
+        };
+
+        orphans.0.push(OrphanRow {
+            id: id.clone(),
+            parent_id,
+            reason,
+        });
+    }
+
+    return Ok(orphans);
+}
+
+/// Remove the detail files of the orphans, returning the number of removed files.
+pub fn prune_orphans(cache: &Cache, orphans: &OrphansTable) -> Result<usize, error::Error> {
+    let mut pruned = 0;
+
+    for orphan in &orphans.0 {
+        if cache.remove_entity(EntityKind::TECHNIQUE, &orphan.id)? {
+            pruned += 1;
+        }
+    }
+
+    return Ok(pruned);
+}
+
+/// Cached listing or entity file that no longer deserializes, e.g. truncated by an
+/// interrupted write.
+#[derive(Debug, Serialize)]
+pub struct CorruptFileRow {
+    pub kind: String,
+    /// ID of the entity, `None` for a listing.
+    pub id: Option<String>,
+    /// Domain of the listing, `None` for an entity or a listing without domain.
+    pub domain: Option<String>,
+    pub path: String,
+    pub error: String,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for CorruptFileRow {
+    fn into(self) -> comfy_table::Row {
+        let file = match (self.id, self.domain) {
+            (Some(id), _) => id,
+            (None, Some(domain)) => format!("{} listing", domain),
+            (None, None) => String::from("listing"),
+        };
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.kind))
+            .add_cell(comfy_table::Cell::new(file))
+            .add_cell(comfy_table::Cell::new(self.error));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CorruptFilesTable(pub Vec<CorruptFileRow>);
+
+impl CorruptFilesTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for CorruptFilesTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&["Type", "File", "Error"]));
+
+        for corrupt_file in self.0 {
+            table.add_row(corrupt_file);
+        }
+
+        return table;
+    }
+}
+
+fn load_cached_entity(cache: &Cache, kind: EntityKind, id: &str) -> Result<(), error::Error> {
+    return match kind {
+        EntityKind::TACTIC => cache.load_entity::<Tactic>(kind, id).map(|_| ()),
+        EntityKind::TECHNIQUE => cache.load_entity::<Technique>(kind, id).map(|_| ()),
+        EntityKind::MITIGATION => cache.load_entity::<Mitigation>(kind, id).map(|_| ()),
+        EntityKind::SOFTWARE => cache.load_entity::<Software>(kind, id).map(|_| ()),
+        EntityKind::GROUP => cache.load_entity::<Group>(kind, id).map(|_| ()),
+        EntityKind::DATASOURCE => cache.load_entity::<DataSource>(kind, id).map(|_| ()),
+    };
+}
+
+/// Cached listings and entities that fail to load.
+pub fn find_corrupt_files(cache: &Cache) -> Result<CorruptFilesTable, error::Error> {
+    let mut corrupt_files = CorruptFilesTable::default();
+
+    for kind in EntityKind::ALL {
+        let kind_name: &str = kind.into();
+        let domains: Vec<Option<&str>> = if kind.has_domain() {
+            DOMAINS.iter().map(|domain| Some(*domain)).collect()
+        } else {
+            vec![None]
+        };
+
+        for domain in domains {
+            if let Err(err) = cache.listing_ids(kind, domain) {
+                corrupt_files.0.push(CorruptFileRow {
+                    kind: kind_name.to_string(),
+                    id: None,
+                    domain: domain.map(String::from),
+                    path: cache.listing_path(kind, domain).display().to_string(),
+                    error: err.to_string(),
+                });
+            }
+        }
+
+        for id in cache.entity_ids(kind)? {
+            if let Err(err) = load_cached_entity(cache, kind, &id) {
+                corrupt_files.0.push(CorruptFileRow {
+                    kind: kind_name.to_string(),
+                    path: cache.entity_path(kind, &id).display().to_string(),
+                    id: Some(id),
+                    domain: None,
+                    error: err.to_string(),
+                });
+            }
+        }
+    }
+
+    return Ok(corrupt_files);
+}
+
+/// Re-fetch the corrupt listings and entities, reporting every re-fetched entity through
+/// `on_entity`. The failed listings are recorded in the summary as well.
+pub fn repair_corrupt_files(
+    cache: &Cache,
+    corrupt_files: &CorruptFilesTable,
+    web_client: &impl WebFetch,
+    mut on_entity: impl FnMut(&str, &Result<(), error::Error>),
+) -> Result<SyncSummary, error::Error> {
+    let mut summary = SyncSummary::default();
+
+    for kind in EntityKind::ALL {
+        let kind_name: &str = kind.into();
+        let kind_files = corrupt_files
+            .0
+            .iter()
+            .filter(|corrupt_file| EntityKind::from_str(&corrupt_file.kind).ok() == Some(kind));
+        let mut entity_ids = Vec::new();
+
+        for corrupt_file in kind_files {
+            match &corrupt_file.id {
+                Some(id) => entity_ids.push(id.clone()),
+                None => {
+                    let domain = corrupt_file.domain.as_deref();
+
+                    if let Err(err) = sync::sync_listing(kind, domain, web_client, cache) {
+                        summary.failed.push((
+                            format!("{} listing ({})", kind_name, domain.unwrap_or("index")),
+                            err,
+                        ));
+                    }
+                }
+            }
+        }
+
+        let kind_summary =
+            sync::sync_entities(kind, &entity_ids, web_client, cache, &mut on_entity);
+        summary.synced.extend(kind_summary.synced);
+        summary.failed.extend(kind_summary.failed);
+        summary.throttled += kind_summary.throttled;
+    }
+
+    return Ok(summary);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attack::techniques::{SubTechniqueRow, TechniqueRow, TechniquesTable},
+        fakers::FakeHttpReqwest,
+    };
+
+    fn test_cache(name: &str) -> Result<Cache, error::Error> {
+        let cache = crate::attack::cache::test_cache(&format!("consistency_{}", name));
+
+        for id in ["T1055", "T1055.001", "T1055.999", "T1134.004"] {
+            cache.save_entity(
+                EntityKind::TECHNIQUE,
+                id,
+                &Technique {
+                    id: String::from(id),
+                    ..Default::default()
+                },
+            )?;
+        }
+
+        return Ok(cache);
+    }
+
+    fn listing() -> TechniquesTable {
+        return TechniquesTable(vec![TechniqueRow {
+            id: String::from("T1055"),
+            sub_techniques: Some(vec![SubTechniqueRow {
+                id: String::from(".001"),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }]);
+    }
+
+    #[test]
+    fn test_find_orphans() -> Result<(), error::Error> {
+        let cache = test_cache("find")?;
+
+        let orphans = find_orphans(&cache)?;
+        assert_eq!(orphans.0.len(), 1);
+        assert_eq!(orphans.missing_parents(), vec!["T1134"]);
+
+        cache.save_listing(EntityKind::TECHNIQUE, Some("enterprise"), &listing())?;
+
+        let orphans = find_orphans(&cache)?;
+        assert_eq!(
+            orphans
+                .0
+                .iter()
+                .map(|orphan| (orphan.id.as_str(), orphan.reason))
+                .collect::<Vec<(&str, OrphanReason)>>(),
+            vec![
+                ("T1055.999", OrphanReason::UNLISTED),
+                ("T1134.004", OrphanReason::ORPHANED)
+            ]
+        );
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_corrupt_files() -> Result<(), error::Error> {
+        let cache = test_cache("corrupt")?;
+        cache.save_listing(EntityKind::TECHNIQUE, Some("enterprise"), &listing())?;
+
+        assert!(find_corrupt_files(&cache)?.is_empty());
+
+        std::fs::write(
+            cache.entity_path(EntityKind::TECHNIQUE, "T1055"),
+            "{\"id\": \"T1055\", \"na",
+        )?;
+
+        let corrupt_files = find_corrupt_files(&cache)?;
+        assert_eq!(corrupt_files.0.len(), 1);
+        assert_eq!(corrupt_files.0[0].id.as_deref(), Some("T1055"));
+
+        let fake_reqwest = FakeHttpReqwest::default().set_success_response(
+            include_str!("html/attck/techniques/enterprise_deploy_container.html").to_string(),
+        );
+        let summary = repair_corrupt_files(&cache, &corrupt_files, &fake_reqwest, |_, _| {})?;
+
+        assert_eq!(summary.synced, vec!["T1055"]);
+        assert!(find_corrupt_files(&cache)?.is_empty());
+
+        std::fs::write(
+            cache.listing_path(EntityKind::TECHNIQUE, Some("enterprise")),
+            "",
+        )?;
+
+        let corrupt_files = find_corrupt_files(&cache)?;
+        assert_eq!(corrupt_files.0.len(), 1);
+        assert_eq!(corrupt_files.0[0].id, None);
+        assert_eq!(corrupt_files.0[0].domain.as_deref(), Some("enterprise"));
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_orphans() -> Result<(), error::Error> {
+        let cache = test_cache("prune")?;
+        cache.save_listing(EntityKind::TECHNIQUE, Some("enterprise"), &listing())?;
+
+        assert_eq!(prune_orphans(&cache, &find_orphans(&cache)?)?, 2);
+        assert!(find_orphans(&cache)?.is_empty());
+        assert_eq!(
+            cache.entity_ids(EntityKind::TECHNIQUE)?,
+            vec!["T1055", "T1055.001"]
+        );
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+}
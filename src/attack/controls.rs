@@ -0,0 +1,231 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::{
+    annotations::{Annotations, ImplementationStatus},
+    mitigations::Mitigation,
+};
+
+#[derive(Debug, Default, Serialize)]
+pub struct TechniqueExposureRow {
+    pub id: String,
+    pub name: String,
+    pub mitigations: usize,
+    pub implemented: usize,
+    pub planned: usize,
+    pub not_applicable: usize,
+    pub unimplemented: Vec<String>,
+}
+
+impl TechniqueExposureRow {
+    fn count(&mut self, mitigation_id: &str, status: Option<ImplementationStatus>) {
+        self.mitigations += 1;
+
+        match status {
+            Some(ImplementationStatus::IMPLEMENTED) => self.implemented += 1,
+            Some(ImplementationStatus::NA) => self.not_applicable += 1,
+            Some(ImplementationStatus::PLANNED) => {
+                self.planned += 1;
+                self.unimplemented.push(mitigation_id.to_string());
+            }
+            None => self.unimplemented.push(mitigation_id.to_string()),
+        }
+    }
+
+    /// Share of the applicable mitigations that are not implemented yet (0.0 - 1.0).
+    /// Techniques without any applicable mitigation are considered fully exposed.
+    pub fn exposure(&self) -> f64 {
+        let applicable = self.mitigations - self.not_applicable;
+
+        if applicable == 0 {
+            return 1.0;
+        }
+
+        return self.unimplemented.len() as f64 / applicable as f64;
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for TechniqueExposureRow {
+    fn into(self) -> comfy_table::Row {
+        let exposure = format!("{:.0}%", self.exposure() * 100.0);
+        let implemented = format!(
+            "{}/{}",
+            self.implemented,
+            self.mitigations - self.not_applicable
+        );
+
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(exposure))
+            .add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(self.name))
+            .add_cell(comfy_table::Cell::new(implemented))
+            .add_cell(comfy_table::Cell::new(self.planned))
+            .add_cell(comfy_table::Cell::new(self.unimplemented.join(", ")));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ControlsReport(pub Vec<TechniqueExposureRow>);
+
+impl ControlsReport {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.0.len();
+    }
+}
+
+impl IntoIterator for ControlsReport {
+    type Item = TechniqueExposureRow;
+    type IntoIter = std::vec::IntoIter<TechniqueExposureRow>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.0.into_iter();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for ControlsReport {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table
+            .set_header(crate::theme::header(&[
+                "Exposure",
+                "ID",
+                "Name",
+                "Implemented",
+                "Planned",
+                "Unimplemented Mitigations",
+            ]))
+            .add_rows(
+                self.into_iter()
+                    .map(|row| row.into())
+                    .collect::<Vec<comfy_table::Row>>(),
+            );
+
+        return table;
+    }
+}
+
+/// Summarize the exposure of every technique addressed by `mitigations`, weighting each
+/// technique by the mitigations that are not implemented according to `annotations`.
+/// The report is sorted from the most to the least exposed technique.
+pub fn build_controls_report(
+    mitigations: &[Mitigation],
+    annotations: &Annotations,
+) -> ControlsReport {
+    let mut techniques: BTreeMap<String, TechniqueExposureRow> = BTreeMap::new();
+
+    for mitigation in mitigations {
+        let status = annotations.mitigation_status(&mitigation.id);
+
+        if let Some(addressed_techniques) = &mitigation.addressed_techniques {
+            for technique in &addressed_techniques.0 {
+                techniques
+                    .entry(technique.id.clone())
+                    .or_insert_with(|| TechniqueExposureRow {
+                        id: technique.id.clone(),
+                        name: technique.name.clone(),
+                        ..Default::default()
+                    })
+                    .count(&mitigation.id, status);
+
+                if let Some(sub_techniques) = &technique.sub_techniques {
+                    for sub_technique in sub_techniques {
+                        let id = format!("{}{}", technique.id, sub_technique.id);
+
+                        techniques
+                            .entry(id.clone())
+                            .or_insert_with(|| TechniqueExposureRow {
+                                id,
+                                name: format!("{}: {}", technique.name, sub_technique.name),
+                                ..Default::default()
+                            })
+                            .count(&mitigation.id, status);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut rows: Vec<TechniqueExposureRow> = techniques.into_values().collect();
+    rows.sort_by(|a, b| {
+        b.exposure()
+            .total_cmp(&a.exposure())
+            .then(b.unimplemented.len().cmp(&a.unimplemented.len()))
+            .then(a.id.cmp(&b.id))
+    });
+
+    return ControlsReport(rows);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{attack::mitigations::fetch_mitigation, error, fakers::FakeHttpReqwest};
+
+    const TEST_MITIGATION_ID: &'static str = "M1052";
+    const TEST_TECHNIQUE_ID: &'static str = "T1548.002";
+
+    fn fetch_test_mitigation() -> Result<Mitigation, error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default().set_success_response(
+            include_str!("html/attck/mitigations/user_account_control.html").to_string(),
+        );
+
+        return fetch_mitigation(TEST_MITIGATION_ID, &fake_reqwest);
+    }
+
+    #[test]
+    fn test_unannotated_mitigations_are_fully_exposed() -> Result<(), error::Error> {
+        let report = build_controls_report(&[fetch_test_mitigation()?], &Annotations::default());
+
+        assert_eq!(report.is_empty(), false, "report should not be empty");
+
+        for technique in report {
+            assert_eq!(technique.exposure(), 1.0);
+            assert_eq!(technique.unimplemented, vec![TEST_MITIGATION_ID]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_implemented_mitigation_removes_exposure() -> Result<(), error::Error> {
+        let mut annotations = Annotations::default();
+        annotations.set_mitigation_status(TEST_MITIGATION_ID, ImplementationStatus::IMPLEMENTED);
+
+        let report = build_controls_report(&[fetch_test_mitigation()?], &annotations);
+        let technique = report
+            .into_iter()
+            .find(|technique| technique.id == TEST_TECHNIQUE_ID)
+            .unwrap();
+
+        assert_eq!(technique.exposure(), 0.0);
+        assert_eq!(technique.implemented, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_planned_mitigation_is_still_exposed() -> Result<(), error::Error> {
+        let mut annotations = Annotations::default();
+        annotations.set_mitigation_status(TEST_MITIGATION_ID, ImplementationStatus::PLANNED);
+
+        let report = build_controls_report(&[fetch_test_mitigation()?], &annotations);
+        let technique = report
+            .into_iter()
+            .find(|technique| technique.id == TEST_TECHNIQUE_ID)
+            .unwrap();
+
+        assert_eq!(technique.exposure(), 1.0);
+        assert_eq!(technique.planned, 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,345 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::error;
+
+use super::techniques::Technique;
+
+/// Data sources and components collected by the organization.
+///
+/// One entry per line, `#` starting a comment. An entry is either a data source (`DS0009` or
+/// `Process`, covering all of its components) or a single component (`Process: Process
+/// Creation`, or only `Process Creation`).
+#[derive(Debug, Default)]
+pub struct DataSourceInventory(pub Vec<String>);
+
+impl DataSourceInventory {
+    pub fn load(path: &Path) -> Result<Self, error::Error> {
+        return Ok(Self::parse(&std::fs::read_to_string(path)?));
+    }
+
+    pub fn parse(inventory: &str) -> Self {
+        return Self(
+            inventory
+                .lines()
+                .map(|line| line.split('#').next().unwrap_or_default().trim())
+                .filter(|entry| !entry.is_empty())
+                .map(|entry| entry.to_lowercase())
+                .collect(),
+        );
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+
+    /// Whether the component of the data source is collected.
+    pub fn collects(&self, data_source_id: &str, data_source: &str, component: &str) -> bool {
+        let data_source_id = data_source_id.to_lowercase();
+        let data_source = data_source.to_lowercase();
+        let component = component.to_lowercase();
+
+        return self.0.iter().any(|entry| {
+            *entry == data_source_id
+                || *entry == data_source
+                || *entry == component
+                || entry.split_once(':').is_some_and(|(source, comp)| {
+                    source.trim() == data_source && comp.trim() == component
+                })
+        });
+    }
+}
+
+/// Technique without any of its detecting data components collected.
+#[derive(Debug, Default, Serialize)]
+pub struct CoverageGapRow {
+    pub id: String,
+    pub name: String,
+    /// `Data Source: Data Component` entries that would detect the technique.
+    pub missing: Vec<String>,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for CoverageGapRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(self.name))
+            .add_cell(comfy_table::Cell::new(self.missing.join("\n")));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CoverageReport {
+    /// Techniques with at least one collected data component.
+    pub covered: Vec<String>,
+    /// Techniques without detection data.
+    pub undetectable: Vec<String>,
+    pub gaps: Vec<CoverageGapRow>,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for CoverageReport {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&[
+            "ID",
+            "Name",
+            "Missing Data Components",
+        ]));
+
+        for gap in self.gaps {
+            table.add_row(gap);
+        }
+
+        return table;
+    }
+}
+
+/// Split the techniques by whether their detections overlap with the inventory.
+pub fn build_coverage_report(
+    techniques: &[Technique],
+    inventory: &DataSourceInventory,
+) -> CoverageReport {
+    let mut report = CoverageReport::default();
+
+    for technique in techniques {
+        let detections = match &technique.detections {
+            Some(detections) if !detections.0.is_empty() => detections,
+            _ => {
+                report.undetectable.push(technique.id.clone());
+                continue;
+            }
+        };
+
+        // Components of the same data source leave the ID and data source columns empty.
+        let (mut data_source_id, mut data_source) = ("", "");
+        let mut missing = Vec::new();
+        let mut covered = false;
+
+        for detection in &detections.0 {
+            if !detection.id.is_empty() {
+                data_source_id = &detection.id;
+                data_source = &detection.data_source;
+            }
+
+            if inventory.collects(data_source_id, data_source, &detection.data_comp) {
+                covered = true;
+                break;
+            }
+
+            missing.push(format!("{}: {}", data_source, detection.data_comp));
+        }
+
+        if covered {
+            report.covered.push(technique.id.clone());
+        } else {
+            report.gaps.push(CoverageGapRow {
+                id: technique.id.clone(),
+                name: technique.name.clone(),
+                missing,
+            });
+        }
+    }
+
+    report.gaps.sort_by(|a, b| a.id.cmp(&b.id));
+
+    return report;
+}
+
+/// Data component detecting some of the assessed techniques.
+#[derive(Debug, Default, Serialize)]
+pub struct TelemetryRow {
+    pub data_source_id: String,
+    pub data_source: String,
+    pub component: String,
+    /// IDs of the assessed techniques it detects.
+    pub techniques: Vec<String>,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for TelemetryRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.data_source_id))
+            .add_cell(comfy_table::Cell::new(self.data_source))
+            .add_cell(comfy_table::Cell::new(self.component))
+            .add_cell(comfy_table::Cell::new(self.techniques.len()))
+            .add_cell(comfy_table::Cell::new(self.techniques.join(", ")));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct TelemetryTable(pub Vec<TelemetryRow>);
+
+impl TelemetryTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for TelemetryTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&[
+            "ID",
+            "Data Source",
+            "Data Component",
+            "Detected",
+            "Techniques",
+        ]));
+
+        for component in self.0 {
+            table.add_row(component);
+        }
+
+        return table;
+    }
+}
+
+/// Data components to collect to detect a list of techniques, the inverse of the coverage
+/// report.
+#[derive(Debug, Default, Serialize)]
+pub struct TelemetryReport {
+    /// Components detecting the most techniques first.
+    pub components: TelemetryTable,
+    /// Techniques without detection data.
+    pub undetectable: Vec<String>,
+}
+
+/// Aggregate the data components of the technique detections.
+pub fn build_telemetry_report(techniques: &[Technique]) -> TelemetryReport {
+    let mut report = TelemetryReport::default();
+
+    for technique in techniques {
+        let detections = match &technique.detections {
+            Some(detections) if !detections.0.is_empty() => detections,
+            _ => {
+                report.undetectable.push(technique.id.clone());
+                continue;
+            }
+        };
+
+        // Components of the same data source leave the ID and data source columns empty.
+        let (mut data_source_id, mut data_source) = ("", "");
+
+        for detection in &detections.0 {
+            if !detection.id.is_empty() {
+                data_source_id = &detection.id;
+                data_source = &detection.data_source;
+            }
+
+            let component = report.components.0.iter_mut().find(|component| {
+                component.data_source_id == data_source_id
+                    && component.component == detection.data_comp
+            });
+
+            match component {
+                Some(component) if component.techniques.contains(&technique.id) => {}
+                Some(component) => component.techniques.push(technique.id.clone()),
+                None => report.components.0.push(TelemetryRow {
+                    data_source_id: data_source_id.to_string(),
+                    data_source: data_source.to_string(),
+                    component: detection.data_comp.clone(),
+                    techniques: vec![technique.id.clone()],
+                }),
+            }
+        }
+    }
+
+    report.components.0.sort_by(|a, b| {
+        b.techniques
+            .len()
+            .cmp(&a.techniques.len())
+            .then(a.data_source_id.cmp(&b.data_source_id))
+            .then(a.component.cmp(&b.component))
+    });
+
+    return report;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{attack::techniques::fetch_technique, fakers::FakeHttpReqwest};
+
+    fn deploy_container() -> Result<Technique, error::Error> {
+        return fetch_technique(
+            "T1610",
+            &FakeHttpReqwest::default().set_success_response(
+                include_str!("html/attck/techniques/enterprise_deploy_container.html").to_string(),
+            ),
+        );
+    }
+
+    #[test]
+    fn test_inventory_parse() {
+        let inventory = DataSourceInventory::parse(
+            "# Collected telemetry\nDS0009\n\nContainer: Container Start  # kubelet\nFile Creation\n",
+        );
+
+        assert_eq!(inventory.0.len(), 3);
+        assert!(inventory.collects("DS0009", "Process", "OS API Execution"));
+        assert!(inventory.collects("DS0032", "Container", "Container Start"));
+        assert!(!inventory.collects("DS0032", "Container", "Container Creation"));
+        assert!(inventory.collects("DS0022", "File", "File Creation"));
+    }
+
+    #[test]
+    fn test_coverage_gaps() -> Result<(), error::Error> {
+        let techniques = vec![
+            deploy_container()?,
+            Technique {
+                id: String::from("T1001"),
+                ..Default::default()
+            },
+        ];
+
+        let report = build_coverage_report(
+            &techniques,
+            &DataSourceInventory::parse("Pod: Pod Modification"),
+        );
+        assert_eq!(report.covered, vec!["T1610"]);
+        assert_eq!(report.undetectable, vec!["T1001"]);
+        assert!(report.gaps.is_empty());
+
+        let report = build_coverage_report(&techniques, &DataSourceInventory::parse("Process"));
+        assert_eq!(report.gaps.len(), 1);
+        assert!(report.gaps[0]
+            .missing
+            .contains(&String::from("Container: Container Start")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_telemetry_report() -> Result<(), error::Error> {
+        let technique = deploy_container()?;
+        let components = technique.detections.as_ref().unwrap().0.len();
+        let techniques = vec![
+            technique,
+            Technique {
+                id: String::from("T1001"),
+                ..Default::default()
+            },
+        ];
+
+        let report = build_telemetry_report(&techniques);
+        assert_eq!(report.undetectable, vec!["T1001"]);
+        assert_eq!(report.components.0.len(), components);
+        assert!(report.components.0.iter().any(|component| {
+            component.data_source_id == "DS0032"
+                && component.data_source == "Container"
+                && component.component == "Container Start"
+                && component.techniques == vec!["T1610"]
+        }));
+
+        Ok(())
+    }
+}
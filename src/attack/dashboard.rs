@@ -0,0 +1,349 @@
+use serde::Serialize;
+
+use crate::error;
+
+use super::{
+    annotations::{Annotations, ImplementationStatus},
+    cache::{Cache, EntityKind},
+    techniques::Technique,
+};
+
+/// Number of recently modified techniques shown.
+pub const RECENTLY_MODIFIED: usize = 5;
+
+/// Listed and cached entities of a type, with the last sync.
+#[derive(Debug, Default, Serialize)]
+pub struct EntityCountRow {
+    pub kind: String,
+    /// Entities of the cached listing, `None` when it has not been synced.
+    pub listed: Option<usize>,
+    pub cached: usize,
+    pub synced_at: Option<String>,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for EntityCountRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.kind))
+            .add_cell(comfy_table::Cell::new(
+                self.listed
+                    .map_or_else(|| String::from("-"), |listed| listed.to_string()),
+            ))
+            .add_cell(comfy_table::Cell::new(self.cached))
+            .add_cell(comfy_table::Cell::new(
+                self.synced_at.unwrap_or_else(|| String::from("never")),
+            ));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct EntityCountsTable(pub Vec<EntityCountRow>);
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for EntityCountsTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&[
+            "Type",
+            "Listed",
+            "Cached",
+            "Last sync",
+        ]));
+
+        for count in self.0 {
+            table.add_row(count);
+        }
+
+        return table;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ModifiedTechniqueRow {
+    pub id: String,
+    pub name: String,
+    pub modified: String,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for ModifiedTechniqueRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(self.name))
+            .add_cell(comfy_table::Cell::new(self.modified));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ModifiedTechniquesTable(pub Vec<ModifiedTechniqueRow>);
+
+impl ModifiedTechniquesTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for ModifiedTechniquesTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&["ID", "Name", "Modified"]));
+
+        for technique in self.0 {
+            table.add_row(technique);
+        }
+
+        return table;
+    }
+}
+
+/// Cached techniques with at least one mitigation annotated as implemented.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct MitigationCoverage {
+    pub assessed_mitigations: usize,
+    pub covered_techniques: usize,
+    pub techniques: usize,
+}
+
+impl MitigationCoverage {
+    pub fn percentage(&self) -> f64 {
+        if self.techniques == 0 {
+            return 0.0;
+        }
+
+        return self.covered_techniques as f64 * 100.0 / self.techniques as f64;
+    }
+}
+
+/// At-a-glance state of the local knowledge base for a domain.
+#[derive(Debug, Default, Serialize)]
+pub struct Dashboard {
+    pub domain: String,
+    pub attack_version: Option<String>,
+    pub counts: EntityCountsTable,
+    pub recently_modified: ModifiedTechniquesTable,
+    /// `None` when no mitigation has been annotated yet.
+    pub coverage: Option<MitigationCoverage>,
+}
+
+impl Dashboard {
+    pub fn build(
+        cache: &Cache,
+        annotations: &Annotations,
+        domain: &str,
+    ) -> Result<Self, error::Error> {
+        let mut counts = EntityCountsTable::default();
+
+        for kind in EntityKind::ALL {
+            let listed_ids = domain_listing_ids(cache, kind, domain)?;
+            let cached_ids = cache.entity_ids(kind)?;
+            let kind_name: &str = kind.into();
+
+            counts.0.push(EntityCountRow {
+                kind: kind_name.to_string(),
+                listed: listed_ids.as_ref().map(|listed_ids| listed_ids.len()),
+                cached: match (&listed_ids, kind.has_domain()) {
+                    (Some(listed_ids), true) => cached_ids
+                        .iter()
+                        .filter(|id| listed_ids.contains(id))
+                        .count(),
+                    _ => cached_ids.len(),
+                },
+                synced_at: cache.synced_at(kind)?.map(|synced_at| {
+                    chrono::DateTime::<chrono::Utc>::from(synced_at)
+                        .format("%Y-%m-%d %H:%M UTC")
+                        .to_string()
+                }),
+            });
+        }
+
+        let techniques = domain_techniques(cache, domain)?;
+
+        let mut modified_techniques: Vec<&Technique> = techniques
+            .iter()
+            .filter(|technique| technique.modified.is_some())
+            .collect();
+        modified_techniques.sort_by(|a, b| b.modified.cmp(&a.modified).then(a.id.cmp(&b.id)));
+        let recently_modified = ModifiedTechniquesTable(
+            modified_techniques
+                .iter()
+                .take(RECENTLY_MODIFIED)
+                .map(|technique| ModifiedTechniqueRow {
+                    id: technique.id.clone(),
+                    name: technique.name.clone(),
+                    modified: technique.modified.clone().unwrap_or_default(),
+                })
+                .collect(),
+        );
+
+        let coverage = if annotations.mitigations.is_empty() {
+            None
+        } else {
+            Some(MitigationCoverage {
+                assessed_mitigations: annotations.mitigations.len(),
+                covered_techniques: techniques
+                    .iter()
+                    .filter(|technique| is_covered(annotations, technique))
+                    .count(),
+                techniques: techniques.len(),
+            })
+        };
+
+        return Ok(Self {
+            domain: domain.to_string(),
+            attack_version: cache.attack_version()?,
+            counts,
+            recently_modified,
+            coverage,
+        });
+    }
+}
+
+/// Upper-cased IDs of the cached listing of the domain (of every domain for the types
+/// without one).
+fn domain_listing_ids(
+    cache: &Cache,
+    kind: EntityKind,
+    domain: &str,
+) -> Result<Option<Vec<String>>, error::Error> {
+    let listing_domain = if kind.has_domain() {
+        Some(domain)
+    } else {
+        None
+    };
+
+    return Ok(cache
+        .listing_ids(kind, listing_domain)?
+        .map(|ids| ids.iter().map(|id| id.to_uppercase()).collect()));
+}
+
+/// Cached techniques of the domain: those of its listing, or tagged with it when the
+/// listing has not been synced.
+fn domain_techniques(cache: &Cache, domain: &str) -> Result<Vec<Technique>, error::Error> {
+    let listed_ids = domain_listing_ids(cache, EntityKind::TECHNIQUE, domain)?;
+    let techniques: Vec<Technique> = cache.load_entities(EntityKind::TECHNIQUE)?;
+
+    return Ok(techniques
+        .into_iter()
+        .filter(|technique| match &listed_ids {
+            Some(listed_ids) => listed_ids.contains(&technique.id.to_uppercase()),
+            None => technique.domain.as_deref() == Some(domain),
+        })
+        .collect());
+}
+
+fn is_covered(annotations: &Annotations, technique: &Technique) -> bool {
+    return technique.mitigations.as_ref().is_some_and(|mitigations| {
+        mitigations.0.iter().any(|mitigation| {
+            annotations.mitigation_status(&mitigation.id) == Some(ImplementationStatus::IMPLEMENTED)
+        })
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack::{
+        mitigations::{MitigationRow, MitigationTable},
+        techniques::{TechniqueRow, TechniquesTable},
+    };
+
+    fn technique(id: &str, modified: Option<&str>, mitigation_id: &str) -> Technique {
+        return Technique {
+            id: String::from(id),
+            name: format!("Technique {}", id),
+            modified: modified.map(String::from),
+            mitigations: Some(MitigationTable(vec![MitigationRow {
+                id: String::from(mitigation_id),
+                ..Default::default()
+            }])),
+            ..Default::default()
+        };
+    }
+
+    fn test_cache() -> Result<Cache, error::Error> {
+        let cache = crate::attack::cache::test_cache("dashboard");
+
+        cache.save_listing(
+            EntityKind::TECHNIQUE,
+            Some("enterprise"),
+            &TechniquesTable(
+                ["T1190", "T1610", "T1055"]
+                    .iter()
+                    .map(|id| TechniqueRow {
+                        id: id.to_string(),
+                        ..Default::default()
+                    })
+                    .collect(),
+            ),
+        )?;
+
+        for technique in [
+            technique("T1190", Some("2023-10-17"), "M1051"),
+            technique("T1610", Some("2024-04-12"), "M1047"),
+            technique("T1055", None, "M1040"),
+            // Listed under another domain only.
+            technique("T0866", Some("2024-05-01"), "M1051"),
+        ] {
+            cache.save_entity(EntityKind::TECHNIQUE, &technique.id, &technique)?;
+        }
+
+        return Ok(cache);
+    }
+
+    #[test]
+    fn test_dashboard() -> Result<(), error::Error> {
+        let cache = test_cache()?;
+        let mut annotations = Annotations::default();
+
+        let dashboard = Dashboard::build(&cache, &annotations, "enterprise")?;
+
+        let technique_count = dashboard
+            .counts
+            .0
+            .iter()
+            .find(|count| count.kind == "techniques")
+            .unwrap();
+        assert_eq!(
+            (technique_count.listed, technique_count.cached),
+            (Some(3), 3)
+        );
+        assert!(technique_count.synced_at.is_some());
+        assert_eq!(
+            dashboard
+                .recently_modified
+                .0
+                .iter()
+                .map(|technique| technique.id.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["T1610", "T1190"]
+        );
+        assert_eq!(dashboard.coverage, None);
+
+        annotations.set_mitigation_status("M1051", ImplementationStatus::IMPLEMENTED);
+        annotations.set_mitigation_status("M1047", ImplementationStatus::PLANNED);
+
+        let coverage = Dashboard::build(&cache, &annotations, "enterprise")?
+            .coverage
+            .unwrap();
+        assert_eq!(
+            coverage,
+            MitigationCoverage {
+                assessed_mitigations: 2,
+                covered_techniques: 1,
+                techniques: 3,
+            }
+        );
+        assert_eq!(format!("{:.1}", coverage.percentage()), "33.3");
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+}
@@ -1,16 +1,18 @@
 use super::{
-    scrape_entity_description, scrape_entity_name, scrape_table, scrape_tables, Row, Table,
+    scrape_card_list, scrape_entity_card, scrape_entity_description, scrape_entity_name,
+    scrape_entity_references, scrape_table, scrape_tables, Reference, Row, Table,
 };
-use crate::{error, remove_ext_link_ref, WebFetch};
+use crate::{error, error::FetchContext, remove_ext_link_ref, WebFetch};
 use select::{
     document::Document,
     predicate::{self, Predicate},
 };
 use std::{cell::RefCell, rc::Rc};
+use serde::{Deserialize, Serialize};
 
 const ATTCK_DATA_SOURCES_URL: &'static str = "https://attack.mitre.org/datasources/";
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct DataSourceRow {
     pub id: String,
     pub name: String,
@@ -46,6 +48,7 @@ impl From<Row> for DataSourceRow {
     }
 }
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Row> for DataSourceRow {
     fn into(self) -> comfy_table::Row {
         let mut row = comfy_table::Row::new();
@@ -57,29 +60,15 @@ impl Into<comfy_table::Row> for DataSourceRow {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct DataSourcesTable(pub Vec<DataSourceRow>);
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Table> for DataSourcesTable {
     fn into(self) -> comfy_table::Table {
-        let mut table = comfy_table::Table::new();
+        let mut table = crate::theme::new_table();
         table
-            .load_preset(comfy_table::presets::UTF8_FULL)
-            .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
-            .set_header(vec![
-                comfy_table::Cell::new("ID")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Name")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Description")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-            ])
+            .set_header(crate::theme::header(&["ID", "Name", "Description"]))
             .add_rows(
                 self.into_iter()
                     .map(|data_source| data_source.into())
@@ -98,10 +87,25 @@ impl DataSourcesTable {
     pub fn len(&self) -> usize {
         return self.0.len();
     }
+
+    pub fn ids(&self) -> Vec<String> {
+        return self.0.iter().map(|row| row.id.clone()).collect();
+    }
+
+    /// (ID, name) of the listed entities.
+    pub fn names(&self) -> Vec<(String, String)> {
+        return self
+            .0
+            .iter()
+            .map(|row| (row.id.clone(), row.name.clone()))
+            .collect();
+    }
 }
 
 pub fn fetch_data_sources(web_client: &impl WebFetch) -> Result<DataSourcesTable, error::Error> {
-    let fetched_response = web_client.fetch(ATTCK_DATA_SOURCES_URL)?;
+    let fetched_response = web_client
+        .fetch(ATTCK_DATA_SOURCES_URL)
+        .listing_context("data sources", ATTCK_DATA_SOURCES_URL)?;
     let document = Document::from(fetched_response.as_str());
 
     return Ok(scrape_tables(&document)
@@ -124,7 +128,7 @@ impl From<Table> for DataSourcesTable {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct SubDetectionRow {
     pub id: String,
     pub name: String,
@@ -144,14 +148,14 @@ impl From<Row> for SubDetectionRow {
         }
 
         if let Some(desc) = row.get_col(4) {
-            sub_detection.detects = remove_ext_link_ref(&desc);
+            sub_detection.detects = remove_ext_link_ref(desc);
         }
 
         return sub_detection;
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct DetectionRow {
     pub domain: String,
     pub id: String,
@@ -198,14 +202,14 @@ impl From<Row> for DetectionRow {
         }
 
         if let Some(desc) = row.get_col(inx) {
-            detection.detects = remove_ext_link_ref(&desc);
+            detection.detects = remove_ext_link_ref(desc);
         }
 
         return detection;
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct DetectionsTable(pub Vec<DetectionRow>);
 
 impl DetectionsTable {
@@ -223,30 +227,11 @@ impl IntoIterator for DetectionsTable {
     }
 }
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Table> for DetectionsTable {
     fn into(self) -> comfy_table::Table {
-        let mut table = comfy_table::Table::new();
-        table
-            .load_preset(comfy_table::presets::UTF8_FULL)
-            .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
-            .set_header(vec![
-                comfy_table::Cell::new("Domain")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("ID")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Name")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Detects")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-            ]);
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&["Domain", "ID", "Name", "Detects"]));
 
         for detection in self {
             table.add_row(vec![
@@ -304,43 +289,61 @@ impl From<Table> for DetectionsTable {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct DataComponent {
     pub name: String,
     pub description: String,
     pub detections: DetectionsTable,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct DataSource {
     pub id: String,
     pub name: String,
+    /// ATT&CK page of the data source.
+    #[serde(default)]
+    pub url: String,
     pub description: String,
     pub components: Vec<DataComponent>,
+    #[serde(default)]
+    pub references: Vec<Reference>,
+    #[serde(default)]
+    pub platforms: Vec<String>,
+    /// Where the data is collected (Host, Network, Cloud Control Plane, ...).
+    #[serde(default)]
+    pub collection_layers: Vec<String>,
+}
+
+/// ATT&CK page of the data source.
+pub fn data_source_url(data_source_id: &str) -> String {
+    return format!("{}{}", ATTCK_DATA_SOURCES_URL, data_source_id.to_uppercase());
 }
 
 pub fn fetch_data_source(
     data_source_id: &str,
     web_client: &impl WebFetch,
 ) -> Result<DataSource, error::Error> {
-    let url = format!(
-        "{}{}",
-        ATTCK_DATA_SOURCES_URL,
-        data_source_id.to_uppercase()
-    );
-    let fetched_response = web_client.fetch(url.as_str())?;
+    let url = data_source_url(data_source_id);
+    let fetched_response = web_client
+        .fetch(&url)
+        .entity_context(data_source_id, &url)?;
     let document = Document::from(fetched_response.as_str());
     let dt_tables = scrape_datasource_tables(&document);
+    let mut card = scrape_entity_card(&document);
 
     return Ok(DataSource {
         id: data_source_id.to_string(),
         name: scrape_entity_name(&document),
+        url: data_source_url(data_source_id),
         description: scrape_entity_description(&document),
         components: get_data_components(dt_tables),
+        references: scrape_entity_references(&document),
+        platforms: scrape_card_list(&mut card, "Platforms", ','),
+        collection_layers: scrape_card_list(&mut card, "Collection Layers", ','),
     });
 }
 
-fn scrape_datasource_tables<'a>(document: &'a Document) -> Vec<(String, String, Table)> {
+fn scrape_datasource_tables(document: &Document) -> Vec<(String, String, Table)> {
     let mut dt_tables: Vec<(String, String, Table)> = Vec::new();
     let name = Rc::new(RefCell::new(String::new()));
     let description = Rc::new(RefCell::new(String::new()));
@@ -423,6 +426,11 @@ mod tests {
         let retrieved_data_comp = fetch_data_source(TEST_DATA_SOURCE, &fake_reqwest)?;
 
         assert_eq!(retrieved_data_comp.components.len(), TEST_DATA_COMPONENTS);
+        assert_eq!(retrieved_data_comp.platforms, vec!["Azure AD", "Windows"]);
+        assert_eq!(
+            retrieved_data_comp.collection_layers,
+            vec!["Cloud Control Plane", "Host"]
+        );
 
         Ok(())
     }
@@ -0,0 +1,286 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{error, WebFetch};
+
+use super::{
+    cache::{Cache, EntityKind, DOMAINS},
+    provenance::Provenance,
+};
+
+const DATASET_RELEASES_URL: &'static str =
+    "https://github.com/fwxs/mitre_cli/releases/download/dataset-";
+const DATASET_FILE_NAME: &'static str = "attack-dataset.json";
+
+/// Dataset compiled into the binary, regenerated with 'attack export dataset'.
+#[cfg(feature = "embedded-data")]
+const EMBEDDED_DATASET: &'static [u8] = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/data/attack-dataset.json"
+));
+
+lazy_static! {
+    static ref EMBEDDED: Option<Dataset> = load_embedded();
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DatasetListing {
+    pub kind: String,
+    pub domain: Option<String>,
+    pub listing: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DatasetEntity {
+    pub kind: String,
+    pub id: String,
+    pub entity: Value,
+}
+
+/// Already parsed ATT&CK listings and entities, installed as is into the cache so the
+/// pages do not have to be scraped one by one.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Dataset {
+    /// ATT&CK release the data was scraped from (e.g. v15.1).
+    pub release: String,
+    pub listings: Vec<DatasetListing>,
+    pub entities: Vec<DatasetEntity>,
+    /// Snapshot the dataset was exported from.
+    #[serde(default)]
+    pub provenance: Option<Provenance>,
+}
+
+impl Dataset {
+    /// Dataset holding every listing and entity of the cache.
+    pub fn from_cache(release: &str, cache: &Cache) -> Result<Self, error::Error> {
+        let mut dataset = Self {
+            release: release.to_string(),
+            ..Default::default()
+        };
+
+        for kind in EntityKind::ALL {
+            let kind_name: &str = kind.into();
+            let domains: Vec<Option<&str>> = if kind.has_domain() {
+                DOMAINS.iter().map(|domain| Some(*domain)).collect()
+            } else {
+                vec![None]
+            };
+
+            for domain in domains {
+                if let Some(listing) = cache.load_listing(kind, domain)? {
+                    dataset.listings.push(DatasetListing {
+                        kind: kind_name.to_string(),
+                        domain: domain.map(String::from),
+                        listing,
+                    });
+                }
+            }
+
+            for id in cache.entity_ids(kind)? {
+                if let Some(entity) = cache.load_entity(kind, &id)? {
+                    dataset.entities.push(DatasetEntity {
+                        kind: kind_name.to_string(),
+                        id,
+                        entity,
+                    });
+                }
+            }
+        }
+
+        return Ok(dataset);
+    }
+
+    /// Write the listings and entities into the cache, overwriting the cached ones.
+    /// Returns the number of installed entities.
+    pub fn install(&self, cache: &Cache) -> Result<usize, error::Error> {
+        for listing in &self.listings {
+            cache.save_listing(
+                EntityKind::from_str(&listing.kind)?,
+                listing.domain.as_deref(),
+                &listing.listing,
+            )?;
+        }
+
+        for entity in &self.entities {
+            cache.save_entity(
+                EntityKind::from_str(&entity.kind)?,
+                &entity.id,
+                &entity.entity,
+            )?;
+        }
+
+        if !self.release.is_empty() {
+            cache.save_attack_version(&self.release)?;
+        }
+
+        return Ok(self.entities.len());
+    }
+
+    pub fn listing(&self, kind: EntityKind, domain: Option<&str>) -> Option<&Value> {
+        let kind_name: &str = kind.into();
+
+        return self
+            .listings
+            .iter()
+            .find(|listing| listing.kind == kind_name && listing.domain.as_deref() == domain)
+            .map(|listing| &listing.listing);
+    }
+
+    pub fn entity(&self, kind: EntityKind, id: &str) -> Option<&Value> {
+        let kind_name: &str = kind.into();
+
+        return self
+            .entities
+            .iter()
+            .find(|entity| entity.kind == kind_name && entity.id.eq_ignore_ascii_case(id))
+            .map(|entity| &entity.entity);
+    }
+}
+
+#[cfg(feature = "embedded-data")]
+fn load_embedded() -> Option<Dataset> {
+    return serde_json::from_slice(EMBEDDED_DATASET).ok();
+}
+
+#[cfg(not(feature = "embedded-data"))]
+fn load_embedded() -> Option<Dataset> {
+    return None;
+}
+
+/// Dataset compiled into the binary with the `embedded-data` feature.
+pub fn embedded() -> Option<&'static Dataset> {
+    return EMBEDDED.as_ref();
+}
+
+/// Download URL of the dataset published for the given ATT&CK release.
+pub fn dataset_url(release: &str) -> String {
+    return format!("{}{}/{}", DATASET_RELEASES_URL, release, DATASET_FILE_NAME);
+}
+
+/// Dataset release name (v14) of a version given with or without its `v` prefix.
+pub fn release_name(release: &str) -> String {
+    if release.starts_with(|c: char| c.is_ascii_digit()) {
+        return format!("v{}", release);
+    }
+
+    return release.to_string();
+}
+
+/// Dataset of a release: the dataset file at `release` when it is a path, the dataset
+/// published for the release otherwise.
+pub fn load_release(release: &str, web_client: &impl WebFetch) -> Result<Dataset, error::Error> {
+    let path = std::path::Path::new(release);
+
+    if path.is_file() {
+        return Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?);
+    }
+
+    let mut dataset = fetch_dataset(&dataset_url(&release_name(release)), web_client)?;
+
+    if dataset.release.is_empty() {
+        dataset.release = release_name(release);
+    }
+
+    return Ok(dataset);
+}
+
+/// Download the prebuilt dataset from `url`.
+pub fn fetch_dataset(url: &str, web_client: &impl WebFetch) -> Result<Dataset, error::Error> {
+    let fetched_response = web_client.fetch(url)?;
+
+    return Ok(serde_json::from_str(&fetched_response)?);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attack::{
+            cache::test_cache,
+            techniques::{fetch_techniques, Domain, Technique},
+        },
+        fakers::FakeHttpReqwest,
+    };
+
+    #[test]
+    fn test_dataset_roundtrip() -> Result<(), error::Error> {
+        let source_cache = test_cache("dataset_source");
+        let listing = fetch_techniques(
+            Domain::MOBILE,
+            &FakeHttpReqwest::default().set_success_response(
+                include_str!("html/attck/techniques/mobile.html").to_string(),
+            ),
+        )?;
+        source_cache.save_listing(EntityKind::TECHNIQUE, Some("mobile"), &listing)?;
+        source_cache.save_entity(
+            EntityKind::TECHNIQUE,
+            "T1610",
+            &Technique {
+                id: String::from("T1610"),
+                name: String::from("Deploy Container"),
+                ..Default::default()
+            },
+        )?;
+
+        let dataset = Dataset::from_cache("v15.1", &source_cache)?;
+        let fake_reqwest =
+            FakeHttpReqwest::default().set_success_response(serde_json::to_string(&dataset)?);
+        let fetched_dataset = fetch_dataset(&dataset_url("v15.1"), &fake_reqwest)?;
+
+        let target_cache = test_cache("dataset_target");
+        let installed = fetched_dataset.install(&target_cache)?;
+
+        assert_eq!(fetched_dataset.release, "v15.1");
+        assert_eq!(installed, 1);
+        assert_eq!(
+            target_cache.listing_ids(EntityKind::TECHNIQUE, Some("mobile"))?,
+            Some(listing.ids())
+        );
+        assert_eq!(
+            target_cache
+                .load_entity::<Technique>(EntityKind::TECHNIQUE, "T1610")?
+                .map(|technique| technique.name),
+            Some(String::from("Deploy Container"))
+        );
+
+        std::fs::remove_dir_all(source_cache.root())?;
+        std::fs::remove_dir_all(target_cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dataset_lookup() -> Result<(), error::Error> {
+        let dataset: Dataset = serde_json::from_value(serde_json::json!({
+            "release": "v15.1",
+            "listings": [{"kind": "groups", "domain": null, "listing": []}],
+            "entities": [{"kind": "groups", "id": "G0018", "entity": {"id": "G0018"}}]
+        }))?;
+
+        assert!(dataset.listing(EntityKind::GROUP, None).is_some());
+        assert!(dataset.listing(EntityKind::GROUP, Some("mobile")).is_none());
+        assert_eq!(
+            dataset.entity(EntityKind::GROUP, "g0018"),
+            Some(&serde_json::json!({"id": "G0018"}))
+        );
+        assert!(dataset.entity(EntityKind::SOFTWARE, "G0018").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_release_name() {
+        assert_eq!(release_name("14"), "v14");
+        assert_eq!(release_name("v15.1"), "v15.1");
+    }
+
+    #[test]
+    fn test_dataset_url() {
+        assert_eq!(
+            dataset_url("v15.1"),
+            "https://github.com/fwxs/mitre_cli/releases/download/dataset-v15.1/attack-dataset.json"
+        );
+    }
+}
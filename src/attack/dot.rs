@@ -0,0 +1,307 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use super::{
+    cache::{EntityKind, Snapshot},
+    groups::Group,
+    mitigations::Mitigation,
+    software::Software,
+    techniques::Technique,
+};
+
+const MITIGATES_RELATIONSHIP: &'static str = "mitigates";
+
+/// Shape and fill colour of the entity nodes.
+fn node_style(kind: EntityKind) -> (&'static str, &'static str) {
+    return match kind {
+        EntityKind::TECHNIQUE => ("box", "#f4cccc"),
+        EntityKind::MITIGATION => ("octagon", "#d9ead3"),
+        EntityKind::SOFTWARE => ("component", "#fff2cc"),
+        EntityKind::GROUP => ("ellipse", "#cfe2f3"),
+        EntityKind::TACTIC | EntityKind::DATASOURCE => ("note", "#eeeeee"),
+    };
+}
+
+pub(super) struct Node {
+    pub(super) kind: EntityKind,
+    pub(super) name: Option<String>,
+}
+
+/// Entities and relationships of the graph, keyed by the (uppercase) ATT&CK ID.
+#[derive(Default)]
+pub(super) struct GraphBuilder {
+    pub(super) nodes: BTreeMap<String, Node>,
+    pub(super) edges: BTreeSet<(String, String, &'static str)>,
+}
+
+impl GraphBuilder {
+    /// Graph of the techniques, mitigations, software and groups of the snapshot.
+    pub(super) fn from_snapshot(snapshot: &Snapshot) -> Self {
+        let mut builder = Self::default();
+
+        for technique in &snapshot.techniques {
+            builder.add_technique(technique);
+        }
+
+        for mitigation in &snapshot.mitigations {
+            builder.add_mitigation(mitigation);
+        }
+
+        for software in &snapshot.software {
+            builder.add_software(software);
+        }
+
+        for group in &snapshot.groups {
+            builder.add_group(group);
+        }
+
+        return builder;
+    }
+
+    /// Register an entity, a described entity takes precedence over a referenced one.
+    pub(super) fn add_node(&mut self, id: &str, kind: EntityKind, name: Option<&str>) {
+        let node = self
+            .nodes
+            .entry(id.to_uppercase())
+            .or_insert(Node { kind, name: None });
+
+        if let Some(name) = name.filter(|name| !name.is_empty()) {
+            node.name = Some(name.to_string());
+        }
+    }
+
+    pub(super) fn relate(
+        &mut self,
+        source_id: &str,
+        target_id: &str,
+        relationship_type: &'static str,
+    ) {
+        self.edges.insert((
+            source_id.to_uppercase(),
+            target_id.to_uppercase(),
+            relationship_type,
+        ));
+    }
+
+    fn add_technique(&mut self, technique: &Technique) {
+        self.add_node(&technique.id, EntityKind::TECHNIQUE, Some(&technique.name));
+
+        if let Some((parent_id, _)) = technique.id.split_once('.') {
+            self.add_node(parent_id, EntityKind::TECHNIQUE, None);
+            self.relate(&technique.id, parent_id, "subtechnique-of");
+        }
+
+        if let Some(mitigations) = &technique.mitigations {
+            for mitigation in &mitigations.0 {
+                self.add_node(
+                    &mitigation.id,
+                    EntityKind::MITIGATION,
+                    Some(&mitigation.name),
+                );
+                self.relate(&mitigation.id, &technique.id, MITIGATES_RELATIONSHIP);
+            }
+        }
+    }
+
+    fn add_mitigation(&mut self, mitigation: &Mitigation) {
+        self.add_node(
+            &mitigation.id,
+            EntityKind::MITIGATION,
+            Some(&mitigation.name),
+        );
+
+        if let Some(techniques) = &mitigation.addressed_techniques {
+            for technique_id in techniques.ids() {
+                self.add_node(&technique_id, EntityKind::TECHNIQUE, None);
+                self.relate(&mitigation.id, &technique_id, MITIGATES_RELATIONSHIP);
+            }
+        }
+    }
+
+    fn add_software(&mut self, software: &Software) {
+        self.add_node(&software.id, EntityKind::SOFTWARE, Some(&software.name));
+
+        if let Some(techniques) = &software.techniques {
+            for technique_id in techniques.ids() {
+                self.add_node(&technique_id, EntityKind::TECHNIQUE, None);
+                self.relate(&software.id, &technique_id, "uses");
+            }
+        }
+
+        if let Some(groups) = &software.groups {
+            for group in &groups.0 {
+                self.add_node(&group.id, EntityKind::GROUP, Some(&group.name));
+                self.relate(&group.id, &software.id, "uses");
+            }
+        }
+    }
+
+    fn add_group(&mut self, group: &Group) {
+        self.add_node(&group.id, EntityKind::GROUP, Some(&group.name));
+
+        if let Some(techniques) = &group.techniques {
+            for technique_id in techniques.ids() {
+                self.add_node(&technique_id, EntityKind::TECHNIQUE, None);
+                self.relate(&group.id, &technique_id, "uses");
+            }
+        }
+
+        if let Some(software) = &group.software {
+            for software_row in &software.0 {
+                self.add_node(
+                    &software_row.id,
+                    EntityKind::SOFTWARE,
+                    Some(&software_row.name),
+                );
+                self.relate(&group.id, &software_row.id, "uses");
+            }
+        }
+    }
+
+    /// Keep the entities reachable from `root_id` (following "uses" and "subtechnique-of")
+    /// and the mitigations of the reached techniques.
+    fn focus(&mut self, root_id: &str) {
+        let mut reachable: BTreeSet<String> = BTreeSet::new();
+        let mut pending = VecDeque::from([root_id.to_uppercase()]);
+
+        while let Some(id) = pending.pop_front() {
+            if !reachable.insert(id.clone()) {
+                continue;
+            }
+
+            for (source_id, target_id, relationship_type) in &self.edges {
+                if *source_id == id && *relationship_type != MITIGATES_RELATIONSHIP {
+                    pending.push_back(target_id.clone());
+                }
+            }
+        }
+
+        for (source_id, target_id, relationship_type) in &self.edges {
+            if *relationship_type == MITIGATES_RELATIONSHIP && reachable.contains(target_id) {
+                reachable.insert(source_id.clone());
+            }
+        }
+
+        self.nodes.retain(|id, _| reachable.contains(id));
+        self.edges.retain(|(source_id, target_id, _)| {
+            reachable.contains(source_id) && reachable.contains(target_id)
+        });
+    }
+
+    fn build(self) -> String {
+        let mut dot = String::from("digraph attack {\n");
+        dot.push_str("    rankdir=LR;\n");
+        dot.push_str("    node [style=filled, fontname=\"Helvetica\"];\n");
+        dot.push_str("    edge [fontname=\"Helvetica\", fontsize=10];\n\n");
+
+        for (id, node) in &self.nodes {
+            let (shape, color) = node_style(node.kind);
+            let label = match &node.name {
+                Some(name) => format!("{}\\n{}", id, escape(name)),
+                None => id.clone(),
+            };
+
+            dot.push_str(&format!(
+                "    \"{}\" [label=\"{}\", shape={}, fillcolor=\"{}\"];\n",
+                id, label, shape, color
+            ));
+        }
+
+        dot.push('\n');
+
+        for (source_id, target_id, relationship_type) in &self.edges {
+            dot.push_str(&format!(
+                "    \"{}\" -> \"{}\" [label=\"{}\"];\n",
+                source_id, target_id, relationship_type
+            ));
+        }
+
+        dot.push_str("}\n");
+
+        return dot;
+    }
+}
+
+fn escape(label: &str) -> String {
+    return label.replace('\\', "\\\\").replace('"', "\\\"");
+}
+
+/// Graphviz DOT graph of the snapshot entities and their relationships.
+///
+/// Entities referenced by a cached entity but not cached themselves are drawn with their
+/// ID only. With `root_id`, only the footprint of that entity is kept.
+pub fn build_dot_graph(snapshot: &Snapshot, root_id: Option<&str>) -> String {
+    let mut builder = GraphBuilder::from_snapshot(snapshot);
+
+    if let Some(root_id) = root_id {
+        builder.focus(root_id);
+    }
+
+    return builder.build();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attack::{
+            mitigations::{MitigationRow, MitigationTable},
+            software::fetch_software_info,
+        },
+        error,
+        fakers::FakeHttpReqwest,
+    };
+
+    fn test_snapshot() -> Result<Snapshot, error::Error> {
+        let software = fetch_software_info(
+            "S0029",
+            &FakeHttpReqwest::default()
+                .set_success_response(include_str!("html/attck/software/psexec.html").to_string()),
+        )?;
+        let technique = Technique {
+            id: String::from("T1569.002"),
+            name: String::from("Service \"Execution\""),
+            mitigations: Some(MitigationTable(vec![MitigationRow {
+                id: String::from("M1026"),
+                name: String::from("Privileged Account Management"),
+                description: String::new(),
+            }])),
+            ..Default::default()
+        };
+        let unrelated_technique = Technique {
+            id: String::from("T1610"),
+            name: String::from("Deploy Container"),
+            ..Default::default()
+        };
+
+        return Ok(Snapshot {
+            techniques: vec![technique, unrelated_technique],
+            software: vec![software],
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn test_dot_graph() -> Result<(), error::Error> {
+        let dot = build_dot_graph(&test_snapshot()?, None);
+
+        assert!(dot.starts_with("digraph attack {"));
+        assert!(dot.contains("\"S0029\" -> \"T1569.002\" [label=\"uses\"];"));
+        assert!(dot.contains("\"M1026\" -> \"T1569.002\" [label=\"mitigates\"];"));
+        assert!(dot.contains("\"T1569.002\" -> \"T1569\" [label=\"subtechnique-of\"];"));
+        assert!(dot.contains("label=\"T1569.002\\nService \\\"Execution\\\"\""));
+        assert!(dot.contains("\"T1610\""));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dot_graph_focus() -> Result<(), error::Error> {
+        let dot = build_dot_graph(&test_snapshot()?, Some("s0029"));
+
+        assert!(dot.contains("\"S0029\" -> \"T1569.002\""));
+        assert!(dot.contains("\"M1026\" -> \"T1569.002\""));
+        assert!(!dot.contains("\"T1610\""));
+
+        Ok(())
+    }
+}
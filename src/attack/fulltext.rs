@@ -0,0 +1,279 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+use tantivy::{
+    collector::TopDocs,
+    doc,
+    query::QueryParser,
+    schema::{Field, Schema, Value, STORED, STRING, TEXT},
+    snippet::SnippetGenerator,
+    Index, IndexWriter, TantivyDocument,
+};
+
+use crate::error;
+
+use super::{
+    cache::{Cache, Snapshot},
+    search,
+};
+
+const INDEX_DIR_NAME: &'static str = "fulltext";
+/// Memory budget of the index writer, split across its threads.
+const WRITER_MEMORY_BYTES: usize = 50_000_000;
+/// Characters of the snippets of the matching texts.
+const SNIPPET_CHARS: usize = 120;
+
+struct IndexFields {
+    kind: Field,
+    id: Field,
+    name: Field,
+    field: Field,
+    text: Field,
+}
+
+impl IndexFields {
+    fn from_schema(schema: &Schema) -> Result<Self, error::Error> {
+        return Ok(Self {
+            kind: schema.get_field("kind")?,
+            id: schema.get_field("id")?,
+            name: schema.get_field("name")?,
+            field: schema.get_field("field")?,
+            text: schema.get_field("text")?,
+        });
+    }
+}
+
+/// One document per text of an entity (description, procedure, detection...), the names
+/// and texts being tokenized for the ranked queries.
+fn index_schema() -> Schema {
+    let mut builder = Schema::builder();
+    builder.add_text_field("kind", STRING | STORED);
+    builder.add_text_field("id", STRING | STORED);
+    builder.add_text_field("name", TEXT | STORED);
+    builder.add_text_field("field", STORED);
+    builder.add_text_field("text", TEXT | STORED);
+
+    return builder.build();
+}
+
+#[derive(Debug, Serialize)]
+pub struct RankedMatchRow {
+    /// BM25 relevance of the text, the higher the better.
+    pub score: f32,
+    /// Entity type ("techniques", "groups", ...).
+    pub kind: String,
+    pub id: String,
+    pub name: String,
+    /// Text of the entity matching the query: "description", "procedure G0016", ...
+    pub field: String,
+    pub snippet: String,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for RankedMatchRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(format!("{:.2}", self.score)))
+            .add_cell(comfy_table::Cell::new(self.kind))
+            .add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(self.name))
+            .add_cell(comfy_table::Cell::new(self.field))
+            .add_cell(comfy_table::Cell::new(self.snippet));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct RankedMatchesTable(pub Vec<RankedMatchRow>);
+
+impl RankedMatchesTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for RankedMatchesTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&[
+            "Score", "Type", "ID", "Name", "Field", "Snippet",
+        ]));
+
+        for ranked_match in self.0 {
+            table.add_row(ranked_match);
+        }
+
+        return table;
+    }
+}
+
+/// Tantivy index of the texts of the cached entity details, rebuilt with
+/// 'attack index build' after a sync.
+pub struct FulltextIndex {
+    root: PathBuf,
+}
+
+impl FulltextIndex {
+    /// Index stored next to the cached entities.
+    pub fn for_cache(cache: &Cache) -> Self {
+        return Self::with_root(cache.root().join(INDEX_DIR_NAME));
+    }
+
+    pub fn with_root(root: PathBuf) -> Self {
+        return Self { root };
+    }
+
+    pub fn root(&self) -> &Path {
+        return &self.root;
+    }
+
+    pub fn exists(&self) -> bool {
+        return self.root.join("meta.json").exists();
+    }
+
+    /// Replace the index with the texts of the snapshot (see [`search::search_contents`]).
+    /// Returns the number of indexed texts.
+    pub fn build(&self, snapshot: &Snapshot) -> Result<usize, error::Error> {
+        if self.root.exists() {
+            fs::remove_dir_all(&self.root)?;
+        }
+        fs::create_dir_all(&self.root)?;
+
+        let index = Index::create_in_dir(&self.root, index_schema())?;
+        let fields = IndexFields::from_schema(&index.schema())?;
+        let mut writer: IndexWriter = index.writer(WRITER_MEMORY_BYTES)?;
+        let mut indexed = 0;
+
+        for entity in search::entity_texts(snapshot) {
+            let kind_name: &str = entity.kind.into();
+
+            for (field, text) in entity.texts {
+                if text.trim().is_empty() {
+                    continue;
+                }
+
+                writer.add_document(doc!(
+                    fields.kind => kind_name,
+                    fields.id => entity.id,
+                    fields.name => entity.name,
+                    fields.field => field,
+                    fields.text => text,
+                ))?;
+                indexed += 1;
+            }
+        }
+
+        writer.commit()?;
+
+        return Ok(indexed);
+    }
+
+    /// The `limit` texts best matching the query (tantivy query syntax: words, "phrases",
+    /// AND/OR, -excluded), searched in the entity names and texts.
+    pub fn search(&self, query: &str, limit: usize) -> Result<RankedMatchesTable, error::Error> {
+        if !self.exists() {
+            return Err(error::Error::General(String::from(
+                "No full-text index in the local cache, run 'attack index build' first",
+            )));
+        }
+
+        let index = Index::open_in_dir(&self.root)?;
+        let fields = IndexFields::from_schema(&index.schema())?;
+        let searcher = index.reader()?.searcher();
+        let query = QueryParser::for_index(&index, vec![fields.name, fields.text])
+            .parse_query(query)
+            .map_err(|err| error::Error::InvalidValue(format!("Invalid query: {}", err)))?;
+        let mut snippets = SnippetGenerator::create(&searcher, &*query, fields.text)?;
+        snippets.set_max_num_chars(SNIPPET_CHARS);
+
+        let mut matches = RankedMatchesTable::default();
+
+        for (score, address) in searcher.search(&query, &TopDocs::with_limit(limit))? {
+            let document: TantivyDocument = searcher.doc(address)?;
+            let stored = |field: Field| -> String {
+                return document
+                    .get_first(field)
+                    .and_then(|value| value.as_str().map(String::from))
+                    .unwrap_or_default();
+            };
+            let snippet = snippets.snippet_from_doc(&document);
+            let snippet = if snippet.is_empty() {
+                // Only the name matched, show the beginning of the text.
+                stored(fields.text).chars().take(SNIPPET_CHARS).collect()
+            } else {
+                snippet.fragment().to_string()
+            };
+
+            matches.0.push(RankedMatchRow {
+                score,
+                kind: stored(fields.kind),
+                id: stored(fields.id),
+                name: stored(fields.name),
+                field: stored(fields.field),
+                snippet: snippet.split_whitespace().collect::<Vec<&str>>().join(" "),
+            });
+        }
+
+        return Ok(matches);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack::{groups::Group, techniques::Technique};
+
+    #[test]
+    fn test_build_and_search() -> Result<(), error::Error> {
+        let index = FulltextIndex::with_root(std::env::temp_dir().join("mitre_cli_fulltext"));
+        let snapshot = Snapshot {
+            techniques: vec![
+                Technique {
+                    id: String::from("T1003.001"),
+                    name: String::from("LSASS Memory"),
+                    description: String::from(
+                        "Adversaries may attempt to access credential material stored in the \
+                         process memory of the Local Security Authority Subsystem Service.",
+                    ),
+                    ..Default::default()
+                },
+                Technique {
+                    id: String::from("T1059.001"),
+                    name: String::from("PowerShell"),
+                    description: String::from("Adversaries may abuse PowerShell commands."),
+                    ..Default::default()
+                },
+            ],
+            groups: vec![Group {
+                id: String::from("G0016"),
+                name: String::from("APT29"),
+                desc: String::from("APT29 dumped the LSASS process memory with procdump."),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert!(index.search("lsass", 10).is_err());
+        assert_eq!(index.build(&snapshot)?, 3);
+
+        let matches = index.search("lsass memory", 10)?;
+        assert_eq!(matches.0.len(), 2);
+        // The name and the description of the technique both match.
+        assert_eq!(matches.0[0].id, "T1003.001");
+        assert_eq!(matches.0[1].kind, "groups");
+        assert!(matches.0[1].snippet.contains("LSASS"));
+
+        assert_eq!(index.search("powershell", 1)?.0[0].id, "T1059.001");
+        assert!(index.search("kerberoasting", 10)?.is_empty());
+        assert!(index.search("name:(", 10).is_err());
+
+        fs::remove_dir_all(index.root())?;
+
+        Ok(())
+    }
+}
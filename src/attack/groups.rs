@@ -1,15 +1,20 @@
+use std::collections::HashMap;
+
 use select::document::Document;
+use serde::{Deserialize, Serialize};
 
-use crate::{error, WebFetch};
+use crate::{error, error::FetchContext, WebFetch};
 
 use super::{
-    scrape_entity_description, scrape_entity_h2_tables, scrape_entity_name, scrape_tables,
-    techniques::domain::DomainTechniquesTable, Row, Table,
+    scrape_entity_description, scrape_entity_h2_tables, scrape_entity_name,
+    scrape_entity_references, scrape_tables,
+    techniques::domain::{DomainTechniquesTable, UsedTechniques},
+    Reference, Row, Table,
 };
 
 const ATTCK_GROUPS_URL: &'static str = "https://attack.mitre.org/groups/";
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct GroupRow {
     pub id: String,
     pub name: String,
@@ -50,6 +55,7 @@ impl From<Row> for GroupRow {
     }
 }
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Row> for GroupRow {
     fn into(self) -> comfy_table::Row {
         let mut row = comfy_table::Row::new();
@@ -68,33 +74,20 @@ impl Into<comfy_table::Row> for GroupRow {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct GroupsTable(pub Vec<GroupRow>);
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Table> for GroupsTable {
     fn into(self) -> comfy_table::Table {
-        let mut table = comfy_table::Table::new();
+        let mut table = crate::theme::new_table();
         table
-            .load_preset(comfy_table::presets::UTF8_FULL)
-            .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
-            .set_header(vec![
-                comfy_table::Cell::new("ID")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Name")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Associated Groups")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Description")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-            ])
+            .set_header(crate::theme::header(&[
+                "ID",
+                "Name",
+                "Associated Groups",
+                "Description",
+            ]))
             .add_rows(
                 self.into_iter()
                     .map(|group| group.into())
@@ -113,10 +106,81 @@ impl GroupsTable {
     pub fn len(&self) -> usize {
         return self.0.len();
     }
+
+    pub fn ids(&self) -> Vec<String> {
+        return self.0.iter().map(|row| row.id.clone()).collect();
+    }
+
+    /// (ID, name) of the listed entities, associated names included.
+    pub fn names(&self) -> Vec<(String, String)> {
+        return self
+            .0
+            .iter()
+            .flat_map(|row| {
+                std::iter::once(row.name.clone())
+                    .chain(row.assoc_groups.iter().flatten().cloned())
+                    .map(|name| (row.id.clone(), name))
+            })
+            .collect();
+    }
+
+    /// Groups using techniques of the domain (enterprise, mobile, ics), according to
+    /// `techniques_of` (`None` when not cached). Returns them with the IDs of the left out
+    /// groups that are not cached.
+    pub fn in_domain(
+        self,
+        domain: &str,
+        techniques_of: impl Fn(&str) -> Result<Option<UsedTechniques>, error::Error>,
+    ) -> Result<(Self, Vec<String>), error::Error> {
+        let mut groups = Vec::new();
+        let mut uncached = Vec::new();
+
+        for group in self.0 {
+            match techniques_of(&group.id)? {
+                Some(techniques) if techniques.has_domain(domain) => groups.push(group),
+                Some(_) => {}
+                None => uncached.push(group.id),
+            }
+        }
+
+        return Ok((Self(groups), uncached));
+    }
+
+    /// Groups whose listed description, or the cached one given by `description_of`, contains
+    /// `keyword` (case insensitive).
+    pub fn with_description(
+        self,
+        keyword: &str,
+        description_of: impl Fn(&str) -> Result<Option<GroupDescription>, error::Error>,
+    ) -> Result<Self, error::Error> {
+        let keyword = keyword.to_lowercase();
+        let mut groups = Vec::new();
+
+        for group in self.0 {
+            let matches = group.description.to_lowercase().contains(&keyword)
+                || description_of(&group.id)?
+                    .is_some_and(|cached| cached.desc.to_lowercase().contains(&keyword));
+
+            if matches {
+                groups.push(group);
+            }
+        }
+
+        return Ok(Self(groups));
+    }
+}
+
+/// Description of a cached group, loaded without the rest of the group.
+#[derive(Default, Debug, Deserialize)]
+pub struct GroupDescription {
+    #[serde(default)]
+    pub desc: String,
 }
 
 pub fn fetch_groups(web_client: &impl WebFetch) -> Result<GroupsTable, error::Error> {
-    let fetched_response = web_client.fetch(ATTCK_GROUPS_URL)?;
+    let fetched_response = web_client
+        .fetch(ATTCK_GROUPS_URL)
+        .listing_context("groups", ATTCK_GROUPS_URL)?;
     let document = Document::from(fetched_response.as_str());
 
     return Ok(scrape_tables(&document)
@@ -139,11 +203,27 @@ impl From<Table> for GroupsTable {
     }
 }
 
-#[derive(Debug, Default)]
+/// Technique names of the scraped cells span several lines.
+fn collapse_whitespace(text: &str) -> String {
+    return text.split_whitespace().collect::<Vec<&str>>().join(" ");
+}
+
+/// Technique named by a software row, its ID resolved from the cached technique listings.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TechniqueRef {
+    /// `None` when the name is not listed by the cache.
+    pub id: Option<String>,
+    pub name: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct SoftwareRow {
     pub id: String,
     pub name: String,
     pub techniques: Vec<String>,
+    /// `techniques` resolved by `SoftwareTable::resolve_techniques`.
+    #[serde(default)]
+    pub technique_refs: Vec<TechniqueRef>,
 }
 
 impl From<Row> for SoftwareRow {
@@ -159,13 +239,14 @@ impl From<Row> for SoftwareRow {
         }
 
         if let Some(techniques) = row.get_col(3) {
-            software.techniques = techniques.split(",").map(String::from).collect();
+            software.techniques = techniques.split(",").map(collapse_whitespace).collect();
         }
 
         return software;
     }
 }
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Row> for SoftwareRow {
     fn into(self) -> comfy_table::Row {
         let mut row = comfy_table::Row::new();
@@ -177,7 +258,7 @@ impl Into<comfy_table::Row> for SoftwareRow {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct SoftwareTable(pub Vec<SoftwareRow>);
 
 impl IntoIterator for SoftwareTable {
@@ -205,26 +286,12 @@ impl From<Table> for Option<SoftwareTable> {
     }
 }
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Table> for SoftwareTable {
     fn into(self) -> comfy_table::Table {
-        let mut table = comfy_table::Table::new();
+        let mut table = crate::theme::new_table();
         table
-            .load_preset(comfy_table::presets::UTF8_FULL)
-            .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
-            .set_header(vec![
-                comfy_table::Cell::new("ID")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Name")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Techniques")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-            ])
+            .set_header(crate::theme::header(&["ID", "Name", "Techniques"]))
             .add_rows(
                 self.into_iter()
                     .map(|group| group.into())
@@ -240,29 +307,62 @@ impl SoftwareTable {
         return self.0.is_empty();
     }
 
+    /// Resolve the technique names of the rows against the (ID, name) of the cached technique
+    /// listings (`Cache::listing_names`), sub-techniques named `Technique: Sub-technique`.
+    pub fn resolve_techniques(&mut self, technique_names: &[(String, String)]) {
+        let ids: HashMap<String, &str> = technique_names
+            .iter()
+            .map(|(id, name)| (name.to_lowercase(), id.as_str()))
+            .collect();
+
+        for software in self.0.iter_mut() {
+            software.technique_refs = software
+                .techniques
+                .iter()
+                .map(|technique| collapse_whitespace(technique))
+                .filter(|technique| !technique.is_empty())
+                .map(|technique| TechniqueRef {
+                    id: ids.get(&technique.to_lowercase()).map(|id| id.to_string()),
+                    name: technique,
+                })
+                .collect();
+        }
+    }
+
     pub fn len(&self) -> usize {
         return self.0.len();
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Group {
     pub id: String,
     pub name: String,
+    /// ATT&CK page of the group.
+    #[serde(default)]
+    pub url: String,
     pub desc: String,
     pub assoc_groups: Option<Vec<String>>,
     pub techniques: Option<DomainTechniquesTable>,
     pub software: Option<SoftwareTable>,
+    #[serde(default)]
+    pub references: Vec<Reference>,
+}
+
+/// ATT&CK page of the group.
+pub fn group_url(group_id: &str) -> String {
+    return format!("{}{}", ATTCK_GROUPS_URL, group_id);
 }
 
 pub fn fetch_group(group_id: &str, web_client: &impl WebFetch) -> Result<Group, error::Error> {
-    let fetched_response =
-        web_client.fetch(format!("{}{}", ATTCK_GROUPS_URL, group_id).as_str())?;
+    let url = group_url(group_id);
+    let fetched_response = web_client.fetch(&url).entity_context(group_id, &url)?;
     let document = Document::from(fetched_response.as_str());
     let mut tables = scrape_entity_h2_tables(&document);
     let group = Group {
         id: group_id.to_string(),
         name: scrape_entity_name(&document),
+        url: group_url(group_id),
         desc: scrape_entity_description(&document),
         techniques: if let Some(techniques_table) = tables.remove("techniques") {
             techniques_table.into()
@@ -274,16 +374,13 @@ pub fn fetch_group(group_id: &str, web_client: &impl WebFetch) -> Result<Group,
         } else {
             None
         },
-        assoc_groups: if let Some(assoc_groups_table) = tables.remove("aliasDescription") {
-            Some(
-                assoc_groups_table
-                    .into_iter()
-                    .map(|row| row.cols[0].clone())
-                    .collect(),
-            )
-        } else {
-            None
-        },
+        assoc_groups: tables.remove("aliasDescription").map(|assoc_groups_table| {
+            assoc_groups_table
+                .into_iter()
+                .map(|row| row.cols[0].clone())
+                .collect()
+        }),
+        references: scrape_entity_references(&document),
     };
 
     return Ok(group);
@@ -337,6 +434,48 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_resolve_software_techniques() -> Result<(), error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default()
+            .set_success_response(include_str!("html/attck/groups/admin_338.html").to_string());
+        let mut software = fetch_group(TEST_GROUP, &fake_reqwest)?.software.unwrap();
+
+        software.resolve_techniques(&[
+            (
+                String::from("T1071"),
+                String::from("Application Layer Protocol"),
+            ),
+            (
+                String::from("T1071.001"),
+                String::from("Application Layer Protocol: Web Protocols"),
+            ),
+            (
+                String::from("T1082"),
+                String::from("System Information Discovery"),
+            ),
+        ]);
+
+        assert_eq!(
+            software.0[0].technique_refs,
+            vec![
+                TechniqueRef {
+                    id: Some(String::from("T1071.001")),
+                    name: String::from("Application Layer Protocol: Web Protocols"),
+                },
+                TechniqueRef {
+                    id: None,
+                    name: String::from("Non-Application Layer Protocol"),
+                },
+                TechniqueRef {
+                    id: Some(String::from("T1082")),
+                    name: String::from("System Information Discovery"),
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_fetch_group_information_with_assoc_groups() -> Result<(), error::Error> {
         let fake_reqwest = FakeHttpReqwest::default().set_success_response(
@@ -360,6 +499,69 @@ mod tests {
             true,
             "group software should not be empty"
         );
+        assert_eq!(
+            group
+                .references
+                .iter()
+                .map(|reference| reference.number)
+                .collect::<Vec<Option<u32>>>(),
+            (1..=5).map(Some).collect::<Vec<Option<u32>>>()
+        );
+
+        Ok(())
+    }
+    #[test]
+    fn test_groups_with_description() -> Result<(), error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default()
+            .set_success_response(include_str!("html/attck/groups/groups.html").to_string());
+        let retrieved_groups = fetch_groups(&fake_reqwest)?;
+
+        let financial_groups = retrieved_groups.with_description("FINANCIAL", |id| {
+            return Ok((id == "G0016").then(|| GroupDescription {
+                desc: String::from("APT29 also targeted financial institutions."),
+            }));
+        })?;
+        let ids = financial_groups.ids();
+
+        assert!(ids.contains(&String::from("G0018")));
+        assert!(ids.contains(&String::from("G0082")));
+        assert!(ids.contains(&String::from("G0016")));
+        assert!(!ids.contains(&String::from("G0007")));
+
+        Ok(())
+    }
+    #[test]
+    fn test_groups_in_domain() -> Result<(), error::Error> {
+        let listed_groups =
+            || {
+                return fetch_groups(&FakeHttpReqwest::default().set_success_response(
+                    include_str!("html/attck/groups/groups.html").to_string(),
+                ));
+            };
+        let techniques_of = |id: &str| {
+            if id == "G0007" {
+                return Ok(None);
+            }
+
+            if id != TEST_GROUP {
+                return Ok(Some(UsedTechniques::default()));
+            }
+
+            let fake_reqwest = FakeHttpReqwest::default()
+                .set_success_response(include_str!("html/attck/groups/admin_338.html").to_string());
+
+            return fetch_group(id, &fake_reqwest).map(|group| {
+                Some(UsedTechniques {
+                    techniques: group.techniques,
+                })
+            });
+        };
+
+        let (enterprise_groups, uncached) =
+            listed_groups()?.in_domain("enterprise", techniques_of)?;
+        assert_eq!(enterprise_groups.ids(), vec![TEST_GROUP]);
+        assert_eq!(uncached, vec!["G0007"]);
+        assert!(listed_groups()?.in_domain("ics", techniques_of)?.0.is_empty());
 
         Ok(())
     }
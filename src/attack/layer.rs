@@ -0,0 +1,251 @@
+use serde::Serialize;
+
+use crate::error;
+
+use super::techniques::domain::DomainTechniquesTable;
+
+/// Version of the Navigator layer format written.
+pub const LAYER_FORMAT_VERSION: &'static str = "4.5";
+/// Color of the highlighted techniques.
+pub const HIGHLIGHT_COLOR: &'static str = "#e60d0d";
+
+#[derive(Debug, Default, Serialize)]
+pub struct LayerVersions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attack: Option<String>,
+    pub layer: String,
+}
+
+/// Technique entry of a layer, its comment holding the procedure of the entity.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayerTechnique {
+    #[serde(rename = "techniqueID")]
+    pub technique_id: String,
+    pub score: u32,
+    pub color: String,
+    pub comment: String,
+    pub enabled: bool,
+    pub show_subtechniques: bool,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayerGradient {
+    pub colors: Vec<String>,
+    pub min_value: u32,
+    pub max_value: u32,
+}
+
+/// ATT&CK Navigator layer highlighting the techniques of a group or software.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Layer {
+    pub name: String,
+    pub versions: LayerVersions,
+    /// Navigator domain (enterprise-attack, mobile-attack, ics-attack).
+    pub domain: String,
+    pub description: String,
+    pub techniques: Vec<LayerTechnique>,
+    pub gradient: LayerGradient,
+    pub hide_disabled: bool,
+}
+
+impl Layer {
+    /// Layer of the `domain` (enterprise, mobile, ics) techniques of the table, the other
+    /// domains left out.
+    pub fn from_techniques(
+        name: &str,
+        description: &str,
+        domain: &str,
+        techniques: &DomainTechniquesTable,
+        attack_version: Option<&str>,
+    ) -> Result<Self, error::Error> {
+        check_domain(domain)?;
+
+        let highlighted = |technique_id: String, comment: &str, show_subtechniques: bool| {
+            return LayerTechnique {
+                technique_id,
+                score: 1,
+                color: HIGHLIGHT_COLOR.to_string(),
+                comment: comment.to_string(),
+                enabled: true,
+                show_subtechniques,
+            };
+        };
+        let mut layer_techniques = Vec::new();
+
+        for technique in techniques.0.iter().filter(|technique| {
+            technique.domain.is_empty() || technique.domain.eq_ignore_ascii_case(domain)
+        }) {
+            let sub_techniques = technique.sub_techniques.as_deref().unwrap_or_default();
+
+            layer_techniques.push(highlighted(
+                technique.id.clone(),
+                &technique.used_for,
+                !sub_techniques.is_empty(),
+            ));
+            layer_techniques.extend(sub_techniques.iter().map(|sub_technique| {
+                highlighted(
+                    format!("{}{}", technique.id, sub_technique.id),
+                    &sub_technique.used_for,
+                    false,
+                )
+            }));
+        }
+
+        return Ok(Self {
+            name: name.to_string(),
+            versions: LayerVersions::new(attack_version),
+            domain: format!("{}-attack", domain),
+            description: description.to_string(),
+            techniques: layer_techniques,
+            gradient: LayerGradient {
+                colors: vec![String::from("#ffffff"), HIGHLIGHT_COLOR.to_string()],
+                min_value: 0,
+                max_value: 1,
+            },
+            hide_disabled: false,
+        });
+    }
+
+    /// Heatmap layer of the `domain` techniques scored by `scores` (technique ID, score,
+    /// comment), coloured from white (0) to the highlight color (highest score).
+    pub fn heatmap(
+        name: &str,
+        description: &str,
+        domain: &str,
+        scores: &[(String, u32, String)],
+        attack_version: Option<&str>,
+    ) -> Result<Self, error::Error> {
+        check_domain(domain)?;
+
+        return Ok(Self {
+            name: name.to_string(),
+            versions: LayerVersions::new(attack_version),
+            domain: format!("{}-attack", domain),
+            description: description.to_string(),
+            techniques: scores
+                .iter()
+                .map(|(technique_id, score, comment)| LayerTechnique {
+                    technique_id: technique_id.clone(),
+                    score: *score,
+                    comment: comment.clone(),
+                    enabled: true,
+                    ..Default::default()
+                })
+                .collect(),
+            gradient: LayerGradient {
+                colors: vec![
+                    String::from("#ffffff"),
+                    String::from("#ffe766"),
+                    HIGHLIGHT_COLOR.to_string(),
+                ],
+                min_value: 0,
+                max_value: scores.iter().map(|(_, score, _)| *score).max().unwrap_or(1),
+            },
+            hide_disabled: false,
+        });
+    }
+}
+
+impl LayerVersions {
+    fn new(attack_version: Option<&str>) -> Self {
+        return Self {
+            // Layers only take the major ATT&CK version.
+            attack: attack_version.and_then(|version| {
+                version
+                    .trim_start_matches('v')
+                    .split('.')
+                    .next()
+                    .map(String::from)
+            }),
+            layer: LAYER_FORMAT_VERSION.to_string(),
+        };
+    }
+}
+
+fn check_domain(domain: &str) -> Result<(), error::Error> {
+    if !matches!(domain, "enterprise" | "mobile" | "ics") {
+        return Err(error::Error::InvalidValue(format!(
+            "{} is not a valid ATT&CK domain",
+            domain
+        )));
+    }
+
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack::techniques::domain::{DomainSubTechniqueRow, DomainTechniqueRow};
+
+    #[test]
+    fn test_layer_from_techniques() -> Result<(), error::Error> {
+        let techniques = DomainTechniquesTable(vec![
+            DomainTechniqueRow {
+                domain: String::from("Enterprise"),
+                id: String::from("T1059"),
+                used_for: String::from("APT29 has used scripts"),
+                sub_techniques: Some(vec![DomainSubTechniqueRow {
+                    id: String::from(".001"),
+                    used_for: String::from("APT29 has used PowerShell"),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            },
+            DomainTechniqueRow {
+                domain: String::from("Mobile"),
+                id: String::from("T1406"),
+                ..Default::default()
+            },
+        ]);
+
+        let layer = Layer::from_techniques(
+            "APT29 (G0016)",
+            "Russian group",
+            "enterprise",
+            &techniques,
+            Some("v15.1"),
+        )?;
+        let layer_json = serde_json::to_value(&layer)?;
+
+        assert_eq!(layer_json["domain"], "enterprise-attack");
+        assert_eq!(layer_json["versions"]["attack"], "15");
+        assert_eq!(layer_json["techniques"].as_array().unwrap().len(), 2);
+        assert_eq!(layer_json["techniques"][0]["techniqueID"], "T1059");
+        assert_eq!(layer_json["techniques"][0]["showSubtechniques"], true);
+        assert_eq!(layer_json["techniques"][1]["techniqueID"], "T1059.001");
+        assert_eq!(
+            layer_json["techniques"][1]["comment"],
+            "APT29 has used PowerShell"
+        );
+        assert!(Layer::from_techniques("", "", "pre", &techniques, None).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_heatmap_layer() -> Result<(), error::Error> {
+        let layer = Layer::heatmap(
+            "Top techniques",
+            "",
+            "enterprise",
+            &[
+                (String::from("T1059"), 12, String::from("12 groups")),
+                (String::from("T1105"), 4, String::from("4 groups")),
+            ],
+            None,
+        )?;
+        let layer_json = serde_json::to_value(&layer)?;
+
+        assert_eq!(layer_json["gradient"]["maxValue"], 12);
+        assert_eq!(layer_json["techniques"][1]["score"], 4);
+        // The color comes from the gradient.
+        assert_eq!(layer_json["techniques"][1]["color"], "");
+        assert!(layer_json["versions"].get("attack").is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::groups::Group;
+
+/// Content of the cells of the group-by-technique matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixCell {
+    /// 1 when the group uses the technique, 0 otherwise.
+    PRESENCE,
+    /// Procedure of the group ("used for" text), empty when not used.
+    PROCEDURE,
+}
+
+/// Wide CSV matrix of the groups (rows) by the techniques and sub-techniques they use
+/// (columns, sorted by ID). With `domain`, only the techniques used in that domain are
+/// kept.
+pub fn build_group_matrix(groups: &[Group], domain: Option<&str>, cell: MatrixCell) -> String {
+    // Group ID -> technique ID -> procedure.
+    let mut procedures: BTreeMap<&str, BTreeMap<String, String>> = BTreeMap::new();
+    let mut technique_ids: BTreeSet<String> = BTreeSet::new();
+
+    for group in groups {
+        let group_procedures = procedures.entry(&group.id).or_default();
+
+        for technique in group.techniques.iter().flat_map(|techniques| &techniques.0) {
+            if domain.is_some_and(|domain| !technique.domain.eq_ignore_ascii_case(domain)) {
+                continue;
+            }
+
+            let sub_techniques = technique
+                .sub_techniques
+                .iter()
+                .flatten()
+                .map(|sub| (format!("{}{}", technique.id, sub.id), sub.used_for.as_str()));
+
+            for (id, used_for) in [(technique.id.clone(), technique.used_for.as_str())]
+                .into_iter()
+                .chain(sub_techniques)
+            {
+                let id = id.to_uppercase();
+                let used_for = used_for.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+                technique_ids.insert(id.clone());
+                group_procedures.insert(id, used_for);
+            }
+        }
+    }
+
+    let mut csv = ["group_id", "group_name"]
+        .iter()
+        .map(|column| column.to_string())
+        .chain(technique_ids.iter().cloned())
+        .collect::<Vec<String>>()
+        .join(",");
+    csv.push('\n');
+
+    let mut sorted_groups: Vec<&Group> = groups.iter().collect();
+    sorted_groups.sort_by(|a, b| a.id.cmp(&b.id));
+
+    for group in sorted_groups {
+        let group_procedures = &procedures[group.id.as_str()];
+        let mut fields = vec![crate::csv_field(&group.id), crate::csv_field(&group.name)];
+
+        fields.extend(technique_ids.iter().map(|technique_id| {
+            match (cell, group_procedures.get(technique_id)) {
+                (MatrixCell::PRESENCE, Some(_)) => String::from("1"),
+                (MatrixCell::PRESENCE, None) => String::from("0"),
+                (MatrixCell::PROCEDURE, Some(used_for)) => crate::csv_field(used_for),
+                (MatrixCell::PROCEDURE, None) => String::new(),
+            }
+        }));
+
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+
+    return csv;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack::techniques::domain::{
+        DomainSubTechniqueRow, DomainTechniqueRow, DomainTechniquesTable,
+    };
+
+    fn test_groups() -> Vec<Group> {
+        return vec![
+            Group {
+                id: String::from("G0016"),
+                name: String::from("APT29"),
+                techniques: Some(DomainTechniquesTable(vec![DomainTechniqueRow {
+                    domain: String::from("Enterprise"),
+                    id: String::from("T1059"),
+                    used_for: String::from("APT29 has used scripts"),
+                    sub_techniques: Some(vec![DomainSubTechniqueRow {
+                        id: String::from(".001"),
+                        used_for: String::from("APT29 has used PowerShell, and WMI"),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                }])),
+                ..Default::default()
+            },
+            Group {
+                id: String::from("G0007"),
+                name: String::from("APT28"),
+                techniques: Some(DomainTechniquesTable(vec![DomainTechniqueRow {
+                    domain: String::from("Mobile"),
+                    id: String::from("T1406"),
+                    used_for: String::from("APT28 has obfuscated files"),
+                    ..Default::default()
+                }])),
+                ..Default::default()
+            },
+        ];
+    }
+
+    #[test]
+    fn test_presence_matrix() {
+        assert_eq!(
+            build_group_matrix(&test_groups(), None, MatrixCell::PRESENCE),
+            "group_id,group_name,T1059,T1059.001,T1406\n\
+             G0007,APT28,0,0,1\n\
+             G0016,APT29,1,1,0\n"
+        );
+        assert_eq!(
+            build_group_matrix(&test_groups(), Some("enterprise"), MatrixCell::PRESENCE),
+            "group_id,group_name,T1059,T1059.001\n\
+             G0007,APT28,0,0\n\
+             G0016,APT29,1,1\n"
+        );
+    }
+
+    #[test]
+    fn test_procedure_matrix() {
+        let matrix = build_group_matrix(&test_groups(), None, MatrixCell::PROCEDURE);
+
+        assert_eq!(
+            matrix.lines().nth(2),
+            Some("G0016,APT29,APT29 has used scripts,\"APT29 has used PowerShell, and WMI\",")
+        );
+    }
+}
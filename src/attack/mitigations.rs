@@ -1,12 +1,14 @@
 use std::str::FromStr;
 
 use select::document::Document;
+use serde::{Deserialize, Serialize};
 
-use crate::{error, WebFetch};
+use crate::{error, error::FetchContext, WebFetch};
 
 use super::{
-    scrape_entity_description, scrape_entity_h2_tables, scrape_entity_name, scrape_tables,
-    techniques::domain::DomainTechniquesTable, Row, Table,
+    scrape_entity_card, scrape_entity_created, scrape_entity_description, scrape_entity_h2_tables,
+    scrape_entity_modified, scrape_entity_name, scrape_entity_references, scrape_tables,
+    techniques::domain::DomainTechniquesTable, Reference, Row, Table,
 };
 
 const ATTCK_MITIGATION_URL: &'static str = "https://attack.mitre.org/mitigations/";
@@ -43,13 +45,14 @@ impl Into<&'static str> for Domain {
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Serialize, Deserialize)]
 pub struct MitigationRow {
     pub id: String,
     pub name: String,
     pub description: String,
 }
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Row> for MitigationRow {
     fn into(self) -> comfy_table::Row {
         let mut row = comfy_table::Row::new();
@@ -61,7 +64,7 @@ impl Into<comfy_table::Row> for MitigationRow {
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Serialize, Deserialize)]
 pub struct MitigationTable(pub Vec<MitigationRow>);
 
 impl IntoIterator for MitigationTable {
@@ -73,26 +76,12 @@ impl IntoIterator for MitigationTable {
     }
 }
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Table> for MitigationTable {
     fn into(self) -> comfy_table::Table {
-        let mut table = comfy_table::Table::new();
+        let mut table = crate::theme::new_table();
         table
-            .load_preset(comfy_table::presets::UTF8_FULL)
-            .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
-            .set_header(vec![
-                comfy_table::Cell::new("ID")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Name")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Description")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-            ])
+            .set_header(crate::theme::header(&["ID", "Name", "Description"]))
             .add_rows(
                 self.into_iter()
                     .map(|mitigation| mitigation.into())
@@ -111,13 +100,27 @@ impl MitigationTable {
     pub fn len(&self) -> usize {
         return self.0.len();
     }
+
+    pub fn ids(&self) -> Vec<String> {
+        return self.0.iter().map(|row| row.id.clone()).collect();
+    }
+
+    /// (ID, name) of the listed entities.
+    pub fn names(&self) -> Vec<(String, String)> {
+        return self
+            .0
+            .iter()
+            .map(|row| (row.id.clone(), row.name.clone()))
+            .collect();
+    }
 }
 
 pub fn fetch_mitigations(
     mitigation_type: Domain,
     web_client: &impl WebFetch,
 ) -> Result<MitigationTable, error::Error> {
-    let fetched_response = web_client.fetch(mitigation_type.into())?;
+    let url: &str = mitigation_type.into();
+    let fetched_response = web_client.fetch(url).listing_context("mitigations", url)?;
     let document = Document::from(fetched_response.as_str());
 
     return Ok(scrape_tables(&document)
@@ -172,31 +175,60 @@ impl From<Table> for Option<MitigationTable> {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Mitigation {
     pub id: String,
     pub name: String,
+    /// ATT&CK page of the mitigation.
+    #[serde(default)]
+    pub url: String,
     pub desc: String,
     pub addressed_techniques: Option<DomainTechniquesTable>,
+    /// Domain (enterprise, mobile, ics) the mitigation was listed under, when known.
+    #[serde(default)]
+    pub domain: Option<String>,
+    #[serde(default)]
+    pub references: Vec<Reference>,
+    /// Version of the mitigation in ATT&CK (e.g. 1.1).
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Creation of the mitigation in ATT&CK (YYYY-MM-DD).
+    #[serde(default)]
+    pub created: Option<String>,
+    /// Last modification of the mitigation in ATT&CK (YYYY-MM-DD).
+    #[serde(default)]
+    pub modified: Option<String>,
+}
+
+/// ATT&CK page of the mitigation.
+pub fn mitigation_url(mitigation_id: &str) -> String {
+    return format!("{}{}", ATTCK_MITIGATION_URL, mitigation_id);
 }
 
 pub fn fetch_mitigation(
     mitigation_id: &str,
     web_client: &impl WebFetch,
 ) -> Result<Mitigation, error::Error> {
-    let fetched_response =
-        web_client.fetch(format!("{}{}", ATTCK_MITIGATION_URL, mitigation_id).as_str())?;
+    let url = mitigation_url(mitigation_id);
+    let fetched_response = web_client.fetch(&url).entity_context(mitigation_id, &url)?;
     let document = Document::from(fetched_response.as_str());
     let mut tables = scrape_entity_h2_tables(&document);
+    let mut card = scrape_entity_card(&document);
     let mitigation = Mitigation {
         id: mitigation_id.to_string(),
         name: scrape_entity_name(&document),
+        url: mitigation_url(mitigation_id),
         desc: scrape_entity_description(&document),
         addressed_techniques: if let Some(techniques_table) = tables.remove("techniques") {
             techniques_table.into()
         } else {
             None
         },
+        domain: None,
+        references: scrape_entity_references(&document),
+        version: card.remove("Version"),
+        created: scrape_entity_created(&mut card),
+        modified: scrape_entity_modified(&mut card),
     };
 
     return Ok(mitigation);
@@ -278,6 +310,9 @@ mod tests {
             true,
             "techniques addressed by mitigation should not be abscent"
         );
+        assert_eq!(mitigation.version.as_deref(), Some("1.1"));
+        assert_eq!(mitigation.created.as_deref(), Some("2019-06-11"));
+        assert_eq!(mitigation.modified.as_deref(), Some("2020-03-31"));
 
         Ok(())
     }
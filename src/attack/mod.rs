@@ -1,36 +1,92 @@
-// TODO: Save an offline version of the downloaded data.
 // TODO: Create the command line version for ATT&CK.
 
 use std::collections::HashMap;
 
 use crate::remove_ext_link_ref;
+use serde::{Deserialize, Serialize};
 use select::{
     document::Document,
     predicate::{self, Predicate},
 };
 
+pub mod annotations;
+#[cfg(feature = "archive")]
+pub mod archive;
+pub mod cache;
+pub mod caldera;
+pub mod changelog;
+pub mod compare;
+pub mod controls;
+pub mod consistency;
+pub mod coverage;
+pub mod dashboard;
 pub mod data_sources;
+pub mod dataset;
+pub mod dot;
+#[cfg(feature = "fulltext")]
+pub mod fulltext;
 pub mod groups;
+pub mod layer;
+pub mod matrix;
 pub mod mitigations;
+pub mod names;
+pub mod plan;
+pub mod provenance;
+pub mod query;
+pub mod resolve;
+pub mod rule_coverage;
+pub mod runbook;
+pub mod schema;
+pub mod search;
 pub mod software;
+pub mod spool;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod stats;
+pub mod stix;
+pub mod stix_import;
+pub mod sync;
 pub mod tactics;
+pub mod tagging;
 pub mod techniques;
+pub mod usage;
+pub mod vault;
+pub mod webhook;
+
+/// Entry of the references section of an entity page.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Reference {
+    pub description: String,
+    pub url: String,
+    /// Number of the `[n]` citations of the reference.
+    #[serde(default)]
+    pub number: Option<u32>,
+}
+
+const ATTACK_URL: &'static str = "https://attack.mitre.org";
 
 #[derive(Default, Debug)]
 pub struct Row {
     pub cols: Vec<String>,
+    /// Absolute URLs of the links of every column, filled by `scrape_table`.
+    pub links: Vec<Vec<String>>,
 }
 
 impl Row {
     pub fn get_col(&self, inx: usize) -> Option<&String> {
         return self.cols.get(inx);
     }
+
+    pub fn get_links(&self, inx: usize) -> &[String] {
+        return self.links.get(inx).map(Vec::as_slice).unwrap_or_default();
+    }
 }
 
 impl FromIterator<String> for Row {
     fn from_iter<T: IntoIterator<Item = String>>(iter: T) -> Self {
         return Self {
-            cols: iter.into_iter().map(String::from).collect(),
+            cols: iter.into_iter().collect(),
+            links: Vec::new(),
         };
     }
 }
@@ -65,29 +121,43 @@ impl IntoIterator for Table {
     }
 }
 
-fn scrape_table(table_node: select::node::Node) -> Table {
-    let mut table = Table::default();
+/// URL of a link of an ATT&CK page, the relative ones resolved against the site.
+fn absolute_url(href: &str) -> String {
+    if href.starts_with('/') {
+        return format!("{}{}", ATTACK_URL, href);
+    }
 
-    table.headers = table_node
-        .find(
-            predicate::Name("thead")
-                .descendant(predicate::Name("tr").descendant(predicate::Element)),
-        )
-        .map(|node_text| node_text.text())
-        .collect::<Vec<String>>();
+    return href.to_string();
+}
 
-    table.rows.extend(
-        table_node
+fn scrape_table(table_node: select::node::Node) -> Table {
+    return Table {
+        headers: table_node
+            .find(
+                predicate::Name("thead")
+                    .descendant(predicate::Name("tr").descendant(predicate::Element)),
+            )
+            .map(|node_text| node_text.text())
+            .collect::<Vec<String>>(),
+        rows: table_node
             .find(predicate::Name("tbody").descendant(predicate::Name("tr")))
-            .map(|row| {
-                row.find(predicate::Name("td"))
+            .map(|row| Row {
+                cols: row
+                    .find(predicate::Name("td"))
                     .map(|col| col.text().trim().to_string())
-                    .collect::<Row>()
+                    .collect(),
+                links: row
+                    .find(predicate::Name("td"))
+                    .map(|col| {
+                        col.find(predicate::Name("a"))
+                            .filter_map(|a_node| a_node.attr("href"))
+                            .map(absolute_url)
+                            .collect()
+                    })
+                    .collect(),
             })
             .collect::<Vec<Row>>(),
-    );
-
-    return table;
+    };
 }
 
 fn scrape_tables(document: &Document) -> Vec<Table> {
@@ -101,6 +171,7 @@ fn scrape_entity_name(document: &Document) -> String {
     return document
         .find(predicate::Name("h1").child(predicate::Text))
         .map(|h1_node| h1_node.text().trim().to_string())
+        .filter(|name| !name.is_empty())
         .collect::<Vec<String>>()
         .join(" ");
 }
@@ -119,6 +190,87 @@ fn scrape_entity_description(document: &Document) -> String {
     return remove_ext_link_ref(&desc);
 }
 
+/// Scrape the key/value pairs shown in the entity card (ID, Type, Platforms, Version...).
+/// Keys are stripped of their trailing colon and values have their whitespace collapsed.
+fn scrape_entity_card(document: &Document) -> HashMap<String, String> {
+    let mut card: HashMap<String, String> = HashMap::new();
+
+    for title_node in document.find(
+        predicate::Class("card-data").descendant(predicate::Name("span").and(predicate::Class("card-title"))),
+    ) {
+        let title = title_node.text();
+        let key = title.trim_matches(|c: char| c == ':' || c.is_whitespace());
+
+        if let Some(parent_node) = title_node.parent() {
+            let value = parent_node
+                .text()
+                .trim_start()
+                .trim_start_matches(title.as_str())
+                .trim_start_matches(|c: char| c == ':' || c.is_whitespace())
+                .split_whitespace()
+                .collect::<Vec<&str>>()
+                .join(" ");
+
+            card.insert(key.to_string(), value);
+        }
+    }
+
+    return card;
+}
+
+/// "Last Modified" date of the entity card (e.g. 01 April 2022) as YYYY-MM-DD.
+fn scrape_entity_modified(card: &mut HashMap<String, String>) -> Option<String> {
+    return scrape_card_date(card, "Last Modified");
+}
+
+/// Creation date of the entity card, as YYYY-MM-DD.
+fn scrape_entity_created(card: &mut HashMap<String, String>) -> Option<String> {
+    return scrape_card_date(card, "Created");
+}
+
+fn scrape_card_date(card: &mut HashMap<String, String>, key: &str) -> Option<String> {
+    return card.remove(key).map(|date| {
+        chrono::NaiveDate::parse_from_str(&date, "%d %B %Y")
+            .map_or(date, |parsed_date| parsed_date.format("%Y-%m-%d").to_string())
+    });
+}
+
+/// Contributors credited in the entity card, one entry per person or organization.
+fn scrape_entity_contributors(card: &mut HashMap<String, String>) -> Vec<String> {
+    return scrape_card_list(card, "Contributors", ';');
+}
+
+fn scrape_card_list(card: &mut HashMap<String, String>, key: &str, separator: char) -> Vec<String> {
+    return card
+        .remove(key)
+        .map(|values| {
+            values
+                .split(separator)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+}
+
+fn scrape_entity_references(document: &Document) -> Vec<Reference> {
+    return document
+        .find(
+            predicate::Name("span")
+                .and(predicate::Class("scite-citation-text"))
+                .child(predicate::Name("a")),
+        )
+        .map(|a_node| Reference {
+            description: a_node.text().split_whitespace().collect::<Vec<&str>>().join(" "),
+            url: a_node.attr("href").unwrap_or_default().to_string(),
+            number: a_node
+                .attr("name")
+                .and_then(|name| name.strip_prefix("scite-"))
+                .and_then(|number| number.parse().ok()),
+        })
+        .collect();
+}
+
 fn scrape_entity_h2_tables(document: &Document) -> HashMap<String, Table> {
     let tag = "h2";
     let mut table_id: Option<&str> = None;
@@ -142,3 +294,31 @@ fn scrape_entity_h2_tables(document: &Document) -> HashMap<String, Table> {
 
     return tables;
 }
+
+/// Free-text paragraphs placed under the `section_id` h2 of an entity page, outside of its table.
+fn scrape_entity_h2_text(document: &Document, section_id: &str) -> Option<String> {
+    let tag = "h2";
+    let mut in_section = false;
+    let mut paragraphs: Vec<String> = Vec::new();
+
+    for node in document.find(
+        predicate::Name("div")
+            .and(predicate::Class("container-fluid"))
+            .child(predicate::Name(tag).or(predicate::Name("p"))),
+    ) {
+        if node.name() == Some(tag) {
+            in_section = node.attr("id") == Some(section_id);
+        } else if in_section {
+            let paragraph = remove_ext_link_ref(node.text().trim());
+            if !paragraph.is_empty() {
+                paragraphs.push(paragraph);
+            }
+        }
+    }
+
+    if paragraphs.is_empty() {
+        return None;
+    }
+
+    return Some(paragraphs.join("\n\n"));
+}
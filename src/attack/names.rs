@@ -0,0 +1,240 @@
+use crate::error;
+
+/// Largest edit distance of a name suggested in place of an unknown one.
+const MAX_SUGGESTION_DISTANCE: usize = 3;
+const MAX_SUGGESTIONS: usize = 5;
+
+fn normalize(name: &str) -> String {
+    return name.trim().to_lowercase();
+}
+
+/// Names an entity can be referred by: its full name and, for sub-techniques
+/// (`Technique: Sub-technique`), its own name.
+fn name_variants(name: &str) -> Vec<String> {
+    let mut variants = vec![normalize(name)];
+
+    if let Some((_, sub_name)) = name.split_once(": ") {
+        variants.push(normalize(sub_name));
+    }
+
+    return variants;
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut distances: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut previous = distances[0];
+        distances[0] = i + 1;
+
+        for (j, b_char) in b.iter().enumerate() {
+            let current = distances[j + 1];
+            distances[j + 1] = if a_char == *b_char {
+                previous
+            } else {
+                1 + previous.min(current).min(distances[j])
+            };
+            previous = current;
+        }
+    }
+
+    return distances[b.len()];
+}
+
+/// `ID (Name)` of the candidates, closest names first.
+fn describe_candidates(candidates: &[&(String, String)], name: &str) -> String {
+    let mut candidates = candidates.to_vec();
+    candidates.sort_by_key(|(id, candidate_name)| {
+        (
+            name_variants(candidate_name)
+                .iter()
+                .map(|variant| edit_distance(variant, name))
+                .min()
+                .unwrap_or_default(),
+            id.clone(),
+        )
+    });
+    candidates.dedup_by(|a, b| a.0 == b.0);
+
+    return candidates
+        .iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(id, candidate_name)| format!("{} ({})", id, candidate_name.trim()))
+        .collect::<Vec<String>>()
+        .join(", ");
+}
+
+fn distinct_ids(candidates: &[&(String, String)]) -> Vec<String> {
+    let mut ids: Vec<String> = candidates.iter().map(|(id, _)| id.to_uppercase()).collect();
+    ids.sort();
+    ids.dedup();
+
+    return ids;
+}
+
+/// ID of the entity named `name` among the listed (ID, name) pairs.
+///
+/// Names are compared case-insensitively. A name matching no entity exactly resolves to the
+/// single entity whose name contains it. Ambiguous and unknown names fail, suggesting the
+/// closest names.
+pub fn resolve_name(names: &[(String, String)], name: &str) -> Result<String, error::Error> {
+    let needle = normalize(name);
+
+    let exact_matches: Vec<&(String, String)> = names
+        .iter()
+        .filter(|(_, candidate_name)| name_variants(candidate_name).contains(&needle))
+        .collect();
+    let candidates = if exact_matches.is_empty() {
+        names
+            .iter()
+            .filter(|(_, candidate_name)| normalize(candidate_name).contains(&needle))
+            .collect()
+    } else {
+        exact_matches
+    };
+
+    let ids = distinct_ids(&candidates);
+
+    if ids.len() == 1 {
+        return Ok(ids[0].clone());
+    }
+
+    if !ids.is_empty() {
+        return Err(error::Error::InvalidValue(format!(
+            "\"{}\" is ambiguous, did you mean: {}?",
+            name,
+            describe_candidates(&candidates, &needle)
+        )));
+    }
+
+    let suggestions: Vec<&(String, String)> = names
+        .iter()
+        .filter(|(_, candidate_name)| {
+            name_variants(candidate_name)
+                .iter()
+                .any(|variant| edit_distance(variant, &needle) <= MAX_SUGGESTION_DISTANCE)
+        })
+        .collect();
+
+    if suggestions.is_empty() {
+        return Err(error::Error::InvalidValue(format!(
+            "No entity named \"{}\"",
+            name
+        )));
+    }
+
+    return Err(error::Error::InvalidValue(format!(
+        "No entity named \"{}\", did you mean: {}?",
+        name,
+        describe_candidates(&suggestions, &needle)
+    )));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attack::{
+            groups::fetch_groups,
+            techniques::{fetch_techniques, Domain},
+        },
+        fakers::FakeHttpReqwest,
+    };
+
+    fn enterprise_names() -> Result<Vec<(String, String)>, error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default().set_success_response(
+            include_str!("html/attck/techniques/enterprise.html").to_string(),
+        );
+
+        return Ok(fetch_techniques(Domain::ENTERPRISE, &fake_reqwest)?.names());
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("injection", "injection"), 0);
+        assert_eq!(edit_distance("injecton", "injection"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_resolve_exact_name() -> Result<(), error::Error> {
+        let names = enterprise_names()?;
+
+        assert_eq!(resolve_name(&names, "process injection")?, "T1055");
+        assert_eq!(
+            resolve_name(&names, "Process Injection: Dynamic-link Library Injection")?,
+            "T1055.001"
+        );
+        assert_eq!(
+            resolve_name(&names, "Dynamic-link Library Injection")?,
+            "T1055.001"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_ambiguous_name() -> Result<(), error::Error> {
+        let names = vec![
+            (
+                String::from("T1078.002"),
+                String::from("Valid Accounts: Domain Accounts"),
+            ),
+            (
+                String::from("T1087.002"),
+                String::from("Account Discovery: Domain Accounts"),
+            ),
+        ];
+
+        assert_eq!(
+            resolve_name(&names, "Domain Accounts"),
+            Err(error::Error::InvalidValue(String::from(
+                "\"Domain Accounts\" is ambiguous, did you mean: \
+                 T1078.002 (Valid Accounts: Domain Accounts), \
+                 T1087.002 (Account Discovery: Domain Accounts)?"
+            )))
+        );
+        assert_eq!(resolve_name(&names, "account discovery")?, "T1087.002");
+
+        match resolve_name(&enterprise_names()?, "Injection") {
+            Err(error::Error::InvalidValue(message)) => assert!(message.contains("ambiguous")),
+            resolved => panic!("Unexpected resolution {:?}", resolved),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_mistyped_name() -> Result<(), error::Error> {
+        let names = enterprise_names()?;
+
+        match resolve_name(&names, "Proces Injecton") {
+            Err(error::Error::InvalidValue(message)) => {
+                assert!(message.contains("did you mean: T1055 (Process Injection)"));
+            }
+            resolved => panic!("Unexpected resolution {:?}", resolved),
+        }
+
+        assert_eq!(
+            resolve_name(&names, "nothing like this"),
+            Err(error::Error::InvalidValue(String::from(
+                "No entity named \"nothing like this\""
+            )))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_associated_group_name() -> Result<(), error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default()
+            .set_success_response(include_str!("html/attck/groups/groups.html").to_string());
+        let names = fetch_groups(&fake_reqwest)?.names();
+
+        assert_eq!(resolve_name(&names, "Cleaver")?, "G0003");
+        assert_eq!(resolve_name(&names, "TG-2889")?, "G0003");
+
+        Ok(())
+    }
+}
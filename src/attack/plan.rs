@@ -0,0 +1,212 @@
+use serde::Serialize;
+
+use crate::error;
+
+use super::{
+    cache::{Cache, EntityKind},
+    techniques::Technique,
+};
+
+/// Mitigation addressing some of the planned techniques.
+#[derive(Debug, Default, Serialize)]
+pub struct PlannedMitigationRow {
+    pub id: String,
+    pub name: String,
+    /// IDs of the planned techniques it addresses.
+    pub techniques: Vec<String>,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for PlannedMitigationRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(self.name))
+            .add_cell(comfy_table::Cell::new(self.techniques.len()))
+            .add_cell(comfy_table::Cell::new(self.techniques.join(", ")));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PlannedMitigationsTable(pub Vec<PlannedMitigationRow>);
+
+impl PlannedMitigationsTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for PlannedMitigationsTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&[
+            "ID",
+            "Name",
+            "Covered",
+            "Techniques",
+        ]));
+
+        for mitigation in self.0 {
+            table.add_row(mitigation);
+        }
+
+        return table;
+    }
+}
+
+/// Mitigations addressing a list of techniques, those covering the most techniques first.
+#[derive(Debug, Default, Serialize)]
+pub struct MitigationPlan {
+    pub mitigations: PlannedMitigationsTable,
+    /// Techniques without a cached page, left out of the plan.
+    pub uncached: Vec<String>,
+    /// Cached techniques without any mitigation.
+    pub unmitigated: Vec<String>,
+}
+
+impl MitigationPlan {
+    /// Plan of the cached pages of the techniques.
+    pub fn build(cache: &Cache, technique_ids: &[String]) -> Result<Self, error::Error> {
+        let (techniques, uncached) = cached_techniques(cache, technique_ids)?;
+        let mut plan = Self::from_techniques(&techniques);
+        plan.uncached = uncached;
+
+        return Ok(plan);
+    }
+
+    pub fn from_techniques(techniques: &[Technique]) -> Self {
+        let mut mitigations: Vec<PlannedMitigationRow> = Vec::new();
+        let mut unmitigated = Vec::new();
+
+        for technique in techniques {
+            let technique_mitigations = technique
+                .mitigations
+                .iter()
+                .flat_map(|mitigations| mitigations.0.iter());
+            let mut mitigated = false;
+
+            for mitigation in technique_mitigations {
+                mitigated = true;
+
+                match mitigations.iter_mut().find(|row| row.id == mitigation.id) {
+                    Some(row) if row.techniques.contains(&technique.id) => {}
+                    Some(row) => row.techniques.push(technique.id.clone()),
+                    None => mitigations.push(PlannedMitigationRow {
+                        id: mitigation.id.clone(),
+                        name: mitigation.name.clone(),
+                        techniques: vec![technique.id.clone()],
+                    }),
+                }
+            }
+
+            if !mitigated {
+                unmitigated.push(technique.id.clone());
+            }
+        }
+
+        mitigations.sort_by(|a, b| {
+            b.techniques
+                .len()
+                .cmp(&a.techniques.len())
+                .then(a.id.cmp(&b.id))
+        });
+
+        return Self {
+            mitigations: PlannedMitigationsTable(mitigations),
+            uncached: Vec::new(),
+            unmitigated,
+        };
+    }
+}
+
+/// Cached pages of the techniques, and the (upper-cased) IDs of those not cached.
+pub fn cached_techniques(
+    cache: &Cache,
+    technique_ids: &[String],
+) -> Result<(Vec<Technique>, Vec<String>), error::Error> {
+    let mut techniques = Vec::with_capacity(technique_ids.len());
+    let mut uncached = Vec::new();
+
+    for technique_id in technique_ids {
+        let technique_id = technique_id.to_uppercase();
+
+        match cache.load_entity::<Technique>(EntityKind::TECHNIQUE, &technique_id)? {
+            Some(technique) => techniques.push(technique),
+            None => uncached.push(technique_id),
+        }
+    }
+
+    return Ok((techniques, uncached));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack::mitigations::{MitigationRow, MitigationTable};
+
+    fn technique(id: &str, mitigation_ids: &[&str]) -> Technique {
+        return Technique {
+            id: String::from(id),
+            name: format!("Technique {}", id),
+            mitigations: if mitigation_ids.is_empty() {
+                None
+            } else {
+                Some(MitigationTable(
+                    mitigation_ids
+                        .iter()
+                        .map(|mitigation_id| MitigationRow {
+                            id: mitigation_id.to_string(),
+                            name: format!("Mitigation {}", mitigation_id),
+                            ..Default::default()
+                        })
+                        .collect(),
+                ))
+            },
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn test_mitigation_plan() -> Result<(), error::Error> {
+        let cache = crate::attack::cache::test_cache("plan");
+
+        for technique in [
+            technique("T1059", &["M1042", "M1038"]),
+            technique("T1003", &["M1043", "M1027"]),
+            technique("T1566", &["M1049", "M1017"]),
+            technique("T1204", &["M1038", "M1017"]),
+            technique("T1480", &[]),
+        ] {
+            cache.save_entity(EntityKind::TECHNIQUE, &technique.id, &technique)?;
+        }
+
+        let technique_ids: Vec<String> = ["T1059", "t1566", "T1204", "T1480", "T1190"]
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+        let plan = MitigationPlan::build(&cache, &technique_ids)?;
+
+        assert_eq!(
+            plan.mitigations
+                .0
+                .iter()
+                .map(|mitigation| format!("{} {}", mitigation.id, mitigation.techniques.join(",")))
+                .collect::<Vec<String>>(),
+            vec![
+                "M1017 T1566,T1204",
+                "M1038 T1059,T1204",
+                "M1042 T1059",
+                "M1049 T1566"
+            ]
+        );
+        assert_eq!(plan.unmitigated, vec!["T1480"]);
+        assert_eq!(plan.uncached, vec!["T1190"]);
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+}
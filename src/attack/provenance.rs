@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error;
+
+use super::{
+    cache::{Cache, EntityKind},
+    dataset::Dataset,
+};
+
+const TOOL_NAME: &'static str = "mitre_cli";
+
+/// Custom property of the STIX bundles holding their provenance.
+pub const STIX_PROPERTY: &'static str = "x_mitre_cli_provenance";
+
+/// Snapshot an exported artifact was produced from, embedded in every export so downstream
+/// consumers can trace it back.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub tool: String,
+    pub tool_version: String,
+    /// ATT&CK release the cache was synced from, when known.
+    pub attack_version: Option<String>,
+    /// Last sync of every cached entity type (RFC 3339).
+    pub synced_at: BTreeMap<String, String>,
+    pub exported_at: String,
+    /// SHA-256 of the canonical JSON of the cached listings and entities.
+    pub content_hash: String,
+}
+
+impl Provenance {
+    pub fn from_cache(cache: &Cache, exported_at: &str) -> Result<Self, error::Error> {
+        return Self::from_dataset(cache, &Dataset::from_cache("", cache)?, exported_at);
+    }
+
+    /// Provenance of a dataset built from the cache, saving building it twice.
+    pub fn from_dataset(
+        cache: &Cache,
+        dataset: &Dataset,
+        exported_at: &str,
+    ) -> Result<Self, error::Error> {
+        let mut synced_at = BTreeMap::new();
+
+        for kind in EntityKind::ALL {
+            if let Some(kind_synced_at) = cache.synced_at(kind)? {
+                let kind_name: &str = kind.into();
+                synced_at.insert(
+                    kind_name.to_string(),
+                    chrono::DateTime::<chrono::Utc>::from(kind_synced_at)
+                        .format("%Y-%m-%dT%H:%M:%SZ")
+                        .to_string(),
+                );
+            }
+        }
+
+        return Ok(Self {
+            tool: TOOL_NAME.to_string(),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            attack_version: if dataset.release.is_empty() {
+                cache.attack_version()?
+            } else {
+                Some(dataset.release.clone())
+            },
+            synced_at,
+            exported_at: exported_at.to_string(),
+            content_hash: content_hash(dataset)?,
+        });
+    }
+
+    /// `Key: value` lines, for the exports embedding it as comments.
+    pub fn to_lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("Generated by: {} {}", self.tool, self.tool_version),
+            format!(
+                "ATT&CK version: {}",
+                self.attack_version.as_deref().unwrap_or("unknown")
+            ),
+        ];
+        lines.extend(
+            self.synced_at
+                .iter()
+                .map(|(kind, synced_at)| format!("Synced {}: {}", kind, synced_at)),
+        );
+        lines.push(format!("Exported at: {}", self.exported_at));
+        lines.push(format!("Content hash: sha256:{}", self.content_hash));
+
+        return lines;
+    }
+}
+
+/// Record of the last sync, written at the root of the cache so downstream consumers know
+/// which release and how fresh the offline data is.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncManifest {
+    pub tool: String,
+    pub tool_version: String,
+    /// ATT&CK release the cache was synced from, when known.
+    pub attack_version: Option<String>,
+    /// End of the sync (RFC 3339).
+    pub synced_at: String,
+    /// Cached entities of every type, after the sync.
+    pub entities: BTreeMap<String, usize>,
+}
+
+impl SyncManifest {
+    pub fn from_cache(cache: &Cache, synced_at: &str) -> Result<Self, error::Error> {
+        let mut entities = BTreeMap::new();
+
+        for kind in EntityKind::ALL {
+            let kind_name: &str = kind.into();
+            entities.insert(kind_name.to_string(), cache.entity_ids(kind)?.len());
+        }
+
+        return Ok(Self {
+            tool: TOOL_NAME.to_string(),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            attack_version: cache.attack_version()?,
+            synced_at: synced_at.to_string(),
+            entities,
+        });
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for SyncManifest {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&["Field", "Value"]));
+        table.add_row(vec![
+            String::from("Generated by"),
+            format!("{} {}", self.tool, self.tool_version),
+        ]);
+        table.add_row(vec![
+            String::from("ATT&CK version"),
+            self.attack_version
+                .unwrap_or_else(|| String::from("unknown")),
+        ]);
+        table.add_row(vec![String::from("Synced at"), self.synced_at]);
+
+        for (kind, count) in self.entities {
+            table.add_row(vec![format!("Cached {}", kind), count.to_string()]);
+        }
+
+        return table;
+    }
+}
+
+/// Hex SHA-256 of the listings and entities of the dataset, the release left out.
+///
+/// The dataset lists the entity types in a fixed order and the IDs sorted, and the JSON
+/// objects are serialized with sorted keys, so the same content always hashes the same.
+pub fn content_hash(dataset: &Dataset) -> Result<String, error::Error> {
+    let digest = Sha256::digest(serde_json::to_vec(&(&dataset.listings, &dataset.entities))?);
+
+    return Ok(digest.iter().map(|byte| format!("{:02x}", byte)).collect());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack::techniques::{Technique, TechniquesTable};
+
+    fn test_cache(name: &str) -> Result<Cache, error::Error> {
+        let cache = crate::attack::cache::test_cache(&format!("provenance_{}", name));
+
+        cache.save_listing(
+            EntityKind::TECHNIQUE,
+            Some("enterprise"),
+            &TechniquesTable::default(),
+        )?;
+        cache.save_entity(
+            EntityKind::TECHNIQUE,
+            "T1610",
+            &Technique {
+                id: String::from("T1610"),
+                name: String::from("Deploy Container"),
+                ..Default::default()
+            },
+        )?;
+
+        return Ok(cache);
+    }
+
+    #[test]
+    fn test_provenance_from_cache() -> Result<(), error::Error> {
+        let cache = test_cache("from_cache")?;
+        cache.save_attack_version("v15.1")?;
+
+        let provenance = Provenance::from_cache(&cache, "2024-05-01T00:00:00.000Z")?;
+
+        assert_eq!(provenance.tool, "mitre_cli");
+        assert_eq!(provenance.attack_version, Some(String::from("v15.1")));
+        assert_eq!(
+            provenance.synced_at.keys().collect::<Vec<&String>>(),
+            vec!["techniques"]
+        );
+        assert_eq!(provenance.content_hash.len(), 64);
+        assert!(provenance
+            .to_lines()
+            .contains(&String::from("ATT&CK version: v15.1")));
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_manifest() -> Result<(), error::Error> {
+        let cache = test_cache("manifest")?;
+        cache.save_attack_version("v15.1")?;
+        cache.save_manifest(&SyncManifest::from_cache(
+            &cache,
+            "2024-05-01T00:00:00.000Z",
+        )?)?;
+
+        let manifest = cache.manifest()?.unwrap();
+
+        assert_eq!(manifest.attack_version, Some(String::from("v15.1")));
+        assert_eq!(manifest.synced_at, "2024-05-01T00:00:00.000Z");
+        assert_eq!(manifest.entities.get("techniques"), Some(&1));
+        assert_eq!(manifest.entities.get("groups"), Some(&0));
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_content_hash_tracks_content() -> Result<(), error::Error> {
+        let cache = test_cache("content_hash")?;
+        let hash = content_hash(&Dataset::from_cache("v15.1", &cache)?)?;
+
+        assert_eq!(hash, content_hash(&Dataset::from_cache("v14", &cache)?)?);
+
+        cache.save_entity(
+            EntityKind::TECHNIQUE,
+            "T1610",
+            &Technique {
+                id: String::from("T1610"),
+                name: String::from("Renamed"),
+                ..Default::default()
+            },
+        )?;
+
+        assert_ne!(hash, content_hash(&Dataset::from_cache("v15.1", &cache)?)?);
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+}
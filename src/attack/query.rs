@@ -0,0 +1,285 @@
+use std::cmp::Ordering;
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::error;
+
+/// Row filter, sort and pagination applied to a listing after it is loaded.
+#[derive(Debug, Default)]
+pub struct ListingQuery {
+    filter: Option<regex::Regex>,
+    sort_by: Option<String>,
+    offset: usize,
+    limit: Option<usize>,
+}
+
+impl ListingQuery {
+    /// `filter` is a case-insensitive regex, taken as a plain substring when it is not a valid
+    /// one. `sort_by` is a column name ("Data Source" or data_source).
+    pub fn new(filter: Option<&str>, sort_by: Option<&str>) -> Self {
+        return Self {
+            filter: filter.map(|pattern| {
+                regex::Regex::new(&format!("(?i){}", pattern)).unwrap_or_else(|_| {
+                    regex::Regex::new(&format!("(?i){}", regex::escape(pattern))).unwrap()
+                })
+            }),
+            sort_by: sort_by.map(column_key),
+            ..Default::default()
+        };
+    }
+
+    /// Keep `limit` rows (all of them when `None`) after skipping the first `offset` ones,
+    /// once filtered and sorted.
+    pub fn paginate(mut self, offset: usize, limit: Option<usize>) -> Self {
+        self.offset = offset;
+        self.limit = limit;
+
+        return self;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.filter.is_none()
+            && self.sort_by.is_none()
+            && self.offset == 0
+            && self.limit.is_none();
+    }
+
+    /// Rows of `listing` matching the filter, sorted by the column, then paginated.
+    ///
+    /// A row matches when any of its fields does. A row only matching through some of its
+    /// nested rows (sub-techniques) keeps those nested rows only.
+    pub fn apply<T>(&self, listing: T) -> Result<T, error::Error>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        if self.is_empty() {
+            return Ok(listing);
+        }
+
+        let mut rows = match serde_json::to_value(&listing)? {
+            Value::Array(rows) => rows,
+            _ => return Ok(listing),
+        };
+
+        if let Some(filter) = &self.filter {
+            rows = rows
+                .into_iter()
+                .filter_map(|row| filter_row(filter, row))
+                .collect();
+        }
+
+        if let Some(sort_by) = &self.sort_by {
+            let column = rows
+                .iter()
+                .filter_map(|row| row.as_object())
+                .flat_map(|row| row.keys())
+                .find(|key| column_key(key) == *sort_by)
+                .cloned();
+
+            match column {
+                Some(column) => rows.sort_by(|a, b| compare_values(&a[&column], &b[&column])),
+                None if rows.is_empty() => {}
+                None => {
+                    return Err(error::Error::InvalidValue(format!(
+                        "Unknown column {} (available: {})",
+                        sort_by,
+                        columns(&rows).join(", ")
+                    )))
+                }
+            };
+        }
+
+        let rows = rows
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect();
+
+        return Ok(serde_json::from_value(Value::Array(rows))?);
+    }
+}
+
+/// Column name as matched by `--sort-by` ("Data Source" is data_source).
+fn column_key(name: &str) -> String {
+    return name.trim().to_lowercase().replace([' ', '-'], "_");
+}
+
+fn columns(rows: &[Value]) -> Vec<String> {
+    let mut columns: Vec<String> = rows
+        .iter()
+        .filter_map(|row| row.as_object())
+        .flat_map(|row| row.keys().cloned())
+        .collect();
+    columns.sort();
+    columns.dedup();
+
+    return columns;
+}
+
+fn matches(filter: &regex::Regex, value: &Value) -> bool {
+    return match value {
+        Value::String(text) => filter.is_match(text),
+        Value::Array(values) => values.iter().any(|value| matches(filter, value)),
+        Value::Object(fields) => fields.values().any(|value| matches(filter, value)),
+        Value::Null => false,
+        value => filter.is_match(&value.to_string()),
+    };
+}
+
+/// The row when its own fields match, else the row with its matching nested rows only, if any.
+fn filter_row(filter: &regex::Regex, row: Value) -> Option<Value> {
+    let mut fields = match row {
+        Value::Object(fields) => fields,
+        row => return matches(filter, &row).then_some(row),
+    };
+
+    let own_match = fields
+        .values()
+        .filter(|value| !is_nested_rows(value))
+        .any(|value| matches(filter, value));
+
+    if own_match {
+        return Some(Value::Object(fields));
+    }
+
+    let mut nested_match = false;
+
+    for value in fields.values_mut() {
+        if let Value::Array(nested_rows) = value {
+            if nested_rows.iter().any(|nested_row| nested_row.is_object()) {
+                nested_rows.retain(|nested_row| matches(filter, nested_row));
+                nested_match |= !nested_rows.is_empty();
+            }
+        }
+    }
+
+    return nested_match.then_some(Value::Object(fields));
+}
+
+fn is_nested_rows(value: &Value) -> bool {
+    return value
+        .as_array()
+        .is_some_and(|values| values.iter().any(|value| value.is_object()));
+}
+
+/// Numbers numerically, everything else case-insensitively as text, missing values last.
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    return match (a, b) {
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::Null, _) => Ordering::Greater,
+        (_, Value::Null) => Ordering::Less,
+        (Value::Number(a), Value::Number(b)) => a
+            .as_f64()
+            .partial_cmp(&b.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Value::String(a), Value::String(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+        (a, b) => a
+            .to_string()
+            .to_lowercase()
+            .cmp(&b.to_string().to_lowercase()),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attack::techniques::{fetch_techniques, Domain},
+        fakers::FakeHttpReqwest,
+    };
+
+    fn enterprise_techniques() -> Result<crate::attack::techniques::TechniquesTable, error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default().set_success_response(
+            include_str!("html/attck/techniques/enterprise.html").to_string(),
+        );
+
+        return fetch_techniques(Domain::ENTERPRISE, &fake_reqwest);
+    }
+
+    #[test]
+    fn test_filter_keeps_matching_sub_techniques() -> Result<(), error::Error> {
+        let techniques = ListingQuery::new(Some("dynamic-link library injection"), None)
+            .apply(enterprise_techniques()?)?;
+
+        let injection = techniques
+            .0
+            .iter()
+            .find(|technique| technique.id == "T1055")
+            .unwrap();
+        assert_eq!(
+            injection
+                .sub_techniques
+                .as_ref()
+                .unwrap()
+                .iter()
+                .map(|sub_technique| sub_technique.name.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["Dynamic-link Library Injection"]
+        );
+        assert!(techniques.0.len() < enterprise_techniques()?.0.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_regex_and_sort() -> Result<(), error::Error> {
+        let techniques = ListingQuery::new(Some("^process (injection|discovery)$"), Some("Name"))
+            .apply(enterprise_techniques()?)?;
+
+        assert_eq!(
+            techniques
+                .0
+                .iter()
+                .map(|technique| technique.id.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["T1057", "T1055"]
+        );
+
+        let techniques =
+            ListingQuery::new(Some("C++"), Some("id")).apply(enterprise_techniques()?)?;
+        assert!(techniques
+            .0
+            .windows(2)
+            .all(|pair| pair[0].id.to_lowercase() <= pair[1].id.to_lowercase()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_paginate() -> Result<(), error::Error> {
+        let ids = |query: ListingQuery| -> Result<Vec<String>, error::Error> {
+            return Ok(query
+                .apply(enterprise_techniques()?)?
+                .0
+                .into_iter()
+                .map(|technique| technique.id)
+                .collect());
+        };
+        let sorted_ids = ids(ListingQuery::new(None, Some("id")))?;
+
+        assert_eq!(
+            ids(ListingQuery::new(None, Some("id")).paginate(2, Some(3)))?,
+            sorted_ids[2..5].to_vec()
+        );
+        assert_eq!(
+            ids(ListingQuery::new(None, Some("id")).paginate(sorted_ids.len() - 1, None))?,
+            vec![sorted_ids.last().unwrap().clone()]
+        );
+        assert!(ids(ListingQuery::default().paginate(sorted_ids.len(), Some(10)))?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_unknown_column() -> Result<(), error::Error> {
+        match ListingQuery::new(None, Some("severity")).apply(enterprise_techniques()?) {
+            Err(error::Error::InvalidValue(message)) => {
+                assert!(message.starts_with("Unknown column severity (available: description, id"))
+            }
+            sorted => panic!("Unexpected result {:?}", sorted.map(|table| table.0.len())),
+        }
+
+        Ok(())
+    }
+}
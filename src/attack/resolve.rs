@@ -0,0 +1,107 @@
+use serde_json::Value;
+
+use crate::error;
+
+use super::cache::{Cache, EntityKind};
+
+/// Replace the IDs of the related tables of a described entity (JSON) with the cached entities
+/// they refer to, returning the number of resolved IDs.
+///
+/// Only the rows directly held by the entity are resolved, the nested entities keep their own
+/// rows as IDs. Entities missing from the cache are left as IDs.
+pub fn resolve_related(cache: &Cache, entity: &mut Value) -> Result<usize, error::Error> {
+    let mut resolved = 0;
+
+    if let Value::Object(fields) = entity {
+        for field in fields.values_mut() {
+            resolved += resolve_rows(cache, field)?;
+        }
+    }
+
+    return Ok(resolved);
+}
+
+/// Resolve the `id` of every object of the (possibly nested) lists of `value`.
+fn resolve_rows(cache: &Cache, value: &mut Value) -> Result<usize, error::Error> {
+    let mut resolved = 0;
+
+    match value {
+        Value::Array(rows) => {
+            for row in rows {
+                resolved += resolve_rows(cache, row)?;
+            }
+        }
+        Value::Object(row) => {
+            if let Some(Value::String(id)) = row.get("id") {
+                if let Some(related) = load_related(cache, id)? {
+                    row.insert(String::from("id"), related);
+                    resolved += 1;
+                }
+            }
+
+            for (key, field) in row.iter_mut() {
+                if key != "id" && field.is_array() {
+                    resolved += resolve_rows(cache, field)?;
+                }
+            }
+        }
+        _ => {}
+    };
+
+    return Ok(resolved);
+}
+
+fn load_related(cache: &Cache, id: &str) -> Result<Option<Value>, error::Error> {
+    return match EntityKind::from_id(id) {
+        Some(kind) => cache.load_entity(kind, id),
+        None => Ok(None),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attack::{mitigations::Mitigation, techniques::fetch_technique},
+        fakers::FakeHttpReqwest,
+    };
+
+    #[test]
+    fn test_resolve_related() -> Result<(), error::Error> {
+        let cache = crate::attack::cache::test_cache("resolve");
+        cache.save_entity(
+            EntityKind::MITIGATION,
+            "M1047",
+            &Mitigation {
+                id: String::from("M1047"),
+                name: String::from("Audit"),
+                ..Default::default()
+            },
+        )?;
+        let technique = fetch_technique(
+            "T1610",
+            &FakeHttpReqwest::default().set_success_response(
+                include_str!("html/attck/techniques/enterprise_deploy_container.html").to_string(),
+            ),
+        )?;
+        let mut technique_json = serde_json::to_value(&technique)?;
+
+        assert_eq!(resolve_related(&cache, &mut technique_json)?, 1);
+
+        let mitigations = technique_json["mitigations"].as_array().unwrap();
+        let resolved_mitigation = mitigations
+            .iter()
+            .find(|mitigation| mitigation["id"].is_object())
+            .unwrap();
+        assert_eq!(resolved_mitigation["id"]["name"], "Audit");
+        assert!(mitigations
+            .iter()
+            .filter(|mitigation| mitigation["id"] != resolved_mitigation["id"])
+            .all(|mitigation| mitigation["id"].is_string()));
+        assert_eq!(technique_json["id"], "T1610");
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+}
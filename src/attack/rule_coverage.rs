@@ -0,0 +1,437 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+use serde::Serialize;
+
+use crate::error;
+
+use super::{
+    cache::EntityKind,
+    layer::{Layer, LayerGradient},
+    tactics::Tactic,
+};
+
+/// Detection rules of the organization mapped to the techniques they detect.
+///
+/// CSV with the rule name in the first column and the technique IDs in the second one,
+/// several IDs being separated by `;`, `|` or spaces (`Suspicious PowerShell,T1059.001`).
+/// A header on the first line and the lines starting with `#` are skipped.
+#[derive(Debug, Default)]
+pub struct RuleMappings {
+    /// Upper-cased technique ID -> rules detecting it.
+    pub rules: BTreeMap<String, Vec<String>>,
+}
+
+impl RuleMappings {
+    pub fn load(path: &Path) -> Result<Self, error::Error> {
+        return Self::parse(&std::fs::read_to_string(path)?);
+    }
+
+    pub fn parse(mappings: &str) -> Result<Self, error::Error> {
+        let mut parsed = Self::default();
+
+        for (inx, line) in mappings.lines().enumerate() {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+
+            let fields = crate::csv_record(line);
+            let (rule, techniques) = match fields.as_slice() {
+                [rule, techniques, ..] => (rule.trim(), techniques),
+                _ => {
+                    return Err(error::Error::Parse(format!(
+                        "Line {} of the mappings: expected 'rule,technique IDs'",
+                        inx + 1
+                    )))
+                }
+            };
+            let technique_ids: Vec<&str> = techniques
+                .split(|c: char| c == ';' || c == '|' || c.is_whitespace())
+                .filter(|id| !id.is_empty())
+                .collect();
+
+            if let Some(invalid_id) = technique_ids
+                .iter()
+                .find(|id| EntityKind::from_id(id) != Some(EntityKind::TECHNIQUE))
+            {
+                // The header names its columns instead of giving IDs.
+                if inx == 0 {
+                    continue;
+                }
+
+                return Err(error::Error::Parse(format!(
+                    "Line {} of the mappings: {} is not a technique ID",
+                    inx + 1,
+                    invalid_id
+                )));
+            }
+
+            for technique_id in technique_ids {
+                let rules = parsed.rules.entry(technique_id.to_uppercase()).or_default();
+
+                if !rules.iter().any(|mapped| mapped == rule) {
+                    rules.push(rule.to_string());
+                }
+            }
+        }
+
+        if parsed.is_empty() {
+            return Err(error::Error::Parse(String::from(
+                "No rule to technique mapping in the mappings",
+            )));
+        }
+
+        return Ok(parsed);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.rules.is_empty();
+    }
+
+    /// Number of distinct rules of the mappings.
+    pub fn rule_count(&self) -> usize {
+        return self
+            .rules
+            .values()
+            .flatten()
+            .collect::<std::collections::BTreeSet<&String>>()
+            .len();
+    }
+
+    /// Rules detecting the technique itself.
+    pub fn rules_of(&self, technique_id: &str) -> &[String] {
+        return self
+            .rules
+            .get(&technique_id.to_uppercase())
+            .map_or(&[], |rules| rules.as_slice());
+    }
+}
+
+/// Detection rule coverage of the techniques of a tactic.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct TacticScoreRow {
+    pub id: String,
+    pub name: String,
+    /// Techniques detected by a rule, on their own or through a sub-technique.
+    pub covered: usize,
+    pub techniques: usize,
+}
+
+impl TacticScoreRow {
+    pub fn percentage(&self) -> f64 {
+        if self.techniques == 0 {
+            return 0.0;
+        }
+
+        return self.covered as f64 * 100.0 / self.techniques as f64;
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for TacticScoreRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(&self.id))
+            .add_cell(comfy_table::Cell::new(&self.name))
+            .add_cell(comfy_table::Cell::new(self.covered))
+            .add_cell(comfy_table::Cell::new(self.techniques))
+            .add_cell(comfy_table::Cell::new(format!("{:.1}%", self.percentage())));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct TacticScoresTable(pub Vec<TacticScoreRow>);
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for TacticScoresTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&[
+            "ID",
+            "Tactic",
+            "Covered",
+            "Techniques",
+            "Coverage",
+        ]));
+
+        for tactic in self.0 {
+            table.add_row(tactic);
+        }
+
+        return table;
+    }
+}
+
+/// Technique without any detection rule, nor for its sub-techniques.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct RuleGapRow {
+    pub id: String,
+    pub name: String,
+    pub tactics: Vec<String>,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for RuleGapRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(self.name))
+            .add_cell(comfy_table::Cell::new(self.tactics.join(", ")));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct RuleGapsTable(pub Vec<RuleGapRow>);
+
+impl RuleGapsTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for RuleGapsTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&["ID", "Name", "Tactics"]));
+
+        for gap in self.0 {
+            table.add_row(gap);
+        }
+
+        return table;
+    }
+}
+
+/// Coverage of the techniques of a domain by the mapped detection rules.
+#[derive(Debug, Default, Serialize)]
+pub struct RuleCoverageScore {
+    pub tactics: TacticScoresTable,
+    pub gaps: RuleGapsTable,
+    /// Mapped technique IDs missing from the tactics of the domain.
+    pub unknown: Vec<String>,
+    /// Number of rules of every technique and sub-technique of the domain.
+    #[serde(skip)]
+    rule_counts: BTreeMap<String, usize>,
+}
+
+impl RuleCoverageScore {
+    /// Score the techniques of the tactics (those of one domain, in the kill-chain order). A
+    /// technique is covered when a rule detects it or one of its sub-techniques.
+    pub fn build(tactics: &[Tactic], mappings: &RuleMappings) -> Self {
+        let mut score = Self::default();
+        let mut gaps: BTreeMap<String, RuleGapRow> = BTreeMap::new();
+
+        for tactic in tactics {
+            let mut row = TacticScoreRow {
+                id: tactic.id.clone(),
+                name: tactic.name.clone(),
+                ..Default::default()
+            };
+
+            for technique in tactic
+                .techniques
+                .iter()
+                .flat_map(|techniques| &techniques.0)
+            {
+                let technique_rules = mappings.rules_of(&technique.id).len();
+                let mut covered = technique_rules > 0;
+                score
+                    .rule_counts
+                    .insert(technique.id.to_uppercase(), technique_rules);
+
+                for sub_technique in technique.sub_techniques.iter().flatten() {
+                    let sub_technique_id = format!("{}{}", technique.id, sub_technique.id);
+                    let sub_technique_rules = mappings.rules_of(&sub_technique_id).len();
+
+                    covered |= sub_technique_rules > 0;
+                    score
+                        .rule_counts
+                        .insert(sub_technique_id.to_uppercase(), sub_technique_rules);
+                }
+
+                row.techniques += 1;
+
+                if covered {
+                    row.covered += 1;
+                } else {
+                    gaps.entry(technique.id.to_uppercase())
+                        .or_insert_with(|| RuleGapRow {
+                            id: technique.id.clone(),
+                            name: technique.name.clone(),
+                            tactics: Vec::new(),
+                        })
+                        .tactics
+                        .push(tactic.name.clone());
+                }
+            }
+
+            score.tactics.0.push(row);
+        }
+
+        score.gaps = RuleGapsTable(gaps.into_values().collect());
+        score.unknown = mappings
+            .rules
+            .keys()
+            .filter(|technique_id| !score.rule_counts.contains_key(*technique_id))
+            .cloned()
+            .collect();
+
+        return score;
+    }
+
+    /// Distinct techniques covered, over all the tactics.
+    pub fn covered_techniques(&self) -> (usize, usize) {
+        let gap_ids: BTreeSet<&str> = self.gaps.0.iter().map(|gap| gap.id.as_str()).collect();
+        let technique_ids: BTreeSet<&String> = self
+            .rule_counts
+            .keys()
+            .filter(|technique_id| !technique_id.contains('.'))
+            .collect();
+
+        return (
+            technique_ids
+                .iter()
+                .filter(|technique_id| !gap_ids.contains(technique_id.as_str()))
+                .count(),
+            technique_ids.len(),
+        );
+    }
+
+    /// Navigator layer of the `domain` techniques scored by their number of rules, from red
+    /// (no rule) to green (the most rules).
+    pub fn to_layer(
+        &self,
+        domain: &str,
+        attack_version: Option<&str>,
+    ) -> Result<Layer, error::Error> {
+        let scores: Vec<(String, u32, String)> = self
+            .rule_counts
+            .iter()
+            .map(|(technique_id, rules)| {
+                (
+                    technique_id.clone(),
+                    *rules as u32,
+                    format!("{} detection rules", rules),
+                )
+            })
+            .collect();
+        let mut layer = Layer::heatmap(
+            "Detection rule coverage",
+            "Techniques scored by the number of mapped detection rules",
+            domain,
+            &scores,
+            attack_version,
+        )?;
+        layer.gradient = LayerGradient {
+            colors: vec![
+                String::from("#ff6666"),
+                String::from("#ffe766"),
+                String::from("#8ec843"),
+            ],
+            ..layer.gradient
+        };
+
+        return Ok(layer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack::techniques::{SubTechniqueRow, TechniqueRow, TechniquesTable};
+
+    fn tactic(id: &str, name: &str, technique_ids: &[&str]) -> Tactic {
+        return Tactic {
+            id: id.to_string(),
+            name: name.to_string(),
+            techniques: Some(TechniquesTable(
+                technique_ids
+                    .iter()
+                    .map(|technique_id| TechniqueRow {
+                        id: technique_id.to_string(),
+                        name: format!("Technique {}", technique_id),
+                        sub_techniques: (*technique_id == "T1059").then(|| {
+                            vec![SubTechniqueRow {
+                                id: String::from(".001"),
+                                name: String::from("PowerShell"),
+                                ..Default::default()
+                            }]
+                        }),
+                        ..Default::default()
+                    })
+                    .collect(),
+            )),
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn test_parse_mappings() -> Result<(), error::Error> {
+        let mappings = RuleMappings::parse(
+            "rule,technique\n\
+             # Sigma rules\n\
+             Encoded PowerShell,T1059.001\n\
+             \"Dump LSASS, procdump\",t1003.001;T1003\n\
+             Encoded PowerShell,T1059.001\n",
+        )?;
+
+        assert_eq!((mappings.rules.len(), mappings.rule_count()), (3, 2));
+        assert_eq!(mappings.rules_of("T1059.001"), ["Encoded PowerShell"]);
+        assert_eq!(mappings.rules_of("T1003.001"), ["Dump LSASS, procdump"]);
+        assert!(RuleMappings::parse("Rule,T1059\nOther rule,G0016\n").is_err());
+        assert!(RuleMappings::parse("T1059\n").is_err());
+        assert!(RuleMappings::parse("# Sigma rules\nrule,technique\nOther rule,T1059\n").is_err());
+        assert!(RuleMappings::parse("Rule,T1059\nOther rule,not mapped\n").is_err());
+        assert!(RuleMappings::parse("rule,technique\n").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rule_coverage_score() -> Result<(), error::Error> {
+        let tactics = vec![
+            tactic("TA0002", "Execution", &["T1059", "T1203"]),
+            tactic("TA0006", "Credential Access", &["T1003"]),
+        ];
+        let mappings = RuleMappings::parse(
+            "Encoded PowerShell,T1059.001\nKerberoasting,T1558.003\nLSASS access,T1003\n",
+        )?;
+
+        let score = RuleCoverageScore::build(&tactics, &mappings);
+
+        assert_eq!(
+            score
+                .tactics
+                .0
+                .iter()
+                .map(|tactic| (tactic.id.as_str(), tactic.covered, tactic.techniques))
+                .collect::<Vec<_>>(),
+            vec![("TA0002", 1, 2), ("TA0006", 1, 1)]
+        );
+        assert_eq!(score.tactics.0[0].percentage(), 50.0);
+        assert_eq!(
+            score.gaps.0,
+            vec![RuleGapRow {
+                id: String::from("T1203"),
+                name: String::from("Technique T1203"),
+                tactics: vec![String::from("Execution")],
+            }]
+        );
+        assert_eq!(score.unknown, vec!["T1558.003"]);
+        assert_eq!(score.covered_techniques(), (2, 3));
+
+        let layer_json = serde_json::to_value(score.to_layer("enterprise", Some("v15"))?)?;
+        assert_eq!(layer_json["gradient"]["maxValue"], 1);
+        assert_eq!(layer_json["gradient"]["colors"][0], "#ff6666");
+        assert_eq!(layer_json["techniques"].as_array().unwrap().len(), 4);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,173 @@
+use std::path::{Path, PathBuf};
+
+use crate::error;
+
+use super::techniques::{technique_url, Technique};
+
+/// Sections left for the SOC team to fill, with their prompts.
+const TEMPLATE_SECTIONS: [(&'static str, &'static str); 4] = [
+    (
+        "Triage",
+        "Alert sources, queries and the conditions confirming a true positive.",
+    ),
+    (
+        "Investigation",
+        "Artifacts to collect and questions to answer (scope, user, host, timeline).",
+    ),
+    (
+        "Containment and remediation",
+        "Actions to take, and who approves them.",
+    ),
+    ("Escalation", "When and to whom the incident is escalated."),
+];
+
+const NOT_DOCUMENTED: &'static str = "_None documented by ATT&CK._\n";
+
+/// Markdown runbook template of the technique, pre-filled with its ATT&CK detection
+/// guidance, mitigations and procedure examples.
+pub fn build_runbook(technique: &Technique) -> String {
+    let mut runbook = format!(
+        "# {} {} - Runbook\n\n- ATT&CK: {}\n",
+        technique.id,
+        technique.name,
+        technique_url(&technique.id)
+    );
+
+    if let Some(domain) = &technique.domain {
+        runbook.push_str(&format!("- Domain: {}\n", domain));
+    }
+
+    runbook.push_str(&format!("\n## Summary\n\n{}\n", technique.description));
+
+    runbook.push_str("\n## Detection\n\n");
+    match &technique.detections {
+        Some(detections) if !detections.0.is_empty() => runbook.push_str(
+            &detections
+                .0
+                .iter()
+                .map(|detection| {
+                    format!(
+                        "### {} {}: {}\n\n{}\n",
+                        detection.id,
+                        detection.data_source,
+                        detection.data_comp,
+                        detection.detects.as_deref().unwrap_or_default()
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join("\n"),
+        ),
+        _ => runbook.push_str(NOT_DOCUMENTED),
+    };
+
+    runbook.push_str("\n## Mitigations\n\n");
+    match &technique.mitigations {
+        Some(mitigations) if !mitigations.0.is_empty() => {
+            for mitigation in &mitigations.0 {
+                runbook.push_str(&format!(
+                    "- **{} {}**: {}\n",
+                    mitigation.id, mitigation.name, mitigation.description
+                ));
+            }
+        }
+        _ => runbook.push_str(NOT_DOCUMENTED),
+    };
+
+    runbook.push_str("\n## Procedure examples\n\n");
+    match &technique.procedures {
+        Some(procedures) if !procedures.0.is_empty() => {
+            for procedure in &procedures.0 {
+                let procedure_type: String = procedure.procedure_type.clone().into();
+                runbook.push_str(&format!(
+                    "- **{} {}** ({}): {}\n",
+                    procedure.id, procedure.name, procedure_type, procedure.description
+                ));
+            }
+        }
+        _ => runbook.push_str(NOT_DOCUMENTED),
+    };
+
+    for (title, prompt) in TEMPLATE_SECTIONS {
+        runbook.push_str(&format!("\n## {}\n\n- [ ] _{}_\n", title, prompt));
+    }
+
+    return runbook;
+}
+
+/// Write the runbook of the technique into `out_dir` (`<ID>.md`), returning its path, or
+/// `None` when it already exists and `overwrite` is not set, as it may have been edited.
+pub fn write_runbook(
+    technique: &Technique,
+    out_dir: &Path,
+    overwrite: bool,
+) -> Result<Option<PathBuf>, error::Error> {
+    let path = out_dir.join(format!("{}.md", technique.id.to_uppercase()));
+
+    if path.exists() && !overwrite {
+        return Ok(None);
+    }
+
+    std::fs::create_dir_all(out_dir)?;
+    std::fs::write(&path, build_runbook(technique))?;
+
+    return Ok(Some(path));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{attack::techniques::fetch_technique, fakers::FakeHttpReqwest};
+
+    fn deploy_container() -> Result<Technique, error::Error> {
+        return fetch_technique(
+            "T1610",
+            &FakeHttpReqwest::default().set_success_response(
+                include_str!("html/attck/techniques/enterprise_deploy_container.html").to_string(),
+            ),
+        );
+    }
+
+    #[test]
+    fn test_build_runbook() -> Result<(), error::Error> {
+        let runbook = build_runbook(&deploy_container()?);
+
+        assert!(runbook.starts_with(
+            "# T1610 Deploy Container - Runbook\n\n\
+             - ATT&CK: https://attack.mitre.org/techniques/T1610\n"
+        ));
+        assert!(runbook.contains("\n### DS0032 Container: Container Creation\n"));
+        assert!(runbook.contains("\n- **M1047 Audit**: "));
+        assert!(runbook.contains("\n## Procedure examples\n\n- **"));
+        assert!(runbook
+            .ends_with("\n## Escalation\n\n- [ ] _When and to whom the incident is escalated._\n"));
+
+        let runbook = build_runbook(&Technique {
+            id: String::from("T1610"),
+            ..Default::default()
+        });
+        assert!(runbook.contains("\n## Mitigations\n\n_None documented by ATT&CK._\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_runbook_keeps_existing() -> Result<(), error::Error> {
+        let out_dir = std::env::temp_dir().join("mitre_cli_runbook");
+        let _ = std::fs::remove_dir_all(&out_dir);
+        let technique = deploy_container()?;
+
+        let path = write_runbook(&technique, &out_dir, false)?.unwrap();
+        assert_eq!(path, out_dir.join("T1610.md"));
+
+        std::fs::write(&path, "Edited")?;
+        assert_eq!(write_runbook(&technique, &out_dir, false)?, None);
+        assert_eq!(std::fs::read_to_string(&path)?, "Edited");
+
+        assert!(write_runbook(&technique, &out_dir, true)?.is_some());
+        assert_ne!(std::fs::read_to_string(&path)?, "Edited");
+
+        std::fs::remove_dir_all(&out_dir)?;
+
+        Ok(())
+    }
+}
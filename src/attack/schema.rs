@@ -0,0 +1,320 @@
+use std::{collections::BTreeSet, fs, path::Path};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::{error, storage};
+
+use super::{
+    cache::{Cache, EntityKind, DOMAINS},
+    data_sources::{DataSource, DataSourcesTable},
+    groups::{Group, GroupsTable},
+    mitigations::{Mitigation, MitigationTable},
+    software::{Software, SoftwareTable},
+    tactics::{Tactic, TacticsTable},
+    techniques::{Technique, TechniquesTable},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SchemaStatus {
+    /// Loads into the current model, but was written by a release with other fields.
+    OUTDATED,
+    /// Does not load into the current model.
+    INCOMPATIBLE,
+}
+
+impl Into<&'static str> for SchemaStatus {
+    fn into(self) -> &'static str {
+        match self {
+            Self::OUTDATED => "Outdated",
+            Self::INCOMPATIBLE => "Incompatible",
+        }
+    }
+}
+
+/// Cached file not matching the current model.
+#[derive(Debug, Serialize)]
+pub struct SchemaIssueRow {
+    pub kind: String,
+    /// Entity ID, or `<domain> listing` for a listing.
+    pub file: String,
+    pub path: String,
+    pub status: SchemaStatus,
+    /// Missing and unknown fields of an outdated file, the error of an incompatible one.
+    pub details: String,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for SchemaIssueRow {
+    fn into(self) -> comfy_table::Row {
+        let status: &str = self.status.into();
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.kind))
+            .add_cell(comfy_table::Cell::new(self.file))
+            .add_cell(comfy_table::Cell::new(status))
+            .add_cell(comfy_table::Cell::new(self.details));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SchemaIssuesTable(pub Vec<SchemaIssueRow>);
+
+impl SchemaIssuesTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+
+    pub fn count(&self, status: SchemaStatus) -> usize {
+        return self.0.iter().filter(|issue| issue.status == status).count();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for SchemaIssuesTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&["Type", "File", "Status", "Details"]));
+
+        for issue in self.0 {
+            table.add_row(issue);
+        }
+
+        return table;
+    }
+}
+
+/// Fields of `current` missing from `stored`, and those of `stored` unknown to `current`,
+/// as dotted paths (`[]` standing for the array items).
+fn diff_fields(
+    stored: &Value,
+    current: &Value,
+    path: &str,
+    missing: &mut BTreeSet<String>,
+    unknown: &mut BTreeSet<String>,
+) {
+    match (stored, current) {
+        (Value::Object(stored), Value::Object(current)) => {
+            let field_path = |key: &str| {
+                if path.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{}.{}", path, key)
+                }
+            };
+
+            for (key, current_value) in current {
+                match stored.get(key) {
+                    Some(stored_value) => diff_fields(
+                        stored_value,
+                        current_value,
+                        &field_path(key),
+                        missing,
+                        unknown,
+                    ),
+                    None => {
+                        missing.insert(field_path(key));
+                    }
+                }
+            }
+
+            for key in stored.keys().filter(|key| !current.contains_key(*key)) {
+                unknown.insert(field_path(key));
+            }
+        }
+        (Value::Array(stored), Value::Array(current)) => {
+            for (stored_item, current_item) in stored.iter().zip(current) {
+                diff_fields(
+                    stored_item,
+                    current_item,
+                    &format!("{}[]", path),
+                    missing,
+                    unknown,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Load the file into `T`, rewriting it with the current fields if `migrate` is set.
+fn validate_as<T: Serialize + DeserializeOwned>(
+    path: &Path,
+    migrate: bool,
+) -> Result<Option<(SchemaStatus, String)>, error::Error> {
+    let stored: Value = match serde_json::from_str(&fs::read_to_string(path)?) {
+        Ok(stored) => stored,
+        Err(err) => return Ok(Some((SchemaStatus::INCOMPATIBLE, err.to_string()))),
+    };
+    let current = match serde_json::from_value::<T>(stored.clone()) {
+        Ok(entity) => serde_json::to_value(entity)?,
+        Err(err) => return Ok(Some((SchemaStatus::INCOMPATIBLE, err.to_string()))),
+    };
+    let (mut missing, mut unknown) = (BTreeSet::new(), BTreeSet::new());
+    diff_fields(&stored, &current, "", &mut missing, &mut unknown);
+
+    if missing.is_empty() && unknown.is_empty() {
+        return Ok(None);
+    }
+
+    if migrate {
+        storage::save_json(path, &current)?;
+    }
+
+    let mut details = Vec::new();
+
+    if !missing.is_empty() {
+        details.push(format!(
+            "missing: {}",
+            missing.into_iter().collect::<Vec<String>>().join(", ")
+        ));
+    }
+
+    if !unknown.is_empty() {
+        details.push(format!(
+            "unknown: {}",
+            unknown.into_iter().collect::<Vec<String>>().join(", ")
+        ));
+    }
+
+    return Ok(Some((SchemaStatus::OUTDATED, details.join("; "))));
+}
+
+fn validate_listing(
+    kind: EntityKind,
+    path: &Path,
+    migrate: bool,
+) -> Result<Option<(SchemaStatus, String)>, error::Error> {
+    return match kind {
+        EntityKind::TACTIC => validate_as::<TacticsTable>(path, migrate),
+        EntityKind::TECHNIQUE => validate_as::<TechniquesTable>(path, migrate),
+        EntityKind::MITIGATION => validate_as::<MitigationTable>(path, migrate),
+        EntityKind::SOFTWARE => validate_as::<SoftwareTable>(path, migrate),
+        EntityKind::GROUP => validate_as::<GroupsTable>(path, migrate),
+        EntityKind::DATASOURCE => validate_as::<DataSourcesTable>(path, migrate),
+    };
+}
+
+fn validate_entity(
+    kind: EntityKind,
+    path: &Path,
+    migrate: bool,
+) -> Result<Option<(SchemaStatus, String)>, error::Error> {
+    return match kind {
+        EntityKind::TACTIC => validate_as::<Tactic>(path, migrate),
+        EntityKind::TECHNIQUE => validate_as::<Technique>(path, migrate),
+        EntityKind::MITIGATION => validate_as::<Mitigation>(path, migrate),
+        EntityKind::SOFTWARE => validate_as::<Software>(path, migrate),
+        EntityKind::GROUP => validate_as::<Group>(path, migrate),
+        EntityKind::DATASOURCE => validate_as::<DataSource>(path, migrate),
+    };
+}
+
+/// Cached listings and entities not matching the current models. With `migrate`, the
+/// outdated files are rewritten with the current fields (the new ones left to their default
+/// until the entity is synced again); the incompatible files are left as is.
+pub fn validate_cache(cache: &Cache, migrate: bool) -> Result<SchemaIssuesTable, error::Error> {
+    let mut issues = SchemaIssuesTable::default();
+
+    for kind in EntityKind::ALL {
+        let kind_name: &str = kind.into();
+        let domains: Vec<Option<&str>> = if kind.has_domain() {
+            DOMAINS.iter().map(|domain| Some(*domain)).collect()
+        } else {
+            vec![None]
+        };
+
+        for domain in domains {
+            let path = cache.listing_path(kind, domain);
+
+            if !path.exists() {
+                continue;
+            }
+
+            if let Some((status, details)) = validate_listing(kind, &path, migrate)? {
+                issues.0.push(SchemaIssueRow {
+                    kind: kind_name.to_string(),
+                    file: format!("{} listing", domain.unwrap_or("index")),
+                    path: path.display().to_string(),
+                    status,
+                    details,
+                });
+            }
+        }
+
+        for id in cache.entity_ids(kind)? {
+            let path = cache.entity_path(kind, &id);
+
+            if let Some((status, details)) = validate_entity(kind, &path, migrate)? {
+                issues.0.push(SchemaIssueRow {
+                    kind: kind_name.to_string(),
+                    file: id,
+                    path: path.display().to_string(),
+                    status,
+                    details,
+                });
+            }
+        }
+    }
+
+    return Ok(issues);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_cache() -> Result<(), error::Error> {
+        let cache = crate::attack::cache::test_cache("schema");
+
+        cache.save_entity(
+            EntityKind::TECHNIQUE,
+            "T1610",
+            &Technique {
+                id: String::from("T1610"),
+                ..Default::default()
+            },
+        )?;
+        std::fs::write(
+            cache.entity_path(EntityKind::TECHNIQUE, "T1611"),
+            r#"{"id": "T1611", "name": "Escape to Host", "description": "", "procedures": null,
+                "mitigations": null, "detections": null, "permissions": []}"#,
+        )?;
+        std::fs::write(
+            cache.entity_path(EntityKind::TECHNIQUE, "T1612"),
+            r#"{"id": "T1612", "name": 1612}"#,
+        )?;
+
+        let issues = validate_cache(&cache, false)?;
+
+        assert_eq!(
+            issues
+                .0
+                .iter()
+                .map(|issue| (issue.file.as_str(), issue.status))
+                .collect::<Vec<(&str, SchemaStatus)>>(),
+            vec![
+                ("T1611", SchemaStatus::OUTDATED),
+                ("T1612", SchemaStatus::INCOMPATIBLE)
+            ]
+        );
+        assert_eq!(
+            issues.0[0].details,
+            "missing: contributors, deprecated, detection_notes, domain, modified, parent, \
+             platforms, references, revoked_by, sub_techniques, url; unknown: permissions"
+        );
+
+        validate_cache(&cache, true)?;
+
+        let issues = validate_cache(&cache, false)?;
+        assert_eq!(issues.count(SchemaStatus::OUTDATED), 0);
+        assert_eq!(issues.count(SchemaStatus::INCOMPATIBLE), 1);
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,907 @@
+use serde::Serialize;
+
+use crate::error;
+
+use super::{
+    cache::{Cache, EntityKind, Snapshot, DOMAINS},
+    data_sources::DataSourcesTable,
+    groups::GroupsTable,
+    mitigations::MitigationTable,
+    names,
+    software::SoftwareTable,
+    tactics::{Tactic, TacticsTable},
+    techniques::{self, domain::DomainTechniquesTable, TechniquePlatforms, TechniquesTable},
+};
+
+/// Characters of context kept on each side of the keyword in the snippets.
+const SNIPPET_CONTEXT: usize = 40;
+
+/// Field of the entity matching the searched term, the best first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchedField {
+    ID,
+    NAME,
+    ALIAS,
+    DESCRIPTION,
+}
+
+impl Into<&'static str> for MatchedField {
+    fn into(self) -> &'static str {
+        return match self {
+            Self::ID => "id",
+            Self::NAME => "name",
+            Self::ALIAS => "alias",
+            Self::DESCRIPTION => "description",
+        };
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResultRow {
+    /// Entity type ("techniques", "groups", ...).
+    pub kind: String,
+    pub id: String,
+    pub name: String,
+    pub matched: MatchedField,
+    pub description: String,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for SearchResultRow {
+    fn into(self) -> comfy_table::Row {
+        let matched: &str = self.matched.into();
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.kind))
+            .add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(self.name))
+            .add_cell(comfy_table::Cell::new(matched))
+            .add_cell(comfy_table::Cell::new(self.description));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SearchResultsTable(pub Vec<SearchResultRow>);
+
+impl SearchResultsTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for SearchResultsTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&[
+            "Type",
+            "ID",
+            "Name",
+            "Matched",
+            "Description",
+        ]));
+
+        for result in self.0 {
+            table.add_row(result);
+        }
+
+        return table;
+    }
+}
+
+/// Restriction of a search to the techniques of tactics and platforms, checked against the
+/// cached techniques and tactics.
+#[derive(Debug, Default)]
+pub struct SearchFilter {
+    /// Platforms (any of them, case insensitive) the techniques apply to.
+    pub platforms: Vec<String>,
+    /// Tactics (any of them) listing the techniques, given by ID or name.
+    pub tactics: Vec<String>,
+}
+
+impl SearchFilter {
+    pub fn is_empty(&self) -> bool {
+        return self.platforms.is_empty() && self.tactics.is_empty();
+    }
+}
+
+/// Searchable fields of a listed entity.
+struct Entry {
+    kind: EntityKind,
+    id: String,
+    name: String,
+    aliases: Vec<String>,
+    description: String,
+}
+
+impl Entry {
+    fn new(kind: EntityKind, id: String, name: String, description: String) -> Self {
+        return Self {
+            kind,
+            id,
+            name,
+            aliases: Vec::new(),
+            description,
+        };
+    }
+
+    /// Best field containing every word, or all the fields together for the words spread
+    /// over them (counted as a description match).
+    fn matched_field(&self, term: &str, words: &[String]) -> Option<MatchedField> {
+        let contains_words = |text: &str| {
+            let text = text.to_lowercase();
+            return words.iter().all(|word| text.contains(word));
+        };
+
+        if self.id.eq_ignore_ascii_case(term.trim()) {
+            return Some(MatchedField::ID);
+        }
+
+        if contains_words(&self.name) {
+            return Some(MatchedField::NAME);
+        }
+
+        if self.aliases.iter().any(|alias| contains_words(alias)) {
+            return Some(MatchedField::ALIAS);
+        }
+
+        let all_fields = format!(
+            "{} {} {} {}",
+            self.id,
+            self.name,
+            self.aliases.join(" "),
+            self.description
+        );
+
+        if contains_words(&all_fields) {
+            return Some(MatchedField::DESCRIPTION);
+        }
+
+        return None;
+    }
+}
+
+/// Entities of every type matching every word of `term` (case insensitive), searched in
+/// the cached listings of every domain: ID matches first, then names (exact ones first),
+/// aliases and descriptions.
+///
+/// A non-empty `filter` keeps only the techniques it selects, the term being then optional.
+pub fn search_cache(
+    cache: &Cache,
+    term: &str,
+    filter: &SearchFilter,
+) -> Result<SearchResultsTable, error::Error> {
+    let words: Vec<String> = term.split_whitespace().map(str::to_lowercase).collect();
+    let mut synced = false;
+    let mut results: Vec<SearchResultRow> = Vec::new();
+
+    if words.is_empty() && filter.is_empty() {
+        return Err(error::Error::InvalidValue(String::from(
+            "The search term is empty",
+        )));
+    }
+
+    let tactic_technique_ids = tactic_technique_ids(cache, &filter.tactics)?;
+
+    for kind in EntityKind::ALL {
+        let entries = match listing_entries(cache, kind)? {
+            Some(entries) => entries,
+            None => continue,
+        };
+        synced = true;
+
+        for entry in entries {
+            let already_found = results
+                .iter()
+                .any(|result| result.id.eq_ignore_ascii_case(&entry.id));
+
+            if already_found {
+                continue;
+            }
+
+            if !filter.is_empty()
+                && !is_filtered_technique(cache, &entry, tactic_technique_ids.as_deref(), filter)?
+            {
+                continue;
+            }
+
+            if let Some(matched) = entry.matched_field(term, &words) {
+                let kind_name: &str = entry.kind.into();
+
+                results.push(SearchResultRow {
+                    kind: kind_name.to_string(),
+                    id: entry.id,
+                    name: entry.name,
+                    matched,
+                    description: entry.description,
+                });
+            }
+        }
+    }
+
+    if !synced {
+        return Err(error::Error::General(String::from(
+            "No listing cached, run 'attack sync' first",
+        )));
+    }
+
+    // Stable: the entity types keep their order within a matched field, after the exact
+    // names.
+    results.sort_by_key(|result| {
+        (
+            result.matched,
+            !result.name.eq_ignore_ascii_case(term.trim()),
+        )
+    });
+
+    return Ok(SearchResultsTable(results));
+}
+
+/// IDs of the techniques listed by the cached `tactics` (IDs or names), `None` if no tactic
+/// is given.
+fn tactic_technique_ids(
+    cache: &Cache,
+    tactics: &[String],
+) -> Result<Option<Vec<String>>, error::Error> {
+    if tactics.is_empty() {
+        return Ok(None);
+    }
+
+    let cached_tactics: Vec<Tactic> = cache.load_entities(EntityKind::TACTIC)?;
+    let tactic_names: Vec<(String, String)> = cached_tactics
+        .iter()
+        .map(|tactic| (tactic.id.clone(), tactic.name.clone()))
+        .collect();
+    let mut technique_ids = Vec::new();
+
+    for tactic in tactics {
+        let tactic_id = match EntityKind::from_id(tactic) {
+            Some(EntityKind::TACTIC) => tactic.to_uppercase(),
+            _ => names::resolve_name(&tactic_names, tactic)?,
+        };
+        let cached_tactic = cached_tactics
+            .iter()
+            .find(|cached_tactic| cached_tactic.id == tactic_id)
+            .ok_or_else(|| {
+                error::Error::General(format!(
+                    "Tactic {} not cached, run 'attack sync tactics' first",
+                    tactic_id
+                ))
+            })?;
+
+        technique_ids.extend(
+            cached_tactic
+                .techniques
+                .iter()
+                .flat_map(|techniques| techniques.0.iter().map(|row| row.id.clone())),
+        );
+    }
+
+    return Ok(Some(technique_ids));
+}
+
+/// Whether the entry is a technique of the filtered tactics and platforms. Sub-techniques
+/// belong to the tactics of their parent and, when not cached, run on its platforms.
+fn is_filtered_technique(
+    cache: &Cache,
+    entry: &Entry,
+    tactic_technique_ids: Option<&[String]>,
+    filter: &SearchFilter,
+) -> Result<bool, error::Error> {
+    if !matches!(entry.kind, EntityKind::TECHNIQUE) {
+        return Ok(false);
+    }
+
+    let parent_id = techniques::parent_id(&entry.id).unwrap_or_else(|| entry.id.clone());
+
+    if tactic_technique_ids.is_some_and(|ids| !ids.contains(&parent_id)) {
+        return Ok(false);
+    }
+
+    if filter.platforms.is_empty() {
+        return Ok(true);
+    }
+
+    let technique: Option<TechniquePlatforms> =
+        match cache.load_entity(EntityKind::TECHNIQUE, &entry.id)? {
+            Some(technique) => Some(technique),
+            None => cache.load_entity(EntityKind::TECHNIQUE, &parent_id)?,
+        };
+
+    return Ok(technique.is_some_and(|technique| {
+        technique.platforms.iter().any(|technique_platform| {
+            filter
+                .platforms
+                .iter()
+                .any(|platform| platform.eq_ignore_ascii_case(technique_platform))
+        })
+    }));
+}
+
+/// Entities of the cached listings of the type (of every domain), `None` if none is synced.
+fn listing_entries(cache: &Cache, kind: EntityKind) -> Result<Option<Vec<Entry>>, error::Error> {
+    let domains: Vec<Option<&str>> = if kind.has_domain() {
+        DOMAINS.iter().map(|domain| Some(*domain)).collect()
+    } else {
+        vec![None]
+    };
+    let mut entries: Option<Vec<Entry>> = None;
+
+    for domain in domains {
+        let listing_entries: Option<Vec<Entry>> = match kind {
+            EntityKind::TACTIC => {
+                cache
+                    .load_listing::<TacticsTable>(kind, domain)?
+                    .map(|listing| {
+                        listing
+                            .into_iter()
+                            .map(|row| Entry::new(kind, row.id, row.name, row.description))
+                            .collect()
+                    })
+            }
+            EntityKind::TECHNIQUE => cache
+                .load_listing::<TechniquesTable>(kind, domain)?
+                .map(technique_entries),
+            EntityKind::MITIGATION => {
+                cache
+                    .load_listing::<MitigationTable>(kind, domain)?
+                    .map(|listing| {
+                        listing
+                            .into_iter()
+                            .map(|row| Entry::new(kind, row.id, row.name, row.description))
+                            .collect()
+                    })
+            }
+            EntityKind::SOFTWARE => {
+                cache
+                    .load_listing::<SoftwareTable>(kind, domain)?
+                    .map(|listing| {
+                        listing
+                            .into_iter()
+                            .map(|row| Entry {
+                                aliases: row.assoc_software.unwrap_or_default(),
+                                ..Entry::new(kind, row.id, row.name, row.description)
+                            })
+                            .collect()
+                    })
+            }
+            EntityKind::GROUP => cache
+                .load_listing::<GroupsTable>(kind, domain)?
+                .map(|listing| {
+                    listing
+                        .into_iter()
+                        .map(|row| Entry {
+                            aliases: row.assoc_groups.unwrap_or_default(),
+                            ..Entry::new(kind, row.id, row.name, row.description)
+                        })
+                        .collect()
+                }),
+            EntityKind::DATASOURCE => {
+                cache
+                    .load_listing::<DataSourcesTable>(kind, domain)?
+                    .map(|listing| {
+                        listing
+                            .into_iter()
+                            .map(|row| Entry::new(kind, row.id, row.name, row.description))
+                            .collect()
+                    })
+            }
+        };
+
+        if let Some(listing_entries) = listing_entries {
+            entries.get_or_insert_with(Vec::new).extend(listing_entries);
+        }
+    }
+
+    return Ok(entries);
+}
+
+/// Techniques of the listing, followed by their sub-techniques (T1055.001, named
+/// "Process Injection: Dynamic-link Library Injection").
+fn technique_entries(listing: TechniquesTable) -> Vec<Entry> {
+    let mut entries = Vec::new();
+
+    for row in listing {
+        let sub_techniques = row.sub_techniques.unwrap_or_default();
+        entries.push(Entry::new(
+            EntityKind::TECHNIQUE,
+            row.id.clone(),
+            row.name.clone(),
+            row.description,
+        ));
+
+        for sub_technique in sub_techniques {
+            entries.push(Entry::new(
+                EntityKind::TECHNIQUE,
+                format!("{}{}", row.id, sub_technique.id),
+                format!("{}: {}", row.name, sub_technique.name),
+                sub_technique.description,
+            ));
+        }
+    }
+
+    return entries;
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContentMatchRow {
+    /// Entity type ("techniques", "groups", ...).
+    pub kind: String,
+    pub id: String,
+    pub name: String,
+    /// Text of the entity holding the keyword: "description", "procedure G0016",
+    /// "detection DS0009: Process Creation", ...
+    pub field: String,
+    pub snippet: String,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for ContentMatchRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.kind))
+            .add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(self.name))
+            .add_cell(comfy_table::Cell::new(self.field))
+            .add_cell(comfy_table::Cell::new(self.snippet));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ContentMatchesTable(pub Vec<ContentMatchRow>);
+
+impl ContentMatchesTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for ContentMatchesTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&[
+            "Type", "ID", "Name", "Field", "Snippet",
+        ]));
+
+        for content_match in self.0 {
+            table.add_row(content_match);
+        }
+
+        return table;
+    }
+}
+
+/// Searchable texts (field label, text) of a cached entity detail.
+pub(super) struct EntityTexts<'a> {
+    pub(super) kind: EntityKind,
+    pub(super) id: &'a str,
+    pub(super) name: &'a str,
+    pub(super) texts: Vec<(String, &'a str)>,
+}
+
+/// Every text of the cached entity details: the descriptions, procedure examples, technique
+/// uses, detections and mitigation notes.
+pub(super) fn entity_texts(snapshot: &Snapshot) -> Vec<EntityTexts<'_>> {
+    let mut entities = Vec::new();
+
+    for tactic in &snapshot.tactics {
+        entities.push(EntityTexts {
+            kind: EntityKind::TACTIC,
+            id: &tactic.id,
+            name: &tactic.name,
+            texts: vec![(String::from("description"), tactic.description.as_str())],
+        });
+    }
+
+    for technique in &snapshot.techniques {
+        let mut texts = vec![(String::from("description"), technique.description.as_str())];
+        texts.extend(technique.procedures.iter().flat_map(|procedures| {
+            procedures.0.iter().map(|procedure| {
+                (
+                    format!("procedure {}", procedure.id),
+                    procedure.description.as_str(),
+                )
+            })
+        }));
+        texts.extend(technique.detections.iter().flat_map(|detections| {
+            detections.0.iter().filter_map(|detection| {
+                detection.detects.as_deref().map(|detects| {
+                    (
+                        format!("detection {}: {}", detection.id, detection.data_comp),
+                        detects,
+                    )
+                })
+            })
+        }));
+        texts.extend(technique.mitigations.iter().flat_map(|mitigations| {
+            mitigations.0.iter().map(|mitigation| {
+                (
+                    format!("mitigation {}", mitigation.id),
+                    mitigation.description.as_str(),
+                )
+            })
+        }));
+
+        entities.push(EntityTexts {
+            kind: EntityKind::TECHNIQUE,
+            id: &technique.id,
+            name: &technique.name,
+            texts,
+        });
+    }
+
+    for mitigation in &snapshot.mitigations {
+        let mut texts = vec![(String::from("description"), mitigation.desc.as_str())];
+        texts.extend(technique_uses(&mitigation.addressed_techniques));
+
+        entities.push(EntityTexts {
+            kind: EntityKind::MITIGATION,
+            id: &mitigation.id,
+            name: &mitigation.name,
+            texts,
+        });
+    }
+
+    for software in &snapshot.software {
+        let mut texts = vec![(String::from("description"), software.desc.as_str())];
+        texts.extend(technique_uses(&software.techniques));
+
+        entities.push(EntityTexts {
+            kind: EntityKind::SOFTWARE,
+            id: &software.id,
+            name: &software.name,
+            texts,
+        });
+    }
+
+    for group in &snapshot.groups {
+        let mut texts = vec![(String::from("description"), group.desc.as_str())];
+        texts.extend(technique_uses(&group.techniques));
+
+        entities.push(EntityTexts {
+            kind: EntityKind::GROUP,
+            id: &group.id,
+            name: &group.name,
+            texts,
+        });
+    }
+
+    for data_source in &snapshot.data_sources {
+        let mut texts = vec![(
+            String::from("description"),
+            data_source.description.as_str(),
+        )];
+
+        for component in &data_source.components {
+            texts.push((
+                format!("component {}", component.name),
+                component.description.as_str(),
+            ));
+
+            for detection in &component.detections.0 {
+                texts.push((
+                    format!("detection {}: {}", detection.id, component.name),
+                    detection.detects.as_str(),
+                ));
+                texts.extend(
+                    detection
+                        .sub_detections
+                        .iter()
+                        .flatten()
+                        .map(|sub_detection| {
+                            (
+                                format!(
+                                    "detection {}{}: {}",
+                                    detection.id, sub_detection.id, component.name
+                                ),
+                                sub_detection.detects.as_str(),
+                            )
+                        }),
+                );
+            }
+        }
+
+        entities.push(EntityTexts {
+            kind: EntityKind::DATASOURCE,
+            id: &data_source.id,
+            name: &data_source.name,
+            texts,
+        });
+    }
+
+    return entities;
+}
+
+/// Every text of the cached entity details holding `keyword` (case insensitive), see
+/// [`entity_texts`].
+pub fn search_contents(
+    snapshot: &Snapshot,
+    keyword: &str,
+) -> Result<ContentMatchesTable, error::Error> {
+    let keyword = keyword.trim();
+
+    if keyword.is_empty() {
+        return Err(error::Error::InvalidValue(String::from(
+            "The searched keyword is empty",
+        )));
+    }
+
+    let mut matches = ContentMatchesTable::default();
+
+    for entity in entity_texts(snapshot) {
+        let kind_name: &str = entity.kind.into();
+
+        for (field, text) in entity.texts {
+            if let Some(snippet) = snippet(text, keyword) {
+                matches.0.push(ContentMatchRow {
+                    kind: kind_name.to_string(),
+                    id: entity.id.to_string(),
+                    name: entity.name.to_string(),
+                    field,
+                    snippet,
+                });
+            }
+        }
+    }
+
+    return Ok(matches);
+}
+
+/// "Used for" texts of the techniques (and sub-techniques) of a group, software or
+/// mitigation.
+fn technique_uses(techniques: &Option<DomainTechniquesTable>) -> Vec<(String, &str)> {
+    let mut uses = Vec::new();
+
+    for technique in techniques.iter().flat_map(|techniques| techniques.0.iter()) {
+        uses.push((
+            format!("technique {}", technique.id),
+            technique.used_for.as_str(),
+        ));
+        uses.extend(
+            technique
+                .sub_techniques
+                .iter()
+                .flatten()
+                .map(|sub_technique| {
+                    (
+                        format!("technique {}{}", technique.id, sub_technique.id),
+                        sub_technique.used_for.as_str(),
+                    )
+                }),
+        );
+    }
+
+    return uses;
+}
+
+/// Whitespace collapsed excerpt of `text` around the first `keyword` (ASCII case
+/// insensitive), `None` when it is not found.
+fn snippet(text: &str, keyword: &str) -> Option<String> {
+    let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+    let start = text.char_indices().map(|(inx, _)| inx).find(|inx| {
+        text.as_bytes()
+            .get(*inx..inx + keyword.len())
+            .is_some_and(|candidate| candidate.eq_ignore_ascii_case(keyword.as_bytes()))
+    })?;
+    let end = start + keyword.len();
+
+    let before: Vec<char> = text[..start].chars().collect();
+    let after: Vec<char> = text[end..].chars().collect();
+    let mut snippet = String::new();
+
+    if before.len() > SNIPPET_CONTEXT {
+        snippet.push_str("...");
+    }
+
+    snippet.extend(&before[before.len().saturating_sub(SNIPPET_CONTEXT)..]);
+    snippet.push_str(&text[start..end]);
+    snippet.extend(after.iter().take(SNIPPET_CONTEXT));
+
+    if after.len() > SNIPPET_CONTEXT {
+        snippet.push_str("...");
+    }
+
+    return Some(snippet);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attack::{
+            groups::fetch_groups,
+            techniques::{fetch_technique, fetch_techniques, Domain},
+        },
+        fakers::FakeHttpReqwest,
+    };
+
+    fn test_cache(name: &str) -> Result<Cache, error::Error> {
+        let cache = crate::attack::cache::test_cache(&format!("search_{}", name));
+
+        cache.save_listing(
+            EntityKind::TECHNIQUE,
+            Some("enterprise"),
+            &fetch_techniques(
+                Domain::ENTERPRISE,
+                &FakeHttpReqwest::default().set_success_response(
+                    include_str!("html/attck/techniques/enterprise.html").to_string(),
+                ),
+            )?,
+        )?;
+        cache.save_listing(
+            EntityKind::GROUP,
+            None,
+            &fetch_groups(
+                &FakeHttpReqwest::default().set_success_response(
+                    include_str!("html/attck/groups/groups.html").to_string(),
+                ),
+            )?,
+        )?;
+
+        return Ok(cache);
+    }
+
+    #[test]
+    fn test_search_every_type() -> Result<(), error::Error> {
+        let cache = test_cache("techniques")?;
+
+        let results = search_cache(&cache, "apt29", &SearchFilter::default())?;
+        assert_eq!(
+            results
+                .0
+                .iter()
+                .map(|result| result.id.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["G0016"]
+        );
+        assert_eq!(results.0[0].kind, "groups");
+
+        let results = search_cache(&cache, "Phishing", &SearchFilter::default())?;
+        assert_eq!(
+            (results.0[0].id.as_str(), results.0[0].matched),
+            ("T1566", MatchedField::NAME)
+        );
+        assert!(results
+            .0
+            .iter()
+            .any(|result| result.kind == "groups" && result.matched == MatchedField::DESCRIPTION));
+
+        let results = search_cache(&cache, "t1055.001", &SearchFilter::default())?;
+        assert_eq!(
+            (results.0[0].id.as_str(), results.0[0].matched),
+            ("T1055.001", MatchedField::ID)
+        );
+        assert_eq!(
+            results.0[0].name,
+            "Process Injection: Dynamic-link Library Injection"
+        );
+
+        assert!(search_cache(&cache, "  ", &SearchFilter::default()).is_err());
+        assert!(search_cache(
+            &Cache::with_root(cache.root().join("empty")),
+            "apt29",
+            &SearchFilter::default()
+        )
+        .is_err());
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_filtered_techniques() -> Result<(), error::Error> {
+        let cache = test_cache("filtered")?;
+        let technique = |id: &str, platforms: &[&str]| techniques::Technique {
+            id: String::from(id),
+            platforms: platforms
+                .iter()
+                .map(|platform| platform.to_string())
+                .collect(),
+            ..Default::default()
+        };
+        cache.save_entity(
+            EntityKind::TECHNIQUE,
+            "T1548",
+            &technique("T1548", &["Linux", "macOS", "Windows"]),
+        )?;
+        cache.save_entity(
+            EntityKind::TECHNIQUE,
+            "T1548.002",
+            &technique("T1548.002", &["Windows"]),
+        )?;
+        cache.save_entity(
+            EntityKind::TECHNIQUE,
+            "T1610",
+            &technique("T1610", &["Containers"]),
+        )?;
+        cache.save_entity(
+            EntityKind::TACTIC,
+            "TA0004",
+            &Tactic {
+                id: String::from("TA0004"),
+                name: String::from("Privilege Escalation"),
+                techniques: Some(serde_json::from_value(serde_json::json!([
+                    {"id": "T1548", "name": "", "description": ""},
+                    {"id": "T1610", "name": "", "description": ""}
+                ]))?),
+                ..Default::default()
+            },
+        )?;
+
+        let filter = SearchFilter {
+            platforms: vec![String::from("macos")],
+            tactics: vec![String::from("privilege escalation")],
+        };
+        let results = search_cache(&cache, "", &filter)?;
+        assert_eq!(
+            results
+                .0
+                .iter()
+                .map(|result| result.id.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["T1548", "T1548.001", "T1548.003", "T1548.004"]
+        );
+
+        let filter = SearchFilter {
+            tactics: vec![String::from("TA0004")],
+            ..Default::default()
+        };
+        assert_eq!(search_cache(&cache, "container", &filter)?.0[0].id, "T1610");
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_contents() -> Result<(), error::Error> {
+        let technique = fetch_technique(
+            "T1610",
+            &FakeHttpReqwest::default().set_success_response(
+                include_str!("html/attck/techniques/enterprise_deploy_container.html").to_string(),
+            ),
+        )?;
+        let snapshot = Snapshot {
+            techniques: vec![technique],
+            ..Default::default()
+        };
+
+        let matches = search_contents(&snapshot, "DOKI")?;
+        assert_eq!(matches.0.len(), 1);
+        assert_eq!(matches.0[0].field, "procedure S0600");
+        assert_eq!(
+            matches.0[0].snippet,
+            "Doki was run through a deployed container."
+        );
+
+        let fields: Vec<String> = search_contents(&snapshot, "newly constructed")?
+            .0
+            .into_iter()
+            .map(|content_match| content_match.field)
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                "detection DS0032: Container Creation",
+                "detection DS0014: Pod Creation"
+            ]
+        );
+
+        let matches = search_contents(&snapshot, "kubernetes")?;
+        assert_eq!(matches.0[0].field, "description");
+        assert!(matches.0[0].snippet.starts_with("..."));
+        assert!(matches.0[0]
+            .snippet
+            .contains("such as the Kubernetes dashboard"));
+
+        assert!(search_contents(&snapshot, "mainframe")?.is_empty());
+
+        Ok(())
+    }
+}
@@ -1,22 +1,29 @@
 use select::document::Document;
+use serde::{Deserialize, Serialize};
 
-use crate::{error, WebFetch};
+use crate::{error, error::FetchContext, WebFetch};
 
 use super::{
-    scrape_entity_description, scrape_entity_h2_tables, scrape_entity_name, scrape_tables,
-    techniques::domain::DomainTechniquesTable, Row, Table,
+    scrape_entity_card, scrape_entity_contributors, scrape_entity_description,
+    scrape_entity_h2_tables, scrape_entity_name, scrape_entity_references, scrape_tables,
+    techniques::domain::{DomainTechniquesTable, UsedTechniques},
+    Reference, Row, Table,
 };
 
 const ATTCK_SOFTWARE_URL: &'static str = "https://attack.mitre.org/software/";
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct SoftwareRow {
     pub id: String,
     pub name: String,
     pub assoc_software: Option<Vec<String>>,
     pub description: String,
+    /// Malware or tool, from the Type column of the listing or the cached software.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub software_type: Option<String>,
 }
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Row> for SoftwareRow {
     fn into(self) -> comfy_table::Row {
         let mut row = comfy_table::Row::new();
@@ -31,6 +38,10 @@ impl Into<comfy_table::Row> for SoftwareRow {
             ))
             .add_cell(comfy_table::Cell::new(self.description));
 
+        if let Some(software_type) = self.software_type {
+            row.add_cell(comfy_table::Cell::new(software_type));
+        }
+
         return row;
     }
 }
@@ -68,7 +79,7 @@ impl From<Row> for SoftwareRow {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct SoftwareTable(pub Vec<SoftwareRow>);
 
 impl SoftwareTable {
@@ -79,10 +90,97 @@ impl SoftwareTable {
     pub fn len(&self) -> usize {
         return self.0.len();
     }
+
+    pub fn ids(&self) -> Vec<String> {
+        return self.0.iter().map(|row| row.id.clone()).collect();
+    }
+
+    /// (ID, name) of the listed entities, associated names included.
+    pub fn names(&self) -> Vec<(String, String)> {
+        return self
+            .0
+            .iter()
+            .flat_map(|row| {
+                std::iter::once(row.name.clone())
+                    .chain(row.assoc_software.iter().flatten().cloned())
+                    .map(|name| (row.id.clone(), name))
+            })
+            .collect();
+    }
+
+    /// Fill the type of the listed software missing one from `type_of`, returning `None` for
+    /// the software not cached.
+    pub fn set_types(
+        &mut self,
+        type_of: impl Fn(&str) -> Result<Option<SoftwareType>, error::Error>,
+    ) -> Result<(), error::Error> {
+        for software in self.0.iter_mut().filter(|row| row.software_type.is_none()) {
+            if let Some(cached) = type_of(&software.id)? {
+                software.software_type = cached.software_type;
+            }
+        }
+
+        return Ok(());
+    }
+
+    /// Software using techniques of the domain (enterprise, mobile, ics), according to
+    /// `techniques_of` (`None` when not cached). Returns them with the IDs of the left out
+    /// software that are not cached.
+    pub fn in_domain(
+        self,
+        domain: &str,
+        techniques_of: impl Fn(&str) -> Result<Option<UsedTechniques>, error::Error>,
+    ) -> Result<(Self, Vec<String>), error::Error> {
+        let mut software = Vec::new();
+        let mut uncached = Vec::new();
+
+        for row in self.0 {
+            match techniques_of(&row.id)? {
+                Some(techniques) if techniques.has_domain(domain) => software.push(row),
+                Some(_) => {}
+                None => uncached.push(row.id),
+            }
+        }
+
+        return Ok((Self(software), uncached));
+    }
+
+    /// IDs of the listed software whose type is unknown.
+    pub fn untyped_ids(&self) -> Vec<String> {
+        return self
+            .0
+            .iter()
+            .filter(|row| row.software_type.is_none())
+            .map(|row| row.id.clone())
+            .collect();
+    }
+
+    /// Listing restricted to the software of the type (malware or tool, case insensitive).
+    pub fn of_type(self, software_type: &str) -> Self {
+        return Self(
+            self.0
+                .into_iter()
+                .filter(|row| {
+                    row.software_type
+                        .as_deref()
+                        .is_some_and(|row_type| row_type.eq_ignore_ascii_case(software_type))
+                })
+                .collect(),
+        );
+    }
+}
+
+/// Type of a cached software, loaded without the rest of the software.
+#[derive(Default, Debug, Deserialize)]
+pub struct SoftwareType {
+    #[serde(default)]
+    pub software_type: Option<String>,
 }
 
 pub fn fetch_software(web_client: &impl WebFetch) -> Result<SoftwareTable, error::Error> {
-    let fetched_response = web_client.fetch(ATTCK_SOFTWARE_URL)?;
+    let fetched_response = web_client
+        .fetch(ATTCK_SOFTWARE_URL)
+        .listing_context("software", ATTCK_SOFTWARE_URL)?;
     let document = Document::from(fetched_response.as_str());
 
     return Ok(scrape_tables(&document)
@@ -90,35 +188,32 @@ pub fn fetch_software(web_client: &impl WebFetch) -> Result<SoftwareTable, error
         .map_or(SoftwareTable::default(), |table| table.into()));
 }
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Table> for SoftwareTable {
     fn into(self) -> comfy_table::Table {
-        let mut table = comfy_table::Table::new();
-        table
-            .load_preset(comfy_table::presets::UTF8_FULL)
-            .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
-            .set_header(vec![
-                comfy_table::Cell::new("ID")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Name")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Associated Software")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Description")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-            ])
-            .add_rows(
-                self.into_iter()
-                    .map(|software| software.into())
-                    .collect::<Vec<comfy_table::Row>>(),
-            );
+        let show_types = self
+            .0
+            .iter()
+            .any(|software| software.software_type.is_some());
+        let mut header =
+            crate::theme::header(&["ID", "Name", "Associated Software", "Description"]);
+
+        if show_types {
+            header.push(crate::theme::header_cell("Type"));
+        }
+
+        let mut table = crate::theme::new_table();
+        table.set_header(header).add_rows(
+            self.into_iter()
+                .map(|mut software| {
+                    if show_types && software.software_type.is_none() {
+                        software.software_type = Some(String::default());
+                    }
+
+                    software.into()
+                })
+                .collect::<Vec<comfy_table::Row>>(),
+        );
 
         return table;
     }
@@ -135,11 +230,30 @@ impl IntoIterator for SoftwareTable {
 
 impl From<Table> for SoftwareTable {
     fn from(table: Table) -> Self {
-        return Self(table.into_iter().map(SoftwareRow::from).collect());
+        let type_inx = table
+            .headers
+            .iter()
+            .position(|header| header.trim().eq_ignore_ascii_case("Type"));
+
+        return Self(
+            table
+                .into_iter()
+                .map(|row| {
+                    let software_type = type_inx
+                        .and_then(|inx| row.get_col(inx))
+                        .map(|software_type| software_type.to_lowercase());
+
+                    SoftwareRow {
+                        software_type,
+                        ..SoftwareRow::from(row)
+                    }
+                })
+                .collect(),
+        );
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct AssocGroupsRow {
     pub id: String,
     pub name: String,
@@ -161,6 +275,7 @@ impl From<Row> for AssocGroupsRow {
     }
 }
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Row> for AssocGroupsRow {
     fn into(self) -> comfy_table::Row {
         let mut row = comfy_table::Row::new();
@@ -171,7 +286,7 @@ impl Into<comfy_table::Row> for AssocGroupsRow {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct AssocGroupsTable(pub Vec<AssocGroupsRow>);
 
 impl IntoIterator for AssocGroupsTable {
@@ -199,22 +314,12 @@ impl From<Table> for Option<AssocGroupsTable> {
     }
 }
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Table> for AssocGroupsTable {
     fn into(self) -> comfy_table::Table {
-        let mut table = comfy_table::Table::new();
+        let mut table = crate::theme::new_table();
         table
-            .load_preset(comfy_table::presets::UTF8_FULL)
-            .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
-            .set_header(vec![
-                comfy_table::Cell::new("ID")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Name")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-            ])
+            .set_header(crate::theme::header(&["ID", "Name"]))
             .add_rows(
                 self.into_iter()
                     .map(|row| row.into())
@@ -225,27 +330,43 @@ impl Into<comfy_table::Table> for AssocGroupsTable {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Software {
     pub id: String,
     pub name: String,
+    /// ATT&CK page of the software.
+    #[serde(default)]
+    pub url: String,
     pub desc: String,
+    pub software_type: Option<String>,
     pub techniques: Option<DomainTechniquesTable>,
     pub groups: Option<AssocGroupsTable>,
+    #[serde(default)]
+    pub references: Vec<Reference>,
+    #[serde(default)]
+    pub contributors: Vec<String>,
+}
+
+/// ATT&CK page of the software.
+pub fn software_url(software_id: &str) -> String {
+    return format!("{}{}", ATTCK_SOFTWARE_URL, software_id);
 }
 
 pub fn fetch_software_info(
     software_id: &str,
     web_client: &impl WebFetch,
 ) -> Result<Software, crate::error::Error> {
-    let fetched_response =
-        web_client.fetch(format!("{}{}", ATTCK_SOFTWARE_URL, software_id).as_str())?;
+    let url = software_url(software_id);
+    let fetched_response = web_client.fetch(&url).entity_context(software_id, &url)?;
     let document = Document::from(fetched_response.as_str());
     let mut tables = scrape_entity_h2_tables(&document);
+    let mut card = scrape_entity_card(&document);
     let software = Software {
         id: software_id.to_string(),
         name: scrape_entity_name(&document),
+        url: software_url(software_id),
         desc: scrape_entity_description(&document),
+        software_type: card.remove("Type").map(|software_type| software_type.to_lowercase()),
         techniques: if let Some(techniques_table) = tables.remove("techniques") {
             techniques_table.into()
         } else {
@@ -256,6 +377,8 @@ pub fn fetch_software_info(
         } else {
             None
         },
+        references: scrape_entity_references(&document),
+        contributors: scrape_entity_contributors(&mut card),
     };
 
     return Ok(software);
@@ -305,6 +428,52 @@ mod tests {
             true,
             "groups that employ this software should not be empty"
         );
+        assert_eq!(retrieved_software.software_type, Some(String::from("tool")));
+        assert_eq!(
+            retrieved_software.contributors,
+            vec![String::from("Janantha Marasinghe")]
+        );
+
+        Ok(())
+    }
+    #[test]
+    fn test_software_types() -> Result<(), error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default()
+            .set_success_response(include_str!("html/attck/software/software.html").to_string());
+        let mut retrieved_software = fetch_software(&fake_reqwest)?;
+        assert!(retrieved_software
+            .0
+            .iter()
+            .all(|software| software.software_type.is_none()));
+
+        retrieved_software.set_types(|id| {
+            return Ok(Some(SoftwareType {
+                software_type: Some(String::from(if id == TEST_SOFTWARE_ID {
+                    "tool"
+                } else {
+                    "malware"
+                })),
+            }));
+        })?;
+
+        assert!(retrieved_software.untyped_ids().is_empty());
+
+        let tools = retrieved_software.of_type("TOOL");
+        assert_eq!(tools.ids(), vec![TEST_SOFTWARE_ID]);
+
+        let typed_software: SoftwareTable = Table {
+            headers: ["ID", "Name", "Associated Software", "Description", "Type"]
+                .map(String::from)
+                .to_vec(),
+            rows: vec![Row {
+                cols: ["S0002", "Mimikatz", "", "Credential dumper.", "TOOL"]
+                    .map(String::from)
+                    .to_vec(),
+                ..Default::default()
+            }],
+        }
+        .into();
+        assert_eq!(typed_software.0[0].software_type.as_deref(), Some("tool"));
 
         Ok(())
     }
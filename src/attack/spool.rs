@@ -0,0 +1,233 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    thread,
+};
+
+use crate::{error, WebFetch};
+
+use super::{
+    cache::{Cache, EntityKind},
+    data_sources, groups, mitigations, software,
+    sync::{self, SyncSummary},
+    tactics, techniques,
+};
+
+const SPOOL_DIR_NAME: &'static str = "spool";
+
+/// ATT&CK page of the entity.
+pub fn entity_url(kind: EntityKind, id: &str) -> String {
+    return match kind {
+        EntityKind::TACTIC => tactics::tactic_url(id),
+        EntityKind::TECHNIQUE => techniques::technique_url(id),
+        EntityKind::MITIGATION => mitigations::mitigation_url(id),
+        EntityKind::SOFTWARE => software::software_url(id),
+        EntityKind::GROUP => groups::group_url(id),
+        EntityKind::DATASOURCE => data_sources::data_source_url(id),
+    };
+}
+
+/// Downloaded HTML pages kept on disk, so they can be parsed (again) without network.
+///
+/// The spool serves the pages through `WebFetch`, letting the regular scrapers parse them.
+pub struct Spool {
+    root: PathBuf,
+}
+
+impl Spool {
+    /// Spool stored next to the cached entities.
+    pub fn for_cache(cache: &Cache) -> Self {
+        return Self::with_root(cache.root().join(SPOOL_DIR_NAME));
+    }
+
+    pub fn with_root(root: PathBuf) -> Self {
+        return Self { root };
+    }
+
+    pub fn root(&self) -> &Path {
+        return &self.root;
+    }
+
+    pub fn page_path(&self, url: &str) -> PathBuf {
+        let file_stem = url
+            .trim_start_matches("https://")
+            .trim_start_matches("http://")
+            .trim_end_matches('/')
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+
+        return self.root.join(format!("{}.html", file_stem));
+    }
+
+    pub fn save_page(&self, url: &str, page: &str) -> Result<(), error::Error> {
+        fs::create_dir_all(&self.root)?;
+        fs::write(self.page_path(url), page)?;
+
+        return Ok(());
+    }
+}
+
+impl WebFetch for Spool {
+    fn fetch(&self, url: &str) -> Result<String, error::Error> {
+        let page_path = self.page_path(url);
+
+        if !page_path.exists() {
+            return Err(error::Error::General(format!(
+                "{} has not been spooled",
+                url
+            )));
+        }
+
+        return Ok(fs::read_to_string(page_path)?);
+    }
+}
+
+/// Download phase: store the pages of `ids` into the spool without parsing them.
+pub fn spool_entities(
+    kind: EntityKind,
+    ids: &[String],
+    web_client: &impl WebFetch,
+    spool: &Spool,
+    mut on_entity: impl FnMut(&str, &Result<(), error::Error>),
+) -> SyncSummary {
+    let mut summary = SyncSummary::default();
+
+    for id in ids {
+        let url = entity_url(kind, id);
+        let result = web_client
+            .fetch(&url)
+            .and_then(|page| spool.save_page(&url, &page));
+        on_entity(id, &result);
+
+        match result {
+            Ok(()) => summary.synced.push(id.to_uppercase()),
+            Err(err) => summary.failed.push((id.to_uppercase(), err)),
+        }
+    }
+
+    return summary;
+}
+
+/// Parse phase: scrape the spooled pages of `ids` into the cache using every available core.
+/// Entities are reported through `on_entity` once all of them have been parsed.
+pub fn parse_spooled(
+    kind: EntityKind,
+    ids: &[String],
+    spool: &Spool,
+    cache: &Cache,
+    mut on_entity: impl FnMut(&str, &Result<(), error::Error>),
+) -> SyncSummary {
+    let workers = thread::available_parallelism().map_or(1, |workers| workers.get());
+    let chunk_size = ids.len().div_ceil(workers).max(1);
+
+    let results: Vec<(String, Result<(), error::Error>)> = thread::scope(|scope| {
+        let handles = ids
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|id| (id.clone(), sync::sync_entity(kind, id, spool, cache)))
+                        .collect::<Vec<(String, Result<(), error::Error>)>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_default())
+            .collect()
+    });
+
+    let mut summary = SyncSummary::default();
+
+    for (id, result) in results {
+        on_entity(&id, &result);
+
+        match result {
+            Ok(()) => summary.synced.push(id.to_uppercase()),
+            Err(err) => summary.failed.push((id.to_uppercase(), err)),
+        }
+    }
+
+    return summary;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{attack::techniques::Technique, fakers::FakeHttpReqwest};
+
+    fn test_dirs(name: &str) -> (Cache, Spool) {
+        let cache = crate::attack::cache::test_cache(&format!("spool_{}", name));
+        let spool = Spool::for_cache(&cache);
+
+        return (cache, spool);
+    }
+
+    #[test]
+    fn test_spool_then_parse() -> Result<(), error::Error> {
+        let (cache, spool) = test_dirs("parse");
+        let ids = vec![String::from("T1610"), String::from("T1134.004")];
+        let fake_reqwest = FakeHttpReqwest::default().set_success_response(
+            include_str!("html/attck/techniques/enterprise_deploy_container.html").to_string(),
+        );
+
+        let downloaded = spool_entities(
+            EntityKind::TECHNIQUE,
+            &ids,
+            &fake_reqwest,
+            &spool,
+            |_, _| {},
+        );
+        assert_eq!(downloaded.synced.len(), 2);
+        assert!(spool
+            .page_path("https://attack.mitre.org/techniques/T1134/004")
+            .exists());
+        assert!(cache.entity_ids(EntityKind::TECHNIQUE)?.is_empty());
+
+        let mut reported = 0;
+        let parsed = parse_spooled(EntityKind::TECHNIQUE, &ids, &spool, &cache, |_, _| {
+            reported += 1
+        });
+
+        assert_eq!(reported, 2);
+        assert_eq!(parsed.synced.len(), 2);
+        assert_eq!(
+            cache
+                .load_entity::<Technique>(EntityKind::TECHNIQUE, "T1134.004")?
+                .map(|technique| technique.name),
+            Some(String::from("Deploy Container"))
+        );
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_without_spooled_page() {
+        let (cache, spool) = test_dirs("missing");
+
+        let parsed = parse_spooled(
+            EntityKind::GROUP,
+            &[String::from("G0018")],
+            &spool,
+            &cache,
+            |_, _| {},
+        );
+
+        assert!(parsed.synced.is_empty());
+        assert!(matches!(
+            &parsed.failed[0].1,
+            error::Error::Fetch { source, .. } if matches!(**source, error::Error::General(_))
+        ));
+    }
+}
@@ -0,0 +1,305 @@
+use std::path::Path;
+
+use rusqlite::{params, Connection};
+
+use crate::error;
+
+use super::{
+    cache::{EntityKind, Snapshot},
+    dot::GraphBuilder,
+};
+
+const SCHEMA: &'static str = "
+CREATE TABLE tactics (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL,
+    domain TEXT,
+    kill_chain_order INTEGER,
+    url TEXT NOT NULL
+);
+CREATE TABLE techniques (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL,
+    domain TEXT,
+    platforms TEXT NOT NULL,
+    detection_notes TEXT,
+    modified TEXT,
+    deprecated INTEGER NOT NULL,
+    url TEXT NOT NULL
+);
+CREATE TABLE subtechniques (
+    id TEXT PRIMARY KEY,
+    parent_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL,
+    domain TEXT,
+    platforms TEXT NOT NULL,
+    detection_notes TEXT,
+    modified TEXT,
+    deprecated INTEGER NOT NULL,
+    url TEXT NOT NULL
+);
+CREATE TABLE mitigations (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL,
+    domain TEXT,
+    url TEXT NOT NULL
+);
+CREATE TABLE software (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL,
+    software_type TEXT,
+    url TEXT NOT NULL
+);
+CREATE TABLE groups (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL,
+    aliases TEXT NOT NULL,
+    url TEXT NOT NULL
+);
+CREATE TABLE relationships (
+    source_id TEXT NOT NULL,
+    source_type TEXT NOT NULL,
+    relationship_type TEXT NOT NULL,
+    target_id TEXT NOT NULL,
+    target_type TEXT NOT NULL,
+    PRIMARY KEY (source_id, relationship_type, target_id)
+);
+CREATE INDEX relationships_target ON relationships (target_id);
+";
+
+/// Write the snapshot entities into a new SQLite database at `out`, one table per entity
+/// type plus a `relationships` table (uses, mitigates, subtechnique-of, includes) to join
+/// them. Multi-valued columns (platforms, aliases) are comma separated. Returns the number
+/// of written relationships.
+pub fn export_sqlite(snapshot: &Snapshot, out: &Path) -> Result<usize, error::Error> {
+    if out.exists() {
+        std::fs::remove_file(out)?;
+    }
+
+    let mut conn = Connection::open(out)?;
+    let tx = conn.transaction()?;
+    tx.execute_batch(SCHEMA)?;
+
+    for tactic in &snapshot.tactics {
+        tx.execute(
+            "INSERT OR REPLACE INTO tactics VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                tactic.id,
+                tactic.name,
+                tactic.description,
+                tactic.domain,
+                tactic.order,
+                tactic.url
+            ],
+        )?;
+    }
+
+    for technique in &snapshot.techniques {
+        let platforms = technique.platforms.join(", ");
+
+        match technique.id.split_once('.') {
+            Some((parent_id, _)) => tx.execute(
+                "INSERT OR REPLACE INTO subtechniques
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    technique.id,
+                    parent_id,
+                    technique.name,
+                    technique.description,
+                    technique.domain,
+                    platforms,
+                    technique.detection_notes,
+                    technique.modified,
+                    technique.deprecated,
+                    technique.url
+                ],
+            )?,
+            None => tx.execute(
+                "INSERT OR REPLACE INTO techniques VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    technique.id,
+                    technique.name,
+                    technique.description,
+                    technique.domain,
+                    platforms,
+                    technique.detection_notes,
+                    technique.modified,
+                    technique.deprecated,
+                    technique.url
+                ],
+            )?,
+        };
+    }
+
+    for mitigation in &snapshot.mitigations {
+        tx.execute(
+            "INSERT OR REPLACE INTO mitigations VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                mitigation.id,
+                mitigation.name,
+                mitigation.desc,
+                mitigation.domain,
+                mitigation.url
+            ],
+        )?;
+    }
+
+    for software in &snapshot.software {
+        tx.execute(
+            "INSERT OR REPLACE INTO software VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                software.id,
+                software.name,
+                software.desc,
+                software.software_type,
+                software.url
+            ],
+        )?;
+    }
+
+    for group in &snapshot.groups {
+        tx.execute(
+            "INSERT OR REPLACE INTO groups VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                group.id,
+                group.name,
+                group.desc,
+                group.assoc_groups.as_deref().unwrap_or_default().join(", "),
+                group.url
+            ],
+        )?;
+    }
+
+    let graph = relationship_graph(snapshot);
+    for (source_id, target_id, relationship_type) in &graph.edges {
+        let kind_name = |id: &String| -> &'static str {
+            return graph.nodes.get(id).map_or("", |node| node.kind.into());
+        };
+
+        tx.execute(
+            "INSERT OR REPLACE INTO relationships VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                source_id,
+                kind_name(source_id),
+                relationship_type,
+                target_id,
+                kind_name(target_id)
+            ],
+        )?;
+    }
+
+    tx.commit()?;
+
+    return Ok(graph.edges.len());
+}
+
+/// Relationships of the entity graph, with the tactics of the techniques.
+fn relationship_graph(snapshot: &Snapshot) -> GraphBuilder {
+    let mut graph = GraphBuilder::from_snapshot(snapshot);
+
+    for tactic in &snapshot.tactics {
+        graph.add_node(&tactic.id, EntityKind::TACTIC, Some(&tactic.name));
+
+        if let Some(techniques) = &tactic.techniques {
+            for technique_id in techniques.ids() {
+                graph.add_node(&technique_id, EntityKind::TECHNIQUE, None);
+                graph.relate(&tactic.id, &technique_id, "includes");
+            }
+        }
+    }
+
+    return graph;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack::{
+        groups::Group,
+        tactics::Tactic,
+        techniques::{
+            domain::{DomainTechniqueRow, DomainTechniquesTable},
+            Technique, TechniqueRow, TechniquesTable,
+        },
+    };
+
+    #[test]
+    fn test_export_sqlite() -> Result<(), error::Error> {
+        let out = std::env::temp_dir().join("mitre_cli_export.db");
+        let snapshot = Snapshot {
+            tactics: vec![Tactic {
+                id: String::from("TA0002"),
+                name: String::from("Execution"),
+                techniques: Some(TechniquesTable(vec![TechniqueRow {
+                    id: String::from("T1059"),
+                    ..Default::default()
+                }])),
+                ..Default::default()
+            }],
+            techniques: vec![
+                Technique {
+                    id: String::from("T1059"),
+                    name: String::from("Command and Scripting Interpreter"),
+                    platforms: vec![String::from("Linux"), String::from("Windows")],
+                    ..Default::default()
+                },
+                Technique {
+                    id: String::from("T1059.001"),
+                    name: String::from("PowerShell"),
+                    ..Default::default()
+                },
+            ],
+            groups: vec![Group {
+                id: String::from("G0016"),
+                name: String::from("APT29"),
+                assoc_groups: Some(vec![String::from("Cozy Bear")]),
+                techniques: Some(DomainTechniquesTable(vec![DomainTechniqueRow {
+                    id: String::from("T1059.001"),
+                    ..Default::default()
+                }])),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(export_sqlite(&snapshot, &out)?, 3);
+        // Exporting again replaces the database.
+        assert_eq!(export_sqlite(&snapshot, &out)?, 3);
+
+        let conn = Connection::open(&out)?;
+        let platforms: String = conn.query_row(
+            "SELECT platforms FROM techniques WHERE id = 'T1059'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(platforms, "Linux, Windows");
+
+        let (group_name, parent_id): (String, String) = conn.query_row(
+            "SELECT g.name, s.parent_id FROM groups g
+             JOIN relationships r ON r.source_id = g.id AND r.relationship_type = 'uses'
+             JOIN subtechniques s ON s.id = r.target_id",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        assert_eq!(group_name, "APT29");
+        assert_eq!(parent_id, "T1059");
+
+        let tactic_type: String = conn.query_row(
+            "SELECT source_type FROM relationships WHERE relationship_type = 'includes'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(tactic_type, "tactics");
+
+        drop(conn);
+        std::fs::remove_file(&out)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,807 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::error;
+
+use super::{
+    cache::{Cache, EntityKind, Snapshot, DOMAINS},
+    techniques::domain::DomainTechniquesTable,
+};
+
+/// Buckets (lowest, highest number of groups) of the groups per technique distribution.
+const GROUP_BUCKETS: [(usize, Option<usize>); 6] = [
+    (0, Some(0)),
+    (1, Some(1)),
+    (2, Some(5)),
+    (6, Some(10)),
+    (11, Some(20)),
+    (21, None),
+];
+
+/// Cached entities of a type in a domain, `domain` being `None` for the data sources and
+/// the entities missing from the domain listings.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct DomainCountRow {
+    pub kind: String,
+    pub domain: Option<String>,
+    pub count: usize,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for DomainCountRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.kind))
+            .add_cell(comfy_table::Cell::new(
+                self.domain.unwrap_or_else(|| String::from("-")),
+            ))
+            .add_cell(comfy_table::Cell::new(self.count));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct DomainCountsTable(pub Vec<DomainCountRow>);
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for DomainCountsTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&["Type", "Domain", "Cached"]));
+
+        for count in self.0 {
+            table.add_row(count);
+        }
+
+        return table;
+    }
+}
+
+/// Techniques and sub-techniques listed by a cached tactic.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct TacticCountRow {
+    pub id: String,
+    pub name: String,
+    pub domain: Option<String>,
+    pub techniques: usize,
+    pub sub_techniques: usize,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for TacticCountRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(self.name))
+            .add_cell(comfy_table::Cell::new(
+                self.domain.unwrap_or_else(|| String::from("-")),
+            ))
+            .add_cell(comfy_table::Cell::new(self.techniques))
+            .add_cell(comfy_table::Cell::new(self.sub_techniques));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct TacticCountsTable(pub Vec<TacticCountRow>);
+
+impl TacticCountsTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for TacticCountsTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&[
+            "ID",
+            "Tactic",
+            "Domain",
+            "Techniques",
+            "Sub-techniques",
+        ]));
+
+        for count in self.0 {
+            table.add_row(count);
+        }
+
+        return table;
+    }
+}
+
+/// Cached techniques split between parent techniques and sub-techniques.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct SubTechniqueStats {
+    pub techniques: usize,
+    pub sub_techniques: usize,
+    /// Parent techniques with at least one cached sub-technique.
+    pub techniques_with_sub_techniques: usize,
+}
+
+impl SubTechniqueStats {
+    /// Sub-techniques per parent technique.
+    pub fn ratio(&self) -> f64 {
+        if self.techniques == 0 {
+            return 0.0;
+        }
+
+        return self.sub_techniques as f64 / self.techniques as f64;
+    }
+}
+
+/// Cached techniques used by a number of cached groups within the bucket.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct GroupUsageRow {
+    /// Number of groups, "2-5", "21+"...
+    pub groups: String,
+    pub techniques: usize,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for GroupUsageRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.groups))
+            .add_cell(comfy_table::Cell::new(self.techniques));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct GroupUsageTable(pub Vec<GroupUsageRow>);
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for GroupUsageTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&["Groups", "Techniques"]));
+
+        for usage in self.0 {
+            table.add_row(usage);
+        }
+
+        return table;
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct SyncDateRow {
+    pub kind: String,
+    pub synced_at: Option<String>,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for SyncDateRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.kind))
+            .add_cell(comfy_table::Cell::new(
+                self.synced_at.unwrap_or_else(|| String::from("never")),
+            ));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SyncDatesTable(pub Vec<SyncDateRow>);
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for SyncDatesTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&["Type", "Last sync"]));
+
+        for sync_date in self.0 {
+            table.add_row(sync_date);
+        }
+
+        return table;
+    }
+}
+
+/// Summary of the local cache, for 'attack stats'.
+#[derive(Debug, Default, Serialize)]
+pub struct CacheStats {
+    pub attack_version: Option<String>,
+    /// End of the last full sync, from the sync manifest.
+    pub last_sync: Option<String>,
+    pub entities: DomainCountsTable,
+    pub tactics: TacticCountsTable,
+    pub sub_techniques: SubTechniqueStats,
+    pub groups_per_technique: GroupUsageTable,
+    pub synced: SyncDatesTable,
+}
+
+impl CacheStats {
+    pub fn build(cache: &Cache) -> Result<Self, error::Error> {
+        let snapshot = cache.snapshot()?;
+        let mut synced = SyncDatesTable::default();
+
+        for kind in EntityKind::ALL {
+            let kind_name: &str = kind.into();
+            synced.0.push(SyncDateRow {
+                kind: kind_name.to_string(),
+                synced_at: cache.synced_at(kind)?.map(|synced_at| {
+                    chrono::DateTime::<chrono::Utc>::from(synced_at)
+                        .format("%Y-%m-%d %H:%M UTC")
+                        .to_string()
+                }),
+            });
+        }
+
+        return Ok(Self {
+            attack_version: cache.attack_version()?,
+            last_sync: cache.manifest()?.map(|manifest| manifest.synced_at),
+            entities: domain_counts(cache, &snapshot)?,
+            tactics: tactic_counts(&snapshot),
+            sub_techniques: sub_technique_stats(&snapshot),
+            groups_per_technique: groups_per_technique(&snapshot),
+            synced,
+        });
+    }
+}
+
+/// Cached entities per type and domain. Tactics, techniques and mitigations belong to the
+/// domain listings including them, software and groups to the domains of the techniques
+/// they use.
+fn domain_counts(cache: &Cache, snapshot: &Snapshot) -> Result<DomainCountsTable, error::Error> {
+    let mut counts = DomainCountsTable::default();
+
+    for kind in EntityKind::ALL {
+        let kind_name: &str = kind.into();
+        let cached_ids = cache.entity_ids(kind)?;
+        let mut per_domain: BTreeMap<Option<&str>, usize> = BTreeMap::new();
+
+        match kind {
+            EntityKind::TACTIC | EntityKind::TECHNIQUE | EntityKind::MITIGATION => {
+                let mut listed: BTreeSet<String> = BTreeSet::new();
+
+                for domain in DOMAINS {
+                    let listed_ids: BTreeSet<String> = cache
+                        .listing_ids(kind, Some(domain))?
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|id| id.to_uppercase())
+                        .collect();
+                    let count = cached_ids
+                        .iter()
+                        .filter(|id| listed_ids.contains(*id))
+                        .count();
+
+                    if count > 0 {
+                        per_domain.insert(Some(domain), count);
+                    }
+                    listed.extend(listed_ids);
+                }
+
+                let unlisted = cached_ids.iter().filter(|id| !listed.contains(*id)).count();
+                if unlisted > 0 {
+                    per_domain.insert(None, unlisted);
+                }
+            }
+            EntityKind::SOFTWARE | EntityKind::GROUP => {
+                let used_techniques: Vec<&Option<DomainTechniquesTable>> =
+                    if kind == EntityKind::SOFTWARE {
+                        snapshot
+                            .software
+                            .iter()
+                            .map(|software| &software.techniques)
+                            .collect()
+                    } else {
+                        snapshot
+                            .groups
+                            .iter()
+                            .map(|group| &group.techniques)
+                            .collect()
+                    };
+
+                for techniques in used_techniques {
+                    let domains: Vec<&str> = DOMAINS
+                        .iter()
+                        .copied()
+                        .filter(|domain| {
+                            techniques
+                                .as_ref()
+                                .is_some_and(|techniques| techniques.has_domain(domain))
+                        })
+                        .collect();
+
+                    if domains.is_empty() {
+                        *per_domain.entry(None).or_default() += 1;
+                    }
+
+                    for domain in domains {
+                        *per_domain.entry(Some(domain)).or_default() += 1;
+                    }
+                }
+            }
+            EntityKind::DATASOURCE => {
+                if !cached_ids.is_empty() {
+                    per_domain.insert(None, cached_ids.len());
+                }
+            }
+        };
+
+        // Domains in the ATT&CK order, the entities without domain last.
+        for domain in DOMAINS.iter().map(|domain| Some(*domain)).chain([None]) {
+            if let Some(count) = per_domain.get(&domain) {
+                counts.0.push(DomainCountRow {
+                    kind: kind_name.to_string(),
+                    domain: domain.map(String::from),
+                    count: *count,
+                });
+            }
+        }
+    }
+
+    return Ok(counts);
+}
+
+/// Techniques and sub-techniques of the cached tactics, in the kill-chain order of every
+/// domain.
+fn tactic_counts(snapshot: &Snapshot) -> TacticCountsTable {
+    let mut tactics: Vec<_> = snapshot.tactics.iter().collect();
+    let domain_rank = |domain: &Option<String>| {
+        return DOMAINS
+            .iter()
+            .position(|known| domain.as_deref() == Some(*known))
+            .unwrap_or(DOMAINS.len());
+    };
+    tactics.sort_by(|a, b| {
+        domain_rank(&a.domain)
+            .cmp(&domain_rank(&b.domain))
+            .then(a.order.cmp(&b.order))
+            .then(a.id.cmp(&b.id))
+    });
+
+    return TacticCountsTable(
+        tactics
+            .into_iter()
+            .map(|tactic| {
+                let rows = tactic
+                    .techniques
+                    .iter()
+                    .flat_map(|techniques| &techniques.0);
+
+                TacticCountRow {
+                    id: tactic.id.clone(),
+                    name: tactic.name.clone(),
+                    domain: tactic.domain.clone(),
+                    techniques: rows.clone().count(),
+                    sub_techniques: rows
+                        .map(|row| row.sub_techniques.as_ref().map_or(0, |subs| subs.len()))
+                        .sum(),
+                }
+            })
+            .collect(),
+    );
+}
+
+fn sub_technique_stats(snapshot: &Snapshot) -> SubTechniqueStats {
+    let mut stats = SubTechniqueStats::default();
+    let mut parent_ids: BTreeSet<String> = BTreeSet::new();
+
+    for technique in &snapshot.techniques {
+        match technique.id.split_once('.') {
+            Some((parent_id, _)) => {
+                stats.sub_techniques += 1;
+                parent_ids.insert(parent_id.to_uppercase());
+            }
+            None => stats.techniques += 1,
+        }
+    }
+
+    stats.techniques_with_sub_techniques = snapshot
+        .techniques
+        .iter()
+        .filter(|technique| parent_ids.contains(&technique.id.to_uppercase()))
+        .count();
+
+    return stats;
+}
+
+/// Number of cached techniques (sub-techniques included) per number of cached groups using
+/// them.
+fn groups_per_technique(snapshot: &Snapshot) -> GroupUsageTable {
+    let mut groups_of: BTreeMap<String, usize> = BTreeMap::new();
+
+    for group in &snapshot.groups {
+        let technique_ids: BTreeSet<String> = group
+            .techniques
+            .iter()
+            .flat_map(|techniques| techniques.ids())
+            .map(|id| id.to_uppercase())
+            .collect();
+
+        for technique_id in technique_ids {
+            *groups_of.entry(technique_id).or_default() += 1;
+        }
+    }
+
+    return GroupUsageTable(
+        GROUP_BUCKETS
+            .iter()
+            .map(|(lowest, highest)| GroupUsageRow {
+                groups: match highest {
+                    Some(highest) if highest == lowest => lowest.to_string(),
+                    Some(highest) => format!("{}-{}", lowest, highest),
+                    None => format!("{}+", lowest),
+                },
+                techniques: snapshot
+                    .techniques
+                    .iter()
+                    .map(|technique| {
+                        groups_of
+                            .get(&technique.id.to_uppercase())
+                            .copied()
+                            .unwrap_or(0)
+                    })
+                    .filter(|groups| {
+                        groups >= lowest && highest.is_none_or(|highest| *groups <= highest)
+                    })
+                    .count(),
+            })
+            .collect(),
+    );
+}
+
+/// Technique referenced by the cached groups and software.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct TechniquePrevalenceRow {
+    pub rank: usize,
+    pub id: String,
+    pub name: String,
+    pub groups: usize,
+    pub software: usize,
+}
+
+impl TechniquePrevalenceRow {
+    pub fn total(&self) -> usize {
+        return self.groups + self.software;
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for TechniquePrevalenceRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.rank))
+            .add_cell(comfy_table::Cell::new(&self.id))
+            .add_cell(comfy_table::Cell::new(&self.name))
+            .add_cell(comfy_table::Cell::new(self.groups))
+            .add_cell(comfy_table::Cell::new(self.software))
+            .add_cell(comfy_table::Cell::new(self.total()));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct TechniquePrevalenceTable(pub Vec<TechniquePrevalenceRow>);
+
+impl TechniquePrevalenceTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for TechniquePrevalenceTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&[
+            "Rank", "ID", "Name", "Groups", "Software", "Total",
+        ]));
+
+        for technique in self.0 {
+            table.add_row(technique);
+        }
+
+        return table;
+    }
+}
+
+/// The `limit` techniques (sub-techniques included) referenced by the most cached groups
+/// and software, the most used first. With `domain`, only the techniques the groups and
+/// software use in that domain are counted.
+pub fn top_techniques(
+    snapshot: &Snapshot,
+    domain: Option<&str>,
+    limit: usize,
+) -> TechniquePrevalenceTable {
+    // Technique ID -> (name, referencing groups, referencing software).
+    let mut prevalence: BTreeMap<String, (String, usize, usize)> = BTreeMap::new();
+    let mut count = |techniques: &Option<DomainTechniquesTable>, is_group: bool| {
+        let mut counted: BTreeSet<String> = BTreeSet::new();
+
+        for technique in techniques.iter().flat_map(|techniques| &techniques.0) {
+            if domain.is_some_and(|domain| !technique.domain.eq_ignore_ascii_case(domain)) {
+                continue;
+            }
+
+            let sub_techniques = technique.sub_techniques.iter().flatten().map(|sub| {
+                (
+                    format!("{}{}", technique.id, sub.id),
+                    format!("{}: {}", technique.name, sub.name),
+                )
+            });
+
+            for (id, name) in [(technique.id.clone(), technique.name.clone())]
+                .into_iter()
+                .chain(sub_techniques)
+            {
+                let id = id.to_uppercase();
+
+                if !counted.insert(id.clone()) {
+                    continue;
+                }
+
+                let entry = prevalence.entry(id).or_insert((name, 0, 0));
+                if is_group {
+                    entry.1 += 1;
+                } else {
+                    entry.2 += 1;
+                }
+            }
+        }
+    };
+
+    for group in &snapshot.groups {
+        count(&group.techniques, true);
+    }
+
+    for software in &snapshot.software {
+        count(&software.techniques, false);
+    }
+
+    let cached_names: BTreeMap<String, &str> = snapshot
+        .techniques
+        .iter()
+        .map(|technique| (technique.id.to_uppercase(), technique.name.as_str()))
+        .collect();
+    let mut rows: Vec<TechniquePrevalenceRow> = prevalence
+        .into_iter()
+        .map(|(id, (name, groups, software))| TechniquePrevalenceRow {
+            rank: 0,
+            name: cached_names
+                .get(&id)
+                .map_or(name, |cached_name| cached_name.to_string()),
+            id,
+            groups,
+            software,
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        b.total()
+            .cmp(&a.total())
+            .then(b.groups.cmp(&a.groups))
+            .then(a.id.cmp(&b.id))
+    });
+    rows.truncate(limit);
+
+    for (inx, row) in rows.iter_mut().enumerate() {
+        row.rank = inx + 1;
+    }
+
+    return TechniquePrevalenceTable(rows);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack::{
+        groups::Group,
+        software::Software,
+        tactics::Tactic,
+        techniques::{
+            domain::{DomainSubTechniqueRow, DomainTechniqueRow},
+            SubTechniqueRow, Technique, TechniqueRow, TechniquesTable,
+        },
+    };
+
+    fn used_technique(domain: &str, id: &str) -> DomainTechniqueRow {
+        return DomainTechniqueRow {
+            domain: domain.to_string(),
+            id: id.to_string(),
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn test_cache_stats() -> Result<(), error::Error> {
+        let cache = crate::attack::cache::test_cache("stats");
+
+        cache.save_listing(
+            EntityKind::TECHNIQUE,
+            Some("enterprise"),
+            &TechniquesTable(vec![TechniqueRow {
+                id: String::from("T1059"),
+                sub_techniques: Some(vec![SubTechniqueRow {
+                    id: String::from(".001"),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }]),
+        )?;
+        for id in ["T1059", "T1059.001", "T1610"] {
+            cache.save_entity(
+                EntityKind::TECHNIQUE,
+                id,
+                &Technique {
+                    id: id.to_string(),
+                    ..Default::default()
+                },
+            )?;
+        }
+        cache.save_entity(
+            EntityKind::TACTIC,
+            "TA0002",
+            &Tactic {
+                id: String::from("TA0002"),
+                name: String::from("Execution"),
+                domain: Some(String::from("enterprise")),
+                techniques: cache.load_listing(EntityKind::TECHNIQUE, Some("enterprise"))?,
+                ..Default::default()
+            },
+        )?;
+        for (id, techniques) in [
+            (
+                "G0016",
+                vec![
+                    used_technique("enterprise", "T1059"),
+                    used_technique("enterprise", "T1059.001"),
+                ],
+            ),
+            ("G0007", vec![used_technique("enterprise", "T1059")]),
+            ("G0034", vec![used_technique("ics", "T0866")]),
+        ] {
+            cache.save_entity(
+                EntityKind::GROUP,
+                id,
+                &Group {
+                    id: id.to_string(),
+                    techniques: Some(DomainTechniquesTable(techniques)),
+                    ..Default::default()
+                },
+            )?;
+        }
+
+        let stats = CacheStats::build(&cache)?;
+
+        assert_eq!(
+            stats
+                .entities
+                .0
+                .iter()
+                .map(|row| (row.kind.as_str(), row.domain.as_deref(), row.count))
+                .collect::<Vec<_>>(),
+            vec![
+                ("tactics", None, 1),
+                ("techniques", Some("enterprise"), 2),
+                ("techniques", None, 1),
+                ("groups", Some("enterprise"), 2),
+                ("groups", Some("ics"), 1),
+            ]
+        );
+        assert_eq!(
+            stats.tactics.0,
+            vec![TacticCountRow {
+                id: String::from("TA0002"),
+                name: String::from("Execution"),
+                domain: Some(String::from("enterprise")),
+                techniques: 1,
+                sub_techniques: 1,
+            }]
+        );
+        assert_eq!(
+            stats.sub_techniques,
+            SubTechniqueStats {
+                techniques: 2,
+                sub_techniques: 1,
+                techniques_with_sub_techniques: 1,
+            }
+        );
+        assert_eq!(stats.sub_techniques.ratio(), 0.5);
+        assert_eq!(
+            stats
+                .groups_per_technique
+                .0
+                .iter()
+                .map(|row| (row.groups.as_str(), row.techniques))
+                .collect::<Vec<_>>(),
+            vec![
+                ("0", 1),
+                ("1", 1),
+                ("2-5", 1),
+                ("6-10", 0),
+                ("11-20", 0),
+                ("21+", 0)
+            ]
+        );
+        assert_eq!(stats.last_sync, None);
+        assert!(stats.synced.0[1].synced_at.is_some());
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_techniques() {
+        let snapshot = Snapshot {
+            groups: vec![
+                Group {
+                    id: String::from("G0016"),
+                    techniques: Some(DomainTechniquesTable(vec![
+                        DomainTechniqueRow {
+                            domain: String::from("Enterprise"),
+                            id: String::from("T1059"),
+                            name: String::from("Command and Scripting Interpreter"),
+                            sub_techniques: Some(vec![DomainSubTechniqueRow {
+                                id: String::from(".001"),
+                                name: String::from("PowerShell"),
+                                ..Default::default()
+                            }]),
+                            ..Default::default()
+                        },
+                        used_technique("Mobile", "T1406"),
+                    ])),
+                    ..Default::default()
+                },
+                Group {
+                    id: String::from("G0007"),
+                    techniques: Some(DomainTechniquesTable(vec![used_technique(
+                        "Enterprise",
+                        "T1059",
+                    )])),
+                    ..Default::default()
+                },
+            ],
+            software: vec![Software {
+                id: String::from("S0002"),
+                techniques: Some(DomainTechniquesTable(vec![used_technique(
+                    "Enterprise",
+                    "T1003",
+                )])),
+                ..Default::default()
+            }],
+            techniques: vec![Technique {
+                id: String::from("T1003"),
+                name: String::from("OS Credential Dumping"),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let top = top_techniques(&snapshot, None, 10);
+        assert_eq!(
+            top.0
+                .iter()
+                .map(|row| (row.rank, row.id.as_str(), row.groups, row.software))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, "T1059", 2, 0),
+                (2, "T1059.001", 1, 0),
+                (3, "T1406", 1, 0),
+                (4, "T1003", 0, 1),
+            ]
+        );
+        assert_eq!(
+            top.0[1].name,
+            "Command and Scripting Interpreter: PowerShell"
+        );
+        assert_eq!(top.0[3].name, "OS Credential Dumping");
+
+        let top = top_techniques(&snapshot, Some("mobile"), 10);
+        assert_eq!(top.0.len(), 1);
+        assert_eq!(top.0[0].id, "T1406");
+        assert_eq!(top_techniques(&snapshot, None, 1).0.len(), 1);
+    }
+}
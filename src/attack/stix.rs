@@ -0,0 +1,586 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use super::{
+    cache::Snapshot, data_sources::DataSource, groups::Group, mitigations::Mitigation,
+    software::Software, tactics::Tactic, techniques::Technique,
+};
+
+/// Namespace used by OpenCTI (pycti) to generate the deterministic STIX IDs.
+const OPENCTI_NAMESPACE: Uuid = Uuid::from_u128(0x00abedb4_aa42_466c_9c01_fed23315a9b7);
+const TLP_WHITE_MARKING_ID: &'static str =
+    "marking-definition--613f2e26-407d-48c7-9eca-b8e91df99dc9";
+const MITRE_IDENTITY_NAME: &'static str = "The MITRE Corporation";
+const ATTCK_SOURCE_NAME: &'static str = "mitre-attack";
+const ATTCK_KILL_CHAIN_NAME: &'static str = "mitre-attack";
+const ATTCK_URL: &'static str = "https://attack.mitre.org";
+
+/// STIX ID generated the same way OpenCTI does: an UUIDv5 of the canonical JSON of the
+/// contributing properties.
+fn opencti_id(stix_type: &str, contributing_properties: Value) -> String {
+    return format!(
+        "{}--{}",
+        stix_type,
+        Uuid::new_v5(
+            &OPENCTI_NAMESPACE,
+            contributing_properties.to_string().as_bytes()
+        )
+    );
+}
+
+fn attck_object_id(stix_type: &str, attck_id: &str) -> String {
+    return opencti_id(stix_type, json!({ "x_mitre_id": attck_id }));
+}
+
+fn named_object_id(stix_type: &str, name: &str) -> String {
+    return opencti_id(stix_type, json!({ "name": name.trim().to_lowercase() }));
+}
+
+fn relationship_id(relationship_type: &str, source_ref: &str, target_ref: &str) -> String {
+    return opencti_id(
+        "relationship",
+        json!({
+            "relationship_type": relationship_type,
+            "source_ref": source_ref,
+            "target_ref": target_ref,
+        }),
+    );
+}
+
+/// ATT&CK page of the entity, sub-techniques live under their parent technique path.
+fn attck_url(path: &str, attck_id: &str) -> String {
+    return format!("{}/{}/{}/", ATTCK_URL, path, attck_id.replace('.', "/"));
+}
+
+fn external_references(path: &str, attck_id: &str) -> Value {
+    return json!([{
+        "source_name": ATTCK_SOURCE_NAME,
+        "external_id": attck_id,
+        "url": attck_url(path, attck_id),
+    }]);
+}
+
+/// Kill chain phase name of a tactic ("Privilege Escalation" -> "privilege-escalation").
+fn phase_name(tactic_name: &str) -> String {
+    return tactic_name
+        .trim()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join("-");
+}
+
+fn software_stix_type(software: &Software) -> &'static str {
+    return match software.software_type.as_deref() {
+        Some("tool") => "tool",
+        _ => "malware",
+    };
+}
+
+/// STIX 2.1 bundle, IDs follow the OpenCTI conventions so both exports stay consistent.
+struct BundleBuilder<'a> {
+    timestamp: &'a str,
+    identity_id: String,
+    objects: Vec<Value>,
+    /// ATT&CK ID -> STIX ID of the entities included in the bundle.
+    stix_ids: HashMap<String, String>,
+    relationships: BTreeSet<(String, String, String)>,
+}
+
+impl<'a> BundleBuilder<'a> {
+    fn new(timestamp: &'a str) -> Self {
+        let identity_id = opencti_id(
+            "identity",
+            json!({
+                "identity_class": "organization",
+                "name": MITRE_IDENTITY_NAME.to_lowercase(),
+            }),
+        );
+        let identity = json!({
+            "type": "identity",
+            "spec_version": "2.1",
+            "id": identity_id,
+            "created": timestamp,
+            "modified": timestamp,
+            "name": MITRE_IDENTITY_NAME,
+            "identity_class": "organization",
+            "object_marking_refs": [TLP_WHITE_MARKING_ID],
+        });
+        let tlp_white = json!({
+            "type": "marking-definition",
+            "spec_version": "2.1",
+            "id": TLP_WHITE_MARKING_ID,
+            "created": "2017-01-20T00:00:00.000Z",
+            "definition_type": "tlp",
+            "name": "TLP:WHITE",
+            "definition": { "tlp": "white" },
+        });
+
+        return Self {
+            timestamp,
+            identity_id,
+            objects: vec![tlp_white, identity],
+            stix_ids: HashMap::new(),
+            relationships: BTreeSet::new(),
+        };
+    }
+
+    /// Add a domain object with the properties shared by every exported entity.
+    fn add_object(
+        &mut self,
+        stix_type: &str,
+        stix_id: String,
+        attck_id: &str,
+        attck_path: &str,
+        mut properties: Value,
+    ) {
+        let object = properties.as_object_mut().unwrap();
+        object.insert(String::from("type"), json!(stix_type));
+        object.insert(String::from("spec_version"), json!("2.1"));
+        object.insert(String::from("id"), json!(stix_id));
+        object.insert(String::from("created"), json!(self.timestamp));
+        object.insert(String::from("modified"), json!(self.timestamp));
+        object.insert(String::from("created_by_ref"), json!(self.identity_id));
+        object.insert(
+            String::from("object_marking_refs"),
+            json!([TLP_WHITE_MARKING_ID]),
+        );
+        object.insert(String::from("x_mitre_id"), json!(attck_id));
+        object.insert(
+            String::from("external_references"),
+            external_references(attck_path, attck_id),
+        );
+
+        self.stix_ids.insert(attck_id.to_uppercase(), stix_id);
+        self.objects.push(properties);
+    }
+
+    fn add_technique(&mut self, technique: &Technique, kill_chain_phases: &[String]) {
+        let is_subtechnique = technique.id.contains('.');
+
+        self.add_object(
+            "attack-pattern",
+            attck_object_id("attack-pattern", &technique.id),
+            &technique.id,
+            "techniques",
+            json!({
+                "name": technique.name,
+                "description": technique.description,
+                "x_mitre_is_subtechnique": is_subtechnique,
+                "kill_chain_phases": kill_chain_phases
+                    .iter()
+                    .map(|phase| json!({
+                        "kill_chain_name": ATTCK_KILL_CHAIN_NAME,
+                        "phase_name": phase,
+                    }))
+                    .collect::<Vec<Value>>(),
+                "x_mitre_contributors": technique.contributors,
+            }),
+        );
+
+        if let Some((parent_id, _)) = technique.id.split_once('.') {
+            self.relate("subtechnique-of", &technique.id, parent_id);
+        }
+
+        if let Some(mitigations) = &technique.mitigations {
+            for mitigation in &mitigations.0 {
+                self.relate("mitigates", &mitigation.id, &technique.id);
+            }
+        }
+    }
+
+    fn add_mitigation(&mut self, mitigation: &Mitigation) {
+        self.add_object(
+            "course-of-action",
+            attck_object_id("course-of-action", &mitigation.id),
+            &mitigation.id,
+            "mitigations",
+            json!({
+                "name": mitigation.name,
+                "description": mitigation.desc,
+            }),
+        );
+
+        if let Some(techniques) = &mitigation.addressed_techniques {
+            for technique_id in techniques.ids() {
+                self.relate("mitigates", &mitigation.id, &technique_id);
+            }
+        }
+    }
+
+    fn add_software(&mut self, software: &Software) {
+        let stix_type = software_stix_type(software);
+
+        self.add_object(
+            stix_type,
+            named_object_id(stix_type, &software.name),
+            &software.id,
+            "software",
+            json!({
+                "name": software.name,
+                "description": software.desc,
+                "is_family": stix_type == "malware",
+                "x_mitre_contributors": software.contributors,
+            }),
+        );
+
+        if let Some(techniques) = &software.techniques {
+            for technique_id in techniques.ids() {
+                self.relate("uses", &software.id, &technique_id);
+            }
+        }
+    }
+
+    fn add_group(&mut self, group: &Group) {
+        self.add_object(
+            "intrusion-set",
+            named_object_id("intrusion-set", &group.name),
+            &group.id,
+            "groups",
+            json!({
+                "name": group.name,
+                "description": group.desc,
+                "aliases": group.assoc_groups.clone().unwrap_or_default(),
+            }),
+        );
+
+        if let Some(techniques) = &group.techniques {
+            for technique_id in techniques.ids() {
+                self.relate("uses", &group.id, &technique_id);
+            }
+        }
+
+        if let Some(software) = &group.software {
+            for software_row in &software.0 {
+                self.relate("uses", &group.id, &software_row.id);
+            }
+        }
+    }
+
+    fn add_tactic(&mut self, tactic: &Tactic) {
+        self.add_object(
+            "x-mitre-tactic",
+            attck_object_id("x-mitre-tactic", &tactic.id),
+            &tactic.id,
+            "tactics",
+            json!({
+                "name": tactic.name,
+                "description": tactic.description,
+                "x_mitre_shortname": phase_name(&tactic.name),
+            }),
+        );
+    }
+
+    fn add_data_source(&mut self, data_source: &DataSource) {
+        self.add_object(
+            "x-mitre-data-source",
+            attck_object_id("x-mitre-data-source", &data_source.id),
+            &data_source.id,
+            "datasources",
+            json!({
+                "name": data_source.name,
+                "description": data_source.description,
+            }),
+        );
+    }
+
+    fn relate(&mut self, relationship_type: &str, source_id: &str, target_id: &str) {
+        self.relationships.insert((
+            relationship_type.to_string(),
+            source_id.to_uppercase(),
+            target_id.to_uppercase(),
+        ));
+    }
+
+    /// Bundle with the collected objects, relationships pointing to entities outside
+    /// of the bundle are dropped so the import does not reference missing objects.
+    fn build(mut self) -> Value {
+        for (relationship_type, source_id, target_id) in &self.relationships {
+            let (source_ref, target_ref) =
+                match (self.stix_ids.get(source_id), self.stix_ids.get(target_id)) {
+                    (Some(source_ref), Some(target_ref)) => (source_ref, target_ref),
+                    _ => continue,
+                };
+
+            self.objects.push(json!({
+                "type": "relationship",
+                "spec_version": "2.1",
+                "id": relationship_id(relationship_type, source_ref, target_ref),
+                "created": self.timestamp,
+                "modified": self.timestamp,
+                "created_by_ref": self.identity_id,
+                "object_marking_refs": [TLP_WHITE_MARKING_ID],
+                "relationship_type": relationship_type,
+                "source_ref": source_ref,
+                "target_ref": target_ref,
+            }));
+        }
+
+        return json!({
+            "type": "bundle",
+            "id": format!("bundle--{}", Uuid::new_v4()),
+            "objects": self.objects,
+        });
+    }
+}
+
+/// Add the techniques (with the kill chain phases of the cached tactics), mitigations,
+/// software and groups of the snapshot.
+fn add_snapshot_entities(builder: &mut BundleBuilder, snapshot: &Snapshot) {
+    let mut kill_chain_phases: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for tactic in &snapshot.tactics {
+        if let Some(techniques) = &tactic.techniques {
+            for technique_id in techniques.ids() {
+                kill_chain_phases
+                    .entry(technique_id)
+                    .or_default()
+                    .push(phase_name(&tactic.name));
+            }
+        }
+    }
+
+    for technique in &snapshot.techniques {
+        let phases = kill_chain_phases
+            .get(&technique.id.to_uppercase())
+            .or_else(|| {
+                technique
+                    .id
+                    .split_once('.')
+                    .and_then(|(parent_id, _)| kill_chain_phases.get(parent_id))
+            })
+            .cloned()
+            .unwrap_or_default();
+
+        builder.add_technique(technique, &phases);
+    }
+
+    for mitigation in &snapshot.mitigations {
+        builder.add_mitigation(mitigation);
+    }
+
+    for software in &snapshot.software {
+        builder.add_software(software);
+    }
+
+    for group in &snapshot.groups {
+        builder.add_group(group);
+    }
+}
+
+/// Convert the snapshot into a STIX 2.1 bundle importable by OpenCTI.
+///
+/// Techniques are exported as attack patterns (with the kill chain phases of the cached
+/// tactics), mitigations as courses of action, groups as intrusion sets and software as
+/// malware or tools. Every object is created by the MITRE identity and marked as TLP:WHITE.
+pub fn build_opencti_bundle(snapshot: &Snapshot, timestamp: &str) -> Value {
+    let mut builder = BundleBuilder::new(timestamp);
+    add_snapshot_entities(&mut builder, snapshot);
+
+    return builder.build();
+}
+
+/// Convert the snapshot into a generic STIX 2.1 bundle (TAXII servers, other TIPs).
+///
+/// Holds the same objects as the OpenCTI bundle plus the tactics and data sources, using
+/// the `x-mitre-tactic` and `x-mitre-data-source` custom objects of the ATT&CK STIX data.
+pub fn build_stix_bundle(snapshot: &Snapshot, timestamp: &str) -> Value {
+    let mut builder = BundleBuilder::new(timestamp);
+    add_snapshot_entities(&mut builder, snapshot);
+
+    for tactic in &snapshot.tactics {
+        builder.add_tactic(tactic);
+    }
+
+    for data_source in &snapshot.data_sources {
+        builder.add_data_source(data_source);
+    }
+
+    return builder.build();
+}
+
+/// Objects of the bundle as newline delimited JSON, one STIX object per line.
+///
+/// Objects keep the bundle order (markings and identity first, relationships last), so a
+/// connector can push them one by one without referencing an object not yet imported.
+pub fn bundle_to_ndjson(bundle: &Value) -> Result<String, serde_json::Error> {
+    let mut ndjson = String::new();
+
+    for object in bundle["objects"].as_array().into_iter().flatten() {
+        ndjson.push_str(&serde_json::to_string(object)?);
+        ndjson.push('\n');
+    }
+
+    return Ok(ndjson);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attack::{
+            mitigations::{fetch_mitigation, MitigationRow, MitigationTable},
+            software::fetch_software_info,
+        },
+        error,
+        fakers::FakeHttpReqwest,
+    };
+
+    const TEST_TIMESTAMP: &'static str = "2022-10-01T00:00:00.000Z";
+
+    fn objects_of_type<'a>(bundle: &'a Value, stix_type: &str) -> Vec<&'a Value> {
+        return bundle["objects"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|object| object["type"] == stix_type)
+            .collect();
+    }
+
+    fn test_snapshot() -> Result<Snapshot, error::Error> {
+        let mitigation = fetch_mitigation(
+            "M1052",
+            &FakeHttpReqwest::default().set_success_response(
+                include_str!("html/attck/mitigations/user_account_control.html").to_string(),
+            ),
+        )?;
+        let software = fetch_software_info(
+            "S0029",
+            &FakeHttpReqwest::default()
+                .set_success_response(include_str!("html/attck/software/psexec.html").to_string()),
+        )?;
+        let technique = Technique {
+            id: String::from("T1548.002"),
+            name: String::from("Bypass User Account Control"),
+            description: String::from("Custom technique description"),
+            mitigations: Some(MitigationTable(vec![MitigationRow {
+                id: String::from("M1052"),
+                name: String::from("User Account Control"),
+                description: String::new(),
+            }])),
+            ..Default::default()
+        };
+
+        return Ok(Snapshot {
+            techniques: vec![technique],
+            mitigations: vec![mitigation],
+            software: vec![software],
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn test_bundle_identity_and_markings() -> Result<(), error::Error> {
+        let bundle = build_opencti_bundle(&test_snapshot()?, TEST_TIMESTAMP);
+        let identity_id = objects_of_type(&bundle, "identity")[0]["id"].clone();
+
+        assert_eq!(bundle["type"], "bundle");
+        assert_eq!(objects_of_type(&bundle, "marking-definition").len(), 1);
+
+        for object in objects_of_type(&bundle, "attack-pattern")
+            .into_iter()
+            .chain(objects_of_type(&bundle, "course-of-action"))
+            .chain(objects_of_type(&bundle, "tool"))
+            .chain(objects_of_type(&bundle, "relationship"))
+        {
+            assert_eq!(object["created_by_ref"], identity_id);
+            assert_eq!(object["object_marking_refs"][0], TLP_WHITE_MARKING_ID);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bundle_entities() -> Result<(), error::Error> {
+        let bundle = build_opencti_bundle(&test_snapshot()?, TEST_TIMESTAMP);
+        let attack_pattern = objects_of_type(&bundle, "attack-pattern")[0];
+
+        assert_eq!(attack_pattern["x_mitre_id"], "T1548.002");
+        assert_eq!(attack_pattern["x_mitre_is_subtechnique"], true);
+        assert_eq!(
+            attack_pattern["external_references"][0]["url"],
+            "https://attack.mitre.org/techniques/T1548/002/"
+        );
+        assert_eq!(objects_of_type(&bundle, "course-of-action").len(), 1);
+        assert_eq!(objects_of_type(&bundle, "tool").len(), 1);
+        assert_eq!(objects_of_type(&bundle, "malware").len(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bundle_relationships_are_deduplicated_and_resolved() -> Result<(), error::Error> {
+        let bundle = build_opencti_bundle(&test_snapshot()?, TEST_TIMESTAMP);
+        let relationships = objects_of_type(&bundle, "relationship");
+        let mitigates = relationships
+            .iter()
+            .filter(|relationship| relationship["relationship_type"] == "mitigates")
+            .count();
+
+        // Declared by both the technique and the mitigation.
+        assert_eq!(mitigates, 1);
+
+        // The parent technique is not in the snapshot.
+        assert!(relationships
+            .iter()
+            .all(|relationship| relationship["relationship_type"] != "subtechnique-of"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stix_bundle_includes_tactics() -> Result<(), error::Error> {
+        let mut snapshot = test_snapshot()?;
+        snapshot.tactics.push(Tactic {
+            id: String::from("TA0004"),
+            name: String::from("Privilege Escalation"),
+            ..Default::default()
+        });
+
+        let opencti_bundle = build_opencti_bundle(&snapshot, TEST_TIMESTAMP);
+        let stix_bundle = build_stix_bundle(&snapshot, TEST_TIMESTAMP);
+        let tactics = objects_of_type(&stix_bundle, "x-mitre-tactic");
+
+        assert!(objects_of_type(&opencti_bundle, "x-mitre-tactic").is_empty());
+        assert_eq!(tactics.len(), 1);
+        assert_eq!(tactics[0]["x_mitre_shortname"], "privilege-escalation");
+        assert_eq!(
+            objects_of_type(&stix_bundle, "attack-pattern")[0]["id"],
+            objects_of_type(&opencti_bundle, "attack-pattern")[0]["id"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bundle_to_ndjson() -> Result<(), error::Error> {
+        let bundle = build_opencti_bundle(&test_snapshot()?, TEST_TIMESTAMP);
+        let objects = bundle["objects"].as_array().unwrap();
+        let ndjson = bundle_to_ndjson(&bundle)?;
+        let lines = ndjson.lines().collect::<Vec<&str>>();
+
+        assert_eq!(lines.len(), objects.len());
+        assert_eq!(serde_json::from_str::<Value>(lines[0])?, objects[0]);
+        assert_eq!(
+            serde_json::from_str::<Value>(lines[lines.len() - 1])?["type"],
+            "relationship"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deterministic_ids() {
+        assert_eq!(
+            attck_object_id("attack-pattern", "T1003"),
+            attck_object_id("attack-pattern", "T1003")
+        );
+        assert_eq!(
+            named_object_id("intrusion-set", " APT1 "),
+            named_object_id("intrusion-set", "apt1")
+        );
+        assert_eq!(phase_name("Privilege Escalation"), "privilege-escalation");
+    }
+}
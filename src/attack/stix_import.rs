@@ -0,0 +1,1119 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{error, remove_ext_link_ref};
+
+use super::{
+    cache::EntityKind,
+    data_sources::{self, DataComponent, DataSource, DataSourceRow, DataSourcesTable},
+    dataset::{Dataset, DatasetEntity, DatasetListing},
+    groups::{self, Group, GroupRow, GroupsTable, TechniqueRef},
+    mitigations::{Mitigation, MitigationRow, MitigationTable},
+    software::{AssocGroupsRow, AssocGroupsTable, Software, SoftwareRow, SoftwareTable},
+    tactics::{Tactic, TacticRow, TacticsTable},
+    techniques::{
+        self,
+        domain::{DomainSubTechniqueRow, DomainTechniqueRow, DomainTechniquesTable},
+        ParentTechnique, ProcedureRow, ProcedureType, ProceduresTable, SubTechniqueLink,
+        SubTechniqueRow, SubTechniquesTable, Technique, TechniqueRow, TechniquesTable,
+    },
+    Reference,
+};
+
+const ATTCK_SOURCE_NAME: &'static str = "mitre-attack";
+
+lazy_static! {
+    static ref CITATION_RE: regex::Regex = regex::Regex::new(r"\s*\(Citation:[^)]*\)").unwrap();
+    static ref LINK_RE: regex::Regex = regex::Regex::new(r"\[([^\]]*)\]\([^)]*\)").unwrap();
+}
+
+fn str_of<'a>(object: &'a Value, key: &str) -> &'a str {
+    return object[key].as_str().unwrap_or_default();
+}
+
+fn strings_of(object: &Value, key: &str) -> Vec<String> {
+    return object[key]
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|value| value.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+}
+
+/// Neither revoked nor deprecated.
+fn is_active(object: &Value) -> bool {
+    return !object["revoked"].as_bool().unwrap_or(false)
+        && !object["x_mitre_deprecated"].as_bool().unwrap_or(false);
+}
+
+/// ATT&CK ID and page of the object, from its `mitre-attack` external reference.
+fn attck_ref(object: &Value) -> Option<(String, String)> {
+    return object["external_references"]
+        .as_array()?
+        .iter()
+        .find(|reference| str_of(reference, "source_name") == ATTCK_SOURCE_NAME)
+        .map(|reference| {
+            (
+                str_of(reference, "external_id").to_string(),
+                str_of(reference, "url").to_string(),
+            )
+        })
+        .filter(|(id, _)| !id.is_empty());
+}
+
+fn attck_id(object: &Value) -> String {
+    return attck_ref(object).map(|(id, _)| id).unwrap_or_default();
+}
+
+/// Domains (enterprise, mobile, ics) of the object.
+fn domains_of(object: &Value) -> Vec<&'static str> {
+    return strings_of(object, "x_mitre_domains")
+        .iter()
+        .filter_map(|domain| match domain.as_str() {
+            "enterprise-attack" => Some("enterprise"),
+            "mobile-attack" => Some("mobile"),
+            "ics-attack" => Some("ics"),
+            _ => None,
+        })
+        .collect();
+}
+
+/// Domain column of the technique tables of the website (Enterprise, Mobile, ICS).
+fn domain_title(object: &Value) -> String {
+    return match domains_of(object).first() {
+        Some(&"ics") => String::from("ICS"),
+        Some(&"mobile") => String::from("Mobile"),
+        _ => String::from("Enterprise"),
+    };
+}
+
+/// Markdown description of the bundle as the text scraped from the website: without the
+/// citations and the link targets, one paragraph per line.
+fn plain_text(markdown: &str) -> String {
+    return markdown
+        .split('\n')
+        .map(|paragraph| {
+            let paragraph = CITATION_RE.replace_all(paragraph, "");
+
+            remove_ext_link_ref(&LINK_RE.replace_all(&paragraph, "$1"))
+        })
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect::<Vec<String>>()
+        .join("\n");
+}
+
+/// First paragraph of the description, as shown by the listings.
+fn summary(object: &Value) -> String {
+    return plain_text(str_of(object, "description"))
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .to_string();
+}
+
+fn description(object: &Value) -> String {
+    return plain_text(str_of(object, "description"));
+}
+
+fn references(object: &Value) -> Vec<Reference> {
+    return object["external_references"]
+        .as_array()
+        .map(|references| {
+            references
+                .iter()
+                .filter(|reference| str_of(reference, "source_name") != ATTCK_SOURCE_NAME)
+                .filter(|reference| !str_of(reference, "url").is_empty())
+                .enumerate()
+                .map(|(inx, reference)| Reference {
+                    description: match str_of(reference, "description") {
+                        "" => str_of(reference, "source_name").to_string(),
+                        description => description.to_string(),
+                    },
+                    url: str_of(reference, "url").to_string(),
+                    number: Some(inx as u32 + 1),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+}
+
+/// Aliases of a group or software, without its own name.
+fn aliases(object: &Value, key: &str) -> Option<Vec<String>> {
+    let name = str_of(object, "name");
+    let aliases: Vec<String> = strings_of(object, key)
+        .into_iter()
+        .filter(|alias| alias != name)
+        .collect();
+
+    if aliases.is_empty() {
+        return None;
+    }
+
+    return Some(aliases);
+}
+
+/// YYYY-MM-DD date of a STIX timestamp.
+fn date_of(object: &Value, key: &str) -> Option<String> {
+    return object[key]
+        .as_str()
+        .and_then(|timestamp| timestamp.get(..10))
+        .map(String::from);
+}
+
+fn by_attck_id(objects: &mut [&Value]) {
+    objects.sort_by_key(|object| attck_id(object));
+}
+
+/// Objects and relationships of one or more ATT&CK STIX bundles, indexed for the lookups.
+struct StixIndex<'a> {
+    objects: HashMap<&'a str, &'a Value>,
+    /// Active objects (neither revoked nor deprecated) of every STIX type, bundle order.
+    by_type: HashMap<&'a str, Vec<&'a Value>>,
+    techniques: HashMap<String, &'a Value>,
+    relationships_from: HashMap<&'a str, Vec<&'a Value>>,
+    relationships_to: HashMap<&'a str, Vec<&'a Value>>,
+}
+
+impl<'a> StixIndex<'a> {
+    fn new(bundles: &'a [Value]) -> Self {
+        let mut index = Self {
+            objects: HashMap::new(),
+            by_type: HashMap::new(),
+            techniques: HashMap::new(),
+            relationships_from: HashMap::new(),
+            relationships_to: HashMap::new(),
+        };
+
+        for object in bundles.iter().flat_map(|bundle| {
+            bundle["objects"]
+                .as_array()
+                .map(|objects| objects.as_slice())
+                .unwrap_or_default()
+        }) {
+            let stix_id = str_of(object, "id");
+            if stix_id.is_empty() || index.objects.contains_key(stix_id) {
+                continue;
+            }
+            index.objects.insert(stix_id, object);
+
+            let stix_type = str_of(object, "type");
+            if stix_type == "relationship" {
+                if !is_active(object) && str_of(object, "relationship_type") != "revoked-by" {
+                    continue;
+                }
+
+                index
+                    .relationships_from
+                    .entry(str_of(object, "source_ref"))
+                    .or_default()
+                    .push(object);
+                index
+                    .relationships_to
+                    .entry(str_of(object, "target_ref"))
+                    .or_default()
+                    .push(object);
+            } else {
+                if stix_type == "attack-pattern" {
+                    if let Some((id, _)) = attck_ref(object) {
+                        index.techniques.insert(id, object);
+                    }
+                }
+
+                if is_active(object) {
+                    index.by_type.entry(stix_type).or_default().push(object);
+                }
+            }
+        }
+
+        return index;
+    }
+
+    /// Active ATT&CK objects of the types, sorted by ID.
+    fn of_types(&self, stix_types: &[&str]) -> Vec<&'a Value> {
+        let mut objects: Vec<&'a Value> = stix_types
+            .iter()
+            .flat_map(|stix_type| self.by_type.get(stix_type).cloned().unwrap_or_default())
+            .filter(|object| attck_ref(object).is_some())
+            .collect();
+        by_attck_id(&mut objects);
+
+        return objects;
+    }
+
+    /// Active objects of the types targeted by `relationship_type` relationships of `object`,
+    /// with the relationship description.
+    fn targets(
+        &self,
+        object: &Value,
+        relationship_type: &str,
+        stix_types: &[&str],
+    ) -> Vec<(&'a Value, &'a str)> {
+        return self.related(
+            self.relationships_from.get(str_of(object, "id")),
+            "target_ref",
+            relationship_type,
+            stix_types,
+        );
+    }
+
+    /// Active objects of the types with `relationship_type` relationships to `object`.
+    fn sources(
+        &self,
+        object: &Value,
+        relationship_type: &str,
+        stix_types: &[&str],
+    ) -> Vec<(&'a Value, &'a str)> {
+        return self.related(
+            self.relationships_to.get(str_of(object, "id")),
+            "source_ref",
+            relationship_type,
+            stix_types,
+        );
+    }
+
+    fn related(
+        &self,
+        relationships: Option<&Vec<&'a Value>>,
+        end: &str,
+        relationship_type: &str,
+        stix_types: &[&str],
+    ) -> Vec<(&'a Value, &'a str)> {
+        let mut related: Vec<(&'a Value, &'a str)> = relationships
+            .map(|relationships| relationships.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .filter(|relationship| str_of(relationship, "relationship_type") == relationship_type)
+            .filter(|relationship| is_active(relationship))
+            .filter_map(|relationship| {
+                self.objects
+                    .get(str_of(relationship, end))
+                    .filter(|object| stix_types.contains(&str_of(object, "type")))
+                    .filter(|object| is_active(object))
+                    .map(|object| (*object, str_of(relationship, "description")))
+            })
+            .collect();
+        related.sort_by_key(|(object, _)| attck_id(object));
+
+        return related;
+    }
+
+    /// ATT&CK ID of the object revoking `object`.
+    fn revoked_by(&self, object: &Value) -> Option<String> {
+        return self
+            .relationships_from
+            .get(str_of(object, "id"))?
+            .iter()
+            .filter(|relationship| str_of(relationship, "relationship_type") == "revoked-by")
+            .find_map(|relationship| self.objects.get(str_of(relationship, "target_ref")))
+            .and_then(|object| attck_ref(object))
+            .map(|(id, _)| id);
+    }
+
+    /// Tactics of the domain in the matrix order, with their kill chain phase name.
+    fn tactics(&self, domain: &str) -> Vec<(&'a Value, String)> {
+        let in_domain = |object: &&'a Value| domains_of(object).contains(&domain);
+        let mut tactics: Vec<&'a Value> = self
+            .by_type
+            .get("x-mitre-matrix")
+            .map(|matrices| matrices.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .copied()
+            .filter(in_domain)
+            .flat_map(|matrix| strings_of(matrix, "tactic_refs"))
+            .filter_map(|tactic_ref| self.objects.get(tactic_ref.as_str()).copied())
+            .filter(|tactic| is_active(tactic))
+            .collect();
+
+        if tactics.is_empty() {
+            tactics = self
+                .of_types(&["x-mitre-tactic"])
+                .into_iter()
+                .filter(in_domain)
+                .collect();
+        }
+
+        return tactics
+            .into_iter()
+            .map(|tactic| (tactic, str_of(tactic, "x_mitre_shortname").to_string()))
+            .collect();
+    }
+
+    /// Top-level techniques of the domain, sorted by ID.
+    fn domain_techniques(&self, domain: &str) -> Vec<&'a Value> {
+        return self
+            .of_types(&["attack-pattern"])
+            .into_iter()
+            .filter(|technique| !is_subtechnique(technique))
+            .filter(|technique| domains_of(technique).contains(&domain))
+            .collect();
+    }
+
+    /// Active sub-techniques of the technique, sorted by ID.
+    fn sub_techniques(&self, technique: &Value) -> Vec<&'a Value> {
+        let prefix = format!("{}.", attck_id(technique));
+
+        return self
+            .of_types(&["attack-pattern"])
+            .into_iter()
+            .filter(|sub_technique| attck_id(sub_technique).starts_with(&prefix))
+            .collect();
+    }
+
+    fn technique_row(&self, technique: &Value, tactics: &[(&Value, String)]) -> TechniqueRow {
+        let sub_techniques: Vec<SubTechniqueRow> = self
+            .sub_techniques(technique)
+            .into_iter()
+            .map(|sub_technique| SubTechniqueRow {
+                id: attck_id(sub_technique)
+                    .trim_start_matches(&attck_id(technique))
+                    .to_string(),
+                name: str_of(sub_technique, "name").to_string(),
+                description: summary(sub_technique),
+                deprecated: false,
+                revoked_by: None,
+            })
+            .collect();
+
+        return TechniqueRow {
+            id: attck_id(technique),
+            name: str_of(technique, "name").to_string(),
+            description: summary(technique),
+            sub_techniques: if sub_techniques.is_empty() {
+                None
+            } else {
+                Some(sub_techniques)
+            },
+            tactics: tactics
+                .iter()
+                .filter(|(_, phase)| phase_names(technique).contains(phase))
+                .map(|(tactic, _)| str_of(tactic, "name").to_string())
+                .collect(),
+            deprecated: false,
+            revoked_by: None,
+        };
+    }
+
+    /// Techniques table of a group, software or mitigation, the sub-techniques being listed
+    /// under their technique.
+    fn used_techniques(&self, used: Vec<(&'a Value, &'a str)>) -> Option<DomainTechniquesTable> {
+        if used.is_empty() {
+            return None;
+        }
+
+        let mut rows: Vec<DomainTechniqueRow> = Vec::new();
+
+        for (technique, used_for) in used {
+            let id = attck_id(technique);
+            let (parent_id, sub_id) = match id.split_once('.') {
+                Some((parent_id, sub_id)) => (parent_id.to_string(), Some(format!(".{}", sub_id))),
+                None => (id.clone(), None),
+            };
+
+            let inx = match rows.iter().position(|row| row.id == parent_id) {
+                Some(inx) => inx,
+                None => {
+                    let parent = self.techniques.get(&parent_id).copied();
+                    rows.push(DomainTechniqueRow {
+                        domain: domain_title(technique),
+                        id: parent_id,
+                        name: parent
+                            .map_or("", |parent| str_of(parent, "name"))
+                            .to_string(),
+                        used_for: String::new(),
+                        sub_techniques: None,
+                    });
+
+                    rows.len() - 1
+                }
+            };
+
+            match sub_id {
+                Some(sub_id) => rows[inx].sub_techniques.get_or_insert_with(Vec::new).push(
+                    DomainSubTechniqueRow {
+                        id: sub_id,
+                        name: str_of(technique, "name").to_string(),
+                        used_for: plain_text(used_for),
+                    },
+                ),
+                None => rows[inx].used_for = plain_text(used_for),
+            };
+        }
+
+        return Some(DomainTechniquesTable(rows));
+    }
+}
+
+fn is_subtechnique(technique: &Value) -> bool {
+    return technique["x_mitre_is_subtechnique"]
+        .as_bool()
+        .unwrap_or_else(|| attck_id(technique).contains('.'));
+}
+
+/// Kill chain phases (tactic short names) of the technique.
+fn phase_names(technique: &Value) -> Vec<String> {
+    return technique["kill_chain_phases"]
+        .as_array()
+        .map(|phases| {
+            phases
+                .iter()
+                .map(|phase| str_of(phase, "phase_name").to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+}
+
+/// Dataset builder, collecting the listings and entities as serialized by the cache.
+struct DatasetBuilder {
+    dataset: Dataset,
+}
+
+impl DatasetBuilder {
+    fn listing(
+        &mut self,
+        kind: EntityKind,
+        domain: Option<&str>,
+        listing: impl Serialize,
+    ) -> Result<(), error::Error> {
+        let kind_name: &str = kind.into();
+        self.dataset.listings.push(DatasetListing {
+            kind: kind_name.to_string(),
+            domain: domain.map(String::from),
+            listing: serde_json::to_value(listing)?,
+        });
+
+        return Ok(());
+    }
+
+    fn entity(
+        &mut self,
+        kind: EntityKind,
+        id: &str,
+        entity: impl Serialize,
+    ) -> Result<(), error::Error> {
+        let kind_name: &str = kind.into();
+        self.dataset.entities.push(DatasetEntity {
+            kind: kind_name.to_string(),
+            id: id.to_string(),
+            entity: serde_json::to_value(entity)?,
+        });
+
+        return Ok(());
+    }
+}
+
+/// Release (e.g. v15.1) of the ATT&CK collection of the bundles.
+fn release(index: &StixIndex) -> String {
+    return index
+        .by_type
+        .get("x-mitre-collection")
+        .and_then(|collections| collections.first())
+        .map(|collection| str_of(collection, "x_mitre_version"))
+        .filter(|version| !version.is_empty())
+        .map(|version| format!("v{}", version.trim_start_matches('v')))
+        .unwrap_or_default();
+}
+
+/// Convert official ATT&CK STIX bundles (e.g. enterprise-attack.json of the attack-stix-data
+/// repository) into a dataset installable into the cache.
+///
+/// The listings of the domains found in the bundles and the pages of their tactics,
+/// techniques, mitigations, groups, software and data sources are rebuilt from the STIX
+/// objects and relationships. Revoked and deprecated objects are left out, except for the
+/// technique pages which keep their status.
+pub fn dataset_from_bundles(bundles: &[Value]) -> Result<Dataset, error::Error> {
+    if bundles
+        .iter()
+        .any(|bundle| str_of(bundle, "type") != "bundle" || !bundle["objects"].is_array())
+    {
+        return Err(error::Error::Parse(String::from(
+            "Not a STIX bundle: expected a JSON object of type 'bundle' with an 'objects' array",
+        )));
+    }
+
+    let index = StixIndex::new(bundles);
+    let mut builder = DatasetBuilder {
+        dataset: Dataset {
+            release: release(&index),
+            ..Default::default()
+        },
+    };
+
+    let mut domains: Vec<&str> = index
+        .by_type
+        .values()
+        .flatten()
+        .flat_map(|object| domains_of(object))
+        .collect::<HashSet<&str>>()
+        .into_iter()
+        .collect();
+    domains.sort_by_key(|domain| {
+        super::cache::DOMAINS
+            .iter()
+            .position(|known| known == domain)
+    });
+
+    for domain in &domains {
+        add_domain(&index, &mut builder, domain)?;
+    }
+
+    add_technique_pages(&index, &mut builder)?;
+    add_groups(&index, &mut builder)?;
+    add_software(&index, &mut builder)?;
+    add_data_sources(&index, &mut builder)?;
+
+    return Ok(builder.dataset);
+}
+
+/// Tactics, techniques and mitigations listings of the domain, with the tactic and
+/// mitigation pages.
+fn add_domain(
+    index: &StixIndex,
+    builder: &mut DatasetBuilder,
+    domain: &str,
+) -> Result<(), error::Error> {
+    let tactics = index.tactics(domain);
+    let techniques = index.domain_techniques(domain);
+    let mut tactic_rows: Vec<TacticRow> = Vec::new();
+
+    for (order, (tactic, phase)) in tactics.iter().enumerate() {
+        let (id, url) = attck_ref(tactic).unwrap_or_default();
+        let tactic_techniques: Vec<TechniqueRow> = techniques
+            .iter()
+            .filter(|technique| phase_names(technique).contains(phase))
+            .map(|technique| index.technique_row(technique, &tactics))
+            .collect();
+
+        tactic_rows.push(TacticRow {
+            id: id.clone(),
+            name: str_of(tactic, "name").to_string(),
+            description: summary(tactic),
+            order: order + 1,
+            technique_count: Some(tactic_techniques.len()),
+        });
+        builder.entity(
+            EntityKind::TACTIC,
+            &id,
+            Tactic {
+                id: id.clone(),
+                name: str_of(tactic, "name").to_string(),
+                url,
+                description: description(tactic),
+                technique_count: tactic_techniques.len(),
+                techniques: Some(TechniquesTable(tactic_techniques)),
+                domain: Some(domain.to_string()),
+                order: Some(order + 1),
+                references: references(tactic),
+            },
+        )?;
+    }
+
+    builder.listing(EntityKind::TACTIC, Some(domain), TacticsTable(tactic_rows))?;
+    builder.listing(
+        EntityKind::TECHNIQUE,
+        Some(domain),
+        TechniquesTable(
+            techniques
+                .iter()
+                .map(|technique| index.technique_row(technique, &tactics))
+                .collect(),
+        ),
+    )?;
+
+    let mitigations: Vec<&Value> = index
+        .of_types(&["course-of-action"])
+        .into_iter()
+        .filter(|mitigation| attck_id(mitigation).starts_with('M'))
+        .filter(|mitigation| domains_of(mitigation).contains(&domain))
+        .collect();
+
+    builder.listing(
+        EntityKind::MITIGATION,
+        Some(domain),
+        MitigationTable(
+            mitigations
+                .iter()
+                .map(|mitigation| MitigationRow {
+                    id: attck_id(mitigation),
+                    name: str_of(mitigation, "name").to_string(),
+                    description: summary(mitigation),
+                })
+                .collect(),
+        ),
+    )?;
+
+    for mitigation in mitigations {
+        let (id, url) = attck_ref(mitigation).unwrap_or_default();
+
+        builder.entity(
+            EntityKind::MITIGATION,
+            &id,
+            Mitigation {
+                id: id.clone(),
+                name: str_of(mitigation, "name").to_string(),
+                url,
+                desc: description(mitigation),
+                addressed_techniques: index.used_techniques(index.targets(
+                    mitigation,
+                    "mitigates",
+                    &["attack-pattern"],
+                )),
+                domain: Some(domain.to_string()),
+                references: references(mitigation),
+                version: mitigation["x_mitre_version"].as_str().map(String::from),
+                created: date_of(mitigation, "created"),
+                modified: date_of(mitigation, "modified"),
+            },
+        )?;
+    }
+
+    return Ok(());
+}
+
+/// Pages of the techniques and sub-techniques, revoked and deprecated ones included.
+fn add_technique_pages(
+    index: &StixIndex,
+    builder: &mut DatasetBuilder,
+) -> Result<(), error::Error> {
+    let mut techniques: Vec<&Value> = index.techniques.values().copied().collect();
+    by_attck_id(&mut techniques);
+
+    for technique in techniques {
+        let (id, url) = attck_ref(technique).unwrap_or_default();
+        let procedures: Vec<ProcedureRow> = index
+            .sources(technique, "uses", &["intrusion-set", "malware", "tool"])
+            .into_iter()
+            .map(|(user, used_for)| ProcedureRow {
+                id: attck_id(user),
+                name: str_of(user, "name").to_string(),
+                description: plain_text(used_for),
+                procedure_type: if str_of(user, "type") == "intrusion-set" {
+                    ProcedureType::GROUP
+                } else {
+                    ProcedureType::SOFTWARE
+                },
+                url: attck_ref(user).map(|(_, url)| url),
+                citations: Vec::new(),
+            })
+            .collect();
+        let mitigations: Vec<MitigationRow> = index
+            .sources(technique, "mitigates", &["course-of-action"])
+            .into_iter()
+            .map(|(mitigation, mitigates)| MitigationRow {
+                id: attck_id(mitigation),
+                name: str_of(mitigation, "name").to_string(),
+                description: plain_text(mitigates),
+            })
+            .collect();
+        let detections: Vec<techniques::DetectionRow> = index
+            .sources(technique, "detects", &["x-mitre-data-component"])
+            .into_iter()
+            .map(|(component, detects)| {
+                let data_source = index
+                    .objects
+                    .get(str_of(component, "x_mitre_data_source_ref"))
+                    .copied();
+
+                techniques::DetectionRow {
+                    id: data_source.map(attck_id).unwrap_or_default(),
+                    data_source: data_source
+                        .map_or("", |data_source| str_of(data_source, "name"))
+                        .to_string(),
+                    data_comp: str_of(component, "name").to_string(),
+                    detects: Some(plain_text(detects)).filter(|detects| !detects.is_empty()),
+                }
+            })
+            .collect();
+        let parent = if is_subtechnique(technique) {
+            id.split_once('.')
+                .and_then(|(parent_id, _)| index.techniques.get(parent_id))
+                .map(|parent| ParentTechnique {
+                    id: attck_id(parent),
+                    name: str_of(parent, "name").to_string(),
+                })
+        } else {
+            None
+        };
+        let sub_techniques: Vec<SubTechniqueLink> = if parent.is_none() {
+            index
+                .sub_techniques(technique)
+                .into_iter()
+                .map(|sub_technique| SubTechniqueLink {
+                    id: attck_id(sub_technique),
+                    name: str_of(sub_technique, "name").to_string(),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        builder.entity(
+            EntityKind::TECHNIQUE,
+            &id,
+            Technique {
+                id: id.clone(),
+                name: str_of(technique, "name").to_string(),
+                url,
+                description: description(technique),
+                procedures: Some(ProceduresTable(procedures)).filter(|table| !table.0.is_empty()),
+                mitigations: Some(MitigationTable(mitigations)).filter(|table| !table.0.is_empty()),
+                detections: Some(techniques::DetectionsTable(detections))
+                    .filter(|table| !table.0.is_empty()),
+                detection_notes: Some(plain_text(str_of(technique, "x_mitre_detection")))
+                    .filter(|notes| !notes.is_empty()),
+                platforms: strings_of(technique, "x_mitre_platforms"),
+                references: references(technique),
+                contributors: strings_of(technique, "x_mitre_contributors"),
+                domain: domains_of(technique)
+                    .first()
+                    .map(|domain| domain.to_string()),
+                modified: date_of(technique, "modified"),
+                deprecated: technique["x_mitre_deprecated"].as_bool().unwrap_or(false),
+                revoked_by: index.revoked_by(technique),
+                parent,
+                sub_techniques: Some(SubTechniquesTable(sub_techniques))
+                    .filter(|table| !table.is_empty()),
+            },
+        )?;
+    }
+
+    return Ok(());
+}
+
+fn add_groups(index: &StixIndex, builder: &mut DatasetBuilder) -> Result<(), error::Error> {
+    let groups = index.of_types(&["intrusion-set"]);
+
+    builder.listing(
+        EntityKind::GROUP,
+        None,
+        GroupsTable(
+            groups
+                .iter()
+                .map(|group| GroupRow {
+                    id: attck_id(group),
+                    name: str_of(group, "name").to_string(),
+                    assoc_groups: aliases(group, "aliases"),
+                    description: summary(group),
+                })
+                .collect(),
+        ),
+    )?;
+
+    for group in groups {
+        let (id, url) = attck_ref(group).unwrap_or_default();
+        let software: Vec<groups::SoftwareRow> = index
+            .targets(group, "uses", &["malware", "tool"])
+            .into_iter()
+            .map(|(software, _)| {
+                let techniques = index.targets(software, "uses", &["attack-pattern"]);
+
+                groups::SoftwareRow {
+                    id: attck_id(software),
+                    name: str_of(software, "name").to_string(),
+                    techniques: techniques
+                        .iter()
+                        .map(|(technique, _)| str_of(technique, "name").to_string())
+                        .collect(),
+                    technique_refs: techniques
+                        .iter()
+                        .map(|(technique, _)| TechniqueRef {
+                            id: Some(attck_id(technique)),
+                            name: str_of(technique, "name").to_string(),
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        builder.entity(
+            EntityKind::GROUP,
+            &id,
+            Group {
+                id: id.clone(),
+                name: str_of(group, "name").to_string(),
+                url,
+                desc: description(group),
+                assoc_groups: aliases(group, "aliases"),
+                techniques: index.used_techniques(index.targets(
+                    group,
+                    "uses",
+                    &["attack-pattern"],
+                )),
+                software: Some(groups::SoftwareTable(software)).filter(|table| !table.0.is_empty()),
+                references: references(group),
+            },
+        )?;
+    }
+
+    return Ok(());
+}
+
+fn add_software(index: &StixIndex, builder: &mut DatasetBuilder) -> Result<(), error::Error> {
+    let software = index.of_types(&["malware", "tool"]);
+
+    builder.listing(
+        EntityKind::SOFTWARE,
+        None,
+        SoftwareTable(
+            software
+                .iter()
+                .map(|software| SoftwareRow {
+                    id: attck_id(software),
+                    name: str_of(software, "name").to_string(),
+                    assoc_software: aliases(software, "x_mitre_aliases"),
+                    description: summary(software),
+                    software_type: Some(str_of(software, "type").to_string()),
+                })
+                .collect(),
+        ),
+    )?;
+
+    for software in software {
+        let (id, url) = attck_ref(software).unwrap_or_default();
+        let groups: Vec<AssocGroupsRow> = index
+            .sources(software, "uses", &["intrusion-set"])
+            .into_iter()
+            .map(|(group, _)| AssocGroupsRow {
+                id: attck_id(group),
+                name: str_of(group, "name").to_string(),
+            })
+            .collect();
+
+        builder.entity(
+            EntityKind::SOFTWARE,
+            &id,
+            Software {
+                id: id.clone(),
+                name: str_of(software, "name").to_string(),
+                url,
+                desc: description(software),
+                software_type: Some(str_of(software, "type").to_string()),
+                techniques: index.used_techniques(index.targets(
+                    software,
+                    "uses",
+                    &["attack-pattern"],
+                )),
+                groups: Some(AssocGroupsTable(groups)).filter(|table| !table.0.is_empty()),
+                references: references(software),
+                contributors: strings_of(software, "x_mitre_contributors"),
+            },
+        )?;
+    }
+
+    return Ok(());
+}
+
+fn add_data_sources(index: &StixIndex, builder: &mut DatasetBuilder) -> Result<(), error::Error> {
+    let data_sources = index.of_types(&["x-mitre-data-source"]);
+
+    builder.listing(
+        EntityKind::DATASOURCE,
+        None,
+        DataSourcesTable(
+            data_sources
+                .iter()
+                .map(|data_source| DataSourceRow {
+                    id: attck_id(data_source),
+                    name: str_of(data_source, "name").to_string(),
+                    description: summary(data_source),
+                })
+                .collect(),
+        ),
+    )?;
+
+    for data_source in data_sources {
+        let (id, url) = attck_ref(data_source).unwrap_or_default();
+        let components: Vec<DataComponent> = index
+            .by_type
+            .get("x-mitre-data-component")
+            .map(|components| components.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .filter(|component| {
+                str_of(component, "x_mitre_data_source_ref") == str_of(data_source, "id")
+            })
+            .map(|component| DataComponent {
+                name: str_of(component, "name").to_string(),
+                description: description(component),
+                detections: data_sources::DetectionsTable(
+                    index
+                        .used_techniques(index.targets(component, "detects", &["attack-pattern"]))
+                        .map(|techniques| techniques.0)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|technique| data_sources::DetectionRow {
+                            domain: technique.domain,
+                            id: technique.id,
+                            name: technique.name,
+                            detects: technique.used_for,
+                            sub_detections: technique.sub_techniques.map(|sub_techniques| {
+                                sub_techniques
+                                    .into_iter()
+                                    .map(|sub_technique| data_sources::SubDetectionRow {
+                                        id: sub_technique.id,
+                                        name: sub_technique.name,
+                                        detects: sub_technique.used_for,
+                                    })
+                                    .collect()
+                            }),
+                        })
+                        .collect(),
+                ),
+            })
+            .collect();
+
+        builder.entity(
+            EntityKind::DATASOURCE,
+            &id,
+            DataSource {
+                id: id.clone(),
+                name: str_of(data_source, "name").to_string(),
+                url,
+                description: description(data_source),
+                components,
+                references: references(data_source),
+                platforms: strings_of(data_source, "x_mitre_platforms"),
+                collection_layers: strings_of(data_source, "x_mitre_collection_layers"),
+            },
+        )?;
+    }
+
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_bundle() -> Value {
+        return serde_json::from_str(include_str!("json/enterprise-attack-sample.json")).unwrap();
+    }
+
+    #[test]
+    fn test_import_listings() -> Result<(), error::Error> {
+        let dataset = dataset_from_bundles(&[sample_bundle()])?;
+        assert_eq!(dataset.release, "v15.1");
+
+        let tactics: TacticsTable = serde_json::from_value(
+            dataset
+                .listing(EntityKind::TACTIC, Some("enterprise"))
+                .unwrap()
+                .clone(),
+        )?;
+        assert_eq!(
+            tactics
+                .0
+                .iter()
+                .map(|tactic| (tactic.id.as_str(), tactic.order))
+                .collect::<Vec<(&str, usize)>>(),
+            vec![("TA0005", 1), ("TA0004", 2)]
+        );
+
+        let techniques: TechniquesTable = serde_json::from_value(
+            dataset
+                .listing(EntityKind::TECHNIQUE, Some("enterprise"))
+                .unwrap()
+                .clone(),
+        )?;
+        assert_eq!(techniques.ids(), vec!["T1548", "T1548.002"]);
+        assert_eq!(
+            techniques.0[0].description,
+            "Adversaries may circumvent mechanisms designed to control elevate privileges to gain \
+             higher-level permissions."
+        );
+        assert_eq!(
+            techniques.0[0].tactics,
+            vec!["Defense Evasion", "Privilege Escalation"]
+        );
+        assert!(dataset
+            .listing(EntityKind::TECHNIQUE, Some("mobile"))
+            .is_none());
+
+        let groups: GroupsTable =
+            serde_json::from_value(dataset.listing(EntityKind::GROUP, None).unwrap().clone())?;
+        assert_eq!(
+            groups.0[0].assoc_groups,
+            Some(vec![String::from("Fancy Bear"), String::from("Sofacy")])
+        );
+        assert_eq!(
+            groups.0[0].description,
+            "APT28 is a threat group that has been attributed to Russia's General Staff Main \
+             Intelligence Directorate."
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_entities() -> Result<(), error::Error> {
+        let cache = crate::attack::cache::test_cache("stix_import");
+        dataset_from_bundles(&[sample_bundle()])?.install(&cache)?;
+
+        let technique: Technique = cache
+            .load_entity(EntityKind::TECHNIQUE, "T1548.002")?
+            .unwrap();
+        assert_eq!(
+            technique.parent.map(|parent| parent.id).as_deref(),
+            Some("T1548")
+        );
+        assert_eq!(technique.modified.as_deref(), Some("2024-03-28"));
+        assert_eq!(technique.contributors.len(), 2);
+        let procedures = technique.procedures.unwrap();
+        assert_eq!(procedures.0[0].id, "G0007");
+        assert_eq!(procedures.0[0].description, "APT28 bypassed UAC.");
+        let detections = technique.detections.unwrap();
+        assert_eq!(
+            (
+                detections.0[0].id.as_str(),
+                detections.0[0].data_comp.as_str()
+            ),
+            ("DS0009", "Process Creation")
+        );
+
+        let technique: Technique = cache.load_entity(EntityKind::TECHNIQUE, "T1548")?.unwrap();
+        assert_eq!(technique.mitigations.unwrap().0[0].id, "M1026");
+        assert_eq!(technique.references[0].number, Some(1));
+        assert!(technique.detection_notes.is_some());
+        assert_eq!(technique.sub_techniques.unwrap().0[0].id, "T1548.002");
+
+        let revoked: Technique = cache.load_entity(EntityKind::TECHNIQUE, "T1088")?.unwrap();
+        assert_eq!(revoked.revoked_by.as_deref(), Some("T1548.002"));
+        let deprecated: Technique = cache.load_entity(EntityKind::TECHNIQUE, "T1553")?.unwrap();
+        assert!(deprecated.deprecated);
+
+        let group: Group = cache.load_entity(EntityKind::GROUP, "G0007")?.unwrap();
+        let techniques = group.techniques.unwrap();
+        assert_eq!(techniques.ids(), vec!["T1548", "T1548.002"]);
+        assert_eq!(
+            group.software.unwrap().0[0].techniques,
+            vec!["Abuse Elevation Control Mechanism"]
+        );
+
+        let software: Software = cache.load_entity(EntityKind::SOFTWARE, "S0002")?.unwrap();
+        assert_eq!(software.software_type.as_deref(), Some("malware"));
+        assert_eq!(software.groups.unwrap().0[0].id, "G0007");
+
+        let data_source: DataSource = cache
+            .load_entity(EntityKind::DATASOURCE, "DS0009")?
+            .unwrap();
+        assert_eq!(data_source.components[0].detections.0[0].id, "T1548");
+        assert_eq!(cache.attack_version()?.as_deref(), Some("v15.1"));
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_rejects_other_json() {
+        assert!(matches!(
+            dataset_from_bundles(&[serde_json::json!({"techniques": []})]),
+            Err(error::Error::Parse(_))
+        ));
+    }
+}
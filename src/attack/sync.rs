@@ -0,0 +1,881 @@
+use std::{
+    cell::RefCell,
+    collections::{hash_map::DefaultHasher, HashMap},
+    fs,
+    hash::{Hash, Hasher},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::Duration,
+};
+
+use select::document::Document;
+use serde::Serialize;
+
+use crate::{error, throttle, WebFetch};
+
+use super::{
+    cache::{Cache, EntityKind, EntityVersion, DOMAINS},
+    data_sources, groups, mitigations, software, tactics, techniques,
+};
+
+/// Detail pages fetched in parallel by default.
+pub const DEFAULT_JOBS: usize = 4;
+/// Most parallel fetches allowed, to stay polite with attack.mitre.org.
+pub const MAX_JOBS: usize = 16;
+
+lazy_static! {
+    static ref ATTACK_VERSION_RE: regex::Regex =
+        regex::Regex::new(r"ATT&(?:amp;)?CK (v\d+(?:\.\d+)*)</a>").unwrap();
+}
+
+/// ATT&CK release shown in the footer of the pages (e.g. v15.1).
+pub fn scrape_attack_version(page: &str) -> Option<String> {
+    return ATTACK_VERSION_RE
+        .captures(page)
+        .map(|captures| captures[1].to_string());
+}
+
+/// Version metadata shown in the card and footer of an entity page.
+pub fn scrape_entity_version(page: &str) -> EntityVersion {
+    let mut card = super::scrape_entity_card(&Document::from(page));
+
+    return EntityVersion {
+        version: card.remove("Version"),
+        modified: super::scrape_entity_modified(&mut card),
+        attack_version: scrape_attack_version(page),
+    };
+}
+
+/// `WebFetch` keeping the last fetched page, to read the ATT&CK release off its footer.
+struct PageRecorder<'a, W: WebFetch> {
+    web_client: &'a W,
+    last_page: RefCell<Option<String>>,
+}
+
+impl<W: WebFetch> WebFetch for PageRecorder<'_, W> {
+    fn fetch(&self, url: &str) -> Result<String, error::Error> {
+        let page = self.web_client.fetch(url)?;
+        self.last_page.replace(Some(page.clone()));
+
+        return Ok(page);
+    }
+}
+
+/// Outcome of syncing a set of entities into the cache.
+#[derive(Debug, Default)]
+pub struct SyncSummary {
+    pub synced: Vec<String>,
+    /// Cached entities kept as is by an incremental sync, their page showing the same
+    /// version and last modification date.
+    pub unchanged: Vec<String>,
+    pub failed: Vec<(String, error::Error)>,
+    /// Time the sync was paused by throttled (HTTP 429) requests.
+    pub throttled: Duration,
+}
+
+impl SyncSummary {
+    /// Record the outcome of syncing `id` (whether the entity was written), reporting it
+    /// through `on_entity`.
+    fn record(
+        &mut self,
+        id: &str,
+        result: Result<bool, error::Error>,
+        on_entity: &mut impl FnMut(&str, &Result<(), error::Error>),
+    ) {
+        match result {
+            Ok(written) => {
+                on_entity(id, &Ok(()));
+
+                if written {
+                    self.synced.push(id.to_uppercase());
+                } else {
+                    self.unchanged.push(id.to_uppercase());
+                }
+            }
+            Err(err) => {
+                on_entity(id, &Err(err.clone()));
+                self.failed.push((id.to_uppercase(), err));
+            }
+        }
+    }
+}
+
+fn required_domain(kind: EntityKind, domain: Option<&str>) -> Result<&str, error::Error> {
+    return domain.ok_or_else(|| {
+        let kind_name: &str = kind.into();
+
+        error::Error::InvalidValue(format!("A domain is required to sync {}", kind_name))
+    });
+}
+
+/// Entities a sync added to the cache, and those whose cached details it changed.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct CacheChanges {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+}
+
+impl CacheChanges {
+    pub fn between(before: &HashMap<String, u64>, after: &HashMap<String, u64>) -> Self {
+        let mut changes = Self::default();
+
+        for (id, fingerprint) in after {
+            match before.get(id) {
+                None => changes.added.push(id.clone()),
+                Some(before_fingerprint) if before_fingerprint != fingerprint => {
+                    changes.updated.push(id.clone())
+                }
+                Some(_) => {}
+            }
+        }
+
+        changes.added.sort();
+        changes.updated.sort();
+
+        return changes;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.added.is_empty() && self.updated.is_empty();
+    }
+}
+
+/// Hash of the cached detail file of every entity of `kind`, to tell what a sync changed.
+pub fn entity_fingerprints(
+    cache: &Cache,
+    kind: EntityKind,
+) -> Result<HashMap<String, u64>, error::Error> {
+    let mut fingerprints = HashMap::new();
+
+    for id in cache.entity_ids(kind)? {
+        let mut hasher = DefaultHasher::new();
+        fs::read(cache.entity_path(kind, &id))?.hash(&mut hasher);
+        fingerprints.insert(id, hasher.finish());
+    }
+
+    return Ok(fingerprints);
+}
+
+/// Interval between the syncs of `attack sync --watch`: a number of seconds, or a number
+/// followed by s, m, h or d (e.g. 30m, 6h, 1d).
+pub fn parse_interval(interval: &str) -> Result<Duration, error::Error> {
+    let interval = interval.trim();
+    let (amount, unit_secs) = match interval.char_indices().last() {
+        Some((inx, 's')) => (&interval[..inx], 1),
+        Some((inx, 'm')) => (&interval[..inx], 60),
+        Some((inx, 'h')) => (&interval[..inx], 60 * 60),
+        Some((inx, 'd')) => (&interval[..inx], 24 * 60 * 60),
+        _ => (interval, 1),
+    };
+
+    return match amount
+        .parse::<u64>()
+        .ok()
+        .filter(|amount| *amount > 0)
+        .and_then(|amount| amount.checked_mul(unit_secs))
+    {
+        Some(secs) => Ok(Duration::from_secs(secs)),
+        None => Err(error::Error::InvalidValue(format!(
+            "{} is not a valid interval (e.g. 3600, 30m, 6h, 1d)",
+            interval
+        ))),
+    };
+}
+
+/// Fetch the listing of `kind` and store it into the cache.
+/// Returns the IDs of the listed entities (sub-techniques included).
+pub fn sync_listing(
+    kind: EntityKind,
+    domain: Option<&str>,
+    web_client: &impl WebFetch,
+    cache: &Cache,
+) -> Result<Vec<String>, error::Error> {
+    let web_client = &PageRecorder {
+        web_client,
+        last_page: RefCell::default(),
+    };
+    let ids = match kind {
+        EntityKind::TACTIC => {
+            let domain = required_domain(kind, domain)?;
+            let listing = tactics::fetch_tactics(tactics::Domain::from_str(domain)?, web_client)?;
+            cache.save_listing(kind, Some(domain), &listing)?;
+
+            listing.ids()
+        }
+        EntityKind::TECHNIQUE => {
+            let domain = required_domain(kind, domain)?;
+            let listing =
+                techniques::fetch_techniques(techniques::Domain::from_str(domain)?, web_client)?;
+            cache.save_listing(kind, Some(domain), &listing)?;
+
+            listing.ids()
+        }
+        EntityKind::MITIGATION => {
+            let domain = required_domain(kind, domain)?;
+            let listing =
+                mitigations::fetch_mitigations(mitigations::Domain::from_str(domain)?, web_client)?;
+            cache.save_listing(kind, Some(domain), &listing)?;
+
+            listing
+                .0
+                .iter()
+                .map(|mitigation| mitigation.id.clone())
+                .collect()
+        }
+        EntityKind::SOFTWARE => {
+            let listing = software::fetch_software(web_client)?;
+            cache.save_listing(kind, None, &listing)?;
+
+            listing
+                .0
+                .iter()
+                .map(|software| software.id.clone())
+                .collect()
+        }
+        EntityKind::GROUP => {
+            let listing = groups::fetch_groups(web_client)?;
+            cache.save_listing(kind, None, &listing)?;
+
+            listing.ids()
+        }
+        EntityKind::DATASOURCE => {
+            let listing = data_sources::fetch_data_sources(web_client)?;
+            cache.save_listing(kind, None, &listing)?;
+
+            listing
+                .0
+                .iter()
+                .map(|data_source| data_source.id.clone())
+                .collect()
+        }
+    };
+
+    if let Some(attack_version) = web_client
+        .last_page
+        .borrow()
+        .as_deref()
+        .and_then(scrape_attack_version)
+    {
+        cache.save_attack_version(&attack_version)?;
+    }
+
+    return Ok(ids);
+}
+
+/// Fetch the detail page of a single entity and store it into the cache.
+pub fn sync_entity(
+    kind: EntityKind,
+    id: &str,
+    web_client: &impl WebFetch,
+    cache: &Cache,
+) -> Result<(), error::Error> {
+    return fetch_entity(kind, id, web_client, cache, false).map(|_| ());
+}
+
+/// `sync_entity` keeping the cached entity as is when its page shows the same version and
+/// last modification date. Returns whether the entity was written.
+pub fn sync_changed_entity(
+    kind: EntityKind,
+    id: &str,
+    web_client: &impl WebFetch,
+    cache: &Cache,
+) -> Result<bool, error::Error> {
+    return fetch_entity(kind, id, web_client, cache, true);
+}
+
+/// Store the fetched entity and the version of its page, returning whether the entity was
+/// written. With `keep_unchanged`, a cached entity of the same version and last modification
+/// date is kept, only the ATT&CK release of its version being updated.
+fn save_fetched<T: Serialize, W: WebFetch>(
+    kind: EntityKind,
+    id: &str,
+    entity: &T,
+    web_client: &PageRecorder<'_, W>,
+    cache: &Cache,
+    keep_unchanged: bool,
+) -> Result<bool, error::Error> {
+    let version = web_client
+        .last_page
+        .borrow()
+        .as_deref()
+        .map(scrape_entity_version);
+    let unchanged = keep_unchanged
+        && cache.entity_path(kind, id).exists()
+        && match (&version, cache.entity_version(kind, id)?) {
+            (Some(version), Some(cached)) => {
+                version.version.is_some()
+                    && version.version == cached.version
+                    && version.modified == cached.modified
+            }
+            _ => false,
+        };
+
+    if !unchanged {
+        cache.save_entity(kind, id, entity)?;
+    }
+
+    if let Some(version) = version {
+        cache.save_entity_version(kind, id, &version)?;
+    }
+
+    return Ok(!unchanged);
+}
+
+fn fetch_entity(
+    kind: EntityKind,
+    id: &str,
+    web_client: &impl WebFetch,
+    cache: &Cache,
+    keep_unchanged: bool,
+) -> Result<bool, error::Error> {
+    let id = id.to_uppercase();
+    let web_client = &PageRecorder {
+        web_client,
+        last_page: RefCell::default(),
+    };
+
+    // The detail pages are shared by all domains, the domain comes from the listings.
+    return match kind {
+        EntityKind::TACTIC => {
+            let mut tactic = tactics::fetch_tactic(&id, web_client)?;
+            tactic.domain = cache.entity_domain(kind, &id)?;
+
+            save_fetched(kind, &id, &tactic, web_client, cache, keep_unchanged)
+        }
+        EntityKind::TECHNIQUE => {
+            let mut technique = techniques::fetch_technique(&id, web_client)?;
+            technique.domain = cache.entity_domain(kind, &id)?;
+
+            save_fetched(kind, &id, &technique, web_client, cache, keep_unchanged)
+        }
+        EntityKind::MITIGATION => {
+            let mut mitigation = mitigations::fetch_mitigation(&id, web_client)?;
+            mitigation.domain = cache.entity_domain(kind, &id)?;
+
+            save_fetched(kind, &id, &mitigation, web_client, cache, keep_unchanged)
+        }
+        EntityKind::SOFTWARE => {
+            let software = software::fetch_software_info(&id, web_client)?;
+
+            save_fetched(kind, &id, &software, web_client, cache, keep_unchanged)
+        }
+        EntityKind::GROUP => {
+            let group = groups::fetch_group(&id, web_client)?;
+
+            save_fetched(kind, &id, &group, web_client, cache, keep_unchanged)
+        }
+        EntityKind::DATASOURCE => {
+            let data_source = data_sources::fetch_data_source(&id, web_client)?;
+
+            save_fetched(kind, &id, &data_source, web_client, cache, keep_unchanged)
+        }
+    };
+}
+
+/// IDs of `ids` worth syncing again: those not cached, or synced from another ATT&CK release
+/// than the one of the cached listings. ATT&CK only revises entities between releases, so the
+/// others are unchanged. All of them are returned if the cache release is unknown.
+///
+/// The pages of the returned entities still have to be fetched to tell whether their own
+/// version changed, see [`sync_changed_entity`].
+pub fn outdated_ids(
+    kind: EntityKind,
+    ids: &[String],
+    cache: &Cache,
+) -> Result<Vec<String>, error::Error> {
+    let attack_version = match cache.attack_version()? {
+        Some(attack_version) => attack_version,
+        None => return Ok(ids.to_vec()),
+    };
+    let mut outdated = Vec::new();
+
+    for id in ids {
+        let synced_from = cache
+            .entity_version(kind, id)?
+            .and_then(|version| version.attack_version);
+
+        if !cache.entity_path(kind, id).exists() || synced_from.as_ref() != Some(&attack_version) {
+            outdated.push(id.clone());
+        }
+    }
+
+    return Ok(outdated);
+}
+
+/// Sync the detail pages of `ids`, reporting every processed entity through `on_entity`.
+/// A failing entity does not stop the sync, it is recorded in the summary instead.
+pub fn sync_entities(
+    kind: EntityKind,
+    ids: &[String],
+    web_client: &impl WebFetch,
+    cache: &Cache,
+    on_entity: impl FnMut(&str, &Result<(), error::Error>),
+) -> SyncSummary {
+    return sync_entities_with(kind, ids, web_client, cache, false, on_entity);
+}
+
+fn sync_entities_with(
+    kind: EntityKind,
+    ids: &[String],
+    web_client: &impl WebFetch,
+    cache: &Cache,
+    keep_unchanged: bool,
+    mut on_entity: impl FnMut(&str, &Result<(), error::Error>),
+) -> SyncSummary {
+    let mut summary = SyncSummary::default();
+    let throttled_before = throttle::throttled_time();
+
+    for id in ids {
+        let result = fetch_entity(kind, id, web_client, cache, keep_unchanged);
+        summary.record(id, result, &mut on_entity);
+    }
+
+    summary.throttled = throttle::throttled_time() - throttled_before;
+
+    return summary;
+}
+
+/// `sync_entities` fetching up to `jobs` detail pages at once. The entities are reported
+/// through `on_entity` from the calling thread, as they complete. With `keep_unchanged`,
+/// the cached entities whose version did not change are kept (see [`sync_changed_entity`]).
+pub fn sync_entities_parallel<W: WebFetch + Sync>(
+    kind: EntityKind,
+    ids: &[String],
+    jobs: usize,
+    web_client: &W,
+    cache: &Cache,
+    keep_unchanged: bool,
+    mut on_entity: impl FnMut(&str, &Result<(), error::Error>),
+) -> SyncSummary {
+    if jobs <= 1 {
+        return sync_entities_with(kind, ids, web_client, cache, keep_unchanged, on_entity);
+    }
+
+    let mut summary = SyncSummary::default();
+    let throttled_before = throttle::throttled_time();
+    let next_id = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(ids.len()) {
+            let (sender, next_id) = (sender.clone(), &next_id);
+
+            scope.spawn(move || {
+                while let Some(id) = ids.get(next_id.fetch_add(1, Ordering::Relaxed)) {
+                    let result = fetch_entity(kind, id, web_client, cache, keep_unchanged);
+
+                    if sender.send((id, result)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        drop(sender);
+
+        for (id, result) in receiver {
+            summary.record(id, result, &mut on_entity);
+        }
+    });
+
+    summary.throttled = throttle::throttled_time() - throttled_before;
+
+    return summary;
+}
+
+/// Re-fetch the listings and detail pages of `kind` already present in the cache.
+/// Entities that were never synced are left out, so a curated cache keeps its subset.
+pub fn update_cached(
+    kind: EntityKind,
+    web_client: &impl WebFetch,
+    cache: &Cache,
+    on_entity: impl FnMut(&str, &Result<(), error::Error>),
+) -> Result<SyncSummary, error::Error> {
+    let domains: Vec<Option<&str>> = if kind.has_domain() {
+        DOMAINS.iter().map(|domain| Some(*domain)).collect()
+    } else {
+        vec![None]
+    };
+    let mut failed_listings = Vec::new();
+
+    for domain in domains {
+        if !cache.listing_path(kind, domain).exists() {
+            continue;
+        }
+
+        if let Err(err) = sync_listing(kind, domain, web_client, cache) {
+            let kind_name: &str = kind.into();
+            failed_listings.push((
+                format!("{} listing ({})", kind_name, domain.unwrap_or("index")),
+                err,
+            ));
+        }
+    }
+
+    let mut summary = sync_entities(kind, &cache.entity_ids(kind)?, web_client, cache, on_entity);
+    summary.failed.extend(failed_listings);
+
+    return Ok(summary);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attack::{
+            cache::test_cache,
+            techniques::{Technique, TechniquesTable},
+        },
+        fakers::FakeHttpReqwest,
+    };
+
+    #[test]
+    fn test_sync_listing_returns_sub_technique_ids() -> Result<(), error::Error> {
+        let cache = test_cache("sync_listing");
+        let fake_reqwest = FakeHttpReqwest::default()
+            .set_success_response(include_str!("html/attck/techniques/mobile.html").to_string());
+
+        let ids = sync_listing(EntityKind::TECHNIQUE, Some("mobile"), &fake_reqwest, &cache)?;
+
+        assert_eq!(ids.len(), 66 + 41);
+        assert!(ids.iter().any(|id| id.contains('.')));
+        assert!(cache
+            .listing_path(EntityKind::TECHNIQUE, Some("mobile"))
+            .exists());
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_ics_entities_keep_domain() -> Result<(), error::Error> {
+        let cache = test_cache("sync_ics");
+        let listing_reqwest = FakeHttpReqwest::default()
+            .set_success_response(include_str!("html/attck/techniques/ics.html").to_string());
+        let ids = sync_listing(EntityKind::TECHNIQUE, Some("ics"), &listing_reqwest, &cache)?;
+
+        assert!(ids.iter().all(|id| id.starts_with("T0")));
+
+        let detail_reqwest = FakeHttpReqwest::default().set_success_response(
+            include_str!("html/attck/techniques/enterprise_deploy_container.html").to_string(),
+        );
+        sync_entity(EntityKind::TECHNIQUE, &ids[0], &detail_reqwest, &cache)?;
+
+        assert_eq!(
+            cache
+                .load_entity::<Technique>(EntityKind::TECHNIQUE, &ids[0])?
+                .and_then(|technique| technique.domain),
+            Some(String::from("ics"))
+        );
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_ics_tactics_listing() -> Result<(), error::Error> {
+        let cache = test_cache("sync_ics_tactics");
+        let fake_reqwest = FakeHttpReqwest::default()
+            .set_success_response(include_str!("html/attck/tactics/ics.html").to_string());
+
+        let ids = sync_listing(EntityKind::TACTIC, Some("ics"), &fake_reqwest, &cache)?;
+
+        assert!(!ids.is_empty());
+        assert_eq!(
+            cache.entity_domain(EntityKind::TACTIC, &ids[0])?,
+            Some(String::from("ics"))
+        );
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_listing_records_attack_version() -> Result<(), error::Error> {
+        let cache = test_cache("sync_attack_version");
+        let fake_reqwest = FakeHttpReqwest::default().set_success_response(
+            include_str!("html/attck/techniques/enterprise.html").to_string(),
+        );
+
+        sync_listing(
+            EntityKind::TECHNIQUE,
+            Some("enterprise"),
+            &fake_reqwest,
+            &cache,
+        )?;
+
+        assert_eq!(cache.attack_version()?, Some(String::from("v11.3")));
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_listing_requires_domain() {
+        let cache = test_cache("sync_no_domain");
+        let fake_reqwest = FakeHttpReqwest::default();
+
+        assert!(matches!(
+            sync_listing(EntityKind::TECHNIQUE, None, &fake_reqwest, &cache),
+            Err(error::Error::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_sync_entities_records_failures() -> Result<(), error::Error> {
+        let cache = test_cache("sync_failures");
+        let fake_reqwest = FakeHttpReqwest::default()
+            .set_error_response(error::Error::Request(String::from("Reqwest error")));
+        let mut processed = 0;
+
+        let summary = sync_entities(
+            EntityKind::TECHNIQUE,
+            &[String::from("T1610")],
+            &fake_reqwest,
+            &cache,
+            |_, _| processed += 1,
+        );
+
+        assert_eq!(processed, 1);
+        assert!(summary.synced.is_empty());
+        assert_eq!(summary.failed.len(), 1);
+        assert!(cache
+            .load_entity::<Technique>(EntityKind::TECHNIQUE, "T1610")?
+            .is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_entities_parallel() -> Result<(), error::Error> {
+        let cache = test_cache("sync_parallel");
+        let fake_reqwest = FakeHttpReqwest::default().set_success_response(
+            include_str!("html/attck/techniques/enterprise_deploy_container.html").to_string(),
+        );
+        let ids: Vec<String> = ["T1610", "T1611", "T1612", "T1613", "T1614"]
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+        let mut processed = 0;
+
+        let summary = sync_entities_parallel(
+            EntityKind::TECHNIQUE,
+            &ids,
+            3,
+            &fake_reqwest,
+            &cache,
+            false,
+            |_, _| processed += 1,
+        );
+
+        assert_eq!(processed, ids.len());
+        assert_eq!(summary.synced.len(), ids.len());
+        assert!(summary.failed.is_empty());
+        assert_eq!(cache.entity_ids(EntityKind::TECHNIQUE)?, ids);
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_entity_records_version() -> Result<(), error::Error> {
+        let cache = test_cache("sync_entity_version");
+        let fake_reqwest = FakeHttpReqwest::default().set_success_response(
+            include_str!("html/attck/techniques/enterprise_deploy_container.html").to_string(),
+        );
+
+        sync_entity(EntityKind::TECHNIQUE, "T1610", &fake_reqwest, &cache)?;
+
+        assert_eq!(
+            cache.entity_version(EntityKind::TECHNIQUE, "T1610")?,
+            Some(EntityVersion {
+                version: Some(String::from("1.1")),
+                modified: Some(String::from("2022-04-01")),
+                attack_version: Some(String::from("v11.3")),
+            })
+        );
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_changed_entity_keeps_unchanged_versions() -> Result<(), error::Error> {
+        let cache = test_cache("sync_changed_entity");
+        let fake_reqwest = FakeHttpReqwest::default().set_success_response(
+            include_str!("html/attck/techniques/enterprise_deploy_container.html").to_string(),
+        );
+        let curated = Technique {
+            id: String::from("T1610"),
+            name: String::from("Curated"),
+            ..Default::default()
+        };
+        let sync = || sync_changed_entity(EntityKind::TECHNIQUE, "T1610", &fake_reqwest, &cache);
+
+        // Never synced: no version to compare with.
+        cache.save_entity(EntityKind::TECHNIQUE, "T1610", &curated)?;
+        assert!(sync()?);
+
+        // Same version and last modification date: the cached entity is kept.
+        cache.save_entity(EntityKind::TECHNIQUE, "T1610", &curated)?;
+        assert!(!sync()?);
+        assert_eq!(
+            cache
+                .load_entity::<Technique>(EntityKind::TECHNIQUE, "T1610")?
+                .map(|technique| technique.name),
+            Some(String::from("Curated"))
+        );
+
+        cache.save_entity_version(
+            EntityKind::TECHNIQUE,
+            "T1610",
+            &EntityVersion {
+                version: Some(String::from("1.0")),
+                ..Default::default()
+            },
+        )?;
+        assert!(sync()?);
+        assert_eq!(
+            cache
+                .entity_version(EntityKind::TECHNIQUE, "T1610")?
+                .and_then(|version| version.version),
+            Some(String::from("1.1"))
+        );
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_outdated_ids() -> Result<(), error::Error> {
+        let cache = test_cache("sync_outdated");
+        let ids: Vec<String> = ["T1610", "T1611", "T1612"]
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+
+        for (id, attack_version) in [("T1610", "v15.1"), ("T1611", "v14.0")] {
+            cache.save_entity(EntityKind::TECHNIQUE, id, &Technique::default())?;
+            cache.save_entity_version(
+                EntityKind::TECHNIQUE,
+                id,
+                &EntityVersion {
+                    attack_version: Some(attack_version.to_string()),
+                    ..Default::default()
+                },
+            )?;
+        }
+
+        assert_eq!(outdated_ids(EntityKind::TECHNIQUE, &ids, &cache)?, ids);
+
+        cache.save_attack_version("v15.1")?;
+
+        assert_eq!(
+            outdated_ids(EntityKind::TECHNIQUE, &ids, &cache)?,
+            vec!["T1611", "T1612"]
+        );
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache_changes() -> Result<(), error::Error> {
+        let cache = test_cache("sync_changes");
+        let technique = |name: &str| Technique {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        cache.save_entity(
+            EntityKind::TECHNIQUE,
+            "T1610",
+            &technique("Deploy Container"),
+        )?;
+        cache.save_entity(EntityKind::TECHNIQUE, "T1611", &technique("Escape to Host"))?;
+        let before = entity_fingerprints(&cache, EntityKind::TECHNIQUE)?;
+
+        assert!(CacheChanges::between(&before, &before).is_empty());
+
+        cache.save_entity(EntityKind::TECHNIQUE, "T1611", &technique("Escape To Host"))?;
+        cache.save_entity(
+            EntityKind::TECHNIQUE,
+            "T1612",
+            &technique("Build Image on Host"),
+        )?;
+
+        assert_eq!(
+            CacheChanges::between(
+                &before,
+                &entity_fingerprints(&cache, EntityKind::TECHNIQUE)?
+            ),
+            CacheChanges {
+                added: vec![String::from("T1612")],
+                updated: vec![String::from("T1611")],
+            }
+        );
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_interval("30m"), Ok(Duration::from_secs(30 * 60)));
+        assert_eq!(parse_interval("6h"), Ok(Duration::from_secs(6 * 60 * 60)));
+        assert_eq!(parse_interval("1d"), Ok(Duration::from_secs(24 * 60 * 60)));
+        assert!(parse_interval("0h").is_err());
+        assert!(parse_interval("6w").is_err());
+        assert!(parse_interval("").is_err());
+        assert!(parse_interval("999999999999999d").is_err());
+    }
+
+    #[test]
+    fn test_update_cached_only_refreshes_cached_entities() -> Result<(), error::Error> {
+        let cache = test_cache("sync_update");
+        let stale_technique = Technique {
+            id: String::from("T1610"),
+            name: String::from("Stale"),
+            ..Default::default()
+        };
+        cache.save_entity(EntityKind::TECHNIQUE, "T1610", &stale_technique)?;
+        cache.save_listing(
+            EntityKind::TECHNIQUE,
+            Some("enterprise"),
+            &TechniquesTable::default(),
+        )?;
+        let fake_reqwest = FakeHttpReqwest::default().set_success_response(
+            include_str!("html/attck/techniques/enterprise_deploy_container.html").to_string(),
+        );
+
+        let summary = update_cached(EntityKind::TECHNIQUE, &fake_reqwest, &cache, |_, _| {})?;
+
+        assert_eq!(summary.synced, vec![String::from("T1610")]);
+        assert_eq!(
+            cache
+                .load_entity::<Technique>(EntityKind::TECHNIQUE, "T1610")?
+                .map(|technique| technique.name),
+            Some(String::from("Deploy Container"))
+        );
+        assert!(!cache
+            .listing_path(EntityKind::TECHNIQUE, Some("mobile"))
+            .exists());
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+}
@@ -1,12 +1,16 @@
 use std::str::FromStr;
 
 use select::document::Document;
+use serde::{Deserialize, Serialize};
 
-use crate::{error::Error, WebFetch};
+use crate::{
+    error::{Error, FetchContext},
+    WebFetch,
+};
 
 use super::{
-    scrape_entity_description, scrape_entity_name, scrape_tables, techniques::TechniquesTable, Row,
-    Table,
+    scrape_entity_description, scrape_entity_name, scrape_entity_references, scrape_tables,
+    techniques::TechniquesTable, Reference, Row, Table,
 };
 
 const TACTICS_URL: &'static str = "https://attack.mitre.org/tactics/";
@@ -43,11 +47,17 @@ impl Into<&'static str> for Domain {
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Serialize, Deserialize)]
 pub struct TacticRow {
     pub id: String,
     pub name: String,
     pub description: String,
+    /// Position of the tactic in the matrix of its domain (kill-chain order), from 1.
+    #[serde(default)]
+    pub order: usize,
+    /// Techniques of the tactic, filled from the cached tactics.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub technique_count: Option<usize>,
 }
 
 impl From<Row> for TacticRow {
@@ -79,6 +89,7 @@ impl From<Row> for TacticRow {
     }
 }
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Row> for TacticRow {
     fn into(self) -> comfy_table::Row {
         let mut row = comfy_table::Row::new();
@@ -86,11 +97,15 @@ impl Into<comfy_table::Row> for TacticRow {
             .add_cell(comfy_table::Cell::new(self.name))
             .add_cell(comfy_table::Cell::new(self.description));
 
+        if let Some(technique_count) = self.technique_count {
+            row.add_cell(comfy_table::Cell::new(technique_count));
+        }
+
         return row;
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Serialize, Deserialize)]
 pub struct TacticsTable(pub Vec<TacticRow>);
 
 impl IntoIterator for TacticsTable {
@@ -104,35 +119,34 @@ impl IntoIterator for TacticsTable {
 
 impl From<Table> for TacticsTable {
     fn from(table: Table) -> Self {
-        return Self(table.into_iter().map(TacticRow::from).collect());
+        return Self(
+            table
+                .into_iter()
+                .enumerate()
+                .map(|(inx, row)| TacticRow {
+                    order: inx + 1,
+                    ..TacticRow::from(row)
+                })
+                .collect(),
+        );
     }
 }
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Table> for TacticsTable {
     fn into(self) -> comfy_table::Table {
-        let mut table = comfy_table::Table::new();
-        table
-            .load_preset(comfy_table::presets::UTF8_FULL)
-            .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
-            .set_header(vec![
-                comfy_table::Cell::new("ID")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Name")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Description")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-            ])
-            .add_rows(
-                self.into_iter()
-                    .map(|tactic| tactic.into())
-                    .collect::<Vec<comfy_table::Row>>(),
-            );
+        let mut header = crate::theme::header(&["ID", "Name", "Description"]);
+
+        if self.0.iter().any(|tactic| tactic.technique_count.is_some()) {
+            header.push(crate::theme::header_cell("Techniques"));
+        }
+
+        let mut table = crate::theme::new_table();
+        table.set_header(header).add_rows(
+            self.into_iter()
+                .map(|tactic| tactic.into())
+                .collect::<Vec<comfy_table::Row>>(),
+        );
 
         return table;
     }
@@ -146,13 +160,51 @@ impl TacticsTable {
     pub fn is_empty(&self) -> bool {
         return self.0.is_empty();
     }
+
+    pub fn ids(&self) -> Vec<String> {
+        return self.0.iter().map(|row| row.id.clone()).collect();
+    }
+
+    /// (ID, name) of the listed entities.
+    pub fn names(&self) -> Vec<(String, String)> {
+        return self
+            .0
+            .iter()
+            .map(|row| (row.id.clone(), row.name.clone()))
+            .collect();
+    }
+
+    /// Position of the tactic in the matrix, from 1.
+    pub fn order_of(&self, tactic_id: &str) -> Option<usize> {
+        return self
+            .0
+            .iter()
+            .position(|row| row.id.eq_ignore_ascii_case(tactic_id))
+            .map(|inx| inx + 1);
+    }
+
+    /// Fill the technique counts of the listed tactics found in `tactics`.
+    pub fn set_technique_counts(&mut self, tactics: &[Tactic]) {
+        for row in self.0.iter_mut() {
+            row.technique_count = tactics
+                .iter()
+                .find(|tactic| tactic.id.eq_ignore_ascii_case(&row.id))
+                .map(|tactic| {
+                    tactic
+                        .techniques
+                        .as_ref()
+                        .map_or(0, |techniques| techniques.len())
+                });
+        }
+    }
 }
 
 pub fn fetch_tactics(
     tactic_type: Domain,
     req_client: &impl WebFetch,
 ) -> Result<TacticsTable, crate::error::Error> {
-    let fetched_response = req_client.fetch(tactic_type.into())?;
+    let url: &str = tactic_type.into();
+    let fetched_response = req_client.fetch(url).listing_context("tactics", url)?;
     let document = Document::from(fetched_response.as_str());
 
     return Ok(scrape_tables(&document)
@@ -162,29 +214,54 @@ pub fn fetch_tactics(
         }));
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Serialize, Deserialize)]
 pub struct Tactic {
     pub id: String,
     pub name: String,
+    /// ATT&CK page of the tactic.
+    #[serde(default)]
+    pub url: String,
     pub description: String,
     pub techniques: Option<TechniquesTable>,
+    /// Domain (enterprise, mobile, ics) the tactic was listed under, when known.
+    #[serde(default)]
+    pub domain: Option<String>,
+    /// Techniques of the tactic, sub-techniques left out.
+    #[serde(default)]
+    pub technique_count: usize,
+    /// Position of the tactic in the matrix of its domain (kill-chain order), from 1.
+    #[serde(default)]
+    pub order: Option<usize>,
+    #[serde(default)]
+    pub references: Vec<Reference>,
+}
+
+/// ATT&CK page of the tactic.
+pub fn tactic_url(tactic_id: &str) -> String {
+    return format!("{}{}", TACTICS_URL, tactic_id.to_uppercase());
 }
 
 pub fn fetch_tactic(
     tactic_id: &str,
     req_client: &impl WebFetch,
 ) -> Result<Tactic, crate::error::Error> {
-    let url = format!("{}{}", TACTICS_URL, tactic_id.to_uppercase());
-    let fetched_response = req_client.fetch(&url)?;
+    let url = tactic_url(tactic_id);
+    let fetched_response = req_client.fetch(&url).entity_context(tactic_id, &url)?;
     let document = Document::from(fetched_response.as_str());
 
+    let techniques: Option<TechniquesTable> =
+        scrape_tables(&document).pop().map(|table| table.into());
+
     return Ok(Tactic {
         id: tactic_id.to_uppercase(),
         name: scrape_entity_name(&document),
+        url: tactic_url(tactic_id),
         description: scrape_entity_description(&document),
-        techniques: scrape_tables(&document)
-            .pop()
-            .map_or(None, |table| Some(table.into())),
+        technique_count: techniques.as_ref().map_or(0, |techniques| techniques.len()),
+        techniques,
+        domain: None,
+        order: None,
+        references: scrape_entity_references(&document),
     });
 }
 
@@ -253,14 +330,47 @@ mod tests {
         return Ok(());
     }
 
+    #[test]
+    fn test_tactics_kill_chain_order() -> Result<(), crate::error::Error> {
+        let fake_reqwest_client = FakeHttpReqwest::default()
+            .set_success_response(include_str!("html/attck/tactics/enterprise.html").to_string());
+        let mut retrieved_tactics = fetch_tactics(Domain::ENTERPRISE, &fake_reqwest_client)?;
+
+        assert_eq!(retrieved_tactics.0[0].order, 1);
+        assert_eq!(
+            retrieved_tactics.0.last().map(|tactic| tactic.order),
+            Some(SCRAPED_ENTERPRISE_ROWS)
+        );
+        assert_eq!(
+            retrieved_tactics.order_of(&retrieved_tactics.0[2].id.to_lowercase()),
+            Some(3)
+        );
+
+        let tactic_id = retrieved_tactics.0[1].id.clone();
+        retrieved_tactics.set_technique_counts(&[Tactic {
+            id: tactic_id,
+            techniques: Some(TechniquesTable::default()),
+            ..Default::default()
+        }]);
+
+        assert_eq!(retrieved_tactics.0[0].technique_count, None);
+        assert_eq!(retrieved_tactics.0[1].technique_count, Some(0));
+
+        return Ok(());
+    }
+
     #[test]
     fn test_dont_panic_on_request_error() {
         let fake_reqwest_client = FakeHttpReqwest::default()
-            .set_error_response(crate::error::Error::Request(format!("Reqwest error")));
+            .set_error_response(crate::error::Error::Request(String::from("Reqwest error")));
         let error: crate::error::Error =
             fetch_tactics(Domain::ENTERPRISE, &fake_reqwest_client).unwrap_err();
 
-        assert!(matches!(error, crate::error::Error::Request(_)));
+        assert!(matches!(
+            &error,
+            crate::error::Error::Fetch { source, .. }
+                if matches!(**source, crate::error::Error::Request(_))
+        ));
     }
 
     #[test]
@@ -274,6 +384,7 @@ mod tests {
             retrieved_tactic.techniques.is_some(),
             "Retrieved tactic has no techniques"
         );
+        assert_eq!(retrieved_tactic.technique_count, TEST_TACTIC_TECHNIQUE_ROWS);
         assert_eq!(
             retrieved_tactic.techniques.unwrap().len(),
             TEST_TACTIC_TECHNIQUE_ROWS,
@@ -0,0 +1,255 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error, storage};
+
+use super::{
+    cache::{Cache, EntityKind, DOMAINS},
+    techniques::TechniquesTable,
+};
+
+/// Shorter technique names ("Cron", "At") match too many unrelated words.
+const MIN_KEYWORD_LEN: usize = 5;
+
+lazy_static! {
+    static ref TECHNIQUE_ID_RE: regex::Regex =
+        regex::Regex::new(r"\bT\d{4}(?:\.\d{3})?\b").unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchKind {
+    ID,
+    KEYWORD,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TechniqueTag {
+    pub id: String,
+    pub name: String,
+    pub matched_by: MatchKind,
+    pub occurrences: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReportTags {
+    pub report: String,
+    pub techniques: Vec<TechniqueTag>,
+}
+
+/// Technique names known by the cache, used to suggest techniques from free text.
+#[derive(Debug, Default)]
+pub struct TechniqueIndex {
+    names: BTreeMap<String, String>,
+    keywords: Vec<(String, regex::Regex)>,
+}
+
+impl TechniqueIndex {
+    /// Index of the technique listings synced into the cache.
+    pub fn from_cache(cache: &Cache) -> Result<Self, error::Error> {
+        let mut listings = Vec::new();
+
+        for domain in DOMAINS {
+            if let Some(listing) = cache.load_listing(EntityKind::TECHNIQUE, Some(domain))? {
+                listings.push(listing);
+            }
+        }
+
+        if listings.is_empty() {
+            return Err(error::Error::General(String::from(
+                "No technique listing cached, run 'attack sync techniques --domain <domain>' first",
+            )));
+        }
+
+        return Ok(Self::from_listings(&listings));
+    }
+
+    pub fn from_listings(listings: &[TechniquesTable]) -> Self {
+        let mut index = Self::default();
+
+        for listing in listings {
+            for technique in &listing.0 {
+                index.add(&technique.id, &technique.name);
+
+                if let Some(sub_techniques) = &technique.sub_techniques {
+                    for sub_technique in sub_techniques {
+                        index.add(
+                            &format!("{}{}", technique.id, sub_technique.id),
+                            &sub_technique.name,
+                        );
+                    }
+                }
+            }
+        }
+
+        return index;
+    }
+
+    fn add(&mut self, id: &str, name: &str) {
+        self.names.insert(id.to_string(), name.to_string());
+
+        if name.len() >= MIN_KEYWORD_LEN {
+            let keyword_re =
+                regex::Regex::new(&format!(r"(?i)\b{}\b", regex::escape(name))).unwrap();
+            self.keywords.push((id.to_string(), keyword_re));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.names.is_empty();
+    }
+
+    /// Techniques referenced by `text`, either by ID or by name.
+    /// ID references take precedence over name matches of the same technique.
+    pub fn suggest(&self, text: &str) -> Vec<TechniqueTag> {
+        let mut tags: BTreeMap<String, TechniqueTag> = BTreeMap::new();
+
+        for id_match in TECHNIQUE_ID_RE.find_iter(text) {
+            let id = id_match.as_str().to_string();
+
+            tags.entry(id.clone())
+                .or_insert_with(|| TechniqueTag {
+                    name: self.names.get(&id).cloned().unwrap_or_default(),
+                    id,
+                    matched_by: MatchKind::ID,
+                    occurrences: 0,
+                })
+                .occurrences += 1;
+        }
+
+        for (id, keyword_re) in &self.keywords {
+            if tags.contains_key(id) {
+                continue;
+            }
+
+            let occurrences = keyword_re.find_iter(text).count();
+
+            if occurrences > 0 {
+                tags.insert(
+                    id.clone(),
+                    TechniqueTag {
+                        id: id.clone(),
+                        name: self.names[id].clone(),
+                        matched_by: MatchKind::KEYWORD,
+                        occurrences,
+                    },
+                );
+            }
+        }
+
+        return tags.into_values().collect();
+    }
+}
+
+/// Tag the reports of `reports_dir` that are new or changed since their tags were written
+/// into `tags_dir`. The reports are only read. Returns the written tag files.
+pub fn tag_reports(
+    reports_dir: &Path,
+    tags_dir: &Path,
+    index: &TechniqueIndex,
+) -> Result<Vec<PathBuf>, error::Error> {
+    let mut written = Vec::new();
+    let mut reports = fs::read_dir(reports_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect::<Vec<PathBuf>>();
+    reports.sort();
+
+    for report in reports {
+        let report_name = match report.file_name().and_then(|name| name.to_str()) {
+            Some(name) if !name.starts_with('.') => name.to_string(),
+            _ => continue,
+        };
+        let tags_path = tags_dir.join(format!("{}.json", report_name));
+
+        if is_up_to_date(&report, &tags_path) {
+            continue;
+        }
+
+        let text = String::from_utf8_lossy(&fs::read(&report)?).to_string();
+        let report_tags = ReportTags {
+            report: report_name,
+            techniques: index.suggest(&text),
+        };
+
+        storage::save_json(&tags_path, &report_tags)?;
+        written.push(tags_path);
+    }
+
+    return Ok(written);
+}
+
+fn is_up_to_date(report: &Path, tags_path: &Path) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|metadata| metadata.modified());
+
+    return match (modified(report), modified(tags_path)) {
+        (Ok(report_modified), Ok(tags_modified)) => tags_modified >= report_modified,
+        _ => false,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attack::techniques::{fetch_techniques, Domain},
+        fakers::FakeHttpReqwest,
+    };
+
+    const TEST_REPORT: &'static str =
+        "The implant abused T1055 and relied on Scheduled Task/Job for persistence. \
+         Later stages referenced t1055 again and T1055.";
+
+    fn test_index() -> Result<TechniqueIndex, error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default().set_success_response(
+            include_str!("html/attck/techniques/enterprise.html").to_string(),
+        );
+        let listing = fetch_techniques(Domain::ENTERPRISE, &fake_reqwest)?;
+
+        return Ok(TechniqueIndex::from_listings(&[listing]));
+    }
+
+    #[test]
+    fn test_suggest_ids_and_keywords() -> Result<(), error::Error> {
+        let tags = test_index()?.suggest(TEST_REPORT);
+
+        let process_injection = tags.iter().find(|tag| tag.id == "T1055").unwrap();
+        assert_eq!(process_injection.matched_by, MatchKind::ID);
+        assert_eq!(process_injection.occurrences, 2);
+        assert_eq!(process_injection.name, "Process Injection");
+
+        let scheduled_task = tags.iter().find(|tag| tag.id == "T1053").unwrap();
+        assert_eq!(scheduled_task.matched_by, MatchKind::KEYWORD);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tag_reports_only_once() -> Result<(), error::Error> {
+        let root = std::env::temp_dir().join("mitre_cli_tagging");
+        let _ = fs::remove_dir_all(&root);
+        let (reports_dir, tags_dir) = (root.join("reports"), root.join("tags"));
+        fs::create_dir_all(&reports_dir)?;
+        fs::write(reports_dir.join("report.txt"), TEST_REPORT)?;
+
+        let index = test_index()?;
+        let written = tag_reports(&reports_dir, &tags_dir, &index)?;
+
+        assert_eq!(written, vec![tags_dir.join("report.txt.json")]);
+        assert!(tag_reports(&reports_dir, &tags_dir, &index)?.is_empty());
+
+        let report_tags: ReportTags = storage::load_json(&written[0])?.unwrap();
+        assert_eq!(report_tags.report, "report.txt");
+        assert!(!report_tags.techniques.is_empty());
+
+        fs::remove_dir_all(&root)?;
+
+        Ok(())
+    }
+}
@@ -1,17 +1,34 @@
 use std::rc::Rc;
 use std::{cell::RefCell, str::FromStr};
 
-use select::document::Document;
+use select::{
+    document::Document,
+    predicate::{self, Predicate},
+};
+use serde::{Deserialize, Serialize};
 
-use crate::{error, remove_ext_link_ref, WebFetch};
+use crate::{error, error::FetchContext, remove_ext_link_ref, WebFetch};
 
 use super::{
-    mitigations::MitigationTable, scrape_entity_description, scrape_entity_h2_tables,
-    scrape_entity_name, scrape_tables, Row, Table,
+    mitigations::MitigationTable, scrape_card_list, scrape_entity_card, tactics::Tactic,
+    scrape_entity_contributors,
+    scrape_entity_description, scrape_entity_h2_tables, scrape_entity_h2_text,
+    scrape_entity_modified, scrape_entity_name, scrape_entity_references, scrape_table,
+    scrape_tables, Reference, Row, Table, ATTACK_URL,
 };
 
 const TECHNIQUES_URL: &'static str = "https://attack.mitre.org/techniques/";
 
+lazy_static! {
+    static ref DEPRECATED_RE: regex::Regex =
+        regex::Regex::new(r"(?i)this (?:sub-)?technique has been deprecated").unwrap();
+    /// Revocation notice, or the redirection of the revoked page to the revoking technique.
+    static ref REVOKED_BY_RE: regex::Regex = regex::Regex::new(
+        r#"(?is)(?:revoked by|http-equiv="refresh"[^>]*url=)\s*(?:<a href=")?/techniques/(T\d{4})(?:/(\d{3}))?"#
+    )
+    .unwrap();
+}
+
 pub enum Domain {
     ENTERPRISE,
     MOBILE,
@@ -44,11 +61,16 @@ impl Into<&'static str> for Domain {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct SubTechniqueRow {
     pub id: String,
     pub name: String,
     pub description: String,
+    /// Filled from the cached sub-technique, like `revoked_by`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revoked_by: Option<String>,
 }
 
 impl From<Row> for SubTechniqueRow {
@@ -80,12 +102,20 @@ impl From<Row> for SubTechniqueRow {
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Serialize, Deserialize)]
 pub struct TechniqueRow {
     pub id: String,
     pub name: String,
     pub description: String,
     pub sub_techniques: Option<Vec<SubTechniqueRow>>,
+    /// Names of the tactics the technique belongs to, filled from the cached tactics.
+    #[serde(default)]
+    pub tactics: Vec<String>,
+    /// Filled from the cached technique, like `revoked_by`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revoked_by: Option<String>,
 }
 
 impl TechniqueRow {
@@ -127,7 +157,7 @@ impl From<Row> for TechniqueRow {
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Serialize, Deserialize)]
 pub struct TechniquesTable(pub Vec<TechniqueRow>);
 
 impl IntoIterator for TechniquesTable {
@@ -164,47 +194,60 @@ impl From<Table> for TechniquesTable {
     }
 }
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Table> for TechniquesTable {
     fn into(self) -> comfy_table::Table {
-        let mut table = comfy_table::Table::new();
-        table
-            .load_preset(comfy_table::presets::UTF8_FULL)
-            .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
-            .set_header(vec![
-                comfy_table::Cell::new("ID")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Name")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Description")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-            ]);
+        let show_tactics = self.0.iter().any(|technique| !technique.tactics.is_empty());
+        let mut header = crate::theme::header(&["ID", "Name", "Description"]);
+
+        if show_tactics {
+            header.push(crate::theme::header_cell("Tactics"));
+        }
+
+        let mut table = crate::theme::new_table();
+        table.set_header(header);
 
         for technique in self {
-            table.add_row(vec![
+            let tactics = technique.tactics.join(", ");
+            let mut row = vec![
                 comfy_table::Cell::new(technique.id.clone()),
-                comfy_table::Cell::new(technique.name),
+                comfy_table::Cell::new(flagged_name(
+                    technique.name,
+                    technique.deprecated,
+                    technique.revoked_by.as_deref(),
+                )),
                 comfy_table::Cell::new(technique.description),
-            ]);
+            ];
+
+            if show_tactics {
+                row.push(comfy_table::Cell::new(&tactics));
+            }
+
+            table.add_row(row);
 
             if let Some(sub_techniques) = technique.sub_techniques {
                 table.add_rows(
                     sub_techniques
                         .into_iter()
                         .map(|sub_technique| {
-                            vec![
+                            let mut row = vec![
                                 comfy_table::Cell::new(format!(
                                     "{}{}",
                                     technique.id, sub_technique.id
                                 )),
-                                comfy_table::Cell::new(sub_technique.name),
+                                comfy_table::Cell::new(flagged_name(
+                                    sub_technique.name,
+                                    sub_technique.deprecated,
+                                    sub_technique.revoked_by.as_deref(),
+                                )),
                                 comfy_table::Cell::new(sub_technique.description),
-                            ]
+                            ];
+
+                            if show_tactics {
+                                row.push(comfy_table::Cell::new(&tactics));
+                            }
+
+                            row
                         })
                         .collect::<Vec<Vec<comfy_table::Cell>>>(),
                 );
@@ -215,7 +258,232 @@ impl Into<comfy_table::Table> for TechniquesTable {
     }
 }
 
+/// Name shown in the tables, flagging the deprecated and revoked techniques.
+#[cfg(feature = "cli")]
+fn flagged_name(name: String, deprecated: bool, revoked_by: Option<&str>) -> String {
+    return match revoked_by {
+        Some(revoked_by) => format!("{} (revoked by {})", name, revoked_by),
+        None if deprecated => format!("{} (deprecated)", name),
+        None => name,
+    };
+}
+
 impl TechniquesTable {
+    /// Tree of the listed techniques, their sub-techniques indented under them, followed by
+    /// the technique and sub-technique counts.
+    #[cfg(feature = "cli")]
+    pub fn tree(self) -> String {
+        let technique_count = self.0.len();
+        let mut sub_technique_count = 0;
+        let mut tree = String::new();
+
+        for technique in self.0 {
+            let sub_techniques = technique.sub_techniques.unwrap_or_default();
+            let name = flagged_name(
+                technique.name,
+                technique.deprecated,
+                technique.revoked_by.as_deref(),
+            );
+
+            if sub_techniques.is_empty() {
+                tree.push_str(&format!("{} {}\n", technique.id, name));
+            } else {
+                tree.push_str(&format!(
+                    "{} {} ({} sub-techniques)\n",
+                    technique.id,
+                    name,
+                    sub_techniques.len()
+                ));
+            }
+
+            sub_technique_count += sub_techniques.len();
+            let last = sub_techniques.len().saturating_sub(1);
+
+            for (inx, sub_technique) in sub_techniques.into_iter().enumerate() {
+                let branch = if inx == last {
+                    "└──"
+                } else {
+                    "├──"
+                };
+                tree.push_str(&format!(
+                    "{} {}{} {}\n",
+                    branch,
+                    technique.id,
+                    sub_technique.id,
+                    flagged_name(
+                        sub_technique.name,
+                        sub_technique.deprecated,
+                        sub_technique.revoked_by.as_deref(),
+                    )
+                ));
+            }
+        }
+
+        tree.push_str(&format!(
+            "\n{} techniques, {} sub-techniques\n",
+            technique_count, sub_technique_count
+        ));
+
+        return tree;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+
+    /// Fill the deprecation status of the listed techniques and sub-techniques from
+    /// `status_of`, returning `None` for the techniques not cached.
+    pub fn set_status(
+        &mut self,
+        status_of: impl Fn(&str) -> Result<Option<TechniqueStatus>, error::Error>,
+    ) -> Result<(), error::Error> {
+        for technique in self.0.iter_mut() {
+            if let Some(status) = status_of(&technique.id)? {
+                technique.deprecated = status.deprecated;
+                technique.revoked_by = status.revoked_by;
+            }
+
+            for sub_technique in technique.sub_techniques.iter_mut().flatten() {
+                let sub_technique_id = format!("{}{}", technique.id, sub_technique.id);
+
+                if let Some(status) = status_of(&sub_technique_id)? {
+                    sub_technique.deprecated = status.deprecated;
+                    sub_technique.revoked_by = status.revoked_by;
+                }
+            }
+        }
+
+        return Ok(());
+    }
+
+    /// Listing restricted to the techniques and sub-techniques running on any of `platforms`
+    /// (case insensitive), according to `platforms_of`. Those with unknown platforms (`None`
+    /// or empty) are kept.
+    pub fn with_platforms(
+        self,
+        platforms: &[String],
+        platforms_of: impl Fn(&str) -> Result<Option<TechniquePlatforms>, error::Error>,
+    ) -> Result<Self, error::Error> {
+        let runs_on = |id: &str| -> Result<bool, error::Error> {
+            return Ok(match platforms_of(id)? {
+                Some(technique) if !technique.platforms.is_empty() => {
+                    technique.platforms.iter().any(|technique_platform| {
+                        platforms
+                            .iter()
+                            .any(|platform| platform.eq_ignore_ascii_case(technique_platform))
+                    })
+                }
+                _ => true,
+            });
+        };
+        let mut techniques = Vec::new();
+
+        for mut technique in self.0.into_iter() {
+            if !runs_on(&technique.id)? {
+                continue;
+            }
+
+            if let Some(sub_techniques) = technique.sub_techniques.take() {
+                let mut kept_sub_techniques = Vec::new();
+
+                for sub_technique in sub_techniques.into_iter() {
+                    if runs_on(&format!("{}{}", technique.id, sub_technique.id))? {
+                        kept_sub_techniques.push(sub_technique);
+                    }
+                }
+
+                technique.sub_techniques = Some(kept_sub_techniques);
+            }
+
+            techniques.push(technique);
+        }
+
+        return Ok(Self(techniques));
+    }
+
+    /// Listing without the deprecated and revoked techniques and sub-techniques.
+    pub fn without_deprecated(self) -> Self {
+        return Self(
+            self.0
+                .into_iter()
+                .filter(|technique| !technique.deprecated && technique.revoked_by.is_none())
+                .map(|mut technique| {
+                    if let Some(sub_techniques) = technique.sub_techniques.as_mut() {
+                        sub_techniques.retain(|sub_technique| {
+                            !sub_technique.deprecated && sub_technique.revoked_by.is_none()
+                        });
+                    }
+
+                    technique
+                })
+                .collect(),
+        );
+    }
+
+    /// Fill the tactics of the listed techniques from the techniques of `tactics`.
+    pub fn set_tactics(&mut self, tactics: &[Tactic]) {
+        for technique in self.0.iter_mut() {
+            technique.tactics = tactics
+                .iter()
+                .filter(|tactic| lists_technique(tactic, &technique.id))
+                .map(|tactic| tactic.name.clone())
+                .collect();
+        }
+    }
+
+    /// Listing restricted to the techniques of any of `tactics`, with their sub-techniques.
+    pub fn in_tactics(self, tactics: &[Tactic]) -> Self {
+        return Self(
+            self.0
+                .into_iter()
+                .filter(|technique| {
+                    tactics
+                        .iter()
+                        .any(|tactic| lists_technique(tactic, &technique.id))
+                })
+                .collect(),
+        );
+    }
+
+    /// IDs of the listed techniques followed by their sub-techniques.
+    pub fn ids(&self) -> Vec<String> {
+        let mut ids = Vec::new();
+
+        for technique in &self.0 {
+            ids.push(technique.id.clone());
+
+            if let Some(sub_techniques) = &technique.sub_techniques {
+                ids.extend(
+                    sub_techniques
+                        .iter()
+                        .map(|sub_technique| format!("{}{}", technique.id, sub_technique.id)),
+                );
+            }
+        }
+
+        return ids;
+    }
+
+    /// (ID, name) of the listed techniques, sub-techniques being named `Technique: Sub-technique`.
+    pub fn names(&self) -> Vec<(String, String)> {
+        let mut names = Vec::new();
+
+        for technique in &self.0 {
+            names.push((technique.id.clone(), technique.name.clone()));
+
+            if let Some(sub_techniques) = &technique.sub_techniques {
+                names.extend(sub_techniques.iter().map(|sub_technique| {
+                    (
+                        format!("{}{}", technique.id, sub_technique.id),
+                        format!("{}: {}", technique.name, sub_technique.name),
+                    )
+                }));
+            }
+        }
+
+        return names;
+    }
+
     pub fn len(&self) -> usize {
         return self.0.len();
     }
@@ -225,7 +493,8 @@ pub fn fetch_techniques(
     technique_type: Domain,
     web_client: &impl WebFetch,
 ) -> Result<TechniquesTable, error::Error> {
-    let fetched_response = web_client.fetch(technique_type.into())?;
+    let url: &str = technique_type.into();
+    let fetched_response = web_client.fetch(url).listing_context("techniques", url)?;
     let document = Document::from(fetched_response.as_str());
 
     return Ok(scrape_tables(&document)
@@ -233,7 +502,7 @@ pub fn fetch_techniques(
         .map_or(TechniquesTable::default(), |table| table.into()));
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ProcedureType {
     SOFTWARE,
     GROUP,
@@ -268,12 +537,18 @@ impl Default for ProcedureType {
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Serialize, Deserialize)]
 pub struct ProcedureRow {
     pub id: String,
     pub name: String,
     pub description: String,
     pub procedure_type: ProcedureType,
+    /// ATT&CK page of the group, software or campaign.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// URLs of the references cited by the description.
+    #[serde(default)]
+    pub citations: Vec<String>,
 }
 
 impl From<Row> for ProcedureRow {
@@ -285,13 +560,20 @@ impl From<Row> for ProcedureRow {
             procedure.procedure_type = id.into();
         }
 
+        procedure.url = row.get_links(0).first().cloned();
+        procedure.citations = row
+            .get_links(2)
+            .iter()
+            .filter(|url| !url.starts_with(ATTACK_URL))
+            .cloned()
+            .collect();
+
         if let Some(name) = row.get_col(1) {
             procedure.name = name.to_string();
         }
 
         if let Some(desc) = row.get_col(2) {
             procedure.description = desc
-                .to_string()
                 .split("\n")
                 .map(|str_slice| remove_ext_link_ref(str_slice.trim()))
                 .collect::<Vec<String>>()
@@ -302,6 +584,7 @@ impl From<Row> for ProcedureRow {
     }
 }
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Row> for ProcedureRow {
     fn into(self) -> comfy_table::Row {
         let procedure_type: String = self.procedure_type.into();
@@ -315,33 +598,20 @@ impl Into<comfy_table::Row> for ProcedureRow {
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Serialize, Deserialize)]
 pub struct ProceduresTable(pub Vec<ProcedureRow>);
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Table> for ProceduresTable {
     fn into(self) -> comfy_table::Table {
-        let mut table = comfy_table::Table::new();
+        let mut table = crate::theme::new_table();
         table
-            .load_preset(comfy_table::presets::UTF8_FULL)
-            .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
-            .set_header(vec![
-                comfy_table::Cell::new("Procedure Type")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("ID")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Name")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Description")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-            ])
+            .set_header(crate::theme::header(&[
+                "Procedure Type",
+                "ID",
+                "Name",
+                "Description",
+            ]))
             .add_rows(
                 self.into_iter()
                     .map(|row| row.into())
@@ -379,7 +649,7 @@ impl From<Table> for Option<ProceduresTable> {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct DetectionRow {
     pub id: String,
     pub data_source: String,
@@ -411,13 +681,10 @@ impl From<Row> for DetectionRow {
     }
 }
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Row> for DetectionRow {
     fn into(self) -> comfy_table::Row {
-        let detects = if self.detects.is_some() {
-            self.detects.unwrap()
-        } else {
-            String::new()
-        };
+        let detects = self.detects.unwrap_or_default();
 
         let mut row = comfy_table::Row::new();
         row.add_cell(comfy_table::Cell::new(self.id))
@@ -429,33 +696,20 @@ impl Into<comfy_table::Row> for DetectionRow {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct DetectionsTable(pub Vec<DetectionRow>);
 
+#[cfg(feature = "cli")]
 impl Into<comfy_table::Table> for DetectionsTable {
     fn into(self) -> comfy_table::Table {
-        let mut table = comfy_table::Table::new();
+        let mut table = crate::theme::new_table();
         table
-            .load_preset(comfy_table::presets::UTF8_FULL)
-            .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
-            .set_header(vec![
-                comfy_table::Cell::new("Procedure Type")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("ID")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Name")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-                comfy_table::Cell::new("Description")
-                    .set_alignment(comfy_table::CellAlignment::Center)
-                    .add_attribute(comfy_table::Attribute::Bold)
-                    .fg(comfy_table::Color::Red),
-            ])
+            .set_header(crate::theme::header(&[
+                "Procedure Type",
+                "ID",
+                "Name",
+                "Description",
+            ]))
             .add_rows(
                 self.into_iter()
                     .map(|row| row.into())
@@ -506,28 +760,225 @@ impl From<Table> for Option<DetectionsTable> {
     }
 }
 
-#[derive(Default, Debug)]
+/// Technique a sub-technique belongs to.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParentTechnique {
+    pub id: String,
+    pub name: String,
+}
+
+/// Entry of the sub-techniques card of a technique page.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubTechniqueLink {
+    /// Full sub-technique ID (T1134.001).
+    pub id: String,
+    pub name: String,
+}
+
+impl From<Row> for SubTechniqueLink {
+    fn from(row: Row) -> Self {
+        return Self {
+            id: row.get_col(0).cloned().unwrap_or_default(),
+            name: row.get_col(1).cloned().unwrap_or_default(),
+        };
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for SubTechniqueLink {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(self.name));
+
+        return row;
+    }
+}
+
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub struct SubTechniquesTable(pub Vec<SubTechniqueLink>);
+
+impl SubTechniquesTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for SubTechniquesTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table
+            .set_header(crate::theme::header(&["ID", "Name"]))
+            .add_rows(
+                self.0
+                    .into_iter()
+                    .map(|row| row.into())
+                    .collect::<Vec<comfy_table::Row>>(),
+            );
+
+        return table;
+    }
+}
+
+impl From<Table> for Option<SubTechniquesTable> {
+    fn from(table: Table) -> Self {
+        if table.is_empty() {
+            return None;
+        }
+
+        return Some(SubTechniquesTable(
+            table.into_iter().map(SubTechniqueLink::from).collect(),
+        ));
+    }
+}
+
+/// Parent technique ID of a sub-technique ID (T1134.004 -> T1134), `None` for a technique.
+pub fn parent_id(technique_id: &str) -> Option<String> {
+    return technique_id
+        .split_once('.')
+        .map(|(parent_id, _)| parent_id.to_uppercase());
+}
+
+#[derive(Default, Debug, Serialize, Deserialize)]
 pub struct Technique {
     pub id: String,
     pub name: String,
+    /// ATT&CK page of the technique.
+    #[serde(default)]
+    pub url: String,
     pub description: String,
     pub procedures: Option<ProceduresTable>,
     pub mitigations: Option<MitigationTable>,
     pub detections: Option<DetectionsTable>,
+    /// Narrative detection guidance written outside of the detection table.
+    #[serde(default)]
+    pub detection_notes: Option<String>,
+    /// Platforms (Windows, Linux, macOS...) the technique applies to.
+    #[serde(default)]
+    pub platforms: Vec<String>,
+    #[serde(default)]
+    pub references: Vec<Reference>,
+    #[serde(default)]
+    pub contributors: Vec<String>,
+    /// Domain (enterprise, mobile, ics) the technique was listed under, when known.
+    #[serde(default)]
+    pub domain: Option<String>,
+    /// Last modification of the technique in ATT&CK (YYYY-MM-DD).
+    #[serde(default)]
+    pub modified: Option<String>,
+    /// Whether ATT&CK deprecated the technique.
+    #[serde(default)]
+    pub deprecated: bool,
+    /// Technique (or sub-technique) the technique was revoked by, i.e. merged into.
+    #[serde(default)]
+    pub revoked_by: Option<String>,
+    /// Parent technique, for a sub-technique.
+    #[serde(default)]
+    pub parent: Option<ParentTechnique>,
+    /// Sub-techniques of the technique, or those of the parent for a sub-technique (itself
+    /// included).
+    #[serde(default)]
+    pub sub_techniques: Option<SubTechniquesTable>,
+}
+
+impl Technique {
+    pub fn is_sub_technique(&self) -> bool {
+        return parent_id(&self.id).is_some();
+    }
+
+    /// Other sub-techniques of the parent of a sub-technique.
+    pub fn siblings(&self) -> Vec<&SubTechniqueLink> {
+        if !self.is_sub_technique() {
+            return Vec::new();
+        }
+
+        return self
+            .sub_techniques
+            .iter()
+            .flat_map(|sub_techniques| sub_techniques.0.iter())
+            .filter(|sub_technique| !sub_technique.id.eq_ignore_ascii_case(&self.id))
+            .collect();
+    }
+}
+
+/// Parent technique named by the title of a sub-technique page.
+fn scrape_parent_technique(document: &Document, technique_id: &str) -> Option<ParentTechnique> {
+    return parent_id(technique_id).map(|id| ParentTechnique {
+        id,
+        name: document
+            .find(predicate::Attr("id", "subtechnique-parent-name"))
+            .next()
+            .map(|span_node| span_node.text().trim().trim_end_matches(':').to_string())
+            .unwrap_or_default(),
+    });
+}
+
+fn scrape_sub_techniques(document: &Document) -> Option<SubTechniquesTable> {
+    return document
+        .find(predicate::Attr("id", "subtechniques-card-body").descendant(predicate::Name("table")))
+        .next()
+        .and_then(|table_node| scrape_table(table_node).into());
+}
+
+/// Deprecation status of a cached technique, loaded without the rest of the technique.
+#[derive(Default, Debug, Deserialize)]
+pub struct TechniqueStatus {
+    #[serde(default)]
+    pub deprecated: bool,
+    #[serde(default)]
+    pub revoked_by: Option<String>,
+}
+
+fn lists_technique(tactic: &Tactic, technique_id: &str) -> bool {
+    return tactic.techniques.as_ref().is_some_and(|tactic_techniques| {
+        tactic_techniques
+            .0
+            .iter()
+            .any(|tactic_technique| tactic_technique.id == technique_id)
+    });
+}
+
+/// Platforms of a cached technique, loaded without the rest of the technique.
+#[derive(Default, Debug, Deserialize)]
+pub struct TechniquePlatforms {
+    #[serde(default)]
+    pub platforms: Vec<String>,
+}
+
+/// Deprecation notice and revocation of a technique page.
+fn scrape_technique_status(page: &str) -> TechniqueStatus {
+    return TechniqueStatus {
+        deprecated: DEPRECATED_RE.is_match(page),
+        revoked_by: REVOKED_BY_RE
+            .captures(page)
+            .map(|captures| match captures.get(2) {
+                Some(sub_id) => format!("{}.{}", &captures[1], sub_id.as_str()),
+                None => captures[1].to_string(),
+            }),
+    };
+}
+
+/// ATT&CK page of the technique, sub-techniques live under their parent technique path.
+pub fn technique_url(technique_id: &str) -> String {
+    return format!("{}{}", TECHNIQUES_URL, technique_id.to_uppercase().replace(".", "/"));
 }
 
 pub fn fetch_technique(
     technique_id: &str,
     web_client: &impl WebFetch,
 ) -> Result<Technique, error::Error> {
-    let url = format!("{}{}", TECHNIQUES_URL, technique_id.to_uppercase().replace(".", "/"));
-    let fetched_response = web_client.fetch(url.as_str())?;
+    let url = technique_url(technique_id);
+    let fetched_response = web_client.fetch(&url).entity_context(technique_id, &url)?;
     let document = Document::from(fetched_response.as_str());
     let mut tables = scrape_entity_h2_tables(&document);
+    let mut card = scrape_entity_card(&document);
+    let status = scrape_technique_status(&fetched_response);
 
     let technique = Technique {
         id: technique_id.to_string(),
         name: scrape_entity_name(&document),
+        url: technique_url(technique_id),
         description: scrape_entity_description(&document),
         procedures: if let Some(examples_table) = tables.remove("examples") {
             examples_table.into()
@@ -544,6 +995,16 @@ pub fn fetch_technique(
         } else {
             None
         },
+        detection_notes: scrape_entity_h2_text(&document, "detection"),
+        platforms: scrape_card_list(&mut card, "Platforms", ','),
+        references: scrape_entity_references(&document),
+        contributors: scrape_entity_contributors(&mut card),
+        domain: None,
+        modified: scrape_entity_modified(&mut card),
+        deprecated: status.deprecated,
+        revoked_by: status.revoked_by,
+        parent: scrape_parent_technique(&document, technique_id),
+        sub_techniques: scrape_sub_techniques(&document),
     };
 
     return Ok(technique);
@@ -552,12 +1013,13 @@ pub fn fetch_technique(
 pub mod domain {
 
     use crate::{
-        attack::{Row, Table},
+        attack::{tactics::Tactic, Row, Table},
         remove_ext_link_ref,
     };
+    use serde::{Deserialize, Serialize};
     use std::{cell::RefCell, rc::Rc};
 
-    #[derive(Debug, Default)]
+    #[derive(Debug, Default, Clone, Serialize, Deserialize)]
     pub struct DomainSubTechniqueRow {
         pub id: String,
         pub name: String,
@@ -577,7 +1039,7 @@ pub mod domain {
             }
 
             if let Some(used_for) = row.get_col(4) {
-                sub_technique.used_for = remove_ext_link_ref(&used_for.trim())
+                sub_technique.used_for = remove_ext_link_ref(used_for.trim())
                     .split("\n")
                     .map(|str_slice| str_slice.trim().to_string())
                     .collect::<Vec<String>>()
@@ -588,7 +1050,7 @@ pub mod domain {
         }
     }
 
-    #[derive(Debug, Default)]
+    #[derive(Debug, Default, Clone, Serialize, Deserialize)]
     pub struct DomainTechniqueRow {
         pub domain: String,
         pub id: String,
@@ -635,7 +1097,7 @@ pub mod domain {
             }
 
             if let Some(used_for) = row.get_col(inx) {
-                technique.used_for = remove_ext_link_ref(&used_for.trim())
+                technique.used_for = remove_ext_link_ref(used_for.trim())
                     .split("\n")
                     .map(|str_slice| str_slice.trim().to_string())
                     .collect::<Vec<String>>()
@@ -646,9 +1108,25 @@ pub mod domain {
         }
     }
 
-    #[derive(Debug, Default)]
+    #[derive(Debug, Default, Serialize, Deserialize)]
     pub struct DomainTechniquesTable(pub Vec<DomainTechniqueRow>);
 
+    /// Techniques used by a cached group or software, loaded without the rest of the entity.
+    #[derive(Debug, Default, Deserialize)]
+    pub struct UsedTechniques {
+        #[serde(default)]
+        pub techniques: Option<DomainTechniquesTable>,
+    }
+
+    impl UsedTechniques {
+        pub fn has_domain(&self, domain: &str) -> bool {
+            return self
+                .techniques
+                .as_ref()
+                .is_some_and(|techniques| techniques.has_domain(domain));
+        }
+    }
+
     impl DomainTechniquesTable {
         pub fn is_empty(&self) -> bool {
             return self.0.is_empty();
@@ -657,6 +1135,109 @@ pub mod domain {
         pub fn len(&self) -> usize {
             return self.0.len();
         }
+
+        /// Whether any of the listed techniques belongs to the domain (enterprise, mobile, ics).
+        pub fn has_domain(&self, domain: &str) -> bool {
+            return self
+                .0
+                .iter()
+                .any(|technique| technique.domain.eq_ignore_ascii_case(domain));
+        }
+
+        /// IDs of the listed techniques followed by their sub-techniques.
+        pub fn ids(&self) -> Vec<String> {
+            let mut ids = Vec::new();
+
+            for technique in &self.0 {
+                ids.push(technique.id.clone());
+
+                if let Some(sub_techniques) = &technique.sub_techniques {
+                    ids.extend(
+                        sub_techniques
+                            .iter()
+                            .map(|sub_technique| format!("{}{}", technique.id, sub_technique.id)),
+                    );
+                }
+            }
+
+            return ids;
+        }
+
+        /// (ID, name) of the listed techniques, sub-techniques being named `Technique: Sub-technique`.
+        pub fn names(&self) -> Vec<(String, String)> {
+            let mut names = Vec::new();
+
+            for technique in &self.0 {
+                names.push((technique.id.clone(), technique.name.clone()));
+
+                if let Some(sub_techniques) = &technique.sub_techniques {
+                    names.extend(sub_techniques.iter().map(|sub_technique| {
+                        (
+                            format!("{}{}", technique.id, sub_technique.id),
+                            format!("{}: {}", technique.name, sub_technique.name),
+                        )
+                    }));
+                }
+            }
+
+            return names;
+        }
+
+        /// The listed techniques under each of `tactics`, in their order (the kill-chain
+        /// order of the domain listing). Techniques under none of them come last, with an
+        /// empty tactic ID.
+        pub fn by_tactic(&self, tactics: &[Tactic]) -> Vec<TacticTechniques> {
+            let is_under = |tactic: &Tactic, technique: &DomainTechniqueRow| {
+                let technique_id = technique.id.split('.').next().unwrap_or_default();
+
+                return tactic.techniques.as_ref().is_some_and(|tactic_techniques| {
+                    tactic_techniques
+                        .0
+                        .iter()
+                        .any(|tactic_technique| tactic_technique.id == technique_id)
+                });
+            };
+
+            let mut tactic_techniques: Vec<TacticTechniques> = tactics
+                .iter()
+                .map(|tactic| TacticTechniques {
+                    tactic_id: tactic.id.clone(),
+                    tactic_name: tactic.name.clone(),
+                    techniques: Self(
+                        self.0
+                            .iter()
+                            .filter(|technique| is_under(tactic, technique))
+                            .cloned()
+                            .collect(),
+                    ),
+                })
+                .filter(|tactic_techniques| !tactic_techniques.techniques.is_empty())
+                .collect();
+
+            let unmapped: Vec<DomainTechniqueRow> = self
+                .0
+                .iter()
+                .filter(|technique| !tactics.iter().any(|tactic| is_under(tactic, technique)))
+                .cloned()
+                .collect();
+
+            if !unmapped.is_empty() {
+                tactic_techniques.push(TacticTechniques {
+                    techniques: Self(unmapped),
+                    ..Default::default()
+                });
+            }
+
+            return tactic_techniques;
+        }
+    }
+
+    /// Techniques of an entity listed under one of the tactics.
+    #[derive(Debug, Default, Serialize)]
+    pub struct TacticTechniques {
+        pub tactic_id: String,
+        pub tactic_name: String,
+        pub techniques: DomainTechniquesTable,
     }
 
     impl IntoIterator for DomainTechniquesTable {
@@ -693,30 +1274,11 @@ pub mod domain {
         }
     }
 
+    #[cfg(feature = "cli")]
     impl Into<comfy_table::Table> for DomainTechniquesTable {
         fn into(self) -> comfy_table::Table {
-            let mut table = comfy_table::Table::new();
-            table
-                .load_preset(comfy_table::presets::UTF8_FULL)
-                .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
-                .set_header(vec![
-                    comfy_table::Cell::new("Domain")
-                        .set_alignment(comfy_table::CellAlignment::Center)
-                        .add_attribute(comfy_table::Attribute::Bold)
-                        .fg(comfy_table::Color::Red),
-                    comfy_table::Cell::new("ID")
-                        .set_alignment(comfy_table::CellAlignment::Center)
-                        .add_attribute(comfy_table::Attribute::Bold)
-                        .fg(comfy_table::Color::Red),
-                    comfy_table::Cell::new("Name")
-                        .set_alignment(comfy_table::CellAlignment::Center)
-                        .add_attribute(comfy_table::Attribute::Bold)
-                        .fg(comfy_table::Color::Red),
-                    comfy_table::Cell::new("")
-                        .set_alignment(comfy_table::CellAlignment::Center)
-                        .add_attribute(comfy_table::Attribute::Bold)
-                        .fg(comfy_table::Color::Red),
-                ]);
+            let mut table = crate::theme::new_table();
+            table.set_header(crate::theme::header(&["Domain", "ID", "Name", ""]));
 
             for technique in self {
                 table.add_row(vec![
@@ -776,6 +1338,7 @@ mod tests {
     const TEST_TECHNIQUE_PROCEDURES: usize = 4;
     const TEST_TECHNIQUE_MITIGATIONS: usize = 4;
     const TEST_TECHNIQUE_DETECTIONS: usize = 5;
+    const TEST_TECHNIQUE_REFERENCES: usize = 9;
 
     #[test]
     fn test_fetch_enterprise_techniques() -> Result<(), error::Error> {
@@ -823,6 +1386,86 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_set_techniques_tactics() -> Result<(), error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default()
+            .set_success_response(include_str!("html/attck/techniques/mobile.html").to_string());
+        let mut retrieved_techniques = fetch_techniques(Domain::MOBILE, &fake_reqwest)?;
+        let technique_id = retrieved_techniques.0[0].id.clone();
+        let tactic = Tactic {
+            name: String::from("Initial Access"),
+            techniques: Some(TechniquesTable(vec![TechniqueRow {
+                id: technique_id,
+                ..Default::default()
+            }])),
+            ..Default::default()
+        };
+
+        retrieved_techniques.set_tactics(std::slice::from_ref(&tactic));
+
+        assert_eq!(retrieved_techniques.0[0].tactics, vec!["Initial Access"]);
+        assert!(retrieved_techniques.0[1].tactics.is_empty());
+
+        let retrieved_techniques = retrieved_techniques.in_tactics(&[tactic]);
+        assert_eq!(retrieved_techniques.len(), 1);
+        assert_eq!(retrieved_techniques.0[0].tactics, vec!["Initial Access"]);
+
+        #[cfg(feature = "cli")]
+        {
+            let table: comfy_table::Table = retrieved_techniques.into();
+            assert_eq!(table.header().unwrap().cell_count(), 4);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_domain_techniques_by_tactic() {
+        let tactic = |id: &str, name: &str, technique_ids: &[&str]| Tactic {
+            id: String::from(id),
+            name: String::from(name),
+            techniques: Some(TechniquesTable(
+                technique_ids
+                    .iter()
+                    .map(|technique_id| TechniqueRow {
+                        id: technique_id.to_string(),
+                        ..Default::default()
+                    })
+                    .collect(),
+            )),
+            ..Default::default()
+        };
+        let techniques = domain::DomainTechniquesTable(
+            ["T1059", "T1566", "T1078", "T9999"]
+                .iter()
+                .map(|technique_id| domain::DomainTechniqueRow {
+                    id: technique_id.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+        );
+
+        let by_tactic = techniques.by_tactic(&[
+            tactic("TA0001", "Initial Access", &["T1566", "T1078"]),
+            tactic("TA0002", "Execution", &["T1059"]),
+            tactic("TA0003", "Persistence", &["T1078"]),
+            tactic("TA0040", "Impact", &["T1485"]),
+        ]);
+
+        assert_eq!(
+            by_tactic
+                .iter()
+                .map(|tactic| format!("{} {}", tactic.tactic_id, tactic.techniques.ids().join(",")))
+                .collect::<Vec<String>>(),
+            vec![
+                "TA0001 T1566,T1078",
+                "TA0002 T1059",
+                "TA0003 T1078",
+                " T9999"
+            ]
+        );
+    }
+
     #[test]
     fn test_fetch_mobile_subtechniques() -> Result<(), error::Error> {
         let fake_reqwest = FakeHttpReqwest::default()
@@ -889,9 +1532,15 @@ mod tests {
             "Retrieved technique has no detections"
         );
 
+        let procedures = fetched_technique.procedures.unwrap();
+        assert_eq!(procedures.0.len(), TEST_TECHNIQUE_PROCEDURES);
         assert_eq!(
-            fetched_technique.procedures.unwrap().0.len(),
-            TEST_TECHNIQUE_PROCEDURES
+            procedures.0[0].url.as_deref(),
+            Some("https://attack.mitre.org/software/S0600")
+        );
+        assert_eq!(
+            procedures.0[0].citations,
+            vec!["https://www.intezer.com/blog/cloud-security/watch-your-containers-doki-infecting-docker-servers-in-the-cloud/"]
         );
         assert_eq!(
             fetched_technique.mitigations.unwrap().0.len(),
@@ -901,10 +1550,39 @@ mod tests {
             fetched_technique.detections.unwrap().0.len(),
             TEST_TECHNIQUE_DETECTIONS
         );
+        assert_eq!(fetched_technique.contributors.len(), 8);
+        assert_eq!(
+            fetched_technique.contributors.first().map(String::as_str),
+            Some("Alfredo Oliveira, Trend Micro")
+        );
+        assert_eq!(fetched_technique.modified.as_deref(), Some("2022-04-01"));
+        assert!(fetched_technique.detection_notes.is_none());
 
         Ok(())
     }
 
+    #[test]
+    fn test_scrape_detection_notes() {
+        let document = Document::from(
+            r##"<div class="container-fluid">
+                <h2 id="mitigations">Mitigations</h2>
+                <p>This type of attack technique cannot be easily mitigated.</p>
+                <h2 id="detection">Detection</h2>
+                <p>Monitor process creation.<span><sup><a href="#scite-1">[1]</a></sup></span></p>
+                <p> </p>
+                <p>Look for unusual parent processes.</p>
+                <h2 id="references">References</h2>
+                <p>Unrelated.</p>
+            </div>"##,
+        );
+
+        assert_eq!(
+            scrape_entity_h2_text(&document, "detection").as_deref(),
+            Some("Monitor process creation.\n\nLook for unusual parent processes.")
+        );
+        assert!(scrape_entity_h2_text(&document, "examples").is_none());
+    }
+
     #[test]
     fn test_fetch_technique_with_some_tables() -> Result<(), error::Error> {
         let fake_reqwest = FakeHttpReqwest::default().set_success_response(
@@ -925,6 +1603,182 @@ mod tests {
             fetched_sub_techniques.detections.is_some(),
             "Retrieved technique has no procedure examples"
         );
+        assert_eq!(
+            fetched_sub_techniques.references.len(),
+            TEST_TECHNIQUE_REFERENCES
+        );
+
+        for reference in fetched_sub_techniques.references {
+            assert!(reference.url.starts_with("http"), "Reference without URL");
+            assert_ne!(
+                reference.description.is_empty(),
+                true,
+                "Reference without description"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch_sub_technique() -> Result<(), error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default().set_success_response(
+            include_str!("html/attck/techniques/enterprise_parent_pid_spoofing.html").to_string(),
+        );
+        let sub_technique = fetch_technique("T1134.004", &fake_reqwest)?;
+
+        assert_eq!(sub_technique.name, "Parent PID Spoofing");
+        assert_eq!(
+            sub_technique.url,
+            "https://attack.mitre.org/techniques/T1134/004"
+        );
+        assert_eq!(
+            sub_technique.parent,
+            Some(ParentTechnique {
+                id: String::from("T1134"),
+                name: String::from("Access Token Manipulation"),
+            })
+        );
+        assert_eq!(
+            sub_technique
+                .siblings()
+                .iter()
+                .map(|sibling| sibling.id.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["T1134.001", "T1134.002", "T1134.003", "T1134.005"]
+        );
+        assert_eq!(
+            sub_technique.siblings()[0].name,
+            "Token Impersonation/Theft"
+        );
+
+        let fake_reqwest = FakeHttpReqwest::default().set_success_response(
+            include_str!("html/attck/techniques/enterprise_deploy_container.html").to_string(),
+        );
+        let technique = fetch_technique("T1610", &fake_reqwest)?;
+
+        assert!(!technique.is_sub_technique());
+        assert!(technique.parent.is_none() && technique.sub_techniques.is_none());
+        assert!(technique.siblings().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_technique_status() -> Result<(), error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default().set_success_response(
+            include_str!("html/attck/techniques/enterprise_deploy_container.html").to_string(),
+        );
+        let technique = fetch_technique("T1610", &fake_reqwest)?;
+        assert!(!technique.deprecated);
+        assert_eq!(technique.revoked_by, None);
+
+        let status = scrape_technique_status(
+            r#"<div class="card-body"><strong>This technique has been deprecated and should no
+            longer be used.</strong></div>"#,
+        );
+        assert!(status.deprecated);
+        assert_eq!(status.revoked_by, None);
+
+        let status = scrape_technique_status(
+            r#"<meta http-equiv="refresh" content="0; url=/techniques/T1546/004/"/>
+            <p>This technique has been revoked by <a href="/techniques/T1546/004">T1546.004</a></p>"#,
+        );
+        assert!(!status.deprecated);
+        assert_eq!(status.revoked_by.as_deref(), Some("T1546.004"));
+
+        let mut techniques: TechniquesTable = serde_json::from_value(serde_json::json!([
+            {"id": "T1059", "name": "Command and Scripting Interpreter", "description": "",
+             "sub_techniques": [{"id": ".001", "name": "PowerShell", "description": ""},
+                                {"id": ".010", "name": "AutoHotKey", "description": ""}]},
+            {"id": "T1064", "name": "Scripting", "description": "", "sub_techniques": null}
+        ]))?;
+        techniques.set_status(|id| {
+            return Ok(match id {
+                "T1064" => Some(TechniqueStatus {
+                    revoked_by: Some(String::from("T1059")),
+                    ..Default::default()
+                }),
+                "T1059.010" => Some(TechniqueStatus {
+                    deprecated: true,
+                    ..Default::default()
+                }),
+                _ => None,
+            });
+        })?;
+
+        assert_eq!(techniques.0[1].revoked_by.as_deref(), Some("T1059"));
+
+        let techniques = techniques.without_deprecated();
+        assert_eq!(techniques.0.len(), 1);
+        assert_eq!(
+            techniques.0[0].sub_techniques.as_ref().map(Vec::len),
+            Some(1)
+        );
+
+        Ok(())
+    }
+    #[test]
+    fn test_techniques_with_platforms() -> Result<(), error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default().set_success_response(
+            include_str!("html/attck/techniques/enterprise_deploy_container.html").to_string(),
+        );
+        let technique = fetch_technique("T1610", &fake_reqwest)?;
+        assert_eq!(technique.platforms, vec!["Containers"]);
+
+        let techniques: TechniquesTable = serde_json::from_value(serde_json::json!([
+            {"id": "T1059", "name": "Command and Scripting Interpreter", "description": "",
+             "sub_techniques": [{"id": ".001", "name": "PowerShell", "description": ""},
+                                {"id": ".004", "name": "Unix Shell", "description": ""},
+                                {"id": ".010", "name": "AutoHotKey", "description": ""}]},
+            {"id": "T1610", "name": "Deploy Container", "description": "", "sub_techniques": null}
+        ]))?;
+        let techniques = techniques.with_platforms(&[String::from("windows")], |id| {
+            let platforms = match id {
+                "T1059" => vec!["Linux", "macOS", "Windows"],
+                "T1059.004" => vec!["Linux", "macOS"],
+                "T1610" => vec!["Containers"],
+                _ => return Ok(None),
+            };
+
+            return Ok(Some(TechniquePlatforms {
+                platforms: platforms.into_iter().map(String::from).collect(),
+            }));
+        })?;
+
+        assert_eq!(techniques.0.len(), 1);
+        assert_eq!(
+            techniques.0[0]
+                .sub_techniques
+                .iter()
+                .flatten()
+                .map(|sub_technique| sub_technique.id.as_str())
+                .collect::<Vec<&str>>(),
+            vec![".001", ".010"]
+        );
+
+        Ok(())
+    }
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_techniques_tree() -> Result<(), error::Error> {
+        let techniques: TechniquesTable = serde_json::from_value(serde_json::json!([
+            {"id": "T1059", "name": "Command and Scripting Interpreter", "description": "",
+             "sub_techniques": [{"id": ".001", "name": "PowerShell", "description": ""},
+                                {"id": ".010", "name": "AutoHotKey", "description": "",
+                                 "deprecated": true}]},
+            {"id": "T1610", "name": "Deploy Container", "description": "", "sub_techniques": null}
+        ]))?;
+
+        assert_eq!(
+            techniques.tree(),
+            "T1059 Command and Scripting Interpreter (2 sub-techniques)\n\
+             ├── T1059.001 PowerShell\n\
+             └── T1059.010 AutoHotKey (deprecated)\n\
+             T1610 Deploy Container\n\
+             \n\
+             2 techniques, 2 sub-techniques\n"
+        );
 
         Ok(())
     }
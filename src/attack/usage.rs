@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::{cache::Snapshot, techniques::domain::DomainTechniquesTable};
+
+/// Group or software whose cached page references a technique.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct TechniqueUsageRow {
+    pub kind: String,
+    pub id: String,
+    pub name: String,
+    /// Referenced technique, one of the sub-techniques when the parent technique was looked up.
+    pub technique: String,
+    pub used_for: String,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for TechniqueUsageRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.kind))
+            .add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(self.name))
+            .add_cell(comfy_table::Cell::new(self.technique))
+            .add_cell(comfy_table::Cell::new(self.used_for));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct TechniqueUsageTable(pub Vec<TechniqueUsageRow>);
+
+impl TechniqueUsageTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for TechniqueUsageTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table.set_header(crate::theme::header(&[
+            "Type",
+            "ID",
+            "Name",
+            "Technique",
+            "Used for",
+        ]));
+
+        for usage in self.0 {
+            table.add_row(usage);
+        }
+
+        return table;
+    }
+}
+
+/// Inverted index of the cached group and software pages, by referenced technique.
+#[derive(Debug, Default)]
+pub struct UsageIndex {
+    /// Uses keyed by the upper-cased technique (or sub-technique) ID.
+    uses: HashMap<String, Vec<TechniqueUsageRow>>,
+}
+
+impl UsageIndex {
+    pub fn build(snapshot: &Snapshot) -> Self {
+        let mut index = Self::default();
+
+        for group in &snapshot.groups {
+            index.add_uses("group", &group.id, &group.name, group.techniques.as_ref());
+        }
+
+        for software in &snapshot.software {
+            index.add_uses(
+                "software",
+                &software.id,
+                &software.name,
+                software.techniques.as_ref(),
+            );
+        }
+
+        return index;
+    }
+
+    fn add_uses(
+        &mut self,
+        kind: &str,
+        id: &str,
+        name: &str,
+        techniques: Option<&DomainTechniquesTable>,
+    ) {
+        for technique in techniques.iter().flat_map(|techniques| techniques.0.iter()) {
+            let mut uses = vec![(technique.id.clone(), technique.used_for.clone())];
+
+            uses.extend(
+                technique
+                    .sub_techniques
+                    .iter()
+                    .flatten()
+                    .map(|sub_technique| {
+                        (
+                            format!("{}{}", technique.id, sub_technique.id),
+                            sub_technique.used_for.clone(),
+                        )
+                    }),
+            );
+
+            for (technique_id, used_for) in uses {
+                self.uses
+                    .entry(technique_id.to_uppercase())
+                    .or_default()
+                    .push(TechniqueUsageRow {
+                        kind: kind.to_string(),
+                        id: id.to_string(),
+                        name: name.to_string(),
+                        technique: technique_id,
+                        used_for,
+                    });
+            }
+        }
+    }
+
+    /// Groups and software referencing the technique or, for a parent technique, any of its
+    /// sub-techniques. Groups first, then by ID.
+    pub fn technique_usage(&self, technique_id: &str) -> TechniqueUsageTable {
+        let technique_id = technique_id.to_uppercase();
+        let sub_technique_prefix = format!("{}.", technique_id);
+
+        let mut usage: Vec<TechniqueUsageRow> = self
+            .uses
+            .iter()
+            .filter(|(id, _)| **id == technique_id || id.starts_with(&sub_technique_prefix))
+            .flat_map(|(_, uses)| uses.iter().cloned())
+            .collect();
+        usage.sort_by(|a, b| {
+            (b.kind == "group")
+                .cmp(&(a.kind == "group"))
+                .then(a.id.cmp(&b.id))
+                .then(a.technique.cmp(&b.technique))
+        });
+
+        return TechniqueUsageTable(usage);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack::{
+        groups::Group,
+        software::Software,
+        techniques::domain::{DomainSubTechniqueRow, DomainTechniqueRow},
+    };
+
+    fn techniques(technique_id: &str, sub_ids: &[&str]) -> Option<DomainTechniquesTable> {
+        return Some(DomainTechniquesTable(vec![DomainTechniqueRow {
+            id: technique_id.to_string(),
+            used_for: format!("Uses {}", technique_id),
+            sub_techniques: Some(
+                sub_ids
+                    .iter()
+                    .map(|sub_id| DomainSubTechniqueRow {
+                        id: sub_id.to_string(),
+                        used_for: format!("Uses {}{}", technique_id, sub_id),
+                        ..Default::default()
+                    })
+                    .collect(),
+            ),
+            ..Default::default()
+        }]));
+    }
+
+    #[test]
+    fn test_technique_usage() {
+        let index = UsageIndex::build(&Snapshot {
+            groups: vec![Group {
+                id: String::from("G0016"),
+                name: String::from("APT29"),
+                techniques: techniques("T1059", &[".001"]),
+                ..Default::default()
+            }],
+            software: vec![
+                Software {
+                    id: String::from("S0002"),
+                    name: String::from("Mimikatz"),
+                    techniques: techniques("T1003", &[".001"]),
+                    ..Default::default()
+                },
+                Software {
+                    id: String::from("S0154"),
+                    name: String::from("Cobalt Strike"),
+                    techniques: techniques("T1059", &[".003"]),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        });
+
+        let usage = |technique_id: &str| {
+            return index
+                .technique_usage(technique_id)
+                .0
+                .into_iter()
+                .map(|usage| format!("{} {}", usage.id, usage.technique))
+                .collect::<Vec<String>>();
+        };
+
+        assert_eq!(
+            usage("t1059"),
+            vec![
+                "G0016 T1059",
+                "G0016 T1059.001",
+                "S0154 T1059",
+                "S0154 T1059.003"
+            ]
+        );
+        assert_eq!(usage("T1059.003"), vec!["S0154 T1059.003"]);
+        assert!(index.technique_usage("T1134").is_empty());
+    }
+}
@@ -0,0 +1,284 @@
+use std::{collections::BTreeMap, path::Path};
+
+use crate::error;
+
+use super::{
+    annotations::Annotations,
+    cache::{EntityKind, Snapshot},
+    dot::GraphBuilder,
+};
+
+/// Section titles of a relationship, seen from its source and from its target.
+fn relationship_sections(relationship_type: &str) -> (&'static str, &'static str) {
+    return match relationship_type {
+        "uses" => ("Uses", "Used by"),
+        "mitigates" => ("Mitigates", "Mitigated by"),
+        "subtechnique-of" => ("Sub-technique of", "Sub-techniques"),
+        "includes" => ("Techniques", "Tactics"),
+        "detects" => ("Detects", "Detected by"),
+        _ => ("Related", "Related"),
+    };
+}
+
+/// Wiki-link to the note of the entity, showing its name when known.
+fn wiki_link(graph: &GraphBuilder, id: &str) -> String {
+    return match graph.nodes.get(id).and_then(|node| node.name.as_deref()) {
+        Some(name) => format!("[[{}|{} {}]]", id, id, name),
+        None => format!("[[{}]]", id),
+    };
+}
+
+/// Markdown notes (file name, content) of every cached entity, wiki-linked to the
+/// entities they relate to (in both directions), with the local annotations appended.
+pub fn build_vault(snapshot: &Snapshot, annotations: &Annotations) -> BTreeMap<String, String> {
+    let mut graph = GraphBuilder::from_snapshot(snapshot);
+    let mut descriptions: BTreeMap<String, (EntityKind, &str)> = BTreeMap::new();
+
+    for tactic in &snapshot.tactics {
+        graph.add_node(&tactic.id, EntityKind::TACTIC, Some(&tactic.name));
+        descriptions.insert(
+            tactic.id.to_uppercase(),
+            (EntityKind::TACTIC, &tactic.description),
+        );
+
+        if let Some(techniques) = &tactic.techniques {
+            for technique_id in techniques.ids() {
+                graph.add_node(&technique_id, EntityKind::TECHNIQUE, None);
+                graph.relate(&tactic.id, &technique_id, "includes");
+            }
+        }
+    }
+
+    for technique in &snapshot.techniques {
+        descriptions.insert(
+            technique.id.to_uppercase(),
+            (EntityKind::TECHNIQUE, &technique.description),
+        );
+
+        if let Some(detections) = &technique.detections {
+            for detection in detections
+                .0
+                .iter()
+                .filter(|detection| !detection.id.is_empty())
+            {
+                graph.add_node(
+                    &detection.id,
+                    EntityKind::DATASOURCE,
+                    Some(&detection.data_source),
+                );
+                graph.relate(&detection.id, &technique.id, "detects");
+            }
+        }
+    }
+
+    for data_source in &snapshot.data_sources {
+        graph.add_node(
+            &data_source.id,
+            EntityKind::DATASOURCE,
+            Some(&data_source.name),
+        );
+        descriptions.insert(
+            data_source.id.to_uppercase(),
+            (EntityKind::DATASOURCE, &data_source.description),
+        );
+    }
+
+    for mitigation in &snapshot.mitigations {
+        descriptions.insert(
+            mitigation.id.to_uppercase(),
+            (EntityKind::MITIGATION, &mitigation.desc),
+        );
+    }
+
+    for software in &snapshot.software {
+        descriptions.insert(
+            software.id.to_uppercase(),
+            (EntityKind::SOFTWARE, &software.desc),
+        );
+    }
+
+    for group in &snapshot.groups {
+        descriptions.insert(group.id.to_uppercase(), (EntityKind::GROUP, &group.desc));
+    }
+
+    return descriptions
+        .iter()
+        .map(|(id, (kind, description))| {
+            (
+                format!("{}.md", id),
+                build_note(&graph, annotations, id, *kind, description),
+            )
+        })
+        .collect();
+}
+
+fn build_note(
+    graph: &GraphBuilder,
+    annotations: &Annotations,
+    id: &str,
+    kind: EntityKind,
+    description: &str,
+) -> String {
+    let kind_name: &str = kind.into();
+    let name = graph
+        .nodes
+        .get(id)
+        .and_then(|node| node.name.clone())
+        .unwrap_or_default();
+
+    let mut note = format!(
+        "---\nid: {}\ntype: {}\naliases: [{}]\ntags: [attack/{}]\n---\n\n# {} {}\n\n{}\n",
+        id,
+        kind_name,
+        serde_json::Value::from(name.as_str()),
+        kind_name,
+        id,
+        name,
+        description
+    );
+
+    let mut sections: BTreeMap<&'static str, Vec<String>> = BTreeMap::new();
+
+    for (source_id, target_id, relationship_type) in &graph.edges {
+        let (outgoing, incoming) = relationship_sections(relationship_type);
+
+        if source_id == id {
+            sections
+                .entry(outgoing)
+                .or_default()
+                .push(wiki_link(graph, target_id));
+        } else if target_id == id {
+            sections
+                .entry(incoming)
+                .or_default()
+                .push(wiki_link(graph, source_id));
+        }
+    }
+
+    for (title, links) in sections {
+        note.push_str(&format!("\n## {}\n\n", title));
+
+        for link in links {
+            note.push_str(&format!("- {}\n", link));
+        }
+    }
+
+    let status = annotations.mitigation_status(id);
+    let entity_notes = annotations.entity_notes(id);
+
+    if status.is_some() || !entity_notes.is_empty() {
+        note.push_str("\n## Local notes\n\n");
+
+        if let Some(status) = status {
+            let status: &str = status.into();
+            note.push_str(&format!("- Implementation status: {}\n", status));
+        }
+
+        for entity_note in entity_notes {
+            note.push_str(&format!("- {}\n", entity_note));
+        }
+    }
+
+    return note;
+}
+
+/// Write the notes into `out_dir`, returning the number of notes written.
+pub fn write_vault(
+    notes: &BTreeMap<String, String>,
+    out_dir: &Path,
+) -> Result<usize, error::Error> {
+    std::fs::create_dir_all(out_dir)?;
+
+    for (file_name, note) in notes {
+        std::fs::write(out_dir.join(file_name), note)?;
+    }
+
+    return Ok(notes.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack::{
+        annotations::ImplementationStatus,
+        mitigations::Mitigation,
+        tactics::Tactic,
+        techniques::{
+            domain::{DomainTechniqueRow, DomainTechniquesTable},
+            Technique, TechniqueRow, TechniquesTable,
+        },
+    };
+
+    fn test_snapshot() -> Snapshot {
+        return Snapshot {
+            tactics: vec![Tactic {
+                id: String::from("TA0001"),
+                name: String::from("Initial Access"),
+                techniques: Some(TechniquesTable(vec![TechniqueRow {
+                    id: String::from("T1190"),
+                    ..Default::default()
+                }])),
+                ..Default::default()
+            }],
+            techniques: vec![Technique {
+                id: String::from("T1190"),
+                name: String::from("Exploit Public-Facing Application"),
+                description: String::from("Adversaries may exploit a weakness."),
+                ..Default::default()
+            }],
+            mitigations: vec![Mitigation {
+                id: String::from("M1051"),
+                name: String::from("Update Software"),
+                addressed_techniques: Some(DomainTechniquesTable(vec![DomainTechniqueRow {
+                    id: String::from("T1190"),
+                    ..Default::default()
+                }])),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn test_vault_links() {
+        let mut annotations = Annotations::default();
+        annotations.set_mitigation_status("M1051", ImplementationStatus::PLANNED);
+        annotations.add_note("T1190", "Seen against the VPN appliance");
+
+        let vault = build_vault(&test_snapshot(), &annotations);
+        assert_eq!(
+            vault.keys().collect::<Vec<&String>>(),
+            vec!["M1051.md", "T1190.md", "TA0001.md"]
+        );
+
+        let technique_note = &vault["T1190.md"];
+        assert!(technique_note.starts_with("---\nid: T1190\ntype: techniques\n"));
+        assert!(technique_note.contains("# T1190 Exploit Public-Facing Application\n"));
+        assert!(technique_note.contains("## Mitigated by\n\n- [[M1051|M1051 Update Software]]\n"));
+        assert!(technique_note.contains("## Tactics\n\n- [[TA0001|TA0001 Initial Access]]\n"));
+        assert!(technique_note.contains("## Local notes\n\n- Seen against the VPN appliance\n"));
+
+        let mitigation_note = &vault["M1051.md"];
+        assert!(mitigation_note
+            .contains("## Mitigates\n\n- [[T1190|T1190 Exploit Public-Facing Application]]\n"));
+        assert!(mitigation_note.contains("- Implementation status: planned\n"));
+    }
+
+    #[test]
+    fn test_write_vault() -> Result<(), error::Error> {
+        let out_dir = std::env::temp_dir().join("mitre_cli_vault");
+        let _ = std::fs::remove_dir_all(&out_dir);
+
+        let written = write_vault(
+            &build_vault(&test_snapshot(), &Annotations::default()),
+            &out_dir,
+        )?;
+
+        assert_eq!(written, 3);
+        assert!(out_dir.join("TA0001.md").exists());
+
+        std::fs::remove_dir_all(&out_dir)?;
+
+        Ok(())
+    }
+}
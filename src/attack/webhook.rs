@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{error, WebFetch};
+
+use super::sync::CacheChanges;
+
+/// Environment variable holding the webhook notified of the sync changes.
+pub const WEBHOOK_URL_ENV: &'static str = "MITRE_CLI_WEBHOOK_URL";
+/// IDs listed per entity type in the text of the notification, the others counted.
+const TEXT_MAX_IDS: usize = 10;
+
+/// Webhook read from the `MITRE_CLI_WEBHOOK_URL` environment variable, if set.
+pub fn url_from_env() -> Option<String> {
+    return std::env::var(WEBHOOK_URL_ENV)
+        .ok()
+        .filter(|url| !url.is_empty());
+}
+
+/// JSON summary of the entities a sync added or updated.
+///
+/// `text` holds a readable summary, which is the field Slack and Teams incoming webhooks
+/// display. Generic consumers can read the structured fields instead.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct ChangeNotification {
+    pub text: String,
+    pub attack_version: Option<String>,
+    pub synced_at: String,
+    /// Changes by entity type, those without any left out.
+    pub changes: BTreeMap<String, CacheChanges>,
+}
+
+impl ChangeNotification {
+    pub fn new(
+        attack_version: Option<String>,
+        synced_at: &str,
+        changes: Vec<(String, CacheChanges)>,
+    ) -> Self {
+        let changes: BTreeMap<String, CacheChanges> = changes
+            .into_iter()
+            .filter(|(_, kind_changes)| !kind_changes.is_empty())
+            .collect();
+        let mut lines = vec![format!(
+            "ATT&CK {} sync at {}",
+            attack_version.as_deref().unwrap_or("(unknown release)"),
+            synced_at
+        )];
+
+        for (kind_name, kind_changes) in &changes {
+            for (change, ids) in [
+                ("added", &kind_changes.added),
+                ("updated", &kind_changes.updated),
+            ] {
+                if !ids.is_empty() {
+                    lines.push(format!(
+                        "{} {} {}: {}",
+                        ids.len(),
+                        kind_name,
+                        change,
+                        text_ids(ids)
+                    ));
+                }
+            }
+        }
+
+        return Self {
+            text: lines.join("\n"),
+            attack_version,
+            synced_at: synced_at.to_string(),
+            changes,
+        };
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.changes.is_empty();
+    }
+}
+
+fn text_ids(ids: &[String]) -> String {
+    let listed = ids
+        .iter()
+        .take(TEXT_MAX_IDS)
+        .cloned()
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    if ids.len() > TEXT_MAX_IDS {
+        return format!("{} and {} more", listed, ids.len() - TEXT_MAX_IDS);
+    }
+
+    return listed;
+}
+
+/// POST the notification to the webhook.
+pub fn notify(
+    url: &str,
+    notification: &ChangeNotification,
+    web_client: &impl WebFetch,
+) -> Result<(), error::Error> {
+    web_client.post_json(url, &serde_json::to_string(notification)?)?;
+
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fakers::FakeHttpReqwest;
+
+    #[test]
+    fn test_change_notification() -> Result<(), error::Error> {
+        let notification = ChangeNotification::new(
+            Some(String::from("v15.1")),
+            "2024-05-01T00:00:00Z",
+            vec![
+                (
+                    String::from("techniques"),
+                    CacheChanges {
+                        added: (1..=12).map(|inx| format!("T16{:02}", inx)).collect(),
+                        updated: vec![String::from("T1059")],
+                    },
+                ),
+                (String::from("groups"), CacheChanges::default()),
+            ],
+        );
+
+        assert_eq!(
+            notification.changes.keys().collect::<Vec<&String>>(),
+            vec!["techniques"]
+        );
+        assert_eq!(
+            notification.text,
+            "ATT&CK v15.1 sync at 2024-05-01T00:00:00Z\n\
+             12 techniques added: T1601, T1602, T1603, T1604, T1605, T1606, T1607, T1608, \
+             T1609, T1610 and 2 more\n\
+             1 techniques updated: T1059"
+        );
+
+        let fake_reqwest = FakeHttpReqwest::default();
+        notify("https://hooks.example.com", &notification, &fake_reqwest)?;
+        let posted: serde_json::Value = serde_json::from_str(&fake_reqwest.posted_bodies()[0])?;
+
+        assert_eq!(posted["attack_version"], "v15.1");
+        assert_eq!(posted["changes"]["techniques"]["updated"][0], "T1059");
+        assert!(ChangeNotification::new(None, "", vec![]).is_empty());
+
+        Ok(())
+    }
+}
@@ -1,190 +1,655 @@
 use std::str::FromStr;
 
+#[cfg(feature = "archive")]
+use crate::attack::archive;
+#[cfg(feature = "fulltext")]
+use crate::attack::fulltext::FulltextIndex;
+#[cfg(feature = "sqlite")]
+use crate::attack::sqlite;
 use crate::{
-    attack::{data_sources, groups, mitigations, software, tactics, techniques},
-    WebFetch,
+    attack::{
+        annotations::{Annotations, ImplementationStatus},
+        cache::{Cache, EntityKind, Snapshot, DOMAINS},
+        caldera, changelog, compare, consistency, controls, coverage,
+        dashboard::{self, Dashboard},
+        data_sources, dataset, dot, groups, layer, matrix, mitigations, names, plan,
+        provenance::{self, Provenance, SyncManifest},
+        query::ListingQuery,
+        resolve, rule_coverage, runbook, schema, search, software,
+        spool::{self, Spool},
+        stats::{self, CacheStats},
+        stix, stix_import, sync, tactics, tagging, techniques,
+        usage::UsageIndex,
+        vault, webhook,
+    },
+    d3fend, mappings, nvd, WebFetch,
 };
-use structopt::StructOpt;
+use clap::Subcommand;
+
+use super::{GlobalOptions, OutputFormat};
 
+/// Rows per page of 'attack list --page' without --page-size.
+const DEFAULT_PAGE_SIZE: u64 = 50;
 
-#[derive(StructOpt)]
-#[structopt(no_version)]
+#[derive(Subcommand)]
 pub enum AttackDescribeCommand {
     /// ATT&CK Tactic
     Tactic {
         /// Tactic ID
-        id: String,
+        #[arg(required_unless_present_any = ["name", "stdin"])]
+        id: Option<String>,
+
+        /// Tactic name (or associated name), resolved to its ID through the cached listing
+        #[arg(long, conflicts_with = "id")]
+        name: Option<String>,
+
+        /// Replace the IDs of the related tables of the JSON output with the cached entities
+        #[arg(long)]
+        resolve: bool,
+
+        /// Describe every ID read from STDIN (one or more per line, # comments ignored)
+        #[arg(long, conflicts_with_all = ["id", "name"])]
+        stdin: bool,
 
         /// Show techniques related to the retrieved tactic
-        #[structopt(long)]
+        #[arg(long)]
         show_techniques: bool,
     },
     /// ATT&CK Technique
     Technique {
         /// Technique ID
-        id: String,
+        #[arg(required_unless_present_any = ["name", "stdin"])]
+        id: Option<String>,
+
+        /// Technique name (or associated name), resolved to its ID through the cached listing
+        #[arg(long, conflicts_with = "id")]
+        name: Option<String>,
+
+        /// Replace the IDs of the related tables of the JSON output with the cached entities
+        #[arg(long)]
+        resolve: bool,
+
+        /// Describe every ID read from STDIN (one or more per line, # comments ignored)
+        #[arg(long, conflicts_with_all = ["id", "name"])]
+        stdin: bool,
 
         /// Show procedures related to the retrieved technique
-        #[structopt(long)]
+        #[arg(long)]
         show_procedures: bool,
 
         /// Show mitigations related to the retrieved technique
-        #[structopt(long)]
+        #[arg(long)]
         show_mitigations: bool,
 
         /// Show detections related to the retrieved technique
-        #[structopt(long)]
+        #[arg(long)]
         show_detections: bool,
+
+        /// Show D3FEND defensive techniques that counter the retrieved technique
+        #[arg(long)]
+        show_d3fend: bool,
+
+        /// Show the NVD entries of the CVEs referenced by the retrieved technique (API key read from NVD_API_KEY)
+        #[arg(long)]
+        show_cves: bool,
+
+        /// Show the groups and software of the local cache using the technique (or one of its sub-techniques)
+        #[arg(long)]
+        show_usage: bool,
+
+        /// Show the other sub-techniques of the parent of the retrieved sub-technique
+        #[arg(long)]
+        show_siblings: bool,
+
+        /// Show the security controls of the framework (nist-800-53, cis) mapped to the
+        /// retrieved technique
+        #[arg(long, value_name = "FRAMEWORK")]
+        show_controls: Option<String>,
     },
     /// ATT&CK Mitigation
     Mitigation {
         /// Mitigation ID
-        id: String,
+        #[arg(required_unless_present_any = ["name", "stdin"])]
+        id: Option<String>,
+
+        /// Mitigation name (or associated name), resolved to its ID through the cached listing
+        #[arg(long, conflicts_with = "id")]
+        name: Option<String>,
+
+        /// Replace the IDs of the related tables of the JSON output with the cached entities
+        #[arg(long)]
+        resolve: bool,
+
+        /// Describe every ID read from STDIN (one or more per line, # comments ignored)
+        #[arg(long, conflicts_with_all = ["id", "name"])]
+        stdin: bool,
 
         /// Show techniques related to the retrieved mitigation
-        #[structopt(long)]
+        #[arg(long)]
         show_techniques: bool,
     },
     /// ATT&CK Software
     Software {
         /// Software ID
-        id: String,
+        #[arg(required_unless_present_any = ["name", "stdin"])]
+        id: Option<String>,
+
+        /// Software name (or associated name), resolved to its ID through the cached listing
+        #[arg(long, conflicts_with = "id")]
+        name: Option<String>,
+
+        /// Replace the IDs of the related tables of the JSON output with the cached entities
+        #[arg(long)]
+        resolve: bool,
+
+        /// Describe every ID read from STDIN (one or more per line, # comments ignored)
+        #[arg(long, conflicts_with_all = ["id", "name"])]
+        stdin: bool,
 
         /// Show techniques related to the retrieved software
-        #[structopt(long)]
+        #[arg(long)]
         show_techniques: bool,
 
         /// Show groups related to the retrieved software
-        #[structopt(long)]
+        #[arg(long)]
         show_groups: bool,
+
+        /// Show the NVD entries of the CVEs referenced by the retrieved software (API key read from NVD_API_KEY)
+        #[arg(long)]
+        show_cves: bool,
     },
     /// ATT&CK Group
     Group {
         /// Group ID
-        id: String,
+        #[arg(required_unless_present_any = ["name", "stdin"])]
+        id: Option<String>,
+
+        /// Group name (or associated name), resolved to its ID through the cached listing
+        #[arg(long, conflicts_with = "id")]
+        name: Option<String>,
+
+        /// Replace the IDs of the related tables of the JSON output with the cached entities
+        #[arg(long)]
+        resolve: bool,
+
+        /// Describe every ID read from STDIN (one or more per line, # comments ignored)
+        #[arg(long, conflicts_with_all = ["id", "name"])]
+        stdin: bool,
 
         /// Show techniques related to the retrieved group
-        #[structopt(long)]
+        #[arg(long)]
         show_techniques: bool,
 
         /// Show software related to the retrieved group
-        #[structopt(long)]
+        #[arg(long)]
         show_software: bool,
+
+        /// Show the techniques of the group grouped by tactic, in kill-chain order (needs --domain)
+        #[arg(long)]
+        by_tactic: bool,
     },
     /// ATT&CK Data Source
     DataSource {
         /// Data Source ID
-        id: String,
+        #[arg(required_unless_present_any = ["name", "stdin"])]
+        id: Option<String>,
+
+        /// Data Source name (or associated name), resolved to its ID through the cached listing
+        #[arg(long, conflicts_with = "id")]
+        name: Option<String>,
+
+        /// Replace the IDs of the related tables of the JSON output with the cached entities
+        #[arg(long)]
+        resolve: bool,
+
+        /// Describe every ID read from STDIN (one or more per line, # comments ignored)
+        #[arg(long, conflicts_with_all = ["id", "name"])]
+        stdin: bool,
 
         /// Show components related to the retrieved Data Source
-        #[structopt(long)]
+        #[arg(long)]
         show_components: bool
     },
 }
 
 impl AttackDescribeCommand {
-    fn handle(self, req_client: impl WebFetch) -> Result<(), crate::error::Error> {
-        match self {
+    fn handle(
+        self,
+        options: &GlobalOptions,
+        req_client: impl WebFetch,
+    ) -> Result<(), crate::error::Error> {
+        let reads_stdin = match self {
+            AttackDescribeCommand::Tactic { stdin, .. }
+            | AttackDescribeCommand::Technique { stdin, .. }
+            | AttackDescribeCommand::Mitigation { stdin, .. }
+            | AttackDescribeCommand::Software { stdin, .. }
+            | AttackDescribeCommand::Group { stdin, .. }
+            | AttackDescribeCommand::DataSource { stdin, .. } => stdin,
+        };
+
+        if !reads_stdin {
+            let id = self.resolve_id(options)?;
+
+            return self.handle_id(options, &id, req_client);
+        }
+
+        let ids = read_ids(std::io::stdin().lock())?;
+        let mut failed = 0;
+
+        if ids.is_empty() {
+            return Err(crate::error::Error::InvalidValue(String::from(
+                "No IDs read from STDIN",
+            )));
+        }
+
+        // One unknown ID should not lose the enrichment of the others.
+        for id in &ids {
+            if let Err(err) = self.handle_id(options, id, &req_client) {
+                eprintln!("[!] {}: {}", id.to_uppercase(), err);
+                failed += 1;
+            }
+        }
+
+        if failed > 0 {
+            return Err(crate::error::Error::General(format!(
+                "{} of the {} IDs read from STDIN failed",
+                failed,
+                ids.len()
+            )));
+        }
+
+        return Ok(());
+    }
+
+    fn handle_id(
+        &self,
+        options: &GlobalOptions,
+        id: &str,
+        req_client: impl WebFetch,
+    ) -> Result<(), crate::error::Error> {
+        match *self {
             AttackDescribeCommand::Tactic {
-                ref id,
-                show_techniques,
-            } => self.handle_tactic_cmd(&id, show_techniques, req_client)?,
+                show_techniques, ..
+            } => self.handle_tactic_cmd(options, id, show_techniques, req_client)?,
             AttackDescribeCommand::Technique {
-                ref id,
                 show_procedures,
                 show_mitigations,
                 show_detections,
+                show_d3fend,
+                show_cves,
+                show_usage,
+                show_siblings,
+                ref show_controls,
+                ..
             } => self.handle_technique_cmd(
-                &id,
+                options,
+                id,
                 show_procedures,
                 show_mitigations,
                 show_detections,
+                show_d3fend,
+                show_cves,
+                show_usage,
+                show_siblings,
+                show_controls.as_deref(),
                 req_client,
             )?,
             AttackDescribeCommand::Mitigation {
-                ref id,
-                show_techniques,
-            } => self.handle_mitigation_cmd(&id, show_techniques, req_client)?,
+                show_techniques, ..
+            } => self.handle_mitigation_cmd(options, id, show_techniques, req_client)?,
             AttackDescribeCommand::Software {
-                ref id,
                 show_techniques,
                 show_groups,
-            } => self.handle_software_cmd(&id, show_techniques, show_groups, req_client)?,
+                show_cves,
+                ..
+            } => self.handle_software_cmd(
+                options,
+                id,
+                show_techniques,
+                show_groups,
+                show_cves,
+                req_client,
+            )?,
             AttackDescribeCommand::Group {
-                ref id,
                 show_techniques,
                 show_software,
-            } => self.handle_group_cmd(&id, show_software, show_techniques, req_client)?,
-            AttackDescribeCommand::DataSource { ref id, show_components } => {
-                self.handle_data_source_cmd(id, show_components, req_client)?
-            }
+                by_tactic,
+                ..
+            } => self.handle_group_cmd(
+                options,
+                id,
+                show_software,
+                show_techniques,
+                by_tactic,
+                req_client,
+            )?,
+            AttackDescribeCommand::DataSource {
+                show_components, ..
+            } => self.handle_data_source_cmd(options, id, show_components, req_client)?,
         };
 
         return Ok(());
     }
 
+    /// JSON output of the described entity, with the related entities when `--resolve` is given.
+    fn print_json(
+        &self,
+        options: &GlobalOptions,
+        value: &impl serde::Serialize,
+    ) -> Result<(), crate::error::Error> {
+        let resolve = match self {
+            AttackDescribeCommand::Tactic { resolve, .. }
+            | AttackDescribeCommand::Technique { resolve, .. }
+            | AttackDescribeCommand::Mitigation { resolve, .. }
+            | AttackDescribeCommand::Software { resolve, .. }
+            | AttackDescribeCommand::Group { resolve, .. }
+            | AttackDescribeCommand::DataSource { resolve, .. } => *resolve,
+        };
+
+        let mut value = serde_json::to_value(value)?;
+
+        // Entities cached by older releases have no URL.
+        if value["url"].as_str().is_none_or(str::is_empty) {
+            if let Some(id) = value["id"].as_str() {
+                value["url"] = serde_json::Value::from(spool::entity_url(self.kind(), id));
+            }
+        }
+
+        if resolve {
+            resolve::resolve_related(&Cache::open()?, &mut value)?;
+        }
+
+        return options.print_json(&value);
+    }
+
+    fn kind(&self) -> EntityKind {
+        return match self {
+            AttackDescribeCommand::Tactic { .. } => EntityKind::TACTIC,
+            AttackDescribeCommand::Technique { .. } => EntityKind::TECHNIQUE,
+            AttackDescribeCommand::Mitigation { .. } => EntityKind::MITIGATION,
+            AttackDescribeCommand::Software { .. } => EntityKind::SOFTWARE,
+            AttackDescribeCommand::Group { .. } => EntityKind::GROUP,
+            AttackDescribeCommand::DataSource { .. } => EntityKind::DATASOURCE,
+        };
+    }
+
+    /// ID of the described entity, given directly or resolved from `--name`.
+    fn resolve_id(&self, options: &GlobalOptions) -> Result<String, crate::error::Error> {
+        let kind = self.kind();
+        let (id, name) = match self {
+            AttackDescribeCommand::Tactic { id, name, .. }
+            | AttackDescribeCommand::Technique { id, name, .. }
+            | AttackDescribeCommand::Mitigation { id, name, .. }
+            | AttackDescribeCommand::Software { id, name, .. }
+            | AttackDescribeCommand::Group { id, name, .. }
+            | AttackDescribeCommand::DataSource { id, name, .. } => (id, name),
+        };
+
+        if let Some(id) = id {
+            return Ok(id.clone());
+        }
+
+        let name = name.as_deref().unwrap_or_default();
+        let listing_names = Cache::open()?.listing_names(kind)?.ok_or_else(|| {
+            let kind_name: &str = kind.into();
+
+            crate::error::Error::General(format!(
+                "No {} listing cached, run 'attack sync {}' first",
+                kind_name, kind_name
+            ))
+        })?;
+        let id = names::resolve_name(&listing_names, name)?;
+
+        if !options.is_json() {
+            println!("[*] Resolved \"{}\" to {}", name, id);
+        }
+
+        return Ok(id);
+    }
+
     fn handle_tactic_cmd(
         &self,
+        options: &GlobalOptions,
         id: &str,
         show_techniques: bool,
         req_client: impl WebFetch,
     ) -> Result<(), crate::error::Error> {
-        let tactic = tactics::fetch_tactic(id, &req_client)?;
+        let cache = Cache::open()?;
+        let mut tactic: tactics::Tactic = cache.entity_or_fetch(EntityKind::TACTIC, id, || {
+            tactics::fetch_tactic(id, &req_client)
+        })?;
+
+        if tactic.domain.is_none() {
+            tactic.domain = entity_domain(options, &cache, EntityKind::TACTIC, id)?;
+        }
+
+        // Tactics cached by older releases have no count nor order.
+        tactic.technique_count = tactic
+            .techniques
+            .as_ref()
+            .map_or(0, |techniques| techniques.len());
+        let domain_tactics: Option<tactics::TacticsTable> = match &tactic.domain {
+            Some(domain) => cache.load_listing(EntityKind::TACTIC, Some(domain))?,
+            None => None,
+        };
 
-        println!("[*] Tactic ID: {}", tactic.id);
-        println!("[*] Tactic name: {}", tactic.name);
-        println!("[*] Tactic description: {}", tactic.description);
+        if tactic.order.is_none() {
+            tactic.order = domain_tactics
+                .as_ref()
+                .and_then(|domain_tactics| domain_tactics.order_of(&tactic.id));
+        }
+
+        if options.is_json() {
+            return self.print_json(options, &tactic);
+        }
+
+        options.write_line(&format!("[*] Tactic ID: {}", tactic.id))?;
+        options.write_line(&format!("[*] Tactic name: {}", tactic.name))?;
+        print_domain(options, &tactic.domain)?;
+
+        if let (Some(order), Some(domain_tactics)) = (tactic.order, &domain_tactics) {
+            options.write_line(&format!(
+                "[*] Kill-chain position: {} of {}",
+                order,
+                domain_tactics.len()
+            ))?;
+        }
+
+        options.write_line(&format!("[*] Techniques: {}", tactic.technique_count))?;
+        options.write_line(&format!(
+            "[*] Tactic description: {}",
+            options.description(&tactic.description)
+        ))?;
 
         if show_techniques {
             if let Some(technique_table) = tactic.techniques {
-                let technique_table: comfy_table::Table = technique_table.into();
-                println!("{}", technique_table);
+                options.print(technique_table)?;
             } else {
-                println!("[!] No techniques associated");
+                options.write_line("[!] No techniques associated")?;
             }
         }
 
         return Ok(());
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn handle_technique_cmd(
         &self,
+        options: &GlobalOptions,
         id: &str,
         show_procedures: bool,
         show_mitigations: bool,
         show_detections: bool,
+        show_d3fend: bool,
+        show_cves: bool,
+        show_usage: bool,
+        show_siblings: bool,
+        show_controls: Option<&str>,
         req_client: impl WebFetch,
     ) -> Result<(), crate::error::Error> {
-        let technique = techniques::fetch_technique(id, &req_client)?;
+        let framework = show_controls
+            .map(mappings::Framework::from_str)
+            .transpose()?;
+        let cache = Cache::open()?;
+        let mut technique: techniques::Technique =
+            cache.entity_or_fetch(EntityKind::TECHNIQUE, id, || {
+                techniques::fetch_technique(id, &req_client)
+            })?;
+
+        if technique.domain.is_none() {
+            technique.domain = entity_domain(options, &cache, EntityKind::TECHNIQUE, id)?;
+        }
+
+        // Techniques cached by older releases have no parent.
+        if technique.parent.is_none() {
+            if let Some(parent_id) = techniques::parent_id(&technique.id) {
+                let parent: Option<techniques::Technique> =
+                    cache.load_entity(EntityKind::TECHNIQUE, &parent_id)?;
+
+                technique.parent = Some(techniques::ParentTechnique {
+                    name: parent.map(|parent| parent.name).unwrap_or_default(),
+                    id: parent_id,
+                });
+            }
+        }
+
+        let cve_ids = technique_cve_ids(&technique);
+        let usage = if show_usage {
+            Some(UsageIndex::build(&cache.snapshot()?).technique_usage(id))
+        } else {
+            None
+        };
+
+        if options.is_json() {
+            let mut technique_json = serde_json::to_value(&technique)?;
+
+            if let Some(usage) = &usage {
+                technique_json["usage"] = serde_json::to_value(usage)?;
+            }
+
+            if show_d3fend {
+                technique_json["d3fend"] =
+                    serde_json::to_value(d3fend::fetch_defensive_techniques(id, &req_client)?)?;
+            }
+
+            if show_cves {
+                technique_json["cves"] = serde_json::to_value(fetch_cves(&cve_ids, &req_client)?)?;
+            }
+
+            if let Some(framework) = framework {
+                technique_json["controls"] = serde_json::to_value(
+                    mappings::mappings_or_fetch(framework, &req_client)?.technique_controls(id),
+                )?;
+            }
+
+            return self.print_json(options, &technique_json);
+        }
+
+        options.write_line(&format!("[*] Technique ID: {}", technique.id))?;
+        options.write_line(&format!("[*] Technique name: {}", technique.name))?;
+
+        if let Some(parent) = &technique.parent {
+            if parent.name.is_empty() {
+                options.write_line(&format!("[*] Parent technique: {}", parent.id))?;
+            } else {
+                options.write_line(&format!(
+                    "[*] Parent technique: {} - {}",
+                    parent.id, parent.name
+                ))?;
+            }
+        }
+
+        if let Some(revoked_by) = &technique.revoked_by {
+            options.write_line(&format!("[!] Revoked by {}", revoked_by))?;
+        } else if technique.deprecated {
+            options.write_line("[!] Deprecated")?;
+        }
+
+        print_domain(options, &technique.domain)?;
+        options.write_line(&format!(
+            "[*] Technique description: {}",
+            options.description(&technique.description)
+        ))?;
+        print_references(options, &technique.references)?;
+
+        if !technique.contributors.is_empty() {
+            options.write_line(&format!(
+                "[*] Contributors: {}",
+                technique.contributors.join("; ")
+            ))?;
+        }
 
-        println!("[*] Technique ID: {}", technique.id);
-        println!("[*] Technique name: {}", technique.name);
-        println!("[*] Technique description: {}", technique.description);
+        if show_siblings {
+            let siblings =
+                techniques::SubTechniquesTable(technique.siblings().into_iter().cloned().collect());
+
+            if !technique.is_sub_technique() {
+                options.write_line(&format!("[!] {} is not a sub-technique", technique.id))?;
+            } else if siblings.is_empty() {
+                options.write_line("[!] No other sub-techniques")?;
+            } else {
+                options.print(siblings)?;
+            }
+        }
 
         if show_procedures {
             if let Some(procedure_table) = technique.procedures {
-                let procedure_table: comfy_table::Table = procedure_table.into();
-                println!("{}", procedure_table);
+                options.print(procedure_table)?;
             } else {
-                println!("[!] No procedures associated");
+                options.write_line("[!] No procedures associated")?;
             }
         }
 
         if show_mitigations {
             if let Some(mitigation_table) = technique.mitigations {
-                let mitigation_table: comfy_table::Table = mitigation_table.into();
-                println!("{}", mitigation_table);
+                options.print(mitigation_table)?;
             } else {
-                println!("[!] No mitigations associated");
+                options.write_line("[!] No mitigations associated")?;
             }
         }
 
         if show_detections {
+            if let Some(detection_notes) = &technique.detection_notes {
+                options.write_line(&format!("[*] Detection notes: {}", detection_notes))?;
+            }
+
             if let Some(detections_table) = technique.detections {
-                let detections_table: comfy_table::Table = detections_table.into();
-                println!("{}", detections_table);
+                options.print(detections_table)?;
+            } else if technique.detection_notes.is_none() {
+                options.write_line("[!] No detections associated")?;
+            }
+        }
+
+        if show_d3fend {
+            let defensive_techniques = d3fend::fetch_defensive_techniques(id, &req_client)?;
+
+            if defensive_techniques.is_empty() {
+                options.write_line("[!] No D3FEND techniques associated")?;
+            } else {
+                options.print(defensive_techniques)?;
+            }
+        }
+
+        if show_cves {
+            print_cves(options, &cve_ids, &req_client)?;
+        }
+
+        if let Some(framework) = framework {
+            let controls =
+                mappings::mappings_or_fetch(framework, &req_client)?.technique_controls(id);
+
+            if controls.is_empty() {
+                options.write_line(&format!("[!] No {} controls mapped", framework.title()))?;
+            } else {
+                options.print(controls)?;
+            }
+        }
+
+        if let Some(usage) = usage {
+            if usage.is_empty() {
+                options.write_line("[!] No cached group or software uses the technique")?;
             } else {
-                println!("[!] No detections associated");
+                options.print(usage)?;
             }
         }
 
@@ -193,22 +658,56 @@ impl AttackDescribeCommand {
 
     fn handle_mitigation_cmd(
         &self,
+        options: &GlobalOptions,
         id: &str,
         show_techniques: bool,
         req_client: impl WebFetch,
     ) -> Result<(), crate::error::Error> {
-        let mitigation = mitigations::fetch_mitigation(id, &req_client)?;
+        let cache = Cache::open()?;
+        let mut mitigation: mitigations::Mitigation =
+            cache.entity_or_fetch(EntityKind::MITIGATION, id, || {
+                mitigations::fetch_mitigation(id, &req_client)
+            })?;
+
+        if mitigation.domain.is_none() {
+            mitigation.domain = entity_domain(options, &cache, EntityKind::MITIGATION, id)?;
+        }
+
+        if options.is_json() {
+            return self.print_json(options, &mitigation);
+        }
+
+        options.write_line(&format!("[*] Mitigation ID: {}", mitigation.id))?;
+        options.write_line(&format!("[*] Mitigation name: {}", mitigation.name))?;
+        print_domain(options, &mitigation.domain)?;
+        options.write_line(&format!(
+            "[*] Mitigation description: {}",
+            options.description(&mitigation.desc)
+        ))?;
+        print_references(options, &mitigation.references)?;
+
+        if let Some(version) = &mitigation.version {
+            options.write_line(&format!("[*] Mitigation version: {}", version))?;
+        }
 
-        println!("[*] Mitigation ID: {}", mitigation.id);
-        println!("[*] Mitigation name: {}", mitigation.name);
-        println!("[*] Mitigation description: {}", mitigation.desc);
+        if let Some(created) = &mitigation.created {
+            options.write_line(&format!("[*] Created: {}", created))?;
+        }
+
+        if let Some(modified) = &mitigation.modified {
+            options.write_line(&format!("[*] Last modified: {}", modified))?;
+        }
+
+        if let Some(status) = Annotations::load()?.mitigation_status(&mitigation.id) {
+            let status: &str = status.into();
+            options.write_line(&format!("[*] Mitigation implementation status: {}", status))?;
+        }
 
         if show_techniques {
             if let Some(addressed_techniques) = mitigation.addressed_techniques {
-                let addressed_techniques: comfy_table::Table = addressed_techniques.into();
-                println!("{}", addressed_techniques);
+                options.print(addressed_techniques)?;
             } else {
-                println!("[!] No techniques associated");
+                options.write_line("[!] No techniques associated")?;
             }
         }
 
@@ -217,70 +716,171 @@ impl AttackDescribeCommand {
 
     fn handle_software_cmd(
         &self,
+        options: &GlobalOptions,
         id: &str,
         show_techniques: bool,
         show_groups: bool,
+        show_cves: bool,
         req_client: impl WebFetch,
     ) -> Result<(), crate::error::Error> {
-        let software_info = software::fetch_software_info(id, &req_client)?;
+        let software_info: software::Software =
+            Cache::open()?.entity_or_fetch(EntityKind::SOFTWARE, id, || {
+                software::fetch_software_info(id, &req_client)
+            })?;
+        let cve_ids = software_cve_ids(&software_info);
+
+        if options.output == OutputFormat::LAYER {
+            return write_layer(
+                options,
+                &software_info.id,
+                &software_info.name,
+                &software_info.desc,
+                software_info.techniques.as_ref(),
+            );
+        }
+
+        if options.is_json() {
+            let mut software_json = serde_json::to_value(&software_info)?;
+
+            if show_cves {
+                software_json["cves"] = serde_json::to_value(fetch_cves(&cve_ids, &req_client)?)?;
+            }
+
+            return self.print_json(options, &software_json);
+        }
+
+        options.write_line(&format!("[*] Software ID: {}", software_info.id))?;
+        options.write_line(&format!("[*] Software name: {}", software_info.name))?;
+
+        if let Some(software_type) = &software_info.software_type {
+            options.write_line(&format!("[*] Software type: {}", software_type))?;
+        }
+
+        options.write_line(&format!(
+            "[*] Software description: {}",
+            options.description(&software_info.desc)
+        ))?;
+        print_references(options, &software_info.references)?;
 
-        println!("[*] Software ID: {}", software_info.id);
-        println!("[*] Software name: {}", software_info.name);
-        println!("[*] Software description: {}", software_info.desc);
+        if !software_info.contributors.is_empty() {
+            options.write_line(&format!(
+                "[*] Contributors: {}",
+                software_info.contributors.join("; ")
+            ))?;
+        }
 
         if show_techniques {
             if let Some(techniques) = software_info.techniques {
-                let techniques: comfy_table::Table = techniques.into();
-                println!("{}", techniques);
+                options.print(techniques)?;
             } else {
-                println!("[!] No techniques associated");
+                options.write_line("[!] No techniques associated")?;
             }
         }
 
         if show_groups {
             if let Some(groups) = software_info.groups {
-                let groups: comfy_table::Table = groups.into();
-                println!("{}", groups);
+                options.print(groups)?;
             } else {
-                println!("[!] No groups associated");
+                options.write_line("[!] No groups associated")?;
             }
         }
 
+        if show_cves {
+            print_cves(options, &cve_ids, &req_client)?;
+        }
+
         return Ok(());
     }
 
     fn handle_group_cmd(
         &self,
+        options: &GlobalOptions,
         id: &str,
         show_software: bool,
         show_techniques: bool,
+        by_tactic: bool,
         req_client: impl WebFetch,
     ) -> Result<(), crate::error::Error> {
-        let group_info = groups::fetch_group(id, &req_client)?;
+        let mut group_info = cached_group(id, &req_client)?;
+
+        if let (Some(software), Some(technique_names)) = (
+            group_info.software.as_mut(),
+            Cache::open()?.listing_names(EntityKind::TECHNIQUE)?,
+        ) {
+            software.resolve_techniques(&technique_names);
+        }
+
+        if options.output == OutputFormat::LAYER {
+            return write_layer(
+                options,
+                &group_info.id,
+                &group_info.name,
+                &group_info.desc,
+                group_info.techniques.as_ref(),
+            );
+        }
+
+        let techniques_by_tactic = match (&group_info.techniques, by_tactic) {
+            (Some(techniques), true) => Some(techniques.by_tactic(
+                &AttackListCommand::technique_tactics(options, true, &req_client)?,
+            )),
+            _ => None,
+        };
+
+        if options.is_json() {
+            let mut group_json = serde_json::to_value(&group_info)?;
+
+            if let Some(techniques_by_tactic) = &techniques_by_tactic {
+                group_json["techniques_by_tactic"] = serde_json::to_value(techniques_by_tactic)?;
+            }
 
-        println!("[*] Group ID: {}", group_info.id);
-        println!("[*] Group name: {}", group_info.name);
-        println!("[*] Group description: {}", group_info.desc);
+            return self.print_json(options, &group_json);
+        }
+
+        options.write_line(&format!("[*] Group ID: {}", group_info.id))?;
+        options.write_line(&format!("[*] Group name: {}", group_info.name))?;
+        options.write_line(&format!(
+            "[*] Group description: {}",
+            options.description(&group_info.desc)
+        ))?;
+        print_references(options, &group_info.references)?;
 
         if let Some(assoc_groups) = group_info.assoc_groups {
-            println!("[*] Associated groups: {}", assoc_groups.join(", "));
+            options.write_line(&format!(
+                "[*] Associated groups: {}",
+                assoc_groups.join(", ")
+            ))?;
         }
 
-        if show_techniques {
+        if let Some(techniques_by_tactic) = techniques_by_tactic {
+            for tactic in techniques_by_tactic {
+                if tactic.tactic_id.is_empty() {
+                    options.write_line(&format!(
+                        "\n[*] Techniques under none of the {} tactics\n",
+                        options.domain()?
+                    ))?;
+                } else {
+                    options.write_line(&format!(
+                        "\n[*] {} - {}\n",
+                        tactic.tactic_id, tactic.tactic_name
+                    ))?;
+                }
+
+                options.print(tactic.techniques)?;
+            }
+        } else if show_techniques || by_tactic {
             if let Some(techniques) = group_info.techniques {
-                let techniques: comfy_table::Table = techniques.into();
-                println!("{}", techniques);
+                options.print(techniques)?;
             } else {
-                println!("[!] No techniques associated");
+                options.write_line("[!] No techniques associated")?;
             }
         }
 
         if show_software {
             if let Some(software) = group_info.software {
-                let software: comfy_table::Table = software.into();
-                println!("{}", software);
+                options.print(software)?;
             } else {
-                println!("[!] No software associated");
+                options.write_line("[!] No software associated")?;
             }
         }
 
@@ -289,32 +889,61 @@ impl AttackDescribeCommand {
 
     fn handle_data_source_cmd(
         &self,
+        options: &GlobalOptions,
         id: &str,
         show_components: bool,
         req_client: impl WebFetch,
     ) -> Result<(), crate::error::Error> {
-        let data_source = data_sources::fetch_data_source(id, &req_client)?;
+        let data_source: data_sources::DataSource =
+            Cache::open()?.entity_or_fetch(EntityKind::DATASOURCE, id, || {
+                data_sources::fetch_data_source(id, &req_client)
+            })?;
+
+        if options.is_json() {
+            return self.print_json(options, &data_source);
+        }
+
+        options.write_line(&format!("[*] Data Source ID: {}", data_source.id))?;
+        options.write_line(&format!("[*] Data Source name: {}", data_source.name))?;
+        options.write_line(&format!(
+            "[*] Data Source description: {}",
+            options.description(&data_source.description)
+        ))?;
+        print_references(options, &data_source.references)?;
+
+        if !data_source.platforms.is_empty() {
+            options.write_line(&format!(
+                "[*] Platforms: {}",
+                data_source.platforms.join(", ")
+            ))?;
+        }
 
-        println!("[*] Data Source ID: {}", data_source.id);
-        println!("[*] Data Source name: {}", data_source.name);
-        println!("[*] Data Source description: {}", data_source.description);
+        if !data_source.collection_layers.is_empty() {
+            options.write_line(&format!(
+                "[*] Collection layers: {}",
+                data_source.collection_layers.join(", ")
+            ))?;
+        }
 
         if show_components {
-            println!("\nData components\n");
-    
+            options.write_line("\nData components\n")?;
+
             for (inx, component) in data_source.components.into_iter().enumerate() {
-                println!("[*] Component No.{} name: {}", inx + 1, component.name);
-                println!(
+                options.write_line(&format!(
+                    "[*] Component No.{} name: {}",
+                    inx + 1,
+                    component.name
+                ))?;
+                options.write_line(&format!(
                     "[*] Component No.{} description: {}",
                     inx + 1,
-                    component.description
-                );
-    
+                    options.description(&component.description)
+                ))?;
+
                 if component.detections.is_empty() {
-                    println!("[!] No detections found.");
+                    options.write_line("[!] No detections found.")?;
                 } else {
-                    let detections: comfy_table::Table = component.detections.into();
-                    println!("{}", detections);
+                    options.print(component.detections)?;
                 }
             }
         }
@@ -323,77 +952,2967 @@ impl AttackDescribeCommand {
     }
 }
 
-#[derive(StructOpt)]
-#[structopt(no_version)]
+#[derive(Subcommand)]
 pub enum AttackListCommand {
     /// Mitre ATT&CK tactics
-    Tactics {
-        /// Tactics of the specified domain (enterprise, ics, mobile)
-        #[structopt(long)]
-        domain: String
-    },
+    Tactics,
     /// Mitre ATT&CK techniques
     Techniques {
-        /// Techniques associated to the specified domain (enterprise, ics, mobile)
-        #[structopt(long)]
-        domain: String
+        /// Group the techniques into sections per tactic of the specified domain
+        #[arg(long)]
+        by_tactic: bool,
+
+        /// Print only the technique IDs, one per line
+        #[arg(long)]
+        ids_only: bool,
+
+        /// Fetch the tactics of the domain missing from the cache to fill the tactics column
+        #[arg(long)]
+        with_tactics: bool,
+
+        /// List the deprecated and revoked techniques too, flagged as such
+        #[arg(long)]
+        include_deprecated: bool,
+
+        /// List only the techniques of the platforms (e.g. windows or linux,macos), fetching
+        /// the techniques missing from the cache
+        #[arg(long, value_delimiter = ',')]
+        platform: Vec<String>,
+
+        /// List only the techniques of the tactics, given by ID or name (e.g. TA0003 or
+        /// persistence)
+        #[arg(long, value_delimiter = ',')]
+        tactic: Vec<String>,
+
+        /// Render the techniques as a tree, their sub-techniques indented under them (table
+        /// output only)
+        #[arg(long)]
+        tree: bool,
     },
     /// Mitre ATT&CK mitigations
-    Mitigations {
-        /// Domain-specific mitre mitigations
-        #[structopt(long)]
-        domain: String
-    },
+    Mitigations,
     /// Mitre ATT&CK software
-    Software,
+    Software {
+        /// List only the software of the type, read from the listing or the cached software
+        #[arg(long = "type", value_parser = ["malware", "tool"])]
+        software_type: Option<String>,
+    },
     /// Mitre ATT&CK groups
-    Groups,
+    Groups {
+        /// List only the groups whose description (the cached one too) contains the keyword,
+        /// case insensitive (e.g. a targeted sector or region)
+        #[arg(long)]
+        filter_desc: Option<String>,
+    },
     /// Mitre ATT&CK data sources
     DataSources,
 }
 
 impl AttackListCommand {
-    fn handle(self, req_client: impl WebFetch) -> Result<(), crate::error::Error> {
-        let entity_table: comfy_table::Table = match self {
-            AttackListCommand::Tactics { domain } => {
-                tactics::fetch_tactics(tactics::Domain::from_str(&domain)?, &req_client)?.into()
+    fn handle(
+        self,
+        options: &GlobalOptions,
+        query: &ListingQuery,
+        count: bool,
+        req_client: impl WebFetch,
+    ) -> Result<(), crate::error::Error> {
+        if matches!(self, AttackListCommand::Techniques { tree: true, .. })
+            && options.output != OutputFormat::TABLE
+        {
+            return Err(crate::error::Error::InvalidValue(String::from(
+                "--tree is only supported with --output table",
+            )));
+        }
+
+        let cache = Cache::open()?;
+
+        match self {
+            AttackListCommand::Tactics => {
+                let domain = options.domain()?;
+                let mut domain_tactics: tactics::TacticsTable =
+                    cache.listing_or_fetch(EntityKind::TACTIC, Some(domain), || {
+                        tactics::fetch_tactics(tactics::Domain::from_str(domain)?, &req_client)
+                    })?;
+                domain_tactics.set_technique_counts(&cache.load_entities(EntityKind::TACTIC)?);
+
+                print_listing(
+                    options,
+                    query.apply(domain_tactics)?,
+                    count,
+                    tactics::TacticsTable::len,
+                )?
             }
-            AttackListCommand::Techniques { domain } => {
-                techniques::fetch_techniques(techniques::Domain::from_str(&domain)?, &req_client)?
-                    .into()
+            AttackListCommand::Techniques {
+                by_tactic: true,
+                ids_only,
+                include_deprecated,
+                platform,
+                tactic,
+                tree,
+                ..
+            } => Self::handle_techniques_by_tactic(
+                options,
+                query,
+                count,
+                ids_only,
+                tree,
+                include_deprecated,
+                &platform,
+                &tactic,
+                req_client,
+            )?,
+            AttackListCommand::Techniques {
+                ids_only,
+                with_tactics,
+                include_deprecated,
+                platform,
+                tactic,
+                tree,
+                ..
+            } => {
+                let domain = options.domain()?;
+                let mut techniques: techniques::TechniquesTable =
+                    cache.listing_or_fetch(EntityKind::TECHNIQUE, Some(domain), || {
+                        techniques::fetch_techniques(
+                            techniques::Domain::from_str(domain)?,
+                            &req_client,
+                        )
+                    })?;
+                let domain_tactics = Self::technique_tactics(
+                    options,
+                    with_tactics || !tactic.is_empty(),
+                    &req_client,
+                )?;
+                techniques.set_tactics(&domain_tactics);
+
+                if !tactic.is_empty() {
+                    techniques = techniques.in_tactics(&selected_tactics(domain_tactics, &tactic)?);
+                }
+                let techniques = technique_status(&cache, techniques, include_deprecated)?;
+                let techniques = technique_platforms(&cache, techniques, &platform, &req_client)?;
+
+                let techniques = query.apply(techniques)?;
+
+                if count {
+                    print_count(options, techniques.ids().len())?;
+                } else if ids_only {
+                    print_technique_ids(options, techniques)?;
+                } else if tree {
+                    options.write(&techniques.tree())?;
+                } else {
+                    options.print(techniques)?;
+                }
             }
-            AttackListCommand::Mitigations { domain } => mitigations::fetch_mitigations(
-                mitigations::Domain::from_str(&domain)?,
-                &req_client,
-            )?
-            .into(),
-            AttackListCommand::Software => software::fetch_software(&req_client)?.into(),
-            AttackListCommand::Groups => groups::fetch_groups(&req_client)?.into(),
-            AttackListCommand::DataSources => data_sources::fetch_data_sources(&req_client)?.into(),
-        };
+            AttackListCommand::Mitigations => {
+                let domain = options.domain()?;
+                let domain_mitigations: mitigations::MitigationTable =
+                    cache.listing_or_fetch(EntityKind::MITIGATION, Some(domain), || {
+                        mitigations::fetch_mitigations(
+                            mitigations::Domain::from_str(domain)?,
+                            &req_client,
+                        )
+                    })?;
 
-        println!("{}", entity_table);
+                print_listing(
+                    options,
+                    query.apply(domain_mitigations)?,
+                    count,
+                    mitigations::MitigationTable::len,
+                )?
+            }
+            AttackListCommand::Software { software_type } => {
+                let mut software: software::SoftwareTable = cache
+                    .listing_or_fetch(EntityKind::SOFTWARE, None, || {
+                        software::fetch_software(&req_client)
+                    })?;
 
-        return Ok(());
-    }
-}
+                software.set_types(|id| cache.load_entity(EntityKind::SOFTWARE, id))?;
 
-#[derive(StructOpt)]
-#[structopt(no_version)]
-pub enum AttackCommand {
-    /// List Mitre ATT&CK entities.
-    List(AttackListCommand),
-    /// Retrieve ATT&CK entity information (Name, Description and associated data)
-    Describe(AttackDescribeCommand),
-}
+                if let Some(software_type) = software_type {
+                    let untyped = software.untyped_ids();
 
-impl AttackCommand {
-    pub(super) fn handle(self, req_client: impl WebFetch) -> Result<(), crate::error::Error> {
-        match self {
-            AttackCommand::List(list_cmd) => list_cmd.handle(req_client)?,
-            AttackCommand::Describe(desc_cmd) => desc_cmd.handle(req_client)?,
-        };
+                    if !untyped.is_empty() {
+                        eprintln!(
+                            "[!] {} software of unknown type left out, run 'attack sync software' to cache them",
+                            untyped.len()
+                        );
+                    }
 
-        return Ok(());
+                    software = software.of_type(&software_type);
+                }
+
+                if let Some(domain) = options.domain.as_deref() {
+                    let (domain_software, uncached) =
+                        software.in_domain(domain, |id| cache.load_entity(EntityKind::SOFTWARE, id))?;
+                    print_uncached_domain_filter("software", &uncached);
+                    software = domain_software;
+                }
+
+                print_listing(
+                    options,
+                    query.apply(software)?,
+                    count,
+                    software::SoftwareTable::len,
+                )?
+            }
+            AttackListCommand::Groups { filter_desc } => {
+                let mut groups: groups::GroupsTable =
+                    cache.listing_or_fetch(EntityKind::GROUP, None, || {
+                        groups::fetch_groups(&req_client)
+                    })?;
+
+                if let Some(domain) = options.domain.as_deref() {
+                    let (domain_groups, uncached) =
+                        groups.in_domain(domain, |id| cache.load_entity(EntityKind::GROUP, id))?;
+                    print_uncached_domain_filter("groups", &uncached);
+                    groups = domain_groups;
+                }
+
+                if let Some(keyword) = filter_desc {
+                    groups = groups.with_description(&keyword, |id| {
+                        cache.load_entity(EntityKind::GROUP, id)
+                    })?;
+                }
+
+                print_listing(
+                    options,
+                    query.apply(groups)?,
+                    count,
+                    groups::GroupsTable::len,
+                )?
+            }
+            AttackListCommand::DataSources => {
+                let data_sources: data_sources::DataSourcesTable = cache
+                    .listing_or_fetch(EntityKind::DATASOURCE, None, || {
+                        data_sources::fetch_data_sources(&req_client)
+                    })?;
+
+                print_listing(
+                    options,
+                    query.apply(data_sources)?,
+                    count,
+                    data_sources::DataSourcesTable::len,
+                )?
+            }
+        };
+
+        return Ok(());
+    }
+
+    /// Tactics used to fill the techniques tactics column: the cached ones, or those of the
+    /// domain (fetched when missing from the cache) with `with_tactics`.
+    fn technique_tactics(
+        options: &GlobalOptions,
+        with_tactics: bool,
+        req_client: &impl WebFetch,
+    ) -> Result<Vec<tactics::Tactic>, crate::error::Error> {
+        let cache = Cache::open()?;
+
+        if !with_tactics {
+            return cache.load_entities(EntityKind::TACTIC);
+        }
+
+        let domain = options.domain()?;
+        let domain_tactics: tactics::TacticsTable =
+            cache.listing_or_fetch(EntityKind::TACTIC, Some(domain), || {
+                tactics::fetch_tactics(tactics::Domain::from_str(domain)?, req_client)
+            })?;
+
+        return domain_tactics
+            .into_iter()
+            .map(|tactic_row| {
+                cache.entity_or_fetch(EntityKind::TACTIC, &tactic_row.id, || {
+                    tactics::fetch_tactic(&tactic_row.id, req_client)
+                })
+            })
+            .collect();
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn handle_techniques_by_tactic(
+        options: &GlobalOptions,
+        query: &ListingQuery,
+        count: bool,
+        ids_only: bool,
+        tree: bool,
+        include_deprecated: bool,
+        platforms: &[String],
+        tactics: &[String],
+        req_client: impl WebFetch,
+    ) -> Result<(), crate::error::Error> {
+        let cache = Cache::open()?;
+        let mut fetched_tactics = Self::technique_tactics(options, true, &req_client)?;
+
+        if !tactics.is_empty() {
+            fetched_tactics = selected_tactics(fetched_tactics, tactics)?;
+        }
+
+        for tactic in fetched_tactics.iter_mut() {
+            if let Some(technique_table) = tactic.techniques.take() {
+                let technique_table =
+                    technique_status(&cache, technique_table, include_deprecated)?;
+                let technique_table =
+                    technique_platforms(&cache, technique_table, platforms, &req_client)?;
+                tactic.techniques = Some(query.apply(technique_table)?);
+            }
+        }
+
+        if count {
+            let mut technique_ids: Vec<String> = fetched_tactics
+                .iter()
+                .flat_map(|tactic| {
+                    tactic
+                        .techniques
+                        .iter()
+                        .flat_map(|techniques| techniques.ids())
+                })
+                .collect();
+            technique_ids.sort();
+            technique_ids.dedup();
+
+            return print_count(options, technique_ids.len());
+        }
+
+        if options.is_json() {
+            return options.print_json(&fetched_tactics);
+        }
+
+        for tactic in fetched_tactics {
+            println!("\n[*] {} - {}\n", tactic.id, tactic.name);
+
+            match tactic.techniques {
+                Some(technique_table) if ids_only => print_technique_ids(options, technique_table)?,
+                Some(technique_table) if tree => options.write(&technique_table.tree())?,
+                Some(technique_table) => options.print(technique_table)?,
+                None => println!("[!] No techniques associated"),
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+/// Print the listing, or only its number of rows (`len`) with `count`.
+fn print_listing<T>(
+    options: &GlobalOptions,
+    listing: T,
+    count: bool,
+    len: impl Fn(&T) -> usize,
+) -> Result<(), crate::error::Error>
+where
+    T: serde::Serialize + Into<comfy_table::Table>,
+{
+    if count {
+        return print_count(options, len(&listing));
+    }
+
+    return options.print(listing);
+}
+
+/// Number of listed entities, printed alone for `--count`.
+fn print_count(options: &GlobalOptions, count: usize) -> Result<(), crate::error::Error> {
+    if options.is_json() {
+        return options.print_json(&serde_json::json!({ "count": count }));
+    }
+
+    return options.write(&format!("{}\n", count));
+}
+
+/// Techniques flagged with the deprecation status of the cached techniques, the deprecated
+/// and revoked ones left out unless `include_deprecated`.
+fn technique_status(
+    cache: &Cache,
+    mut techniques: techniques::TechniquesTable,
+    include_deprecated: bool,
+) -> Result<techniques::TechniquesTable, crate::error::Error> {
+    techniques.set_status(|id| cache.load_entity(EntityKind::TECHNIQUE, id))?;
+
+    if include_deprecated {
+        return Ok(techniques);
+    }
+
+    return Ok(techniques.without_deprecated());
+}
+
+/// Tactics among `tactics` selected by ID or name, in the order of `tactics`.
+fn selected_tactics(
+    tactics: Vec<tactics::Tactic>,
+    selection: &[String],
+) -> Result<Vec<tactics::Tactic>, crate::error::Error> {
+    let tactic_names: Vec<(String, String)> = tactics
+        .iter()
+        .map(|tactic| (tactic.id.clone(), tactic.name.clone()))
+        .collect();
+    let mut selected_ids = Vec::new();
+
+    for tactic in selection {
+        let tactic_id = match EntityKind::from_id(tactic) {
+            Some(EntityKind::TACTIC) => tactic.to_uppercase(),
+            _ => names::resolve_name(&tactic_names, tactic)?,
+        };
+
+        if !tactic_names.iter().any(|(id, _)| *id == tactic_id) {
+            return Err(crate::error::Error::InvalidValue(format!(
+                "No tactic {} in the domain",
+                tactic_id
+            )));
+        }
+
+        selected_ids.push(tactic_id);
+    }
+
+    return Ok(tactics
+        .into_iter()
+        .filter(|tactic| selected_ids.contains(&tactic.id))
+        .collect());
+}
+
+/// Techniques running on any of the `platforms`, all of them if none is given. The platforms
+/// of the techniques missing from the cache are fetched, those of the sub-techniques are only
+/// looked up in the cache.
+fn technique_platforms(
+    cache: &Cache,
+    techniques: techniques::TechniquesTable,
+    platforms: &[String],
+    req_client: &impl WebFetch,
+) -> Result<techniques::TechniquesTable, crate::error::Error> {
+    if platforms.is_empty() {
+        return Ok(techniques);
+    }
+
+    return techniques.with_platforms(platforms, |id| {
+        if techniques::parent_id(id).is_some() {
+            return cache.load_entity(EntityKind::TECHNIQUE, id);
+        }
+
+        return cache
+            .entity_or_fetch(EntityKind::TECHNIQUE, id, || {
+                techniques::fetch_technique(id, req_client).map(|technique| {
+                    techniques::TechniquePlatforms {
+                        platforms: technique.platforms,
+                    }
+                })
+            })
+            .map(Some);
+    });
+}
+
+/// Progress line printed for every processed entity of a sync.
+fn progress_printer(total: usize) -> impl FnMut(&str, &Result<(), crate::error::Error>) {
+    let mut processed = 0;
+
+    return move |id, result| {
+        processed += 1;
+
+        match result {
+            Ok(()) => {
+                log::info!("Synced {}", id.to_uppercase());
+                println!("[*] ({}/{}) {}", processed, total, id.to_uppercase())
+            }
+            Err(err) => {
+                log::warn!("Unable to sync {}: {}", id.to_uppercase(), err);
+                println!(
+                    "[!] ({}/{}) {}: {}",
+                    processed,
+                    total,
+                    id.to_uppercase(),
+                    err
+                )
+            }
+        }
+    };
+}
+
+/// Cached entities an incremental sync kept, their version being unchanged.
+fn print_unchanged(summary: &sync::SyncSummary) {
+    if !summary.unchanged.is_empty() {
+        println!(
+            "[*] Kept {} cached entities whose version did not change",
+            summary.unchanged.len()
+        );
+    }
+}
+
+/// IDs to sync with --incremental, those synced from the current ATT&CK release left out.
+fn incremental_ids(
+    kind: EntityKind,
+    ids: Vec<String>,
+    cache: &Cache,
+) -> Result<Vec<String>, crate::error::Error> {
+    let outdated_ids = sync::outdated_ids(kind, &ids, cache)?;
+
+    if outdated_ids.len() < ids.len() {
+        println!(
+            "[*] Skipped {} entities unchanged since ATT&CK {}",
+            ids.len() - outdated_ids.len(),
+            cache.attack_version()?.unwrap_or_default()
+        );
+    }
+
+    return Ok(outdated_ids);
+}
+
+/// Entities added and updated by a sync, by entity type.
+fn print_sync_changes(changes: &[(String, sync::CacheChanges)]) {
+    println!("[*] Changes");
+
+    for (kind_name, kind_changes) in changes {
+        if kind_changes.is_empty() {
+            println!("[*]   {}: unchanged", kind_name);
+            continue;
+        }
+
+        println!(
+            "[*]   {}: {} added, {} updated",
+            kind_name,
+            kind_changes.added.len(),
+            kind_changes.updated.len()
+        );
+
+        if !kind_changes.added.is_empty() {
+            println!("[*]     Added: {}", kind_changes.added.join(", "));
+        }
+
+        if !kind_changes.updated.is_empty() {
+            println!("[*]     Updated: {}", kind_changes.updated.join(", "));
+        }
+    }
+}
+
+/// Record the sync in the manifest of the cache.
+fn save_sync_manifest(cache: &Cache) -> Result<(), crate::error::Error> {
+    let synced_at = chrono::Utc::now()
+        .format("%Y-%m-%dT%H:%M:%S%.3fZ")
+        .to_string();
+
+    return cache.save_manifest(&SyncManifest::from_cache(cache, &synced_at)?);
+}
+
+/// CVEs mentioned in the description, procedure examples and references of the technique.
+fn technique_cve_ids(technique: &techniques::Technique) -> Vec<String> {
+    return nvd::extract_cve_ids(
+        std::iter::once(technique.description.as_str())
+            .chain(technique.procedures.iter().flat_map(|procedures| {
+                procedures
+                    .0
+                    .iter()
+                    .map(|procedure| procedure.description.as_str())
+            }))
+            .chain(
+                technique
+                    .references
+                    .iter()
+                    .flat_map(|reference| [reference.description.as_str(), reference.url.as_str()]),
+            ),
+    );
+}
+
+/// CVEs mentioned in the description, technique usages and references of the software.
+fn software_cve_ids(software_info: &software::Software) -> Vec<String> {
+    return nvd::extract_cve_ids(
+        std::iter::once(software_info.desc.as_str())
+            .chain(software_info.techniques.iter().flat_map(|techniques| {
+                techniques.0.iter().flat_map(|technique| {
+                    std::iter::once(technique.used_for.as_str()).chain(
+                        technique
+                            .sub_techniques
+                            .iter()
+                            .flatten()
+                            .map(|sub_technique| sub_technique.used_for.as_str()),
+                    )
+                })
+            }))
+            .chain(
+                software_info
+                    .references
+                    .iter()
+                    .flat_map(|reference| [reference.description.as_str(), reference.url.as_str()]),
+            ),
+    );
+}
+
+/// Domain of a described entity cached without one, `--domain` being the fallback when the
+/// cache cannot tell.
+fn entity_domain(
+    options: &GlobalOptions,
+    cache: &Cache,
+    kind: EntityKind,
+    id: &str,
+) -> Result<Option<String>, crate::error::Error> {
+    return Ok(cache
+        .entity_domain(kind, id)?
+        .or_else(|| options.domain.clone()));
+}
+
+fn print_domain(
+    options: &GlobalOptions,
+    domain: &Option<String>,
+) -> Result<(), crate::error::Error> {
+    if let Some(domain) = domain {
+        options.write_line(&format!("[*] Domain: {}", domain))?;
+    }
+
+    return Ok(());
+}
+
+/// References sourcing the `[n]` citations kept by --keep-citations.
+fn print_references(
+    options: &GlobalOptions,
+    references: &[crate::attack::Reference],
+) -> Result<(), crate::error::Error> {
+    if !crate::keeps_citations() || references.is_empty() {
+        return Ok(());
+    }
+
+    options.write_line("[*] References:")?;
+
+    for reference in references {
+        match reference.number {
+            Some(number) => options.write_line(&format!(
+                "[*]   [{}] {} {}",
+                number, reference.description, reference.url
+            ))?,
+            None => options.write_line(&format!(
+                "[*]   {} {}",
+                reference.description, reference.url
+            ))?,
+        }
+    }
+
+    return Ok(());
+}
+
+/// Group from the local cache, fetched when it has not been synced.
+fn cached_group(
+    id: &str,
+    req_client: &impl WebFetch,
+) -> Result<groups::Group, crate::error::Error> {
+    return Cache::open()?.entity_or_fetch(EntityKind::GROUP, id, || {
+        groups::fetch_group(id, req_client)
+    });
+}
+
+/// Write the Navigator layer of the techniques of a group or software, for the domain of
+/// `--domain` or else of its first technique.
+fn write_layer(
+    options: &GlobalOptions,
+    id: &str,
+    name: &str,
+    description: &str,
+    techniques: Option<&techniques::domain::DomainTechniquesTable>,
+) -> Result<(), crate::error::Error> {
+    let empty_techniques = techniques::domain::DomainTechniquesTable::default();
+    let techniques = techniques.unwrap_or(&empty_techniques);
+    let domain = match &options.domain {
+        Some(domain) => domain.clone(),
+        None => techniques.0.first().map_or_else(
+            || String::from("enterprise"),
+            |technique| technique.domain.to_lowercase(),
+        ),
+    };
+    let layer = layer::Layer::from_techniques(
+        &format!("{} ({})", name, id),
+        description,
+        &domain,
+        techniques,
+        Cache::open()?.attack_version()?.as_deref(),
+    )?;
+
+    return options.write(&format!("{}\n", serde_json::to_string_pretty(&layer)?));
+}
+
+fn fetch_cves(
+    cve_ids: &[String],
+    req_client: &impl WebFetch,
+) -> Result<nvd::CvesTable, crate::error::Error> {
+    return nvd::fetch_cves(cve_ids, nvd::api_key_from_env().as_deref(), req_client);
+}
+
+fn print_cves(
+    options: &GlobalOptions,
+    cve_ids: &[String],
+    req_client: &impl WebFetch,
+) -> Result<(), crate::error::Error> {
+    if cve_ids.is_empty() {
+        return options.write_line("[!] No CVEs referenced");
+    }
+
+    return options.print(fetch_cves(cve_ids, req_client)?);
+}
+
+/// IDs listed in `reader`, separated by whitespace or commas, in order and without
+/// duplicates. The text after a # is ignored.
+fn read_ids(reader: impl std::io::BufRead) -> Result<Vec<String>, crate::error::Error> {
+    let mut ids: Vec<String> = Vec::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or_default();
+
+        for id in line.split([' ', '\t', ',']).filter(|id| !id.is_empty()) {
+            if !ids.iter().any(|known_id| known_id.eq_ignore_ascii_case(id)) {
+                ids.push(id.to_string());
+            }
+        }
+    }
+
+    return Ok(ids);
+}
+
+fn print_technique_ids(
+    options: &GlobalOptions,
+    technique_table: techniques::TechniquesTable,
+) -> Result<(), crate::error::Error> {
+    if options.is_json() {
+        return options.print_json(&technique_table.ids());
+    }
+
+    for technique_id in technique_table.ids() {
+        options.write(&format!("{}\n", technique_id))?;
+    }
+
+    return Ok(());
+}
+
+#[derive(Subcommand)]
+pub enum AttackAnnotateCommand {
+    /// Record the local implementation status of an ATT&CK mitigation
+    Mitigation {
+        /// Mitigation ID
+        id: String,
+
+        /// Implementation status (implemented, planned, na)
+        #[arg(long)]
+        status: String,
+    },
+    /// Attach a free text note to any ATT&CK entity
+    Note {
+        /// Entity ID (e.g. T1190, G0018)
+        id: String,
+
+        /// Note text
+        note: String,
+    },
+}
+
+impl AttackAnnotateCommand {
+    fn handle(self) -> Result<(), crate::error::Error> {
+        match self {
+            AttackAnnotateCommand::Mitigation { id, status } => {
+                let status = ImplementationStatus::from_str(&status)?;
+                let mut annotations = Annotations::load()?;
+
+                annotations.set_mitigation_status(&id, status);
+                annotations.save()?;
+
+                let status: &str = status.into();
+                println!("[*] Mitigation {} marked as {}", id.to_uppercase(), status);
+            }
+            AttackAnnotateCommand::Note { id, note } => {
+                let mut annotations = Annotations::load()?;
+
+                annotations.add_note(&id, &note);
+                annotations.save()?;
+
+                println!("[*] Note added to {}", id.to_uppercase());
+            }
+        };
+
+        return Ok(());
+    }
+}
+
+#[derive(Subcommand)]
+pub enum AttackReportCommand {
+    /// Technique exposure weighted by the unimplemented mitigations of the domain
+    Controls,
+}
+
+impl AttackReportCommand {
+    fn handle(
+        self,
+        options: &GlobalOptions,
+        req_client: impl WebFetch,
+    ) -> Result<(), crate::error::Error> {
+        match self {
+            AttackReportCommand::Controls => {
+                let annotations = Annotations::load()?;
+                let cache = Cache::open()?;
+                let domain = options.domain()?;
+                let domain_mitigations: mitigations::MitigationTable =
+                    cache.listing_or_fetch(EntityKind::MITIGATION, Some(domain), || {
+                        mitigations::fetch_mitigations(
+                            mitigations::Domain::from_str(domain)?,
+                            &req_client,
+                        )
+                    })?;
+                let mut fetched_mitigations = Vec::with_capacity(domain_mitigations.len());
+                let (mut implemented, mut planned, mut not_applicable) = (0, 0, 0);
+
+                for mitigation_row in domain_mitigations {
+                    match annotations.mitigation_status(&mitigation_row.id) {
+                        Some(ImplementationStatus::IMPLEMENTED) => implemented += 1,
+                        Some(ImplementationStatus::PLANNED) => planned += 1,
+                        Some(ImplementationStatus::NA) => not_applicable += 1,
+                        None => {}
+                    }
+
+                    fetched_mitigations.push(cache.entity_or_fetch(
+                        EntityKind::MITIGATION,
+                        &mitigation_row.id,
+                        || mitigations::fetch_mitigation(&mitigation_row.id, &req_client),
+                    )?);
+                }
+
+                let report = controls::build_controls_report(&fetched_mitigations, &annotations);
+
+                if options.is_json() {
+                    return options.print_json(&report);
+                }
+
+                println!(
+                    "[*] Mitigations: {} total, {} implemented, {} planned, {} not applicable",
+                    fetched_mitigations.len(),
+                    implemented,
+                    planned,
+                    not_applicable
+                );
+                println!(
+                    "[*] Techniques addressed by the mitigations: {}",
+                    report.len()
+                );
+
+                options.print(report)?;
+            }
+        };
+
+        return Ok(());
+    }
+}
+
+#[derive(Subcommand)]
+pub enum AttackCacheCommand {
+    /// IDs of the cached listing without cached details, one per line
+    Missing {
+        /// Entity type (tactics, techniques, mitigations, software, groups, data-sources)
+        kind: String,
+    },
+    /// Cached sub-techniques whose parent technique is missing or no longer lists them
+    Check {
+        /// Sync the missing parent techniques
+        #[arg(long)]
+        fetch_parents: bool,
+
+        /// Remove the inconsistent sub-technique files
+        #[arg(long)]
+        prune: bool,
+    },
+    /// Manifest of the last sync: ATT&CK release, sync time, entity counts and tool version
+    Stats,
+    /// Cached listings and entities that fail to load, e.g. truncated by an interrupted write
+    Verify {
+        /// Re-fetch the corrupt listings and entities
+        #[arg(long)]
+        repair: bool,
+    },
+    /// Cached files not matching the current models, written by an older release
+    Validate {
+        /// Rewrite the outdated files with the current fields
+        #[arg(long)]
+        migrate: bool,
+    },
+    /// Write the whole cache into a single .tar.zst archive, to import on isolated machines
+    #[cfg(feature = "archive")]
+    Export {
+        /// Output file of the archive (e.g. attack-cache.tar.zst)
+        archive: String,
+    },
+    /// Replace the cache with an archive written by 'attack cache export'
+    #[cfg(feature = "archive")]
+    Import {
+        /// Archive file (e.g. attack-cache.tar.zst)
+        file: String,
+    },
+}
+
+impl AttackCacheCommand {
+    fn handle(
+        self,
+        options: &GlobalOptions,
+        req_client: impl WebFetch,
+    ) -> Result<(), crate::error::Error> {
+        match self {
+            AttackCacheCommand::Missing { kind } => {
+                let missing_ids = Cache::open()?
+                    .missing_ids(EntityKind::from_str(&kind)?, options.domain.as_deref())?;
+
+                if options.is_json() {
+                    return options.print_json(&missing_ids);
+                }
+
+                for id in missing_ids {
+                    options.write(&format!("{}\n", id))?;
+                }
+            }
+            AttackCacheCommand::Check {
+                fetch_parents,
+                prune,
+            } => {
+                let cache = Cache::open()?;
+                let mut orphans = consistency::find_orphans(&cache)?;
+
+                if fetch_parents {
+                    let parent_ids = orphans.missing_parents();
+                    let summary = sync::sync_entities(
+                        EntityKind::TECHNIQUE,
+                        &parent_ids,
+                        &req_client,
+                        &cache,
+                        progress_printer(parent_ids.len()),
+                    );
+                    println!(
+                        "[*] Synced {} parent techniques, {} failed",
+                        summary.synced.len(),
+                        summary.failed.len()
+                    );
+                    orphans = consistency::find_orphans(&cache)?;
+                }
+
+                if prune {
+                    let pruned = consistency::prune_orphans(&cache, &orphans)?;
+                    println!("[*] Pruned {} sub-technique files", pruned);
+
+                    return Ok(());
+                }
+
+                if orphans.is_empty() && !options.is_json() {
+                    println!("[*] The cached sub-techniques are consistent");
+                } else {
+                    options.print(orphans)?;
+                }
+            }
+            AttackCacheCommand::Verify { repair } => {
+                let cache = Cache::open()?;
+                let corrupt_files = consistency::find_corrupt_files(&cache)?;
+
+                if repair && !corrupt_files.is_empty() {
+                    let summary = consistency::repair_corrupt_files(
+                        &cache,
+                        &corrupt_files,
+                        &req_client,
+                        |_, _| {},
+                    )?;
+
+                    for (name, err) in &summary.failed {
+                        eprintln!("[!] {}: {}", name, err);
+                    }
+
+                    println!(
+                        "[*] Repaired {} of {} corrupt files",
+                        corrupt_files.0.len() - summary.failed.len(),
+                        corrupt_files.0.len()
+                    );
+
+                    return Ok(());
+                }
+
+                if corrupt_files.is_empty() && !options.is_json() {
+                    println!("[*] The cached files are valid");
+                } else {
+                    options.print(corrupt_files)?;
+                }
+            }
+            AttackCacheCommand::Validate { migrate } => {
+                let issues = schema::validate_cache(&Cache::open()?, migrate)?;
+
+                if migrate {
+                    println!(
+                        "[*] Migrated {} outdated files, run 'attack update' to fill their new fields",
+                        issues.count(schema::SchemaStatus::OUTDATED)
+                    );
+
+                    let incompatible = issues.count(schema::SchemaStatus::INCOMPATIBLE);
+
+                    if incompatible > 0 {
+                        println!(
+                            "[!] {} incompatible files left, run 'attack cache verify --repair' to re-fetch them",
+                            incompatible
+                        );
+                    }
+
+                    return Ok(());
+                }
+
+                if issues.is_empty() && !options.is_json() {
+                    println!("[*] The cached files match the current models");
+                } else {
+                    options.print(issues)?;
+                }
+            }
+            AttackCacheCommand::Stats => match Cache::open()?.manifest()? {
+                Some(manifest) => options.print(manifest)?,
+                None => {
+                    return Err(crate::error::Error::General(String::from(
+                        "No sync manifest in the local cache, run 'attack sync' first",
+                    )))
+                }
+            },
+            #[cfg(feature = "archive")]
+            AttackCacheCommand::Export { archive: archive_path } => {
+                let archived =
+                    archive::export_cache(&Cache::open()?, std::path::Path::new(&archive_path))?;
+                println!("[*] Exported {} cached files to {}", archived, archive_path);
+            }
+            #[cfg(feature = "archive")]
+            AttackCacheCommand::Import { file } => {
+                let cache = Cache::open()?;
+                let extracted = archive::import_cache(&cache, std::path::Path::new(&file))?;
+                println!(
+                    "[*] Imported {} cached files into {}",
+                    extracted,
+                    cache.root().display()
+                );
+
+                if let Some(manifest) = cache.manifest()? {
+                    options.print(manifest)?;
+                }
+            }
+        };
+
+        return Ok(());
+    }
+}
+
+#[derive(Subcommand)]
+pub enum AttackImportCommand {
+    /// Official ATT&CK STIX bundles (e.g. enterprise-attack.json of the attack-stix-data
+    /// repository), without scraping the website
+    Stix {
+        /// Bundle files, one per domain
+        #[arg(required = true)]
+        files: Vec<String>,
+    },
+}
+
+impl AttackImportCommand {
+    fn handle(self) -> Result<(), crate::error::Error> {
+        match self {
+            AttackImportCommand::Stix { files } => {
+                let bundles = files
+                    .iter()
+                    .map(|file| {
+                        let content = std::fs::read_to_string(file)?;
+
+                        return serde_json::from_str(&content).map_err(|err| {
+                            crate::error::Error::Parse(format!("Unable to parse {}: {}", file, err))
+                        });
+                    })
+                    .collect::<Result<Vec<serde_json::Value>, crate::error::Error>>()?;
+                let dataset = stix_import::dataset_from_bundles(&bundles)?;
+                let cache = Cache::open()?;
+
+                let installed = dataset.install(&cache)?;
+                save_sync_manifest(&cache)?;
+                println!(
+                    "[*] Imported {} entities of ATT&CK {} into {}",
+                    installed,
+                    if dataset.release.is_empty() {
+                        "(unknown release)"
+                    } else {
+                        dataset.release.as_str()
+                    },
+                    cache.root().display()
+                );
+            }
+        };
+
+        return Ok(());
+    }
+}
+
+#[cfg(feature = "fulltext")]
+#[derive(Subcommand)]
+pub enum AttackIndexCommand {
+    /// (Re)build the full-text index of the cached descriptions, procedures and detections,
+    /// queried by 'attack search text'
+    Build,
+}
+
+#[cfg(feature = "fulltext")]
+impl AttackIndexCommand {
+    fn handle(self) -> Result<(), crate::error::Error> {
+        match self {
+            AttackIndexCommand::Build => {
+                let cache = Cache::open()?;
+                let snapshot = cache.snapshot()?;
+
+                if snapshot.is_empty() {
+                    return Err(crate::error::Error::General(String::from(
+                        "The local cache is empty, run 'attack sync' first",
+                    )));
+                }
+
+                let index = FulltextIndex::for_cache(&cache);
+                let indexed = index.build(&snapshot)?;
+                println!(
+                    "[*] Indexed {} texts into {}",
+                    indexed,
+                    index.root().display()
+                );
+            }
+        };
+
+        return Ok(());
+    }
+}
+
+#[derive(Subcommand)]
+pub enum AttackExportCommand {
+    /// STIX 2.1 bundle following the OpenCTI import conventions, written to --out
+    Opencti {
+        /// Write one STIX object per line (NDJSON) instead of a bundle, for connectors
+        #[arg(long)]
+        ndjson: bool,
+    },
+    /// STIX 2.1 bundle of the cached entities, tactics and data sources included, written to
+    /// --out
+    Stix,
+    /// Prebuilt dataset of the cached listings and entities, installable with 'attack
+    /// fetch-dataset', written to --out
+    Dataset {
+        /// ATT&CK release the cache was synced from (e.g. v15.1)
+        #[arg(long)]
+        release: String,
+    },
+    /// Graphviz DOT graph of the entities and their relationships, written to --out or STDOUT
+    Dot {
+        /// Only keep the footprint of the given entity (e.g. a group ID)
+        #[arg(long)]
+        root: Option<String>,
+    },
+    /// CSV matrix of the cached groups (rows) by the techniques they use (columns), of the
+    /// --domain techniques when given, written to --out or STDOUT
+    Matrix {
+        /// Fill the cells with the procedures of the groups instead of 1/0
+        #[arg(long)]
+        procedures: bool,
+    },
+    /// SQLite database of the cached entities and their relationships, for ad-hoc SQL queries
+    #[cfg(feature = "sqlite")]
+    Sqlite {
+        /// Output file of the database (e.g. attack.db), replaced when it exists
+        database: String,
+    },
+    /// Obsidian vault with one Markdown note per cached entity, local annotations included
+    Vault {
+        /// Output directory of the notes
+        #[arg(long)]
+        out_dir: String,
+    },
+    /// CALDERA adversary profile (YAML) emulating a group or a custom set of techniques, written
+    /// to --out or STDOUT
+    Caldera {
+        /// Emulate the techniques used by this group
+        #[arg(long, conflicts_with = "techniques", required_unless_present = "techniques")]
+        group: Option<String>,
+
+        /// Emulate the given technique IDs
+        #[arg(long, num_args = 1..)]
+        techniques: Vec<String>,
+
+        /// Profile name, the group name by default
+        #[arg(long)]
+        name: Option<String>,
+
+        /// CALDERA abilities (directory of ability files, or JSON from GET /api/v2/abilities)
+        /// used to resolve the ability IDs
+        #[arg(long)]
+        abilities: Option<String>,
+    },
+}
+
+impl AttackExportCommand {
+    fn cached_snapshot() -> Result<Snapshot, crate::error::Error> {
+        let snapshot = Cache::open()?.snapshot()?;
+
+        if snapshot.is_empty() {
+            return Err(crate::error::Error::General(String::from(
+                "The local cache is empty, run 'attack sync' first",
+            )));
+        }
+
+        return Ok(snapshot);
+    }
+
+    fn timestamp() -> String {
+        return chrono::Utc::now()
+            .format("%Y-%m-%dT%H:%M:%S%.3fZ")
+            .to_string();
+    }
+
+    fn provenance() -> Result<Provenance, crate::error::Error> {
+        return Provenance::from_cache(&Cache::open()?, &Self::timestamp());
+    }
+
+    /// Provenance as comment lines starting with `prefix`.
+    fn provenance_comments(prefix: &str) -> Result<String, crate::error::Error> {
+        return Ok(Self::provenance()?
+            .to_lines()
+            .iter()
+            .map(|line| format!("{} {}\n", prefix, line))
+            .collect());
+    }
+
+    /// `--out` file of the exports writing their own file format, which requires it.
+    fn out_file<'a>(
+        options: &'a GlobalOptions,
+        export: &str,
+    ) -> Result<&'a std::path::Path, crate::error::Error> {
+        return options.out.as_deref().ok_or_else(|| {
+            crate::error::Error::InvalidValue(format!(
+                "'attack export {}' requires --out <file>",
+                export
+            ))
+        });
+    }
+
+    fn write_bundle(
+        mut bundle: serde_json::Value,
+        out: &std::path::Path,
+    ) -> Result<(), crate::error::Error> {
+        bundle[provenance::STIX_PROPERTY] = serde_json::to_value(Self::provenance()?)?;
+        crate::storage::write_atomic(out, serde_json::to_string_pretty(&bundle)?)?;
+        println!(
+            "[*] Exported {} objects to {}",
+            bundle["objects"].as_array().map_or(0, |objects| objects.len()),
+            out.display()
+        );
+
+        return Ok(());
+    }
+
+    fn handle(
+        self,
+        options: &GlobalOptions,
+        req_client: impl WebFetch,
+    ) -> Result<(), crate::error::Error> {
+        match self {
+            AttackExportCommand::Opencti { ndjson: true } => {
+                let out = Self::out_file(options, "opencti")?;
+                let bundle =
+                    stix::build_opencti_bundle(&Self::cached_snapshot()?, &Self::timestamp());
+                let ndjson = stix::bundle_to_ndjson(&bundle)?;
+
+                crate::storage::write_atomic(out, &ndjson)?;
+                // Every line must stay a STIX object, the provenance goes next to the file.
+                crate::storage::write_atomic(
+                    std::path::Path::new(&format!("{}.provenance.json", out.display())),
+                    serde_json::to_string_pretty(&Self::provenance()?)?,
+                )?;
+                println!(
+                    "[*] Exported {} objects to {}",
+                    ndjson.lines().count(),
+                    out.display()
+                );
+            }
+            AttackExportCommand::Opencti { .. } => {
+                let out = Self::out_file(options, "opencti")?;
+                let bundle =
+                    stix::build_opencti_bundle(&Self::cached_snapshot()?, &Self::timestamp());
+                Self::write_bundle(bundle, out)?;
+            }
+            AttackExportCommand::Stix => {
+                let out = Self::out_file(options, "stix")?;
+                let bundle = stix::build_stix_bundle(&Self::cached_snapshot()?, &Self::timestamp());
+                Self::write_bundle(bundle, out)?;
+            }
+            AttackExportCommand::Dataset { release } => {
+                let out = Self::out_file(options, "dataset")?;
+                let cache = Cache::open()?;
+                let mut dataset = dataset::Dataset::from_cache(&release, &cache)?;
+                dataset.provenance = Some(Provenance::from_dataset(
+                    &cache,
+                    &dataset,
+                    &Self::timestamp(),
+                )?);
+
+                crate::storage::write_atomic(out, serde_json::to_string(&dataset)?)?;
+                println!(
+                    "[*] Exported {} listings and {} entities to {}",
+                    dataset.listings.len(),
+                    dataset.entities.len(),
+                    out.display()
+                );
+            }
+            AttackExportCommand::Dot { root } => {
+                let graph = Self::provenance_comments("//")?
+                    + &dot::build_dot_graph(&Self::cached_snapshot()?, root.as_deref());
+
+                options.write(&graph)?;
+            }
+            AttackExportCommand::Matrix { procedures } => {
+                let snapshot = Self::cached_snapshot()?;
+
+                if snapshot.groups.is_empty() {
+                    return Err(crate::error::Error::General(String::from(
+                        "No cached group, run 'attack sync groups' first",
+                    )));
+                }
+
+                let matrix = matrix::build_group_matrix(
+                    &snapshot.groups,
+                    options.domain.as_deref(),
+                    if procedures {
+                        matrix::MatrixCell::PROCEDURE
+                    } else {
+                        matrix::MatrixCell::PRESENCE
+                    },
+                );
+
+                options.write(&matrix)?;
+            }
+            #[cfg(feature = "sqlite")]
+            AttackExportCommand::Sqlite { database } => {
+                let relationships = sqlite::export_sqlite(
+                    &Self::cached_snapshot()?,
+                    std::path::Path::new(&database),
+                )?;
+
+                println!(
+                    "[*] Exported the cached entities and {} relationships to {}",
+                    relationships, database
+                );
+            }
+            AttackExportCommand::Vault { out_dir } => {
+                let mut notes =
+                    vault::build_vault(&Self::cached_snapshot()?, &Annotations::load()?);
+                notes.insert(
+                    String::from("_Provenance.md"),
+                    format!("# Provenance\n\n{}", Self::provenance_comments("-")?),
+                );
+                let written = vault::write_vault(&notes, std::path::Path::new(&out_dir))?;
+
+                println!("[*] Exported {} notes to {}", written, out_dir);
+            }
+            AttackExportCommand::Caldera {
+                group,
+                techniques,
+                name,
+                abilities,
+            } => {
+                let (profile_name, description, technique_ids) = match group {
+                    Some(group_id) => {
+                        let group = cached_group(&group_id, &req_client)?;
+                        let technique_ids = group
+                            .techniques
+                            .as_ref()
+                            .map_or_else(Vec::new, |techniques| techniques.ids());
+
+                        (name.unwrap_or(group.name), group.desc, technique_ids)
+                    }
+                    None => (
+                        name.unwrap_or_else(|| String::from("Custom adversary")),
+                        String::new(),
+                        techniques,
+                    ),
+                };
+                let abilities = match abilities {
+                    Some(path) => caldera::load_abilities(std::path::Path::new(&path))?,
+                    None => Vec::new(),
+                };
+                let profile = caldera::AdversaryProfile::build(
+                    &profile_name,
+                    &description,
+                    &technique_ids,
+                    &abilities,
+                );
+
+                let yaml = Self::provenance_comments("#")? + &profile.to_yaml();
+
+                options.write(&yaml)?;
+
+                if options.out.is_some() {
+                    println!(
+                        "[*] Exported profile with {} abilities, {} unresolved techniques",
+                        profile.atomic_ordering.len(),
+                        profile.unresolved.len()
+                    );
+                }
+            }
+        };
+
+        return Ok(());
+    }
+}
+
+#[derive(Subcommand)]
+pub enum AttackCoverageCommand {
+    /// Techniques of a group already emulated by the CALDERA abilities
+    Caldera {
+        /// CALDERA abilities directory (e.g. plugins/stockpile/data/abilities) or JSON export
+        #[arg(long)]
+        abilities: String,
+
+        /// Group whose techniques should be emulated
+        #[arg(long)]
+        group: String,
+    },
+    /// Per-tactic coverage of the --domain techniques (enterprise by default) by the detection
+    /// rules of an inventory, with the techniques without any rule
+    Score {
+        /// CSV of the rule to technique mappings ("rule,technique" header, one technique
+        /// per line or several separated by ';')
+        #[arg(long)]
+        mapping: String,
+    },
+}
+
+impl AttackCoverageCommand {
+    fn handle(
+        self,
+        options: &GlobalOptions,
+        req_client: impl WebFetch,
+    ) -> Result<(), crate::error::Error> {
+        match self {
+            AttackCoverageCommand::Caldera { abilities, group } => {
+                let abilities = caldera::load_abilities(std::path::Path::new(&abilities))?;
+                let group = cached_group(&group, &req_client)?;
+                let technique_ids = group
+                    .techniques
+                    .as_ref()
+                    .map_or_else(Vec::new, |techniques| techniques.ids());
+                let coverage = caldera::EmulationCoverage::build(&technique_ids, &abilities);
+
+                if options.is_json() {
+                    return options.print_json(&coverage);
+                }
+
+                println!(
+                    "[*] {} ({}): {} techniques, {} emulated, {} need new abilities",
+                    group.name,
+                    group.id,
+                    coverage.len(),
+                    coverage.emulated(),
+                    coverage.len() - coverage.emulated()
+                );
+
+                if !coverage.is_empty() {
+                    options.print(coverage)?;
+                }
+            }
+            AttackCoverageCommand::Score { mapping } => {
+                let mappings = rule_coverage::RuleMappings::load(std::path::Path::new(&mapping))?;
+
+                let cache = Cache::open()?;
+                let domain = options.domain.as_deref().unwrap_or("enterprise");
+                let domain_tactics: tactics::TacticsTable =
+                    cache.listing_or_fetch(EntityKind::TACTIC, Some(domain), || {
+                        tactics::fetch_tactics(tactics::Domain::from_str(domain)?, &req_client)
+                    })?;
+                let domain_tactics = domain_tactics
+                    .into_iter()
+                    .map(|tactic_row| {
+                        cache.entity_or_fetch(EntityKind::TACTIC, &tactic_row.id, || {
+                            tactics::fetch_tactic(&tactic_row.id, &req_client)
+                        })
+                    })
+                    .collect::<Result<Vec<tactics::Tactic>, crate::error::Error>>()?;
+                let score = rule_coverage::RuleCoverageScore::build(&domain_tactics, &mappings);
+
+                if options.output == OutputFormat::LAYER {
+                    let layer = score.to_layer(domain, cache.attack_version()?.as_deref())?;
+
+                    return options.write(&format!("{}\n", serde_json::to_string_pretty(&layer)?));
+                }
+
+                if options.is_json() {
+                    return options.print_json(&score);
+                }
+
+                let (covered, total) = score.covered_techniques();
+                println!(
+                    "[*] {} of the {} {} techniques covered by {} detection rules",
+                    covered,
+                    total,
+                    domain,
+                    mappings.rule_count()
+                );
+
+                if !score.unknown.is_empty() {
+                    eprintln!(
+                        "[!] Mapped techniques outside of the {} tactics: {}",
+                        domain,
+                        score.unknown.join(", ")
+                    );
+                }
+
+                options.print(score.tactics)?;
+
+                if score.gaps.is_empty() {
+                    println!("[*] Every technique is covered by at least one rule");
+                } else {
+                    options.print(score.gaps)?;
+                }
+            }
+        };
+
+        return Ok(());
+    }
+}
+
+#[derive(Subcommand)]
+pub enum AttackScaffoldCommand {
+    /// Markdown runbook templates, pre-filled with the detection guidance, mitigations and
+    /// procedure examples of the techniques
+    Runbook {
+        /// Technique IDs
+        #[arg(long, num_args = 1.., required = true)]
+        technique: Vec<String>,
+
+        /// Output directory of the runbooks (one <ID>.md file per technique)
+        #[arg(long)]
+        out_dir: String,
+
+        /// Overwrite the existing runbooks
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+impl AttackScaffoldCommand {
+    fn handle(
+        self,
+        options: &GlobalOptions,
+        req_client: impl WebFetch,
+    ) -> Result<(), crate::error::Error> {
+        match self {
+            AttackScaffoldCommand::Runbook {
+                technique,
+                out_dir,
+                force,
+            } => {
+                let cache = Cache::open()?;
+
+                for technique_id in technique {
+                    let mut technique: techniques::Technique =
+                        cache.entity_or_fetch(EntityKind::TECHNIQUE, &technique_id, || {
+                            techniques::fetch_technique(&technique_id, &req_client)
+                        })?;
+
+                    if technique.domain.is_none() {
+                        technique.domain =
+                            entity_domain(options, &cache, EntityKind::TECHNIQUE, &technique.id)?;
+                    }
+
+                    match runbook::write_runbook(&technique, std::path::Path::new(&out_dir), force)? {
+                        Some(path) => println!("[*] Wrote {}", path.display()),
+                        None => println!(
+                            "[!] Runbook of {} already exists, skipped (--force overwrites it)",
+                            technique.id
+                        ),
+                    }
+                }
+            }
+        };
+
+        return Ok(());
+    }
+}
+
+#[derive(Subcommand)]
+pub enum AttackSearchCommand {
+    /// Every entity type (tactics, techniques, mitigations, software, groups, data sources)
+    /// of the cached listings, in one pass
+    Any {
+        /// Searched words, all of them matched (case insensitive) in the IDs, names, aliases
+        /// and descriptions
+        #[arg(required_unless_present_any = ["platform", "tactic"], num_args = 1..)]
+        term: Vec<String>,
+
+        /// Only the techniques of the platforms (e.g. macos or linux,windows), according to
+        /// the cached techniques
+        #[arg(long, value_delimiter = ',')]
+        platform: Vec<String>,
+
+        /// Only the techniques of the tactics, given by ID or name (e.g. TA0006 or
+        /// credential-access), according to the cached tactics
+        #[arg(long, value_delimiter = ',')]
+        tactic: Vec<String>,
+    },
+    /// Ranked full-text search of the cached descriptions, procedures and detections, using
+    /// the index of 'attack index build'
+    #[cfg(feature = "fulltext")]
+    Text {
+        /// Query: words (any of them), "exact phrases", +required and -excluded words
+        #[arg(required = true, num_args = 1..)]
+        query: Vec<String>,
+
+        /// Number of best matching texts shown
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+}
+
+impl AttackSearchCommand {
+    fn handle(self, options: &GlobalOptions) -> Result<(), crate::error::Error> {
+        match self {
+            AttackSearchCommand::Any {
+                term,
+                platform,
+                tactic,
+            } => {
+                let term = term.join(" ");
+                let filter = search::SearchFilter {
+                    platforms: platform,
+                    tactics: tactic,
+                };
+                let results = search::search_cache(&Cache::open()?, &term, &filter)?;
+
+                if options.is_json() {
+                    return options.print_json(&results);
+                }
+
+                if results.is_empty() {
+                    println!("[!] No entity matching '{}'", term);
+                } else {
+                    options.print(results)?;
+                }
+            }
+            #[cfg(feature = "fulltext")]
+            AttackSearchCommand::Text { query, limit } => {
+                let query = query.join(" ");
+                let matches = FulltextIndex::for_cache(&Cache::open()?).search(&query, limit)?;
+
+                if options.is_json() {
+                    return options.print_json(&matches);
+                }
+
+                if matches.is_empty() {
+                    println!("[!] No cached text matching '{}'", query);
+                } else {
+                    options.print(matches)?;
+                }
+            }
+        };
+
+        return Ok(());
+    }
+}
+
+#[derive(Subcommand)]
+pub enum AttackStatsCommand {
+    /// Techniques referenced by the most cached groups and software, as a ranked list or
+    /// (--output layer) a Navigator heatmap layer of the --domain (enterprise by default)
+    TopTechniques {
+        /// Number of techniques ranked
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+}
+
+impl AttackStatsCommand {
+    fn handle(self, options: &GlobalOptions) -> Result<(), crate::error::Error> {
+        match self {
+            AttackStatsCommand::TopTechniques { limit } => {
+                let cache = Cache::open()?;
+                let snapshot = cache.snapshot()?;
+
+                if snapshot.groups.is_empty() && snapshot.software.is_empty() {
+                    return Err(crate::error::Error::General(String::from(
+                        "No cached group or software, run 'attack sync groups' and 'attack sync software' first",
+                    )));
+                }
+
+                let top = stats::top_techniques(&snapshot, options.domain.as_deref(), limit);
+
+                if options.output == OutputFormat::LAYER {
+                    let scores: Vec<(String, u32, String)> = top
+                        .0
+                        .iter()
+                        .map(|technique| {
+                            (
+                                technique.id.clone(),
+                                technique.total() as u32,
+                                format!(
+                                    "#{}: {} groups, {} software",
+                                    technique.rank, technique.groups, technique.software
+                                ),
+                            )
+                        })
+                        .collect();
+                    let layer = layer::Layer::heatmap(
+                        &format!("Top {} techniques", limit),
+                        "Techniques referenced by the most groups and software of the local cache",
+                        options.domain.as_deref().unwrap_or("enterprise"),
+                        &scores,
+                        cache.attack_version()?.as_deref(),
+                    )?;
+
+                    return options.write(&format!("{}\n", serde_json::to_string_pretty(&layer)?));
+                }
+
+                if top.is_empty() {
+                    println!("[!] No technique referenced by the cached groups and software");
+                } else {
+                    options.print(top)?;
+                }
+            }
+        };
+
+        return Ok(());
+    }
+}
+
+#[derive(Subcommand)]
+pub enum AttackMapCommand {
+    /// Security controls addressing the listed techniques, with the techniques without any
+    /// mapped control
+    Controls {
+        /// File listing the technique IDs (one or more per line, # comments ignored)
+        #[arg(long)]
+        techniques: String,
+
+        /// Control framework (nist-800-53, cis)
+        #[arg(long, default_value = "nist-800-53")]
+        framework: String,
+    },
+    /// Download the control mappings of a framework into the local cache
+    Sync {
+        /// Control framework (nist-800-53, cis)
+        framework: String,
+
+        /// URL or file of the mappings instead of their default location, required by the
+        /// CIS Controls (CSV export of the CIS mapping to ATT&CK)
+        #[arg(long)]
+        from: Option<String>,
+    },
+}
+
+impl AttackMapCommand {
+    fn handle(
+        self,
+        options: &GlobalOptions,
+        req_client: impl WebFetch,
+    ) -> Result<(), crate::error::Error> {
+        match self {
+            AttackMapCommand::Controls {
+                techniques,
+                framework,
+            } => {
+                let framework = mappings::Framework::from_str(&framework)?;
+                let technique_ids = read_technique_list(&techniques)?;
+                let report = mappings::ControlsMappingReport::build(
+                    framework,
+                    &mappings::mappings_or_fetch(framework, &req_client)?,
+                    &technique_ids,
+                );
+
+                if options.is_json() {
+                    return options.print_json(&report);
+                }
+
+                println!(
+                    "[*] {} {} controls address {} of the {} listed techniques",
+                    report.controls.len(),
+                    report.framework,
+                    technique_ids.len() - report.unmapped.len(),
+                    technique_ids.len()
+                );
+
+                if !report.unmapped.is_empty() {
+                    println!(
+                        "[!] Techniques without any mapped control: {}",
+                        report.unmapped.join(", ")
+                    );
+                }
+
+                if !report.controls.is_empty() {
+                    options.print(report.controls)?;
+                }
+            }
+            AttackMapCommand::Sync { framework, from } => {
+                let framework = mappings::Framework::from_str(&framework)?;
+                let mappings = mappings::sync_mappings(framework, from.as_deref(), &req_client)?;
+
+                println!(
+                    "[*] {} technique mappings of the {} controls synced",
+                    mappings.len(),
+                    framework.title()
+                );
+            }
+        };
+
+        return Ok(());
+    }
+}
+
+#[derive(Subcommand)]
+pub enum AttackCompareCommand {
+    /// Techniques shared by the software and the groups using each of them
+    Software {
+        /// Software IDs (e.g. S0002 S0029)
+        #[arg(required = true, num_args = 2..)]
+        ids: Vec<String>,
+    },
+}
+
+impl AttackCompareCommand {
+    fn handle(
+        self,
+        options: &GlobalOptions,
+        req_client: impl WebFetch,
+    ) -> Result<(), crate::error::Error> {
+        match self {
+            AttackCompareCommand::Software { ids } => {
+                let cache = Cache::open()?;
+                let mut compared_software: Vec<software::Software> = Vec::new();
+
+                for id in &ids {
+                    let software_info = cache.entity_or_fetch(EntityKind::SOFTWARE, id, || {
+                        software::fetch_software_info(id, &req_client)
+                    })?;
+                    compared_software.push(software_info);
+                }
+
+                let comparison = compare::SoftwareComparison::build(&compared_software);
+
+                if options.is_json() {
+                    return options.print_json(&comparison);
+                }
+
+                for software in &comparison.software {
+                    println!(
+                        "[*] {} ({}): {} techniques, {} used by no other compared software",
+                        software.name,
+                        software.id,
+                        software.techniques,
+                        software.unique_techniques.len()
+                    );
+                }
+
+                println!("\n[*] Shared techniques\n");
+                if comparison.shared_techniques.is_empty() {
+                    println!("[!] No technique is used by all of {}", ids.join(", "));
+                } else {
+                    options.print(comparison.shared_techniques)?;
+                }
+
+                println!("\n[*] Groups using the software\n");
+                if comparison.groups.is_empty() {
+                    println!("[!] No group is known to use {}", ids.join(", "));
+                } else {
+                    options.print(comparison.groups)?;
+                }
+            }
+        };
+
+        return Ok(());
+    }
+}
+
+#[derive(Subcommand)]
+pub enum AttackPlanCommand {
+    /// Mitigations addressing the techniques (from the cached technique pages), those
+    /// covering the most techniques first
+    Mitigations {
+        /// File listing the technique IDs, separated by whitespace or commas (# comments ignored)
+        #[arg(long)]
+        techniques: String,
+    },
+    /// Data components to collect to detect the techniques (from the cached technique
+    /// pages), those detecting the most techniques first
+    Telemetry {
+        /// File listing the technique IDs, separated by whitespace or commas (# comments ignored)
+        #[arg(long)]
+        techniques: String,
+    },
+}
+
+impl AttackPlanCommand {
+    fn handle(self, options: &GlobalOptions) -> Result<(), crate::error::Error> {
+        match self {
+            AttackPlanCommand::Mitigations { techniques } => {
+                let technique_ids = read_technique_list(&techniques)?;
+                let plan = plan::MitigationPlan::build(&Cache::open()?, &technique_ids)?;
+
+                if options.is_json() {
+                    return options.print_json(&plan);
+                }
+
+                if plan.mitigations.is_empty() {
+                    println!("[!] No mitigation addresses the cached techniques");
+                } else {
+                    options.print(plan.mitigations)?;
+                }
+
+                if !plan.unmitigated.is_empty() {
+                    println!(
+                        "[!] Techniques without mitigations: {}",
+                        plan.unmitigated.join(", ")
+                    );
+                }
+
+                print_uncached_techniques(&plan.uncached);
+            }
+            AttackPlanCommand::Telemetry { techniques } => {
+                let technique_ids = read_technique_list(&techniques)?;
+                let (cached_techniques, uncached) =
+                    plan::cached_techniques(&Cache::open()?, &technique_ids)?;
+                let report = coverage::build_telemetry_report(&cached_techniques);
+
+                if options.is_json() {
+                    return options.print_json(&report);
+                }
+
+                if report.components.is_empty() {
+                    println!("[!] No data component detects the cached techniques");
+                } else {
+                    options.print(report.components)?;
+                }
+
+                if !report.undetectable.is_empty() {
+                    println!(
+                        "[!] Techniques without detection data: {}",
+                        report.undetectable.join(", ")
+                    );
+                }
+
+                print_uncached_techniques(&uncached);
+            }
+        };
+
+        return Ok(());
+    }
+}
+
+/// Technique IDs listed in the file, failing when there is none.
+fn read_technique_list(path: &str) -> Result<Vec<String>, crate::error::Error> {
+    let technique_ids = read_ids(std::io::BufReader::new(std::fs::File::open(path)?))?;
+
+    if technique_ids.is_empty() {
+        return Err(crate::error::Error::InvalidValue(format!(
+            "{} does not list any technique",
+            path
+        )));
+    }
+
+    return Ok(technique_ids);
+}
+
+/// Warn about the `kind` entities ("groups", "software") left out of the --domain filter
+/// as not cached.
+fn print_uncached_domain_filter(kind: &str, uncached: &[String]) {
+    if !uncached.is_empty() {
+        eprintln!(
+            "[!] {} {} missing from the cache left out of the --domain filter, run 'attack sync {}' first",
+            uncached.len(),
+            kind,
+            kind
+        );
+    }
+}
+
+fn print_uncached_techniques(uncached: &[String]) {
+    if !uncached.is_empty() {
+        println!(
+            "[!] Techniques missing from the cache, run 'attack sync techniques --ids {}'",
+            uncached.join(" ")
+        );
+    }
+}
+
+#[derive(Subcommand)]
+pub enum AttackCommand {
+    /// List Mitre ATT&CK entities.
+    List {
+        /// Keep the rows matching this case-insensitive substring or regex
+        #[arg(long, global = true)]
+        filter: Option<String>,
+
+        /// Sort the rows by this column
+        #[arg(long, global = true)]
+        sort_by: Option<String>,
+
+        /// Print at most this number of rows (techniques being counted with their
+        /// sub-techniques)
+        #[arg(long, global = true, conflicts_with_all = ["page", "page_size"])]
+        limit: Option<usize>,
+
+        /// Skip this number of rows first (techniques being counted with their sub-techniques)
+        #[arg(long, global = true, default_value_t = 0, conflicts_with_all = ["page", "page_size"])]
+        offset: usize,
+
+        /// Print this page of rows (starting at 1)
+        #[arg(long, global = true, value_parser = clap::value_parser!(u64).range(1..))]
+        page: Option<u64>,
+
+        /// Rows per page (50 by default, techniques being counted with their sub-techniques),
+        /// the first page is printed without --page
+        #[arg(long, global = true, value_parser = clap::value_parser!(u64).range(1..))]
+        page_size: Option<u64>,
+
+        /// Print only the number of listed entities (sub-techniques included), once filtered
+        #[arg(long, global = true)]
+        count: bool,
+
+        #[command(subcommand)]
+        list_cmd: AttackListCommand,
+    },
+    /// Retrieve ATT&CK entity information (Name, Description and associated data)
+    Describe {
+        /// Keep the [n] citations of the descriptions and list the references they point to,
+        /// fetching the entity page instead of reading the cache (which has them stripped)
+        #[arg(long, global = true)]
+        keep_citations: bool,
+
+        #[command(subcommand)]
+        describe_cmd: AttackDescribeCommand,
+    },
+    /// Annotate ATT&CK entities with local information
+    #[command(subcommand)]
+    Annotate(AttackAnnotateCommand),
+    /// Reports combining ATT&CK data with local annotations
+    #[command(subcommand)]
+    Report(AttackReportCommand),
+    /// Plan defenses from ATT&CK data
+    #[command(subcommand)]
+    Plan(AttackPlanCommand),
+    /// Search ATT&CK entities
+    #[command(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
+    Search {
+        /// Match the notes attached with 'attack annotate note' (all words, case insensitive)
+        #[arg(long)]
+        notes: Option<String>,
+
+        /// Match the keyword (case insensitive) within the descriptions, procedure examples
+        /// and detection notes of the cached entities, showing a snippet of every hit
+        #[arg(long, conflicts_with = "notes")]
+        contains: Option<String>,
+
+        #[command(subcommand)]
+        search_cmd: Option<AttackSearchCommand>,
+    },
+    /// Compare ATT&CK entities
+    #[command(subcommand)]
+    Compare(AttackCompareCommand),
+    /// Map ATT&CK techniques to other frameworks
+    #[command(subcommand)]
+    Map(AttackMapCommand),
+    /// Single-screen summary of the local cache for a domain: counts, recently modified
+    /// techniques, cache freshness and mitigation coverage
+    Dashboard,
+    /// Summary of the local cache: entities per type and domain, techniques per tactic,
+    /// sub-technique ratio, groups per technique distribution and last sync dates
+    Stats {
+        #[command(subcommand)]
+        stats_cmd: Option<AttackStatsCommand>,
+    },
+    /// Techniques of the local cache without any detection in the collected data sources
+    #[command(args_conflicts_with_subcommands = true)]
+    Coverage {
+        /// File listing the collected data sources or components, one per line
+        /// (e.g. 'DS0009', 'Process' or 'Process: Process Creation')
+        #[arg(long)]
+        data_sources: Option<String>,
+
+        #[command(subcommand)]
+        coverage_cmd: Option<AttackCoverageCommand>,
+    },
+    /// Download ATT&CK entities into the local cache (tactics, techniques and mitigations need --domain)
+    Sync {
+        /// Entity type (tactics, techniques, mitigations, software, groups, data-sources), or
+        /// 'all' for every type of the comma separated --domain list (all domains by default)
+        kind: String,
+
+        /// Only sync the given IDs instead of the whole listing
+        #[arg(long, num_args = 1..)]
+        ids: Vec<String>,
+
+        /// Download every page into the spool first, then parse them in parallel
+        #[arg(long)]
+        spool: bool,
+
+        /// Only parse the pages already in the spool (no network, cached listing used)
+        #[arg(long)]
+        parse_only: bool,
+
+        /// Detail pages fetched in parallel (throttled requests pause every worker)
+        #[arg(
+            long,
+            default_value_t = sync::DEFAULT_JOBS,
+            value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=sync::MAX_JOBS as u64),
+            conflicts_with_all = ["spool", "parse_only"]
+        )]
+        jobs: usize,
+
+        /// Skip the entities already synced from the current ATT&CK release, and keep the
+        /// cached entities whose page shows the same Version and Last Modified date
+        #[arg(long, conflicts_with_all = ["spool", "parse_only"])]
+        incremental: bool,
+
+        /// Keep syncing every INTERVAL (seconds, or e.g. 30m, 6h, 1d), logging the changes of
+        /// every cycle
+        #[arg(long, value_name = "INTERVAL", conflicts_with_all = ["spool", "parse_only"])]
+        watch: Option<String>,
+
+        /// POST a JSON summary of the added and updated entities to this webhook (Slack, Teams
+        /// or generic), MITRE_CLI_WEBHOOK_URL by default
+        #[arg(long, value_name = "URL", conflicts_with_all = ["spool", "parse_only"])]
+        webhook: Option<String>,
+    },
+    /// Re-fetch the entities already present in the local cache
+    Update {
+        /// Entity type (tactics, techniques, mitigations, software, groups, data-sources), all of them by default
+        kind: Option<String>,
+    },
+    /// Export the entities of the local cache
+    #[command(subcommand)]
+    Export(AttackExportCommand),
+    /// Populate the local cache from offline ATT&CK data
+    #[command(subcommand)]
+    Import(AttackImportCommand),
+    /// Inspect the local cache
+    #[command(subcommand)]
+    Cache(AttackCacheCommand),
+    /// Full-text index of the local cache
+    #[cfg(feature = "fulltext")]
+    #[command(subcommand)]
+    Index(AttackIndexCommand),
+    /// Generate document templates from the ATT&CK data
+    #[command(subcommand)]
+    Scaffold(AttackScaffoldCommand),
+    /// Technique changes between two ATT&CK releases (new, deprecated, renamed, description
+    /// changes), as Markdown or JSON
+    Changelog {
+        /// Older release (e.g. v13), or the path of a dataset file
+        #[arg(long)]
+        from: String,
+
+        /// Newer release, or the path of a dataset file (the local cache by default)
+        #[arg(long)]
+        to: Option<String>,
+    },
+    /// Download a prebuilt dataset into the local cache instead of scraping every page
+    FetchDataset {
+        /// ATT&CK release of the dataset (e.g. v15.1)
+        #[arg(long)]
+        release: String,
+
+        /// Download the dataset from this URL instead of the project releases
+        #[arg(long)]
+        url: Option<String>,
+    },
+    /// Watch a directory of reports and tag them with the referenced techniques
+    TagWatch {
+        /// Directory holding the reports (only read)
+        reports_dir: String,
+
+        /// Directory where the per-report technique mappings are written
+        #[arg(long)]
+        out_dir: String,
+
+        /// Seconds between directory scans
+        #[arg(long, default_value_t = 10)]
+        interval: u64,
+
+        /// Tag the current reports and exit
+        #[arg(long)]
+        once: bool,
+    },
+}
+
+impl AttackCommand {
+    /// Whether the command writes the Navigator layer of `--output layer`.
+    pub(super) fn writes_layer(&self) -> bool {
+        return matches!(
+            self,
+            AttackCommand::Describe {
+                describe_cmd: AttackDescribeCommand::Group { stdin: false, .. }
+                    | AttackDescribeCommand::Software { stdin: false, .. },
+                ..
+            } | AttackCommand::Stats {
+                stats_cmd: Some(AttackStatsCommand::TopTechniques { .. })
+            } | AttackCommand::Coverage {
+                coverage_cmd: Some(AttackCoverageCommand::Score { .. }),
+                ..
+            }
+        );
+    }
+
+    pub(super) fn handle(
+        self,
+        options: &GlobalOptions,
+        req_client: impl WebFetch + Sync,
+    ) -> Result<(), crate::error::Error> {
+        match self {
+            AttackCommand::List {
+                filter,
+                sort_by,
+                limit,
+                offset,
+                page,
+                page_size,
+                count,
+                list_cmd,
+            } => {
+                let (offset, limit) = match (page, page_size) {
+                    (None, None) => (offset, limit),
+                    (page, page_size) => {
+                        let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+                        let offset = (page.unwrap_or(1) - 1)
+                            .checked_mul(page_size)
+                            .and_then(|offset| usize::try_from(offset).ok())
+                            .ok_or_else(|| {
+                                crate::error::Error::InvalidValue(String::from(
+                                    "--page and --page-size are too large",
+                                ))
+                            })?;
+
+                        (offset, Some(usize::try_from(page_size).unwrap_or(usize::MAX)))
+                    }
+                };
+                let query = ListingQuery::new(filter.as_deref(), sort_by.as_deref())
+                    .paginate(offset, limit);
+
+                list_cmd.handle(options, &query, count, req_client)?
+            }
+            AttackCommand::Describe {
+                keep_citations,
+                describe_cmd,
+            } => {
+                crate::set_keep_citations(keep_citations);
+                describe_cmd.handle(options, req_client)?
+            }
+            AttackCommand::Annotate(annotate_cmd) => annotate_cmd.handle()?,
+            AttackCommand::Report(report_cmd) => report_cmd.handle(options, req_client)?,
+            AttackCommand::Plan(plan_cmd) => plan_cmd.handle(options)?,
+            AttackCommand::Search {
+                notes: Some(notes), ..
+            } => Self::handle_notes_search(options, &notes)?,
+            AttackCommand::Search {
+                contains: Some(keyword),
+                ..
+            } => Self::handle_contents_search(options, &keyword)?,
+            AttackCommand::Search {
+                search_cmd: Some(search_cmd),
+                ..
+            } => search_cmd.handle(options)?,
+            AttackCommand::Search { .. } => {
+                return Err(crate::error::Error::InvalidValue(String::from(
+                    "Give --notes <term>, --contains <keyword> or a search sub-command",
+                )))
+            }
+            AttackCommand::Compare(compare_cmd) => compare_cmd.handle(options, req_client)?,
+            AttackCommand::Map(map_cmd) => map_cmd.handle(options, req_client)?,
+            AttackCommand::Dashboard => Self::handle_dashboard(options)?,
+            AttackCommand::Stats {
+                stats_cmd: Some(stats_cmd),
+            } => stats_cmd.handle(options)?,
+            AttackCommand::Stats { stats_cmd: None } => Self::handle_stats(options)?,
+            AttackCommand::Coverage {
+                coverage_cmd: Some(coverage_cmd),
+                ..
+            } => coverage_cmd.handle(options, req_client)?,
+            AttackCommand::Coverage {
+                data_sources: Some(data_sources),
+                ..
+            } => Self::handle_coverage(options, &data_sources)?,
+            AttackCommand::Coverage { .. } => {
+                return Err(crate::error::Error::InvalidValue(String::from(
+                    "Either --data-sources or a coverage subcommand is required",
+                )))
+            }
+            AttackCommand::Sync {
+                kind,
+                ids,
+                spool: false,
+                parse_only: false,
+                jobs,
+                incremental,
+                watch,
+                webhook,
+            } if watch.is_some() || webhook.is_some() || webhook::url_from_env().is_some() => {
+                Self::handle_tracked_sync(
+                    &kind,
+                    options.domain.as_deref(),
+                    ids,
+                    jobs,
+                    incremental,
+                    watch.as_deref(),
+                    webhook.or_else(webhook::url_from_env),
+                    req_client,
+                )?
+            }
+            AttackCommand::Sync {
+                kind,
+                ids,
+                spool,
+                parse_only,
+                jobs,
+                incremental,
+                ..
+            } if kind == "all" => {
+                if !ids.is_empty() || spool || parse_only {
+                    return Err(crate::error::Error::InvalidValue(String::from(
+                        "'attack sync all' takes neither --ids, --spool nor --parse-only",
+                    )));
+                }
+
+                Self::handle_sync_all(options.domain.as_deref(), jobs, incremental, req_client)?
+            }
+            AttackCommand::Sync {
+                kind,
+                ids,
+                spool: false,
+                parse_only: false,
+                jobs,
+                incremental,
+                ..
+            } => Self::handle_sync(
+                &kind,
+                options.domain.as_deref(),
+                ids,
+                jobs,
+                incremental,
+                req_client,
+            )?,
+            AttackCommand::Sync {
+                kind,
+                ids,
+                parse_only,
+                ..
+            } => Self::handle_spooled_sync(
+                &kind,
+                options.domain.as_deref(),
+                ids,
+                parse_only,
+                req_client,
+            )?,
+            AttackCommand::Update { kind } => Self::handle_update(kind.as_deref(), req_client)?,
+            AttackCommand::Export(export_cmd) => export_cmd.handle(options, req_client)?,
+            AttackCommand::Import(import_cmd) => import_cmd.handle()?,
+            AttackCommand::Cache(cache_cmd) => cache_cmd.handle(options, req_client)?,
+            #[cfg(feature = "fulltext")]
+            AttackCommand::Index(index_cmd) => index_cmd.handle()?,
+            AttackCommand::Scaffold(scaffold_cmd) => scaffold_cmd.handle(options, req_client)?,
+            AttackCommand::Changelog { from, to } => {
+                let from = dataset::load_release(&from, &req_client)?;
+                let to = match to {
+                    Some(to) => dataset::load_release(&to, &req_client)?,
+                    None => {
+                        let cache = Cache::open()?;
+                        let release = cache
+                            .attack_version()?
+                            .unwrap_or_else(|| String::from("local cache"));
+
+                        dataset::Dataset::from_cache(&release, &cache)?
+                    }
+                };
+                let changelog = changelog::Changelog::between(&from, &to)?;
+
+                if !changelog.skipped_domains.is_empty() {
+                    eprintln!(
+                        "[!] Skipped the {} techniques, listed in only one of {} and {}",
+                        changelog.skipped_domains.join(", "),
+                        changelog.from,
+                        changelog.to
+                    );
+                }
+
+                if options.is_json() {
+                    return options.print_json(&changelog);
+                }
+
+                options.write(&changelog.to_markdown())?;
+            }
+            AttackCommand::FetchDataset { release, url } => {
+                let url = url.unwrap_or_else(|| dataset::dataset_url(&release));
+                let cache = Cache::open()?;
+
+                println!("[*] Downloading the {} dataset from {}", release, url);
+                let installed = dataset::fetch_dataset(&url, &req_client)?.install(&cache)?;
+                save_sync_manifest(&cache)?;
+                println!(
+                    "[*] Installed {} entities into {}",
+                    installed,
+                    cache.root().display()
+                );
+            }
+            AttackCommand::TagWatch {
+                reports_dir,
+                out_dir,
+                interval,
+                once,
+            } => Self::handle_tag_watch(&reports_dir, &out_dir, interval, once)?,
+        };
+
+        return Ok(());
+    }
+
+    fn handle_notes_search(options: &GlobalOptions, term: &str) -> Result<(), crate::error::Error> {
+        let cache = Cache::open()?;
+        let mut matches = Annotations::load()?.search_notes(term);
+
+        for note_match in matches.0.iter_mut() {
+            if let Some(kind) = EntityKind::from_id(&note_match.id) {
+                let cached_entity: Option<serde_json::Value> =
+                    cache.load_entity(kind, &note_match.id)?;
+
+                if let Some(name) = cached_entity
+                    .as_ref()
+                    .and_then(|entity| entity["name"].as_str())
+                {
+                    note_match.name = name.to_string();
+                }
+            }
+        }
+
+        if options.is_json() {
+            return options.print_json(&matches);
+        }
+
+        if matches.is_empty() {
+            println!("[!] No notes matching '{}'", term);
+            return Ok(());
+        }
+
+        return options.print(matches);
+    }
+
+    fn handle_contents_search(
+        options: &GlobalOptions,
+        keyword: &str,
+    ) -> Result<(), crate::error::Error> {
+        let snapshot = Cache::open()?.snapshot()?;
+
+        if snapshot.is_empty() {
+            return Err(crate::error::Error::General(String::from(
+                "The local cache is empty, run 'attack sync' first",
+            )));
+        }
+
+        let matches = search::search_contents(&snapshot, keyword)?;
+
+        if options.is_json() {
+            return options.print_json(&matches);
+        }
+
+        if matches.is_empty() {
+            println!("[!] No cached entity containing '{}'", keyword);
+            return Ok(());
+        }
+
+        return options.print(matches);
+    }
+
+    fn handle_dashboard(options: &GlobalOptions) -> Result<(), crate::error::Error> {
+        let domain = options.domain()?;
+        let dashboard = Dashboard::build(&Cache::open()?, &Annotations::load()?, domain)?;
+
+        if options.is_json() {
+            return options.print_json(&dashboard);
+        }
+
+        println!(
+            "[*] ATT&CK {} dashboard (ATT&CK version: {})\n",
+            dashboard.domain,
+            dashboard.attack_version.as_deref().unwrap_or("unknown")
+        );
+        options.print(dashboard.counts)?;
+
+        println!(
+            "\n[*] Last {} modified techniques\n",
+            dashboard::RECENTLY_MODIFIED
+        );
+        if dashboard.recently_modified.is_empty() {
+            println!(
+                "[!] No cached technique has a modification date, run 'attack update techniques'"
+            );
+        } else {
+            options.print(dashboard.recently_modified)?;
+        }
+
+        match dashboard.coverage {
+            Some(coverage) => println!(
+                "\n[*] Mitigation coverage: {:.1}% of the cached techniques ({}/{}) have an \
+                 implemented mitigation ({} mitigations assessed)",
+                coverage.percentage(),
+                coverage.covered_techniques,
+                coverage.techniques,
+                coverage.assessed_mitigations
+            ),
+            None => println!(
+                "\n[!] No mitigation assessed yet, see 'attack annotate mitigation <ID> --status'"
+            ),
+        };
+
+        return Ok(());
+    }
+
+    fn handle_stats(options: &GlobalOptions) -> Result<(), crate::error::Error> {
+        let stats = CacheStats::build(&Cache::open()?)?;
+
+        if options.is_json() {
+            return options.print_json(&stats);
+        }
+
+        println!(
+            "[*] Local cache statistics (ATT&CK version: {}, last sync: {})\n",
+            stats.attack_version.as_deref().unwrap_or("unknown"),
+            stats.last_sync.as_deref().unwrap_or("never")
+        );
+        options.print(stats.entities)?;
+
+        println!("\n[*] Techniques per tactic\n");
+        if stats.tactics.is_empty() {
+            println!("[!] No cached tactic, run 'attack sync tactics'");
+        } else {
+            options.print(stats.tactics)?;
+        }
+
+        println!(
+            "\n[*] Sub-techniques: {} for {} techniques ({:.2} per technique, {} techniques \
+             with sub-techniques)",
+            stats.sub_techniques.sub_techniques,
+            stats.sub_techniques.techniques,
+            stats.sub_techniques.ratio(),
+            stats.sub_techniques.techniques_with_sub_techniques
+        );
+
+        println!("\n[*] Techniques per number of groups using them\n");
+        options.print(stats.groups_per_technique)?;
+
+        println!("\n[*] Last sync per entity type\n");
+        options.print(stats.synced)?;
+
+        return Ok(());
+    }
+
+    fn handle_coverage(
+        options: &GlobalOptions,
+        data_sources: &str,
+    ) -> Result<(), crate::error::Error> {
+        let inventory = coverage::DataSourceInventory::load(std::path::Path::new(data_sources))?;
+        let cache = Cache::open()?;
+        let mut cached_techniques: Vec<techniques::Technique> =
+            cache.load_entities(EntityKind::TECHNIQUE)?;
+
+        if inventory.is_empty() {
+            return Err(crate::error::Error::InvalidValue(format!(
+                "{} does not list any data source",
+                data_sources
+            )));
+        }
+
+        if let Some(domain) = options.domain.as_deref() {
+            if let Some(domain_ids) = cache.listing_ids(EntityKind::TECHNIQUE, Some(domain))? {
+                cached_techniques.retain(|technique| domain_ids.contains(&technique.id));
+            }
+        }
+
+        if cached_techniques.is_empty() {
+            return Err(crate::error::Error::General(String::from(
+                "No techniques cached, run 'attack sync techniques' first",
+            )));
+        }
+
+        let report = coverage::build_coverage_report(&cached_techniques, &inventory);
+
+        if options.is_json() {
+            return options.print_json(&report);
+        }
+
+        println!(
+            "[*] Techniques: {} total, {} covered, {} without detection data, {} gaps",
+            cached_techniques.len(),
+            report.covered.len(),
+            report.undetectable.len(),
+            report.gaps.len()
+        );
+
+        if !report.gaps.is_empty() {
+            options.print(report)?;
+        }
+
+        return Ok(());
+    }
+
+    fn handle_sync(
+        kind: &str,
+        domain: Option<&str>,
+        ids: Vec<String>,
+        jobs: usize,
+        incremental: bool,
+        req_client: impl WebFetch + Sync,
+    ) -> Result<(), crate::error::Error> {
+        let kind = EntityKind::from_str(kind)?;
+        let cache = Cache::open()?;
+        let mut ids = if ids.is_empty() {
+            sync::sync_listing(kind, domain, &req_client, &cache)?
+        } else {
+            ids
+        };
+
+        if incremental {
+            ids = incremental_ids(kind, ids, &cache)?;
+        }
+
+        let summary = sync::sync_entities_parallel(
+            kind,
+            &ids,
+            jobs,
+            &req_client,
+            &cache,
+            incremental,
+            progress_printer(ids.len()),
+        );
+        save_sync_manifest(&cache)?;
+
+        println!(
+            "[*] Synced {} entities into {}, {} failed",
+            summary.synced.len(),
+            cache.root().display(),
+            summary.failed.len()
+        );
+        print_unchanged(&summary);
+
+        if !summary.throttled.is_zero() {
+            println!("[*] Paused {}s by throttled requests", summary.throttled.as_secs());
+        }
+
+        return Ok(());
+    }
+
+    fn handle_sync_all(
+        domains: Option<&str>,
+        jobs: usize,
+        incremental: bool,
+        req_client: impl WebFetch + Sync,
+    ) -> Result<(), crate::error::Error> {
+        let domains: Vec<String> = match domains {
+            Some(domains) => domains
+                .split(',')
+                .map(|domain| domain.trim().to_lowercase())
+                .filter(|domain| !domain.is_empty())
+                .collect(),
+            None => DOMAINS.map(String::from).to_vec(),
+        };
+
+        if let Some(domain) = domains
+            .iter()
+            .find(|domain| !DOMAINS.contains(&domain.as_str()))
+        {
+            return Err(crate::error::Error::InvalidValue(format!(
+                "{} is not a valid domain (enterprise, mobile, ics)",
+                domain
+            )));
+        }
+
+        let (domain_kinds, other_kinds): (Vec<EntityKind>, Vec<EntityKind>) = EntityKind::ALL
+            .into_iter()
+            .partition(EntityKind::has_domain);
+        let mut steps: Vec<(EntityKind, Option<&str>)> = Vec::new();
+
+        for domain in &domains {
+            for kind in &domain_kinds {
+                steps.push((*kind, Some(domain.as_str())));
+            }
+        }
+
+        for kind in other_kinds {
+            steps.push((kind, None));
+        }
+
+        let cache = Cache::open()?;
+        let mut results: Vec<(String, String)> = Vec::with_capacity(steps.len());
+        let (mut synced, mut failed, mut throttled) = (0, 0, std::time::Duration::ZERO);
+
+        for (kind, domain) in steps {
+            let kind_name: &str = kind.into();
+            let step_name = match domain {
+                Some(domain) => format!("{} {}", domain, kind_name),
+                None => kind_name.to_string(),
+            };
+
+            println!("[*] Syncing {}", step_name);
+
+            let mut ids = match sync::sync_listing(kind, domain, &req_client, &cache) {
+                Ok(ids) => ids,
+                Err(err) => {
+                    eprintln!("[!] {} listing: {}", step_name, err);
+                    results.push((step_name, String::from("listing failed")));
+                    failed += 1;
+
+                    continue;
+                }
+            };
+
+            if incremental {
+                ids = incremental_ids(kind, ids, &cache)?;
+            }
+
+            let summary = sync::sync_entities_parallel(
+                kind,
+                &ids,
+                jobs,
+                &req_client,
+                &cache,
+                incremental,
+                progress_printer(ids.len()),
+            );
+
+            results.push((
+                step_name,
+                format!(
+                    "{} synced, {} unchanged, {} failed",
+                    summary.synced.len(),
+                    summary.unchanged.len(),
+                    summary.failed.len()
+                ),
+            ));
+            synced += summary.synced.len();
+            failed += summary.failed.len();
+            throttled += summary.throttled;
+        }
+
+        save_sync_manifest(&cache)?;
+
+        println!("\n[*] Sync summary");
+
+        for (step_name, result) in results {
+            println!("[*]   {}: {}", step_name, result);
+        }
+
+        println!(
+            "[*] Synced {} entities into {}, {} failed",
+            synced,
+            cache.root().display(),
+            failed
+        );
+
+        if !throttled.is_zero() {
+            println!("[*] Paused {}s by throttled requests", throttled.as_secs());
+        }
+
+        return Ok(());
+    }
+
+    /// Sync logging the entities it added or updated, and posting them to the webhook. With
+    /// `watch`, the sync runs again every interval until interrupted, a failing cycle being
+    /// logged and retried on the next one.
+    #[allow(clippy::too_many_arguments)]
+    fn handle_tracked_sync(
+        kind: &str,
+        domain: Option<&str>,
+        ids: Vec<String>,
+        jobs: usize,
+        incremental: bool,
+        watch: Option<&str>,
+        webhook: Option<String>,
+        req_client: impl WebFetch + Sync,
+    ) -> Result<(), crate::error::Error> {
+        let interval = watch.map(sync::parse_interval).transpose()?;
+        let kinds = if kind == "all" {
+            if !ids.is_empty() {
+                return Err(crate::error::Error::InvalidValue(String::from(
+                    "'attack sync all' takes no --ids",
+                )));
+            }
+
+            EntityKind::ALL.to_vec()
+        } else {
+            vec![EntityKind::from_str(kind)?]
+        };
+        let cache = Cache::open()?;
+
+        for cycle in 1u64.. {
+            if interval.is_some() {
+                println!(
+                    "[*] Sync cycle {} started at {}",
+                    cycle,
+                    chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ")
+                );
+            }
+
+            let mut fingerprints = Vec::with_capacity(kinds.len());
+
+            for kind in &kinds {
+                fingerprints.push(sync::entity_fingerprints(&cache, *kind)?);
+            }
+
+            let result = if kind == "all" {
+                Self::handle_sync_all(domain, jobs, incremental, &req_client)
+            } else {
+                Self::handle_sync(kind, domain, ids.clone(), jobs, incremental, &req_client)
+            };
+
+            match result {
+                Ok(()) => {
+                    let mut changes = Vec::with_capacity(kinds.len());
+
+                    for (kind, before) in kinds.iter().zip(&fingerprints) {
+                        let kind_name: &str = (*kind).into();
+                        changes.push((
+                            kind_name.to_string(),
+                            sync::CacheChanges::between(
+                                before,
+                                &sync::entity_fingerprints(&cache, *kind)?,
+                            ),
+                        ));
+                    }
+
+                    print_sync_changes(&changes);
+
+                    let notification = webhook::ChangeNotification::new(
+                        cache.attack_version()?,
+                        &chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+                        changes,
+                    );
+
+                    if let Some(url) = webhook.as_deref().filter(|_| !notification.is_empty()) {
+                        match webhook::notify(url, &notification, &req_client) {
+                            Ok(()) => println!("[*] Notified the webhook of the changes"),
+                            Err(err) => eprintln!("[!] Webhook notification failed: {}", err),
+                        }
+                    }
+                }
+                Err(err) if interval.is_some() => {
+                    eprintln!("[!] Sync cycle {} failed: {}", cycle, err)
+                }
+                Err(err) => return Err(err),
+            }
+
+            match interval {
+                Some(interval) => {
+                    println!("[*] Next sync in {}s", interval.as_secs());
+                    std::thread::sleep(interval);
+                }
+                None => break,
+            }
+        }
+
+        return Ok(());
+    }
+
+    fn handle_spooled_sync(
+        kind: &str,
+        domain: Option<&str>,
+        ids: Vec<String>,
+        parse_only: bool,
+        req_client: impl WebFetch,
+    ) -> Result<(), crate::error::Error> {
+        let kind = EntityKind::from_str(kind)?;
+        let cache = Cache::open()?;
+        let spool = Spool::for_cache(&cache);
+        let ids = match (ids.is_empty(), parse_only) {
+            (false, _) => ids,
+            (true, false) => sync::sync_listing(kind, domain, &req_client, &cache)?,
+            (true, true) => cache.listing_ids(kind, domain)?.ok_or_else(|| {
+                crate::error::Error::General(String::from(
+                    "No listing cached, run the spooled sync without --parse-only first",
+                ))
+            })?,
+        };
+
+        if !parse_only {
+            println!("[*] Downloading {} pages into {}", ids.len(), spool.root().display());
+            let downloaded = spool::spool_entities(
+                kind,
+                &ids,
+                &req_client,
+                &spool,
+                progress_printer(ids.len()),
+            );
+            println!(
+                "[*] Downloaded {} pages, {} failed",
+                downloaded.synced.len(),
+                downloaded.failed.len()
+            );
+        }
+
+        println!("[*] Parsing the spooled pages");
+        let parsed = spool::parse_spooled(kind, &ids, &spool, &cache, |id, result| {
+            if let Err(err) = result {
+                eprintln!("[!] {}: {}", id.to_uppercase(), err);
+            }
+        });
+        save_sync_manifest(&cache)?;
+        println!(
+            "[*] Parsed {} entities into {}, {} failed",
+            parsed.synced.len(),
+            cache.root().display(),
+            parsed.failed.len()
+        );
+
+        return Ok(());
+    }
+
+    fn handle_update(
+        kind: Option<&str>,
+        req_client: impl WebFetch,
+    ) -> Result<(), crate::error::Error> {
+        let kinds = match kind {
+            Some(kind) => vec![EntityKind::from_str(kind)?],
+            None => EntityKind::ALL.to_vec(),
+        };
+        let cache = Cache::open()?;
+        let (mut updated, mut failed, mut throttled) = (0, 0, std::time::Duration::ZERO);
+
+        for kind in kinds {
+            let summary = sync::update_cached(kind, &req_client, &cache, |_, _| {})?;
+
+            for (name, err) in &summary.failed {
+                eprintln!("[!] {}: {}", name, err);
+            }
+
+            let kind_name: &str = kind.into();
+            println!("[*] Updated {} {}", summary.synced.len(), kind_name);
+
+            updated += summary.synced.len();
+            failed += summary.failed.len();
+            throttled += summary.throttled;
+        }
+
+        save_sync_manifest(&cache)?;
+
+        println!(
+            "[*] Updated {} cached entities in {}, {} failed",
+            updated,
+            cache.root().display(),
+            failed
+        );
+
+        if !throttled.is_zero() {
+            println!("[*] Paused {}s by throttled requests", throttled.as_secs());
+        }
+
+        return Ok(());
+    }
+
+    fn handle_tag_watch(
+        reports_dir: &str,
+        out_dir: &str,
+        interval: u64,
+        once: bool,
+    ) -> Result<(), crate::error::Error> {
+        let index = tagging::TechniqueIndex::from_cache(&Cache::open()?)?;
+        let (reports_dir, tags_dir) = (
+            std::path::Path::new(reports_dir),
+            std::path::Path::new(out_dir),
+        );
+
+        println!("[*] Watching {} for new reports", reports_dir.display());
+
+        loop {
+            for tags_path in tagging::tag_reports(reports_dir, tags_dir, &index)? {
+                println!("[*] Tagged {}", tags_path.display());
+            }
+
+            if once {
+                return Ok(());
+            }
+
+            std::thread::sleep(std::time::Duration::from_secs(interval));
+        }
     }
 }
@@ -0,0 +1,27 @@
+use super::GlobalOptions;
+use crate::{nvd, WebFetch};
+use clap::Args;
+
+#[derive(Args)]
+pub struct CveCommand {
+    /// CVE IDs (CVE-YYYY-NNNN)
+    #[arg(required = true)]
+    ids: Vec<String>,
+
+    /// NVD API key, defaults to the NVD_API_KEY environment variable
+    #[arg(long)]
+    api_key: Option<String>,
+}
+
+impl CveCommand {
+    pub(super) fn handle(
+        self,
+        options: &GlobalOptions,
+        req_client: impl WebFetch,
+    ) -> Result<(), crate::error::Error> {
+        let api_key = self.api_key.or_else(nvd::api_key_from_env);
+        let cves = nvd::fetch_cves(&self.ids, api_key.as_deref(), &req_client)?;
+
+        return options.print(cves);
+    }
+}
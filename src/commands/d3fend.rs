@@ -0,0 +1,34 @@
+use super::GlobalOptions;
+use crate::{d3fend, WebFetch};
+use clap::Subcommand;
+
+#[derive(Subcommand)]
+pub enum D3fendCommand {
+    /// D3FEND defensive techniques that counter an ATT&CK technique
+    Countermeasures {
+        /// ATT&CK Technique ID
+        id: String,
+    },
+}
+
+impl D3fendCommand {
+    pub(super) fn handle(
+        self,
+        options: &GlobalOptions,
+        req_client: impl WebFetch,
+    ) -> Result<(), crate::error::Error> {
+        match self {
+            D3fendCommand::Countermeasures { id } => {
+                let defensive_techniques = d3fend::fetch_defensive_techniques(&id, &req_client)?;
+
+                if defensive_techniques.is_empty() && !options.is_json() {
+                    println!("[!] No D3FEND techniques mapped to {}", id.to_uppercase());
+                } else {
+                    options.print(defensive_techniques)?;
+                }
+            }
+        };
+
+        return Ok(());
+    }
+}
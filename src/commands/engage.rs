@@ -0,0 +1,67 @@
+use std::str::FromStr;
+
+use super::GlobalOptions;
+use crate::{engage, WebFetch};
+use clap::Subcommand;
+
+#[derive(Subcommand)]
+pub enum EngageCommand {
+    /// List MITRE Engage entities
+    List {
+        /// Entity type (goals, approaches, activities)
+        kind: String,
+    },
+    /// Engage activities that can be used against an ATT&CK technique
+    Activities {
+        /// ATT&CK Technique ID
+        technique_id: String,
+    },
+    /// ATT&CK techniques an Engage activity can be used against
+    Techniques {
+        /// Engage Activity ID
+        activity_id: String,
+    },
+}
+
+impl EngageCommand {
+    pub(super) fn handle(
+        self,
+        options: &GlobalOptions,
+        req_client: impl WebFetch,
+    ) -> Result<(), crate::error::Error> {
+        match self {
+            EngageCommand::List { kind } => {
+                let entities =
+                    engage::fetch_entities(engage::EntityKind::from_str(&kind)?, &req_client)?;
+
+                options.print(entities)?;
+            }
+            EngageCommand::Activities { technique_id } => {
+                let activities = engage::fetch_technique_activities(&technique_id, &req_client)?;
+
+                if activities.is_empty() && !options.is_json() {
+                    println!(
+                        "[!] No Engage activities mapped to {}",
+                        technique_id.to_uppercase()
+                    );
+                } else {
+                    options.print(activities)?;
+                }
+            }
+            EngageCommand::Techniques { activity_id } => {
+                let techniques = engage::fetch_activity_techniques(&activity_id, &req_client)?;
+
+                if techniques.is_empty() && !options.is_json() {
+                    println!(
+                        "[!] No ATT&CK techniques mapped to {}",
+                        activity_id.to_uppercase()
+                    );
+                } else {
+                    options.print(techniques)?;
+                }
+            }
+        };
+
+        return Ok(());
+    }
+}
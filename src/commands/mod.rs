@@ -1,21 +1,684 @@
-use structopt::StructOpt;
+use std::cell::RefCell;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
 
 mod attack;
+mod cve;
+mod d3fend;
+mod engage;
+mod pager;
+mod xlsx;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    TABLE,
+    JSON,
+    /// One JSON object per line, every row of the lists on its own line
+    JSONL,
+    CSV,
+    /// Excel workbook written to --out, one sheet per table
+    XLSX,
+    /// ATT&CK Navigator layer of the techniques of 'attack describe group', 'attack describe
+    /// software', 'attack stats top-techniques' or 'attack coverage score'
+    LAYER,
+}
+
+/// Format of the error printed to STDERR when a command fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ErrorFormat {
+    TEXT,
+    /// {"error": <kind>, "message": <message>, "exit_code": <code>}
+    JSON,
+}
+
+#[derive(Parser)]
+#[command(name = "mitre_cli", about = "An oxidized Mitre Framework's scraper.")]
+pub struct Cli {
+    /// Output format of the retrieved data
+    #[arg(long, global = true, value_enum, default_value = "table")]
+    output: OutputFormat,
+
+    /// ATT&CK domain (enterprise, ics, mobile)
+    #[arg(long, global = true, alias = "industry")]
+    domain: Option<String>,
+
+    /// Log more details to STDERR (-v info, -vv debug, -vvv trace), warnings only by default
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Only log the errors to STDERR
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Append the log records (info and above) to this file, e.g. to keep the sync logs
+    #[arg(long, global = true)]
+    log_file: Option<String>,
+
+    /// Directory of the ATT&CK cache, overriding $MITRE_CLI_HOME and $XDG_CACHE_HOME
+    #[arg(long, global = true)]
+    cache_dir: Option<String>,
+
+    /// Base URL of an ATT&CK website mirror replacing https://attack.mitre.org/ (also set by
+    /// the MITRE_CLI_BASE_URL environment variable)
+    #[arg(long, global = true)]
+    base_url: Option<String>,
+
+    /// Seconds to establish an HTTP connection (default 10, or $MITRE_CLI_CONNECT_TIMEOUT)
+    #[arg(long, global = true, value_parser = clap::value_parser!(u64).range(1..))]
+    connect_timeout: Option<u64>,
+
+    /// Seconds to read an HTTP response (default 60, or $MITRE_CLI_TIMEOUT), failing the
+    /// request instead of stalling a sync on a hung server
+    #[arg(long, global = true, value_parser = clap::value_parser!(u64).range(1..))]
+    timeout: Option<u64>,
+
+    /// Format of the error printed to STDERR on failure. The exit code tells the error apart:
+    /// 1 general, 2 invalid value, 3 network, 4 not found, 5 parse
+    #[arg(long, global = true, value_enum, default_value = "text")]
+    error_format: ErrorFormat,
+
+    /// Print the command metrics (cache hits, HTTP requests, durations) to STDERR
+    #[arg(long, global = true)]
+    metrics: bool,
+
+    /// Append the command metrics as a JSON line to the given file
+    #[arg(long, global = true)]
+    metrics_file: Option<String>,
+
+    /// Only show the first sentence of the descriptions (ignored by the JSON output)
+    #[arg(long, global = true)]
+    brief: bool,
+
+    /// Forbid any network access, serving from the local cache or failing when not synced
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Only output the given comma separated columns or JSON fields (e.g. id,name)
+    #[arg(long, global = true, value_delimiter = ',')]
+    fields: Vec<String>,
+
+    /// Never pipe the tables taller than the terminal through $PAGER (less by default)
+    #[arg(long, global = true)]
+    no_pager: bool,
+
+    /// Table theme
+    #[arg(long, global = true, value_enum, default_value = "red")]
+    theme: crate::theme::Theme,
+
+    /// Leave the colors out of the tables (also set by the NO_COLOR environment variable)
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// ASCII-only tables without colors nor bold, for logs (same as --theme plain)
+    #[arg(long, global = true)]
+    plain: bool,
+
+    /// Write the results (tables, JSON, CSV, XLSX) into this file, atomically replaced, instead of
+    /// STDOUT. The log lines are still printed
+    #[arg(long, global = true)]
+    out: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+impl Cli {
+    pub fn error_format(&self) -> ErrorFormat {
+        return self.error_format;
+    }
+
+    /// Timeouts of the HTTP client: the flags, then the environment, then the defaults.
+    pub fn timeouts(&self) -> Result<crate::Timeouts, crate::error::Error> {
+        let mut timeouts = crate::Timeouts::from_env()?;
+
+        if let Some(connect_timeout) = self.connect_timeout {
+            timeouts.connect = std::time::Duration::from_secs(connect_timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            timeouts.read = std::time::Duration::from_secs(timeout);
+        }
+
+        return Ok(timeouts);
+    }
+
+    pub fn handle(
+        self,
+        req_client: impl crate::WebFetch + Sync,
+    ) -> Result<(), crate::error::Error> {
+        crate::logging::init(
+            crate::logging::level(self.verbose, self.quiet),
+            self.log_file.as_deref().map(std::path::Path::new),
+        )?;
+        if let Some(cache_dir) = &self.cache_dir {
+            crate::storage::set_cache_dir(std::path::PathBuf::from(cache_dir));
+        }
+        migrate_legacy_layout();
+        crate::theme::set_theme(
+            if self.plain {
+                crate::theme::Theme::PLAIN
+            } else {
+                self.theme
+            },
+            !crate::theme::no_color_requested(self.no_color) && self.out.is_none(),
+        );
+
+        let options = GlobalOptions {
+            output: self.output,
+            domain: self.domain,
+            brief: self.brief,
+            fields: self.fields.iter().map(|field| field_key(field)).collect(),
+            pager: !self.no_pager,
+            out: self.out.map(std::path::PathBuf::from),
+            captured: RefCell::new(None),
+            workbook: RefCell::new(xlsx::Workbook::default()),
+        };
+
+        if options.output == OutputFormat::XLSX && options.out.is_none() {
+            return Err(crate::error::Error::InvalidValue(String::from(
+                "--output xlsx requires --out <file>.xlsx",
+            )));
+        }
+
+        if options.output == OutputFormat::LAYER && !self.command.writes_layer() {
+            return Err(crate::error::Error::InvalidValue(String::from(
+                "--output layer is only supported by 'attack describe group', 'attack describe software' (without --stdin), 'attack stats top-techniques' and 'attack coverage score'",
+            )));
+        }
+
+        let base_url = self
+            .base_url
+            .or_else(|| std::env::var(crate::mirror::BASE_URL_ENV).ok());
+        let req_client = crate::mirror::MirrorFetch::new(req_client, base_url.as_deref())?;
+
+        let started = std::time::Instant::now();
+        let result = self.command.handle(
+            &options,
+            crate::metrics::MeteredFetch::new(crate::offline::OfflineFetch::new(
+                req_client,
+                self.offline,
+            )),
+        );
+
+        if self.metrics || self.metrics_file.is_some() {
+            let command = std::env::args().skip(1).collect::<Vec<String>>().join(" ");
+            let metrics = crate::metrics::Metrics::collect(&command, started);
+
+            if self.metrics {
+                eprintln!(
+                    "[*] Metrics: {} cache hits, {} cache misses, {} HTTP requests ({} bytes), \
+                     {} ms HTTP ({} ms throttled), {} ms parsing, {} ms total",
+                    metrics.cache_hits,
+                    metrics.cache_misses,
+                    metrics.http_requests,
+                    metrics.bytes_downloaded,
+                    metrics.http_ms,
+                    metrics.throttled_ms,
+                    metrics.parse_ms,
+                    metrics.total_ms
+                );
+            }
+
+            if let Some(metrics_file) = &self.metrics_file {
+                metrics.append_to(std::path::Path::new(metrics_file))?;
+            }
+        }
+
+        result?;
+
+        return options.write_out();
+    }
+}
+
+/// Flags shared by every sub-command.
+pub(crate) struct GlobalOptions {
+    pub output: OutputFormat,
+    pub domain: Option<String>,
+    pub brief: bool,
+    /// Columns or JSON fields to keep (normalized with `field_key`), all of them when empty.
+    pub fields: Vec<String>,
+    /// Page the tables taller than the terminal.
+    pub pager: bool,
+    /// File the results are written to instead of STDOUT.
+    pub out: Option<std::path::PathBuf>,
+    /// Results captured for `out`, written once the command succeeded. Commands writing
+    /// their own format to `out` (the STIX and dataset exports) capture nothing.
+    captured: RefCell<Option<String>>,
+    /// Sheets of the `--output xlsx` workbook.
+    workbook: RefCell<xlsx::Workbook>,
+}
+
+impl GlobalOptions {
+    pub fn is_json(&self) -> bool {
+        return matches!(self.output, OutputFormat::JSON | OutputFormat::JSONL);
+    }
+
+    /// Domain of the command, failing if it was not given.
+    pub fn domain(&self) -> Result<&str, crate::error::Error> {
+        return self.domain.as_deref().ok_or_else(|| {
+            crate::error::Error::InvalidValue(String::from(
+                "--domain is required (enterprise, ics, mobile)",
+            ))
+        });
+    }
+
+    /// Description as it should be shown in the tables and describe output.
+    pub fn description<'a>(&self, description: &'a str) -> &'a str {
+        if self.brief {
+            return first_sentence(description);
+        }
+
+        return description;
+    }
+
+    pub fn print_json(&self, value: &impl Serialize) -> Result<(), crate::error::Error> {
+        let value = self.project_json(serde_json::to_value(value)?)?;
+
+        if self.output == OutputFormat::JSONL {
+            return self.write(&json_lines(&value)?);
+        }
+
+        return self.write(&format!("{}\n", serde_json::to_string_pretty(&value)?));
+    }
+
+    /// Print the results, or capture them for `--out`.
+    pub fn write(&self, output: &str) -> Result<(), crate::error::Error> {
+        // Only the tables go to the workbook.
+        if self.out.is_some() && self.output != OutputFormat::XLSX {
+            self.captured
+                .borrow_mut()
+                .get_or_insert_with(String::new)
+                .push_str(output);
+
+            return Ok(());
+        }
+
+        print!("{}", output);
+
+        return Ok(());
+    }
+
+    /// Print a line of the results, or capture it for `--out`.
+    pub fn write_line(&self, line: &str) -> Result<(), crate::error::Error> {
+        return self.write(&format!("{}\n", line));
+    }
+
+    /// Atomically replace the `--out` file with the captured results or workbook, if any.
+    fn write_out(&self) -> Result<(), crate::error::Error> {
+        let out = match &self.out {
+            Some(out) => out,
+            None => return Ok(()),
+        };
+
+        if self.output == OutputFormat::XLSX {
+            let workbook = self.workbook.borrow();
+
+            if workbook.is_empty() {
+                return Ok(());
+            }
+
+            crate::storage::write_atomic(out, workbook.to_bytes()?)?;
+        } else if let Some(captured) = self.captured.borrow().as_ref() {
+            crate::storage::write_atomic(out, captured)?;
+        } else {
+            return Ok(());
+        }
 
-#[derive(StructOpt)]
-#[structopt(name = "mitre_cli", about = "An oxidized Mitre Framework's scraper.", no_version)]
+        println!("[*] Results written to {}", out.display());
+
+        return Ok(());
+    }
+
+    /// Print the retrieved data as a table or as JSON, depending on `--output`.
+    pub fn print<T>(&self, value: T) -> Result<(), crate::error::Error>
+    where
+        T: Serialize + Into<comfy_table::Table>,
+    {
+        if self.is_json() {
+            return self.print_json(&value);
+        }
+
+        if self.output == OutputFormat::XLSX {
+            let value = self.brief_json(self.project_json(serde_json::to_value(&value)?)?);
+            self.workbook
+                .borrow_mut()
+                .add_sheet(&xlsx::type_sheet_name::<T>(), value);
+
+            return Ok(());
+        }
+
+        let mut table: comfy_table::Table = value.into();
+
+        if !self.fields.is_empty() {
+            table = self.project_table(&mut table)?;
+        }
+
+        if self.output == OutputFormat::CSV {
+            return self.write(&csv_table(&mut table));
+        }
+
+        if self.brief {
+            table = self.brief_table(&mut table);
+        }
+
+        if self.out.is_some() {
+            // Neither colors nor wrapping to the terminal width in the file.
+            table.force_no_tty();
+
+            return self.write(&format!("{}\n", table));
+        }
+
+        return pager::print_paged(&format!("{}\n", table), self.pager);
+    }
+
+    /// Fields of the JSON objects (or of the objects of a list) kept by `--fields`.
+    fn project_json(
+        &self,
+        value: serde_json::Value,
+    ) -> Result<serde_json::Value, crate::error::Error> {
+        if self.fields.is_empty() {
+            return Ok(value);
+        }
+
+        let project = |object: serde_json::Map<String, serde_json::Value>| {
+            return object
+                .into_iter()
+                .filter(|(key, _)| self.fields.contains(&field_key(key)))
+                .collect::<serde_json::Map<String, serde_json::Value>>();
+        };
+        let mut available: Vec<String> = match &value {
+            serde_json::Value::Array(rows) => rows
+                .iter()
+                .filter_map(|row| row.as_object())
+                .flat_map(|object| object.keys().map(|key| field_key(key)))
+                .collect(),
+            serde_json::Value::Object(object) => object.keys().map(|key| field_key(key)).collect(),
+            _ => Vec::new(),
+        };
+        available.sort();
+        available.dedup();
+        self.check_fields(&available)?;
+
+        return Ok(match value {
+            serde_json::Value::Array(rows) => serde_json::Value::Array(
+                rows.into_iter()
+                    .map(|row| match row {
+                        serde_json::Value::Object(object) => {
+                            serde_json::Value::Object(project(object))
+                        }
+                        row => row,
+                    })
+                    .collect(),
+            ),
+            serde_json::Value::Object(object) => serde_json::Value::Object(project(object)),
+            value => value,
+        });
+    }
+
+    /// Copy of `table` with the columns of `--fields` only, in their order.
+    fn project_table(
+        &self,
+        table: &mut comfy_table::Table,
+    ) -> Result<comfy_table::Table, crate::error::Error> {
+        let header = table.header().cloned().unwrap_or_default();
+        let columns: Vec<String> = header
+            .cell_iter()
+            .map(|cell| field_key(&cell.content()))
+            .collect();
+        self.check_fields(&columns)?;
+
+        let kept_columns: Vec<usize> = self
+            .fields
+            .iter()
+            .filter_map(|field| columns.iter().position(|column| column == field))
+            .collect();
+        let header_cells: Vec<comfy_table::Cell> = header.cell_iter().cloned().collect();
+
+        let mut projected_table = crate::theme::new_table();
+        projected_table.set_header(
+            kept_columns
+                .iter()
+                .map(|inx| header_cells[*inx].clone())
+                .collect::<Vec<comfy_table::Cell>>(),
+        );
+
+        for row in table.row_iter() {
+            let cells: Vec<&comfy_table::Cell> = row.cell_iter().collect();
+            let mut projected_row = comfy_table::Row::new();
+
+            for inx in &kept_columns {
+                projected_row.add_cell(
+                    cells
+                        .get(*inx)
+                        .map_or_else(|| comfy_table::Cell::new(""), |cell| (*cell).clone()),
+                );
+            }
+
+            projected_table.add_row(projected_row);
+        }
+
+        return Ok(projected_table);
+    }
+
+    /// Fail when none of the `--fields` is in the output, listing the available ones. Nested
+    /// tables (describe) only having some of them are fine.
+    fn check_fields(&self, available: &[String]) -> Result<(), crate::error::Error> {
+        if available.is_empty() || self.fields.iter().any(|field| available.contains(field)) {
+            return Ok(());
+        }
+
+        return Err(crate::error::Error::InvalidValue(format!(
+            "Unknown field(s) {} (available: {})",
+            self.fields.join(", "),
+            available.join(", ")
+        )));
+    }
+
+    /// `value` with the descriptions of its objects (nested ones included) shortened.
+    fn brief_json(&self, value: serde_json::Value) -> serde_json::Value {
+        if !self.brief {
+            return value;
+        }
+
+        return match value {
+            serde_json::Value::Array(items) => serde_json::Value::Array(
+                items
+                    .into_iter()
+                    .map(|item| self.brief_json(item))
+                    .collect(),
+            ),
+            serde_json::Value::Object(object) => serde_json::Value::Object(
+                object
+                    .into_iter()
+                    .map(|(key, value)| match value {
+                        serde_json::Value::String(text)
+                            if key == "description" || key == "desc" =>
+                        {
+                            let text = self.description(&text).to_string();
+                            (key, serde_json::Value::String(text))
+                        }
+                        value => (key, self.brief_json(value)),
+                    })
+                    .collect(),
+            ),
+            value => value,
+        };
+    }
+
+    /// Copy of `table` with the cells of the "Description" columns shortened.
+    fn brief_table(&self, table: &mut comfy_table::Table) -> comfy_table::Table {
+        let header = table.header().cloned().unwrap_or_default();
+        let description_columns: Vec<usize> = header
+            .cell_iter()
+            .enumerate()
+            .filter(|(_, cell)| cell.content() == "Description")
+            .map(|(inx, _)| inx)
+            .collect();
+
+        let mut brief_table = crate::theme::new_table();
+        brief_table.set_header(header);
+
+        for row in table.row_iter() {
+            let mut brief_row = comfy_table::Row::new();
+
+            for (inx, cell) in row.cell_iter().enumerate() {
+                if description_columns.contains(&inx) {
+                    brief_row.add_cell(comfy_table::Cell::new(self.description(&cell.content())));
+                } else {
+                    brief_row.add_cell(cell.clone());
+                }
+            }
+
+            brief_table.add_row(brief_row);
+        }
+
+        return brief_table;
+    }
+}
+
+/// Column or JSON field name as matched by `--fields` ("Data Source" is data_source).
+fn field_key(name: &str) -> String {
+    return name.trim().to_lowercase().replace([' ', '-'], "_");
+}
+
+/// Compact JSON of `value`, one line per element when it is a list.
+fn json_lines(value: &impl Serialize) -> Result<String, crate::error::Error> {
+    let rows = match serde_json::to_value(value)? {
+        serde_json::Value::Array(rows) => rows,
+        value => vec![value],
+    };
+    let mut lines = String::new();
+
+    for row in rows {
+        lines.push_str(&serde_json::to_string(&row)?);
+        lines.push('\n');
+    }
+
+    return Ok(lines);
+}
+
+/// CSV with the header and rows of `table`.
+fn csv_table(table: &mut comfy_table::Table) -> String {
+    let mut rows: Vec<Vec<String>> = Vec::new();
+
+    if let Some(header) = table.header() {
+        rows.push(header.cell_iter().map(|cell| cell.content()).collect());
+    }
+
+    for row in table.row_iter() {
+        rows.push(row.cell_iter().map(|cell| cell.content()).collect());
+    }
+
+    return rows
+        .into_iter()
+        .map(|row| {
+            row.iter()
+                .map(|field| crate::csv_field(field))
+                .collect::<Vec<String>>()
+                .join(",")
+                + "\n"
+        })
+        .collect();
+}
+
+/// First line or sentence of `text`. A period only ends the sentence when it is followed by
+/// an uppercase word and does not close an abbreviation such as "e.g.".
+fn first_sentence(text: &str) -> &str {
+    let text = text.trim();
+
+    for (inx, c) in text.char_indices() {
+        if c == '\n' {
+            return text[..inx].trim_end();
+        }
+
+        if !matches!(c, '.' | '!' | '?') {
+            continue;
+        }
+
+        let word = text[..inx].rsplit(' ').next().unwrap_or_default();
+        let rest = &text[inx + 1..];
+
+        if !word.contains('.')
+            && rest.starts_with(' ')
+            && rest
+                .trim_start()
+                .chars()
+                .next()
+                .is_some_and(|next| next.is_uppercase())
+        {
+            return &text[..=inx];
+        }
+    }
+
+    return text;
+}
+
+#[derive(Subcommand)]
 pub enum Command {
     /// Mitre ATT&CK Framework scraper sub-menu
-    Attack(attack::AttackCommand)
+    #[command(subcommand, alias = "attck")]
+    Attack(attack::AttackCommand),
+    /// Mitre D3FEND countermeasures sub-menu
+    #[command(subcommand)]
+    D3fend(d3fend::D3fendCommand),
+    /// Mitre Engage adversary engagement sub-menu
+    #[command(subcommand)]
+    Engage(engage::EngageCommand),
+    /// NVD CVE lookup
+    Cve(cve::CveCommand),
 }
 
 impl Command {
-    pub fn handle(self, req_client: impl crate::WebFetch) -> Result<(), crate::error::Error> {
+    fn writes_layer(&self) -> bool {
+        return matches!(self, Command::Attack(attack_cmd) if attack_cmd.writes_layer());
+    }
 
+    fn handle(
+        self,
+        options: &GlobalOptions,
+        req_client: impl crate::WebFetch + Sync,
+    ) -> Result<(), crate::error::Error> {
         match self {
-            Command::Attack(attack_cmd) => attack_cmd.handle(req_client)?,
+            Command::Attack(attack_cmd) => attack_cmd.handle(options, req_client)?,
+            Command::D3fend(d3fend_cmd) => d3fend_cmd.handle(options, req_client)?,
+            Command::Engage(engage_cmd) => engage_cmd.handle(options, req_client)?,
+            Command::Cve(cve_cmd) => cve_cmd.handle(options, req_client)?,
         };
 
         return Ok(());
     }
-}
\ No newline at end of file
+}
+
+/// Move the data of the `~/.config/mitre_cli` layout to the XDG directories, warning on
+/// failure so the command still runs (against the new, empty, directories).
+fn migrate_legacy_layout() {
+    match crate::storage::migrate_legacy_layout(&[
+        (crate::attack::cache::CACHE_DIR_NAME, true),
+        (crate::attack::annotations::ANNOTATIONS_FILE_NAME, false),
+    ]) {
+        Ok(moved) => {
+            for path in moved {
+                log::info!(
+                    "Migrated the legacy ~/.config/mitre_cli data to {}",
+                    path.display()
+                );
+            }
+        }
+        Err(err) => log::warn!("Unable to migrate ~/.config/mitre_cli: {}", err),
+    }
+}
+
+/// Print the error of a failed command to STDERR.
+pub fn print_error(error: &crate::error::Error, format: ErrorFormat) {
+    match format {
+        ErrorFormat::TEXT => eprintln!("Error: {}", error),
+        ErrorFormat::JSON => eprintln!(
+            "{}",
+            serde_json::json!({
+                "error": error.kind(),
+                "message": error.to_string(),
+                "exit_code": error.exit_code(),
+            })
+        ),
+    };
+}
@@ -0,0 +1,65 @@
+use std::io::{IsTerminal, Write};
+
+/// Pager used when $PAGER is not set.
+const DEFAULT_PAGER: &'static str = "less";
+/// less options set when $LESS is not: quit when the output fits, keep the colors and do
+/// not clear the screen (as git does).
+const DEFAULT_LESS_OPTIONS: &'static str = "FRX";
+
+/// Print `output`, through the pager when enabled, STDOUT is a terminal and `output` is
+/// taller than it. Falls back to printing directly when the pager cannot be started.
+pub(crate) fn print_paged(output: &str, enabled: bool) -> Result<(), crate::error::Error> {
+    if !enabled || !std::io::stdout().is_terminal() || fits_terminal(output) {
+        print!("{}", output);
+
+        return Ok(());
+    }
+
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| String::from(DEFAULT_PAGER));
+    let mut pager_args = pager.split_whitespace();
+
+    let program = match pager_args.next() {
+        Some(program) if program != "cat" => program,
+        _ => {
+            print!("{}", output);
+
+            return Ok(());
+        }
+    };
+
+    let mut command = std::process::Command::new(program);
+    command.args(pager_args).stdin(std::process::Stdio::piped());
+
+    if std::env::var_os("LESS").is_none() {
+        command.env("LESS", DEFAULT_LESS_OPTIONS);
+    }
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(_) => {
+            print!("{}", output);
+
+            return Ok(());
+        }
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // Quitting the pager before the end closes the pipe, which is not an error.
+        if let Err(err) = stdin.write_all(output.as_bytes()) {
+            if err.kind() != std::io::ErrorKind::BrokenPipe {
+                return Err(err.into());
+            }
+        }
+    }
+
+    child.wait()?;
+
+    return Ok(());
+}
+
+fn fits_terminal(output: &str) -> bool {
+    return match crossterm::terminal::size() {
+        Ok((_, rows)) => output.lines().count() < rows as usize,
+        Err(_) => true,
+    };
+}
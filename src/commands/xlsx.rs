@@ -0,0 +1,278 @@
+/// Longest sheet name allowed by Excel.
+const MAX_SHEET_NAME: usize = 31;
+/// Key of the parent row ID added to the nested rows.
+const PARENT_KEY: &'static str = "__parent_id";
+
+/// Rows of a worksheet, with their column titles.
+#[cfg_attr(not(feature = "xlsx"), allow(dead_code))]
+struct Sheet {
+    name: String,
+    columns: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+/// Sheets of the `--output xlsx` workbook: one per printed table, the nested lists of rows
+/// (e.g. the sub-techniques of the techniques) on their own sheets.
+#[derive(Default)]
+pub(crate) struct Workbook {
+    sheets: Vec<Sheet>,
+}
+
+impl Workbook {
+    pub fn is_empty(&self) -> bool {
+        return self.sheets.is_empty();
+    }
+
+    /// Add the rows of `value` (a list of objects, or a single object) as the sheet `name`.
+    pub fn add_sheet(&mut self, name: &str, value: serde_json::Value) {
+        let rows = match value {
+            serde_json::Value::Array(rows) => rows,
+            value => vec![value],
+        };
+
+        self.add_rows(name, &rows, None);
+    }
+
+    fn add_rows(&mut self, name: &str, rows: &[serde_json::Value], parent_column: Option<&str>) {
+        let objects: Vec<&serde_json::Map<String, serde_json::Value>> =
+            rows.iter().filter_map(|row| row.as_object()).collect();
+
+        let nested_keys: Vec<&String> = objects
+            .iter()
+            .flat_map(|object| object.iter())
+            .filter(|(_, value)| is_row_list(value))
+            .map(|(key, _)| key)
+            .collect();
+        let mut keys: Vec<String> = objects
+            .iter()
+            .flat_map(|object| object.keys())
+            .filter(|key| *key != PARENT_KEY && !nested_keys.contains(key))
+            .cloned()
+            .collect();
+        keys.sort_by_key(|key| (column_rank(key), key.clone()));
+        keys.dedup();
+
+        let mut columns: Vec<String> = keys.iter().map(|key| column_title(key)).collect();
+        let mut cell_keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+
+        if let Some(parent_column) = parent_column {
+            columns.insert(0, parent_column.to_string());
+            cell_keys.insert(0, PARENT_KEY);
+        }
+
+        let mut nested_rows: Vec<(String, Vec<serde_json::Value>)> = Vec::new();
+
+        for object in &objects {
+            let parent_id = object.get("id").map(cell_text).unwrap_or_default();
+
+            for (key, value) in object.iter().filter(|(_, value)| is_row_list(value)) {
+                let children = value
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|child| with_parent(child.clone(), &parent_id));
+
+                match nested_rows
+                    .iter_mut()
+                    .find(|(nested_key, _)| nested_key == key)
+                {
+                    Some((_, nested)) => nested.extend(children),
+                    None => nested_rows.push((key.clone(), children.collect())),
+                }
+            }
+        }
+
+        let sheet = Sheet {
+            name: self.sheet_name(name),
+            columns,
+            rows: objects
+                .iter()
+                .map(|object| {
+                    cell_keys
+                        .iter()
+                        .map(|key| object.get(*key).map(cell_text).unwrap_or_default())
+                        .collect()
+                })
+                .collect(),
+        };
+        self.sheets.push(sheet);
+
+        let parent_title = format!("{} ID", singular(name));
+
+        for (key, nested) in nested_rows {
+            self.add_rows(&column_title(&key), &nested, Some(&parent_title));
+        }
+    }
+
+    /// `name` made a valid and unique sheet name.
+    fn sheet_name(&self, name: &str) -> String {
+        let name: String = name
+            .chars()
+            .filter(|c| !matches!(c, '[' | ']' | ':' | '*' | '?' | '/' | '\\'))
+            .take(MAX_SHEET_NAME)
+            .collect();
+        let name = if name.is_empty() {
+            String::from("Sheet")
+        } else {
+            name
+        };
+        let mut unique_name = name.clone();
+        let mut inx = 1;
+
+        while self
+            .sheets
+            .iter()
+            .any(|sheet| sheet.name.eq_ignore_ascii_case(&unique_name))
+        {
+            inx += 1;
+            let suffix = format!(" ({})", inx);
+            unique_name = name
+                .chars()
+                .take(MAX_SHEET_NAME - suffix.len())
+                .collect::<String>()
+                + &suffix;
+        }
+
+        return unique_name;
+    }
+
+    /// Content of the .xlsx file, with bold frozen headers and filters on every sheet.
+    #[cfg(feature = "xlsx")]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, crate::error::Error> {
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let header_format = rust_xlsxwriter::Format::new().set_bold();
+
+        for sheet in &self.sheets {
+            let worksheet = workbook.add_worksheet();
+            worksheet.set_name(&sheet.name)?;
+
+            for (col, column) in sheet.columns.iter().enumerate() {
+                worksheet.write_string_with_format(0, col as u16, column, &header_format)?;
+            }
+
+            for (row_inx, row) in sheet.rows.iter().enumerate() {
+                for (col, cell) in row.iter().enumerate() {
+                    worksheet.write_string(row_inx as u32 + 1, col as u16, cell)?;
+                }
+            }
+
+            if !sheet.columns.is_empty() {
+                worksheet.set_freeze_panes(1, 0)?;
+                worksheet.autofilter(
+                    0,
+                    0,
+                    sheet.rows.len() as u32,
+                    sheet.columns.len() as u16 - 1,
+                )?;
+            }
+
+            worksheet.autofit();
+        }
+
+        return Ok(workbook.save_to_buffer()?);
+    }
+
+    #[cfg(not(feature = "xlsx"))]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, crate::error::Error> {
+        return Err(crate::error::Error::InvalidValue(String::from(
+            "mitre_cli was built without the xlsx feature",
+        )));
+    }
+}
+
+/// Whether `value` is a nested list of rows, moved to its own sheet.
+fn is_row_list(value: &serde_json::Value) -> bool {
+    return value
+        .as_array()
+        .is_some_and(|items| !items.is_empty() && items.iter().all(|item| item.is_object()));
+}
+
+/// Nested row tagged with the ID of its parent. The sub-technique IDs (".001") are made
+/// whole (T1055.001).
+fn with_parent(mut child: serde_json::Value, parent_id: &str) -> serde_json::Value {
+    if let Some(object) = child.as_object_mut() {
+        if let Some(id) = object.get("id").and_then(|id| id.as_str()) {
+            if id.starts_with('.') {
+                let id = format!("{}{}", parent_id, id);
+                object.insert(String::from("id"), serde_json::Value::String(id));
+            }
+        }
+
+        object.insert(
+            String::from(PARENT_KEY),
+            serde_json::Value::String(parent_id.to_string()),
+        );
+    }
+
+    return child;
+}
+
+/// The IDs and names first, the other columns alphabetically.
+fn column_rank(key: &str) -> u8 {
+    return match key {
+        "id" => 0,
+        "name" => 1,
+        _ => 2,
+    };
+}
+
+fn cell_text(value: &serde_json::Value) -> String {
+    return match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .map(cell_text)
+            .collect::<Vec<String>>()
+            .join("; "),
+        value => value.to_string(),
+    };
+}
+
+/// "data_source" as "Data Source", "id" as "ID".
+fn column_title(key: &str) -> String {
+    return key
+        .split(['_', '-', ' '])
+        .filter(|word| !word.is_empty())
+        .map(|word| match word {
+            "id" => String::from("ID"),
+            "url" => String::from("URL"),
+            word => {
+                let mut chars = word.chars();
+                chars.next().map_or_else(String::new, |first| {
+                    first.to_uppercase().collect::<String>() + chars.as_str()
+                })
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+}
+
+/// "Techniques" as "Technique", for the parent column of the nested sheets.
+fn singular(name: &str) -> &str {
+    return name.strip_suffix('s').unwrap_or(name);
+}
+
+/// Sheet name of the printed type: `TechniquesTable` is "Techniques".
+pub(crate) fn type_sheet_name<T>() -> String {
+    let type_name = std::any::type_name::<T>();
+    let type_name = type_name
+        .split('<')
+        .next()
+        .unwrap_or(type_name)
+        .rsplit("::")
+        .next()
+        .unwrap_or(type_name);
+    let type_name = type_name.strip_suffix("Table").unwrap_or(type_name);
+    let mut name = String::new();
+
+    for c in type_name.chars() {
+        if c.is_uppercase() && !name.is_empty() {
+            name.push(' ');
+        }
+
+        name.push(c);
+    }
+
+    return name;
+}
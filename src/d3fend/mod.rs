@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error, WebFetch};
+
+const D3FEND_OFFENSIVE_TECHNIQUE_URL: &'static str =
+    "https://d3fend.mitre.org/api/offensive-technique/attack/";
+const D3FEND_TECHNIQUE_URL: &'static str = "https://d3fend.mitre.org/technique/d3f:";
+
+#[derive(Debug, Default, Deserialize)]
+struct SparqlValue {
+    value: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SparqlResults {
+    bindings: Vec<HashMap<String, SparqlValue>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SparqlResponse {
+    results: SparqlResults,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct OffensiveTechniqueResponse {
+    off_to_def: SparqlResponse,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct DefensiveTechniqueRow {
+    pub id: String,
+    pub name: String,
+    pub tactic: String,
+    pub artifacts: Vec<String>,
+    pub url: String,
+}
+
+impl DefensiveTechniqueRow {
+    fn add_artifact(&mut self, artifact: String) {
+        if !artifact.is_empty() && !self.artifacts.contains(&artifact) {
+            self.artifacts.push(artifact);
+        }
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for DefensiveTechniqueRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.tactic))
+            .add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(self.name))
+            .add_cell(comfy_table::Cell::new(self.artifacts.join(", ")))
+            .add_cell(comfy_table::Cell::new(self.url));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct DefensiveTechniquesTable(pub Vec<DefensiveTechniqueRow>);
+
+impl DefensiveTechniquesTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.0.len();
+    }
+}
+
+impl IntoIterator for DefensiveTechniquesTable {
+    type Item = DefensiveTechniqueRow;
+    type IntoIter = std::vec::IntoIter<DefensiveTechniqueRow>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.0.into_iter();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for DefensiveTechniquesTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table
+            .set_header(crate::theme::header(&[
+                "D3FEND Tactic",
+                "ID",
+                "Name",
+                "Digital Artifacts",
+                "URL",
+            ]))
+            .add_rows(
+                self.into_iter()
+                    .map(|row| row.into())
+                    .collect::<Vec<comfy_table::Row>>(),
+            );
+
+        return table;
+    }
+}
+
+impl From<SparqlResults> for DefensiveTechniquesTable {
+    fn from(results: SparqlResults) -> Self {
+        let mut techniques: Vec<DefensiveTechniqueRow> = Vec::new();
+
+        for mut binding in results.bindings {
+            let id = match binding.remove("def_tech") {
+                Some(def_tech) => ontology_fragment(&def_tech.value),
+                None => continue,
+            };
+            let artifact = binding
+                .remove("def_artifact_label")
+                .map_or(String::new(), |artifact| artifact.value);
+
+            if let Some(technique) = techniques.iter_mut().find(|technique| technique.id == id) {
+                technique.add_artifact(artifact);
+                continue;
+            }
+
+            let mut technique = DefensiveTechniqueRow {
+                url: format!("{}{}", D3FEND_TECHNIQUE_URL, id),
+                id,
+                name: binding
+                    .remove("def_tech_label")
+                    .map_or(String::new(), |label| label.value),
+                tactic: binding
+                    .remove("def_tactic_label")
+                    .map_or(String::new(), |label| label.value),
+                ..Default::default()
+            };
+            technique.add_artifact(artifact);
+            techniques.push(technique);
+        }
+
+        return Self(techniques);
+    }
+}
+
+fn ontology_fragment(uri: &str) -> String {
+    return uri.rsplit('#').next().unwrap_or(uri).to_string();
+}
+
+/// Retrieve the D3FEND defensive techniques that counter the given ATT&CK technique.
+pub fn fetch_defensive_techniques(
+    technique_id: &str,
+    web_client: &impl WebFetch,
+) -> Result<DefensiveTechniquesTable, error::Error> {
+    let url = format!(
+        "{}{}.json",
+        D3FEND_OFFENSIVE_TECHNIQUE_URL,
+        technique_id.to_uppercase()
+    );
+    let fetched_response = web_client.fetch(url.as_str())?;
+    let response: OffensiveTechniqueResponse = serde_json::from_str(&fetched_response)?;
+
+    return Ok(response.off_to_def.results.into());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fakers::FakeHttpReqwest;
+
+    const TEST_TECHNIQUE_ID: &'static str = "T1003";
+    const TEST_DEFENSIVE_TECHNIQUES: usize = 4;
+
+    #[test]
+    fn test_fetch_defensive_techniques() -> Result<(), error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default()
+            .set_success_response(include_str!("json/offensive_technique_t1003.json").to_string());
+
+        let defensive_techniques = fetch_defensive_techniques(TEST_TECHNIQUE_ID, &fake_reqwest)?;
+
+        assert_eq!(defensive_techniques.len(), TEST_DEFENSIVE_TECHNIQUES);
+
+        for technique in defensive_techniques {
+            assert!(
+                !technique.id.contains('#'),
+                "D3FEND ID should not be an URI"
+            );
+            assert_ne!(
+                technique.name.is_empty(),
+                true,
+                "D3FEND name should not be empty"
+            );
+            assert_ne!(
+                technique.tactic.is_empty(),
+                true,
+                "D3FEND tactic should not be empty"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_artifacts_of_repeated_techniques() -> Result<(), error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default()
+            .set_success_response(include_str!("json/offensive_technique_t1003.json").to_string());
+
+        let defensive_techniques = fetch_defensive_techniques(TEST_TECHNIQUE_ID, &fake_reqwest)?;
+        let process_spawn_analysis = defensive_techniques
+            .into_iter()
+            .find(|technique| technique.id == "ProcessSpawnAnalysis")
+            .unwrap();
+
+        assert_eq!(process_spawn_analysis.artifacts.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_json_response() {
+        let fake_reqwest =
+            FakeHttpReqwest::default().set_success_response(String::from("<html></html>"));
+
+        assert!(fetch_defensive_techniques(TEST_TECHNIQUE_ID, &fake_reqwest).is_err());
+    }
+}
@@ -0,0 +1,307 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error, WebFetch};
+
+const ENGAGE_DATA_URL: &'static str =
+    "https://raw.githubusercontent.com/mitre/engage/main/Data/json/";
+const ENGAGE_ATTACK_MAPPING_FILE: &'static str = "attack_mapping.json";
+
+pub enum EntityKind {
+    GOAL,
+    APPROACH,
+    ACTIVITY,
+}
+
+impl FromStr for EntityKind {
+    type Err = error::Error;
+
+    fn from_str(kind_str: &str) -> Result<Self, Self::Err> {
+        match kind_str {
+            "goals" => Ok(Self::GOAL),
+            "approaches" => Ok(Self::APPROACH),
+            "activities" => Ok(Self::ACTIVITY),
+            _ => Err(error::Error::InvalidValue(format!(
+                "{} is not a valid Engage entity type (goals, approaches, activities)",
+                kind_str
+            ))),
+        }
+    }
+}
+
+impl Into<&'static str> for EntityKind {
+    fn into(self) -> &'static str {
+        match self {
+            Self::GOAL => "goal_details.json",
+            Self::APPROACH => "approach_details.json",
+            Self::ACTIVITY => "activity_details.json",
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct EntityDetails {
+    name: String,
+    #[serde(default)]
+    description: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct EngageEntityRow {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for EngageEntityRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(self.name))
+            .add_cell(comfy_table::Cell::new(self.description));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct EngageEntitiesTable(pub Vec<EngageEntityRow>);
+
+impl EngageEntitiesTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.0.len();
+    }
+}
+
+impl IntoIterator for EngageEntitiesTable {
+    type Item = EngageEntityRow;
+    type IntoIter = std::vec::IntoIter<EngageEntityRow>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.0.into_iter();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for EngageEntitiesTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table
+            .set_header(crate::theme::header(&["ID", "Name", "Description"]))
+            .add_rows(
+                self.into_iter()
+                    .map(|row| row.into())
+                    .collect::<Vec<comfy_table::Row>>(),
+            );
+
+        return table;
+    }
+}
+
+impl From<BTreeMap<String, EntityDetails>> for EngageEntitiesTable {
+    fn from(entities: BTreeMap<String, EntityDetails>) -> Self {
+        return Self(
+            entities
+                .into_iter()
+                .map(|(id, details)| EngageEntityRow {
+                    id,
+                    name: details.name,
+                    description: details.description,
+                })
+                .collect(),
+        );
+    }
+}
+
+/// Link between an ATT&CK technique and an Engage activity, through the adversary
+/// vulnerability the activity takes advantage of.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct AttackMappingRow {
+    #[serde(rename = "attack_id")]
+    pub technique_id: String,
+    #[serde(rename = "attack_technique")]
+    pub technique: String,
+    #[serde(rename = "eav_id")]
+    pub vulnerability_id: String,
+    #[serde(rename = "eav")]
+    pub vulnerability: String,
+    #[serde(rename = "eac_id")]
+    pub activity_id: String,
+    #[serde(rename = "eac")]
+    pub activity: String,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for AttackMappingRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.technique_id))
+            .add_cell(comfy_table::Cell::new(self.technique))
+            .add_cell(comfy_table::Cell::new(self.activity_id))
+            .add_cell(comfy_table::Cell::new(self.activity))
+            .add_cell(comfy_table::Cell::new(self.vulnerability));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct AttackMappingsTable(pub Vec<AttackMappingRow>);
+
+impl AttackMappingsTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.0.len();
+    }
+}
+
+impl IntoIterator for AttackMappingsTable {
+    type Item = AttackMappingRow;
+    type IntoIter = std::vec::IntoIter<AttackMappingRow>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.0.into_iter();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for AttackMappingsTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table
+            .set_header(crate::theme::header(&[
+                "ATT&CK ID",
+                "Technique",
+                "Activity ID",
+                "Activity",
+                "Adversary Vulnerability",
+            ]))
+            .add_rows(
+                self.into_iter()
+                    .map(|row| row.into())
+                    .collect::<Vec<comfy_table::Row>>(),
+            );
+
+        return table;
+    }
+}
+
+/// Retrieve the Engage goals, approaches or activities.
+pub fn fetch_entities(
+    kind: EntityKind,
+    web_client: &impl WebFetch,
+) -> Result<EngageEntitiesTable, error::Error> {
+    let file_name: &str = kind.into();
+    let fetched_response = web_client.fetch(&format!("{}{}", ENGAGE_DATA_URL, file_name))?;
+    let entities: BTreeMap<String, EntityDetails> = serde_json::from_str(&fetched_response)?;
+
+    return Ok(entities.into());
+}
+
+fn fetch_attack_mappings(
+    web_client: &impl WebFetch,
+) -> Result<Vec<AttackMappingRow>, error::Error> {
+    let fetched_response = web_client.fetch(&format!(
+        "{}{}",
+        ENGAGE_DATA_URL, ENGAGE_ATTACK_MAPPING_FILE
+    ))?;
+
+    return Ok(serde_json::from_str(&fetched_response)?);
+}
+
+/// Retrieve the Engage activities that take advantage of the given ATT&CK technique.
+pub fn fetch_technique_activities(
+    technique_id: &str,
+    web_client: &impl WebFetch,
+) -> Result<AttackMappingsTable, error::Error> {
+    let technique_id = technique_id.to_uppercase();
+
+    return Ok(AttackMappingsTable(
+        fetch_attack_mappings(web_client)?
+            .into_iter()
+            .filter(|mapping| mapping.technique_id == technique_id)
+            .collect(),
+    ));
+}
+
+/// Retrieve the ATT&CK techniques the given Engage activity can be used against.
+pub fn fetch_activity_techniques(
+    activity_id: &str,
+    web_client: &impl WebFetch,
+) -> Result<AttackMappingsTable, error::Error> {
+    let activity_id = activity_id.to_uppercase();
+
+    return Ok(AttackMappingsTable(
+        fetch_attack_mappings(web_client)?
+            .into_iter()
+            .filter(|mapping| mapping.activity_id == activity_id)
+            .collect(),
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fakers::FakeHttpReqwest;
+
+    #[test]
+    fn test_fetch_goals() -> Result<(), error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default()
+            .set_success_response(include_str!("json/goal_details.json").to_string());
+
+        let goals = fetch_entities(EntityKind::GOAL, &fake_reqwest)?;
+
+        assert_eq!(goals.len(), 3);
+
+        for goal in goals {
+            assert!(goal.id.starts_with("EGO"));
+            assert_ne!(goal.name.is_empty(), true, "Goal name should not be empty");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch_technique_activities() -> Result<(), error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default()
+            .set_success_response(include_str!("json/attack_mapping.json").to_string());
+
+        let activities = fetch_technique_activities("t1003", &fake_reqwest)?;
+
+        assert_eq!(activities.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch_activity_techniques() -> Result<(), error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default()
+            .set_success_response(include_str!("json/attack_mapping.json").to_string());
+
+        let techniques = fetch_activity_techniques("EAC0005", &fake_reqwest)?;
+
+        assert_eq!(techniques.len(), 3);
+        assert!(techniques
+            .into_iter()
+            .any(|mapping| mapping.technique_id == "T1003.001"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_entity_kind() {
+        assert!(matches!(
+            EntityKind::from_str("vulnerabilities"),
+            Err(error::Error::InvalidValue(_))
+        ));
+    }
+}
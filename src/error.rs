@@ -1,13 +1,238 @@
-#[derive(Debug, PartialEq, Clone)]
+use std::{fmt, ops::Deref, sync::Arc};
+
+/// Errors of the scrapers, the cache and the command line interface.
+///
+/// The HTTP, IO and JSON variants keep the underlying error (`std::error::Error::source`)
+/// and, for the HTTP ones, the requested URL and response status.
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum Error {
+    #[error("{0}")]
     Request(String),
+    #[error("{0}")]
     General(String),
-    InvalidValue(String)
+    #[error("{0}")]
+    InvalidValue(String),
+    /// The requested page or entity does not exist.
+    #[error("{0}")]
+    NotFound(String),
+    /// Unreadable data: malformed cache files, imported documents...
+    #[error("{0}")]
+    Parse(String),
+    /// Failed HTTP request, `status` being set for the HTTP error responses (404...).
+    #[error("{}", http_message(.url.as_deref(), &**.source))]
+    Http {
+        url: Option<String>,
+        status: Option<u16>,
+        #[source]
+        source: Shared<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("IO error: {0}")]
+    Io(#[source] Shared<std::io::Error>),
+    #[error("JSON error: {0}")]
+    Json(#[source] Shared<serde_json::Error>),
+    /// Failed fetch of an entity (or listing) page, see [`FetchContext`].
+    #[error("{}", fetch_message(.entity, .url, *.by_id, .source))]
+    Fetch {
+        entity: String,
+        url: String,
+        /// Whether the entity is looked up by ID, `attack search` being suggested when not found.
+        by_id: bool,
+        #[source]
+        source: Shared<Error>,
+    },
+}
+
+/// Underlying error of an [`Error`], shared by its clones.
+///
+/// It is not an error itself: `source()` goes through the `Arc` and returns the wrapped
+/// error, which callers can downcast to its own type (`std::io::Error`...).
+#[derive(Debug)]
+pub struct Shared<E: ?Sized>(pub Arc<E>);
+
+impl<E: ?Sized> Clone for Shared<E> {
+    fn clone(&self) -> Self {
+        return Self(Arc::clone(&self.0));
+    }
+}
+
+impl<E: ?Sized> Deref for Shared<E> {
+    type Target = E;
+
+    fn deref(&self) -> &E {
+        return &self.0;
+    }
+}
+
+impl<E: fmt::Display + ?Sized> fmt::Display for Shared<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return self.0.fmt(f);
+    }
+}
+
+fn http_message(url: Option<&str>, source: &(dyn std::error::Error + Send + Sync)) -> String {
+    return match url {
+        Some(url) => format!("Request to {} failed: {}", url, source),
+        None => format!("Request failed: {}", source),
+    };
+}
+
+fn fetch_message(entity: &str, url: &str, by_id: bool, source: &Error) -> String {
+    if !source.is_not_found() {
+        return format!("Unable to fetch {} from {}: {}", entity, url, source);
+    }
+
+    if by_id {
+        return format!(
+            "{} not found at {}, run 'attack search any {}' to look for the right ID",
+            entity, url, entity
+        );
+    }
+
+    return format!("{} not found at {}", entity, url);
+}
+
+impl Error {
+    /// Exit code of the binary failing with the error, stable across releases.
+    pub fn exit_code(&self) -> u8 {
+        if self.is_not_found() {
+            return 4;
+        }
+
+        return match self {
+            Self::Fetch { source, .. } => source.exit_code(),
+            Self::General(_) | Self::Io(_) => 1,
+            Self::InvalidValue(_) => 2,
+            Self::Request(_) | Self::Http { .. } => 3,
+            Self::NotFound(_) => 4,
+            Self::Parse(_) | Self::Json(_) => 5,
+        };
+    }
+
+    /// Name of the error kind in the JSON error output.
+    pub fn kind(&self) -> &'static str {
+        if self.is_not_found() {
+            return "not_found";
+        }
+
+        return match self {
+            Self::Fetch { source, .. } => source.kind(),
+            Self::General(_) => "general",
+            Self::Io(_) => "io",
+            Self::InvalidValue(_) => "invalid_value",
+            Self::Request(_) | Self::Http { .. } => "request",
+            Self::NotFound(_) => "not_found",
+            Self::Parse(_) | Self::Json(_) => "parse",
+        };
+    }
+
+    /// Whether the page or entity does not exist, HTTP 404 responses included.
+    pub fn is_not_found(&self) -> bool {
+        return match self {
+            Self::NotFound(_) => true,
+            Self::Http { status, .. } => *status == Some(404),
+            Self::Fetch { source, .. } => source.is_not_found(),
+            _ => false,
+        };
+    }
+
+    /// URL of the failed HTTP request, when known.
+    pub fn url(&self) -> Option<&str> {
+        return match self {
+            Self::Http { url, .. } => url.as_deref(),
+            Self::Fetch { url, .. } => Some(url),
+            _ => None,
+        };
+    }
+}
+
+/// Errors are equal when of the same kind with the same message, the underlying errors
+/// not being comparable.
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        return std::mem::discriminant(self) == std::mem::discriminant(other)
+            && self.to_string() == other.to_string();
+    }
+}
+
+/// Context layer of the `fetch_*` functions, naming the entity and URL of a failed request.
+pub trait FetchContext<T> {
+    /// Context of the page of the entity `id` (T1548, G0007...).
+    fn entity_context(self, id: &str, url: &str) -> Result<T, Error>;
+
+    /// Context of a listing page, `listing` naming it ("enterprise techniques"...).
+    fn listing_context(self, listing: &str, url: &str) -> Result<T, Error>;
 }
 
+impl<T> FetchContext<T> for Result<T, Error> {
+    fn entity_context(self, id: &str, url: &str) -> Result<T, Error> {
+        return self.map_err(|err| Error::Fetch {
+            entity: id.to_string(),
+            url: url.to_string(),
+            by_id: true,
+            source: Shared(Arc::new(err)),
+        });
+    }
+
+    fn listing_context(self, listing: &str, url: &str) -> Result<T, Error> {
+        return self.map_err(|err| Error::Fetch {
+            entity: format!("{} listing", listing),
+            url: url.to_string(),
+            by_id: false,
+            source: Shared(Arc::new(err)),
+        });
+    }
+}
+
+#[cfg(feature = "reqwest")]
 impl From<reqwest::Error> for Error {
     fn from(err: reqwest::Error) -> Self {
-        return Self::Request(format!("Reqwest error: {}", err.to_string()));
+        return Self::Http {
+            url: err.url().map(|url| url.to_string()),
+            status: err.status().map(|status| status.as_u16()),
+            source: Shared(Arc::new(err.without_url())),
+        };
+    }
+}
+
+#[cfg(feature = "ureq")]
+impl From<ureq::Error> for Error {
+    fn from(err: ureq::Error) -> Self {
+        let (url, status) = match &err {
+            ureq::Error::Status(status, response) => {
+                (Some(response.get_url().to_string()), Some(*status))
+            }
+            ureq::Error::Transport(transport) => (transport.url().map(|url| url.to_string()), None),
+        };
+
+        return Self::Http {
+            url,
+            status,
+            source: Shared(Arc::new(err)),
+        };
+    }
+}
+
+#[cfg(feature = "xlsx")]
+impl From<rust_xlsxwriter::XlsxError> for Error {
+    fn from(err: rust_xlsxwriter::XlsxError) -> Self {
+        return Self::General(format!("XLSX error: {}", err));
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for Error {
+    fn from(err: rusqlite::Error) -> Self {
+        return Self::General(format!("SQLite error: {}", err));
+    }
+}
+
+#[cfg(feature = "fulltext")]
+impl From<tantivy::TantivyError> for Error {
+    fn from(err: tantivy::TantivyError) -> Self {
+        return match err {
+            tantivy::TantivyError::IoError(err) => Self::Io(Shared(err)),
+            err => Self::General(format!("Full-text index error: {}", err)),
+        };
     }
 }
 
@@ -22,3 +247,91 @@ impl From<String> for Error {
         Error::General(str_err)
     }
 }
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        return Self::Json(Shared(Arc::new(err)));
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        return Self::Io(Shared(Arc::new(err)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_error_sources() {
+        let err: Error = serde_json::from_str::<serde_json::Value>("{")
+            .unwrap_err()
+            .into();
+        assert_eq!((err.kind(), err.exit_code()), ("parse", 5));
+        assert!(err
+            .source()
+            .and_then(|source| source.downcast_ref::<serde_json::Error>())
+            .is_some());
+
+        let err: Error = std::io::Error::new(std::io::ErrorKind::NotFound, "cache.json").into();
+        assert_eq!(err.to_string(), "IO error: cache.json");
+        assert!(err
+            .source()
+            .and_then(|source| source.downcast_ref::<std::io::Error>())
+            .is_some());
+
+        let err = Error::Http {
+            url: Some(String::from("https://attack.mitre.org/techniques/T9999")),
+            status: Some(404),
+            source: Shared(Arc::new(std::io::Error::other(
+                "HTTP status client error (404 Not Found)",
+            ))),
+        };
+        assert!(err.is_not_found());
+        assert_eq!((err.kind(), err.exit_code()), ("not_found", 4));
+        assert_eq!(err.url(), Some("https://attack.mitre.org/techniques/T9999"));
+        assert!(err
+            .source()
+            .and_then(|source| source.downcast_ref::<std::io::Error>())
+            .is_some());
+        assert_eq!(err.clone(), err);
+    }
+
+    #[test]
+    fn test_fetch_context() {
+        let url = "https://attack.mitre.org/techniques/T9999";
+        let err = Err::<(), Error>(Error::Http {
+            url: Some(String::from(url)),
+            status: Some(404),
+            source: Shared(Arc::new(std::io::Error::other(
+                "HTTP status client error (404 Not Found)",
+            ))),
+        })
+        .entity_context("T9999", url)
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "T9999 not found at https://attack.mitre.org/techniques/T9999, \
+             run 'attack search any T9999' to look for the right ID"
+        );
+        assert_eq!((err.kind(), err.exit_code()), ("not_found", 4));
+        assert_eq!(err.url(), Some(url));
+        assert!(err
+            .source()
+            .and_then(|source| source.downcast_ref::<Error>())
+            .is_some_and(Error::is_not_found));
+
+        let err = Err::<(), Error>(Error::Request(String::from("connection refused")))
+            .listing_context("groups", "https://attack.mitre.org/groups/")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unable to fetch groups listing from https://attack.mitre.org/groups/: \
+             connection refused"
+        );
+        assert_eq!((err.kind(), err.exit_code()), ("request", 3));
+    }
+}
@@ -1,49 +1,412 @@
+//! Scraper for the MITRE frameworks (ATT&CK, D3FEND, Engage) and the NVD CVE API.
+//!
+//! Every framework module exposes `fetch_*` functions returning serde models, taking any
+//! [`WebFetch`] implementation ([`HttpReqwest`] for the real sites). ATT&CK entities can be
+//! kept offline with [`attack::cache::Cache`]. Async services can run the same scrapers
+//! with [`async_fetch::scrape`].
+//!
+//! The command line interface (and the comfy-table rendering of the models) lives behind
+//! the default `cli` feature, library users can disable it:
+//!
+//! ```toml
+//! mitre_cli = { version = "1.0", default-features = false }
+//! ```
+//!
+//! ```no_run
+//! use mitre_cli::{attack::groups, error::Error, WebFetch};
+//!
+//! // Any backend: `HttpClient::new()` with the `reqwest` or `ureq` feature, a mirror...
+//! fn print_group(web_client: &impl WebFetch) -> Result<(), Error> {
+//!     let group = groups::fetch_group("G0018", web_client)?;
+//!     println!("{}: {}", group.id, group.name);
+//!
+//!     return Ok(());
+//! }
+//! ```
+
+#![allow(
+    clippy::needless_return,
+    clippy::from_over_into,
+    clippy::redundant_static_lifetimes,
+    clippy::bool_assert_comparison
+)]
+
 #[macro_use]
 extern crate lazy_static;
+/// Async fetching for library users running inside an async runtime.
+pub mod async_fetch;
+/// ATT&CK scrapers, models and local cache.
 pub mod attack;
 pub mod error;
+/// Command line interface.
+#[cfg(feature = "cli")]
 pub mod commands;
+/// D3FEND defensive techniques mapped to ATT&CK techniques.
+pub mod d3fend;
+/// Engage goals, approaches and activities.
+pub mod engage;
+/// Logger of the binary, with its verbosity and optional log file.
+pub mod logging;
+/// Security controls (NIST 800-53, CIS Controls) mapped to ATT&CK techniques.
+pub mod mappings;
+pub mod metrics;
+/// Fetching the ATT&CK pages from a mirror of the website.
+pub mod mirror;
+/// NVD CVE lookups.
+pub mod nvd;
+/// Offline mode, serving from the local cache only.
+pub mod offline;
+/// Local JSON storage under the mitre_cli base directory.
+pub mod storage;
+/// Table styling of the command line output.
+#[cfg(feature = "cli")]
+pub mod theme;
+pub mod throttle;
 
 lazy_static! {
     static ref RE: regex::Regex = regex::Regex::new(r"\[[0-9]+\]").unwrap();
 }
 
+static KEEP_CITATIONS: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Keep the `[n]` citations in the texts scraped from now on, `n` being the number of the
+/// entity reference. They are stripped by default.
+pub fn set_keep_citations(keep_citations: bool) {
+    KEEP_CITATIONS.store(keep_citations, std::sync::atomic::Ordering::Relaxed);
+}
+
+pub fn keeps_citations() -> bool {
+    return KEEP_CITATIONS.load(std::sync::atomic::Ordering::Relaxed);
+}
+
+/// CSV field, quoted when it holds a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        return format!("\"{}\"", field.replace('"', "\"\""));
+    }
+
+    return field.to_string();
+}
+
+/// Fields of a CSV line, the quoted ones unquoted.
+fn csv_record(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+
+    return fields;
+}
+
 fn remove_ext_link_ref(text: &str) -> String {
-    return RE
-        .replace_all(text, "")
+    let text = if keeps_citations() {
+        std::borrow::Cow::Borrowed(text)
+    } else {
+        RE.replace_all(text, "")
+    };
+
+    return text
         .split_whitespace()
         .filter(|text| !text.is_empty())
         .collect::<Vec<&str>>()
         .join(" ");
 }
 
+/// HTTP GET returning the response body, faked in tests.
 pub trait WebFetch {
     fn fetch(&self, url: &str) -> Result<String, error::Error>;
+
+    /// Fetch `url` sending the given extra headers (API keys, ...).
+    fn fetch_with_headers(
+        &self,
+        url: &str,
+        _headers: &[(&str, &str)],
+    ) -> Result<String, error::Error> {
+        return self.fetch(url);
+    }
+
+    /// HTTP POST of the JSON `body` to `url` (webhooks), returning the response body.
+    fn post_json(&self, url: &str, _body: &str) -> Result<String, error::Error> {
+        return Err(error::Error::Request(format!(
+            "Unable to POST to {}, the client only fetches pages",
+            url
+        )));
+    }
+}
+
+/// Borrowed clients, to run several commands with the same one.
+impl<W: WebFetch + ?Sized> WebFetch for &W {
+    fn fetch(&self, url: &str) -> Result<String, error::Error> {
+        return (**self).fetch(url);
+    }
+
+    fn fetch_with_headers(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<String, error::Error> {
+        return (**self).fetch_with_headers(url, headers);
+    }
+
+    fn post_json(&self, url: &str, body: &str) -> Result<String, error::Error> {
+        return (**self).post_json(url, body);
+    }
 }
 
-pub struct HttpReqwest;
+/// Environment variable of the connect timeout of the HTTP clients, in seconds.
+pub const CONNECT_TIMEOUT_ENV: &'static str = "MITRE_CLI_CONNECT_TIMEOUT";
+/// Environment variable of the read timeout of the HTTP clients, in seconds.
+pub const READ_TIMEOUT_ENV: &'static str = "MITRE_CLI_TIMEOUT";
 
+/// Timeouts of the HTTP clients, so a hung server fails the request instead of stalling a
+/// whole sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeouts {
+    /// Time to establish the connection.
+    pub connect: std::time::Duration,
+    /// Time to read the response: reqwest applies it to the whole request, ureq to every read.
+    pub read: std::time::Duration,
+}
+
+impl Timeouts {
+    pub const DEFAULT_CONNECT: std::time::Duration = std::time::Duration::from_secs(10);
+    pub const DEFAULT_READ: std::time::Duration = std::time::Duration::from_secs(60);
+
+    /// Default timeouts, overridden by `$MITRE_CLI_CONNECT_TIMEOUT` and `$MITRE_CLI_TIMEOUT`.
+    pub fn from_env() -> Result<Self, error::Error> {
+        return Ok(Self {
+            connect: env_seconds(CONNECT_TIMEOUT_ENV)?.unwrap_or(Self::DEFAULT_CONNECT),
+            read: env_seconds(READ_TIMEOUT_ENV)?.unwrap_or(Self::DEFAULT_READ),
+        });
+    }
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        return Self {
+            connect: Self::DEFAULT_CONNECT,
+            read: Self::DEFAULT_READ,
+        };
+    }
+}
+
+fn env_seconds(var: &str) -> Result<Option<std::time::Duration>, error::Error> {
+    return match std::env::var(var) {
+        Ok(seconds) if !seconds.trim().is_empty() => match seconds.trim().parse() {
+            Ok(seconds) if seconds > 0 => Ok(Some(std::time::Duration::from_secs(seconds))),
+            _ => Err(error::Error::InvalidValue(format!(
+                "Invalid {} '{}', expected a positive number of seconds",
+                var, seconds
+            ))),
+        },
+        _ => Ok(None),
+    };
+}
+
+/// `WebFetch` over the network, sharing the connections between requests.
+#[cfg(feature = "reqwest")]
+pub struct HttpReqwest {
+    client: reqwest::blocking::Client,
+}
+
+#[cfg(feature = "reqwest")]
 impl WebFetch for HttpReqwest {
     fn fetch(&self, url: &str) -> Result<String, error::Error> {
-        match reqwest::blocking::get(url) {
-            Ok(get_response) => match get_response.error_for_status() {
-                Ok(resp) => match resp.text() {
-                    Ok(text) => Ok(text),
-                    Err(err) => Err(error::Error::from(err))
-                },
-                Err(err) => Err(error::Error::from(err))
-            },
-            Err(err) => Err(error::Error::from(err))
+        return self.fetch_with_headers(url, &[]);
+    }
+
+    /// Throttled (HTTP 429) requests are retried after the wait requested by the server,
+    /// pausing the caller (and so the whole sync) meanwhile.
+    fn fetch_with_headers(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<String, error::Error> {
+        let mut retries = 0;
+
+        loop {
+            throttle::wait_for_resume();
+            let mut request = self.client.get(url);
+
+            for (name, value) in headers {
+                request = request.header(*name, *value);
+            }
+
+            log::debug!("GET {}", url);
+            let response = request.send()?;
+
+            if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+                && retries < throttle::MAX_THROTTLED_RETRIES
+            {
+                let wait = response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|retry_after| retry_after.to_str().ok())
+                    .and_then(|retry_after| {
+                        throttle::parse_retry_after(retry_after, chrono::Utc::now())
+                    })
+                    .unwrap_or(throttle::DEFAULT_RETRY_AFTER);
+
+                eprintln!("[!] Throttled by {}, retrying in {}s", url, wait.as_secs());
+                throttle::pause(wait);
+                retries += 1;
+
+                continue;
+            }
+
+            return Ok(response.error_for_status()?.text()?);
         }
     }
+
+    fn post_json(&self, url: &str, body: &str) -> Result<String, error::Error> {
+        return Ok(self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string())
+            .send()?
+            .error_for_status()?
+            .text()?);
+    }
 }
 
+#[cfg(feature = "reqwest")]
 impl HttpReqwest {
+    /// Client with the default timeouts.
+    ///
+    /// # Panics
+    ///
+    /// When the TLS backend cannot be initialized, like `reqwest::blocking::Client::new`.
+    pub fn new() -> Self {
+        return Self::with_timeouts(Timeouts::default())
+            .expect("Unable to build the HTTP client, the TLS backend failed to initialize");
+    }
+
+    pub fn with_timeouts(timeouts: Timeouts) -> Result<Self, error::Error> {
+        return Ok(Self {
+            client: reqwest::blocking::Client::builder()
+                .connect_timeout(timeouts.connect)
+                .timeout(timeouts.read)
+                .build()?,
+        });
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl Default for HttpReqwest {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+/// `WebFetch` over the network with ureq, for the minimal builds leaving reqwest out.
+#[cfg(feature = "ureq")]
+pub struct HttpUreq {
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "ureq")]
+impl WebFetch for HttpUreq {
+    fn fetch(&self, url: &str) -> Result<String, error::Error> {
+        return self.fetch_with_headers(url, &[]);
+    }
+
+    /// Throttled (HTTP 429) requests are retried after the wait requested by the server,
+    /// pausing the caller (and so the whole sync) meanwhile.
+    fn fetch_with_headers(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<String, error::Error> {
+        let mut retries = 0;
+
+        loop {
+            throttle::wait_for_resume();
+            let mut request = self.agent.get(url);
+
+            for (name, value) in headers {
+                request = request.set(name, value);
+            }
+
+            log::debug!("GET {}", url);
+
+            match request.call() {
+                Err(ureq::Error::Status(429, response))
+                    if retries < throttle::MAX_THROTTLED_RETRIES =>
+                {
+                    let wait = response
+                        .header("Retry-After")
+                        .and_then(|retry_after| {
+                            throttle::parse_retry_after(retry_after, chrono::Utc::now())
+                        })
+                        .unwrap_or(throttle::DEFAULT_RETRY_AFTER);
+
+                    eprintln!("[!] Throttled by {}, retrying in {}s", url, wait.as_secs());
+                    throttle::pause(wait);
+                    retries += 1;
+                }
+                response => return Ok(response?.into_string()?),
+            };
+        }
+    }
+
+    fn post_json(&self, url: &str, body: &str) -> Result<String, error::Error> {
+        return Ok(self
+            .agent
+            .post(url)
+            .set("Content-Type", "application/json")
+            .send_string(body)?
+            .into_string()?);
+    }
+}
+
+#[cfg(feature = "ureq")]
+impl HttpUreq {
     pub fn new() -> Self {
-        return Self{};
+        return Self {
+            agent: Self::agent(Timeouts::default()),
+        };
+    }
+
+    /// Same signature as `HttpReqwest::with_timeouts`, building the agent cannot fail.
+    pub fn with_timeouts(timeouts: Timeouts) -> Result<Self, error::Error> {
+        return Ok(Self {
+            agent: Self::agent(timeouts),
+        });
+    }
+
+    fn agent(timeouts: Timeouts) -> ureq::Agent {
+        return ureq::AgentBuilder::new()
+            .timeout_connect(timeouts.connect)
+            .timeout_read(timeouts.read)
+            .build();
     }
 }
 
+#[cfg(feature = "ureq")]
+impl Default for HttpUreq {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+/// `WebFetch` used by the binary: reqwest, or ureq in the builds leaving reqwest out.
+#[cfg(feature = "reqwest")]
+pub type HttpClient = HttpReqwest;
+#[cfg(all(feature = "ureq", not(feature = "reqwest")))]
+pub type HttpClient = HttpUreq;
+
 #[cfg(test)]
 mod fakers {
     use super::WebFetch;
@@ -52,7 +415,8 @@ mod fakers {
     #[derive(Default)]
     pub struct FakeHttpReqwest {
         success_response: String,
-        error_response: Option<Error>
+        error_response: Option<Error>,
+        posted_bodies: std::sync::Mutex<Vec<String>>
     }
     
     impl FakeHttpReqwest {
@@ -68,6 +432,11 @@ mod fakers {
     
             return self;
         }
+
+        /// Bodies of the `post_json` calls, in order.
+        pub fn posted_bodies(&self) -> Vec<String> {
+            return self.posted_bodies.lock().unwrap().clone();
+        }
     }
     
     impl WebFetch for FakeHttpReqwest {
@@ -78,5 +447,15 @@ mod fakers {
 
             return Ok(self.success_response.clone());
         }
+
+        fn post_json(&self, _: &str, body: &str) -> Result<String, Error> {
+            if let Some(err) = &self.error_response {
+                return Err(err.clone());
+            }
+
+            self.posted_bodies.lock().unwrap().push(body.to_string());
+
+            return Ok(String::new());
+        }
     }
 }
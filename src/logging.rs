@@ -0,0 +1,150 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::Mutex,
+};
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+use crate::error;
+
+/// Logger of the binary: records of the verbosity level go to STDERR, and those of Info and
+/// above (or the verbosity level, when higher) are appended to the log file, if any.
+struct Logger {
+    level: LevelFilter,
+    file: Option<Mutex<File>>,
+}
+
+impl Logger {
+    fn file_level(&self) -> LevelFilter {
+        return self.level.max(LevelFilter::Info);
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        let max_level = match self.file {
+            Some(_) => self.file_level(),
+            None => self.level,
+        };
+
+        return metadata.level() <= max_level;
+    }
+
+    fn log(&self, record: &Record) {
+        if record.level() <= self.level {
+            eprintln!("[{}] {}", record.level(), record.args());
+        }
+
+        if let Some(file) = &self.file {
+            if record.level() <= self.file_level() {
+                if let Ok(mut file) = file.lock() {
+                    let _ = writeln!(
+                        file,
+                        "{} {} {}: {}",
+                        chrono::Utc::now().to_rfc3339(),
+                        record.level(),
+                        record.target(),
+                        record.args()
+                    );
+                }
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(file) = &self.file {
+            if let Ok(mut file) = file.lock() {
+                let _ = file.flush();
+            }
+        }
+    }
+}
+
+/// Log level of the `-v` count: warnings by default, then info, debug and trace. `quiet`
+/// only keeps the errors.
+pub fn level(verbose: u8, quiet: bool) -> LevelFilter {
+    if quiet {
+        return LevelFilter::Error;
+    }
+
+    return match verbose {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+}
+
+/// Install the logger of the process, appending to `log_file` when given.
+pub fn init(level: LevelFilter, log_file: Option<&Path>) -> Result<(), error::Error> {
+    let file = match log_file {
+        Some(path) => Some(Mutex::new(
+            OpenOptions::new().create(true).append(true).open(path)?,
+        )),
+        None => None,
+    };
+    let logger = Logger { level, file };
+    let max_level = if logger.file.is_some() {
+        logger.file_level()
+    } else {
+        level
+    };
+
+    log::set_boxed_logger(Box::new(logger))
+        .map_err(|err| error::Error::General(format!("Unable to set the logger: {}", err)))?;
+    log::set_max_level(max_level);
+
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level() {
+        assert_eq!(level(0, false), LevelFilter::Warn);
+        assert_eq!(level(2, false), LevelFilter::Debug);
+        assert_eq!(level(5, false), LevelFilter::Trace);
+        assert_eq!(level(2, true), LevelFilter::Error);
+    }
+
+    #[test]
+    fn test_log_file() -> Result<(), error::Error> {
+        let path = std::env::temp_dir().join("mitre_cli_test.log");
+        let _ = std::fs::remove_file(&path);
+        let logger = Logger {
+            level: LevelFilter::Error,
+            file: Some(Mutex::new(
+                OpenOptions::new().create(true).append(true).open(&path)?,
+            )),
+        };
+
+        for (level, message) in [
+            (log::Level::Info, "Synced T1548"),
+            (
+                log::Level::Debug,
+                "GET https://attack.mitre.org/techniques/T1548",
+            ),
+        ] {
+            logger.log(
+                &Record::builder()
+                    .level(level)
+                    .target("mitre_cli::sync")
+                    .args(format_args!("{}", message))
+                    .build(),
+            );
+        }
+        logger.flush();
+
+        let logged = std::fs::read_to_string(&path)?;
+        assert_eq!(logged.lines().count(), 1);
+        assert!(logged.ends_with(" INFO mitre_cli::sync: Synced T1548\n"));
+
+        std::fs::remove_file(&path)?;
+
+        Ok(())
+    }
+}
@@ -1,9 +1,23 @@
-use structopt::StructOpt;
+use clap::Parser;
 use mitre_cli::commands;
 
-fn main() -> Result<(), mitre_cli::error::Error> {
-    let arguments: commands::Command = StructOpt::from_args();
-    arguments.handle(mitre_cli::HttpReqwest::new())?;
+#[cfg(not(any(feature = "reqwest", feature = "ureq")))]
+compile_error!("the binary needs an HTTP backend, enable the reqwest or the ureq feature");
 
-    Ok(())
-}
\ No newline at end of file
+fn main() -> std::process::ExitCode {
+    let arguments = commands::Cli::parse();
+    let error_format = arguments.error_format();
+
+    let result = arguments
+        .timeouts()
+        .and_then(mitre_cli::HttpClient::with_timeouts)
+        .and_then(|web_client| arguments.handle(web_client));
+
+    if let Err(err) = result {
+        commands::print_error(&err, error_format);
+
+        return std::process::ExitCode::from(err.exit_code());
+    }
+
+    std::process::ExitCode::SUCCESS
+}
@@ -0,0 +1,594 @@
+use std::{collections::BTreeMap, path::PathBuf, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error, metrics, storage, WebFetch};
+
+const MAPPINGS_EXPLORER_DATA_URL: &'static str =
+    "https://center-for-threat-informed-defense.github.io/mappings-explorer/data/";
+const MAPPINGS_CACHE_DIR_NAME: &'static str = "mappings";
+/// Names of the 18 CIS Controls v8, the families of their safeguards.
+const CIS_CONTROLS: [&'static str; 18] = [
+    "Inventory and Control of Enterprise Assets",
+    "Inventory and Control of Software Assets",
+    "Data Protection",
+    "Secure Configuration of Enterprise Assets and Software",
+    "Account Management",
+    "Access Control Management",
+    "Continuous Vulnerability Management",
+    "Audit Log Management",
+    "Email and Web Browser Protections",
+    "Malware Defenses",
+    "Data Recovery",
+    "Network Infrastructure Management",
+    "Network Monitoring and Defense",
+    "Security Awareness and Skills Training",
+    "Service Provider Management",
+    "Application Software Security",
+    "Incident Response Management",
+    "Penetration Testing",
+];
+
+/// Security control frameworks mapped to the ATT&CK techniques.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framework {
+    /// NIST SP 800-53 revision 5, mapped by the CTID Mappings Explorer.
+    NIST80053,
+    /// CIS Critical Security Controls v8, mapped by CIS. The mapping is only distributed
+    /// as a spreadsheet, synced from its CSV export.
+    CIS,
+}
+
+impl Framework {
+    /// Default location of the mappings of the enterprise techniques, `None` when they
+    /// have to be provided.
+    fn url(&self) -> Option<String> {
+        return match self {
+            Self::NIST80053 => Some(format!(
+                "{}nist_800_53/attack-14.1/nist_800_53-rev5/enterprise/nist_800_53-rev5_attack-14.1-enterprise.json",
+                MAPPINGS_EXPLORER_DATA_URL
+            )),
+            Self::CIS => None,
+        };
+    }
+
+    pub fn title(&self) -> &'static str {
+        return match self {
+            Self::NIST80053 => "NIST 800-53 rev5",
+            Self::CIS => "CIS Controls v8",
+        };
+    }
+
+    fn parse_mappings(&self, content: &str) -> Result<ControlMappingsTable, error::Error> {
+        return match self {
+            Self::NIST80053 => Ok(serde_json::from_str::<MappingsFile>(content)?.into()),
+            Self::CIS => parse_cis_mappings(content),
+        };
+    }
+}
+
+impl FromStr for Framework {
+    type Err = error::Error;
+
+    fn from_str(framework_str: &str) -> Result<Self, Self::Err> {
+        return match framework_str.to_lowercase().as_str() {
+            "nist-800-53" | "nist" => Ok(Self::NIST80053),
+            "cis" | "cis-controls" => Ok(Self::CIS),
+            _ => Err(error::Error::InvalidValue(format!(
+                "{} is not a supported control framework (nist-800-53, cis)",
+                framework_str
+            ))),
+        };
+    }
+}
+
+impl Into<&'static str> for Framework {
+    fn into(self) -> &'static str {
+        return match self {
+            Self::NIST80053 => "nist-800-53",
+            Self::CIS => "cis",
+        };
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MappingsMetadata {
+    /// Control family code ("ac") -> name ("Access Control").
+    #[serde(default)]
+    capability_groups: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MappingObject {
+    attack_object_id: Option<String>,
+    attack_object_name: Option<String>,
+    capability_id: String,
+    #[serde(default)]
+    capability_description: String,
+    capability_group: Option<String>,
+    mapping_type: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MappingsFile {
+    #[serde(default)]
+    metadata: MappingsMetadata,
+    mapping_objects: Vec<MappingObject>,
+}
+
+/// Security control mapped to a technique.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct ControlMappingRow {
+    pub technique_id: String,
+    pub technique_name: String,
+    pub control_id: String,
+    pub control_name: String,
+    /// Control family ("Access Control", ...).
+    pub family: String,
+    /// How the control relates to the technique ("mitigates", ...).
+    pub mapping_type: String,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for ControlMappingRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.control_id))
+            .add_cell(comfy_table::Cell::new(self.control_name))
+            .add_cell(comfy_table::Cell::new(self.family))
+            .add_cell(comfy_table::Cell::new(self.mapping_type));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ControlMappingsTable(pub Vec<ControlMappingRow>);
+
+impl ControlMappingsTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.0.len();
+    }
+
+    /// Controls mapped to the technique (or sub-technique) itself.
+    pub fn technique_controls(&self, technique_id: &str) -> ControlMappingsTable {
+        return Self(
+            self.0
+                .iter()
+                .filter(|mapping| mapping.technique_id.eq_ignore_ascii_case(technique_id))
+                .cloned()
+                .collect(),
+        );
+    }
+}
+
+impl IntoIterator for ControlMappingsTable {
+    type Item = ControlMappingRow;
+    type IntoIter = std::vec::IntoIter<ControlMappingRow>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.0.into_iter();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for ControlMappingsTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table
+            .set_header(crate::theme::header(&[
+                "Control ID",
+                "Control",
+                "Family",
+                "Mapping",
+            ]))
+            .add_rows(
+                self.into_iter()
+                    .map(|row| row.into())
+                    .collect::<Vec<comfy_table::Row>>(),
+            );
+
+        return table;
+    }
+}
+
+impl From<MappingsFile> for ControlMappingsTable {
+    fn from(file: MappingsFile) -> Self {
+        let families = file.metadata.capability_groups;
+
+        return Self(
+            file.mapping_objects
+                .into_iter()
+                // The non-mappable controls have no technique.
+                .filter_map(|mapping| {
+                    let technique_id = mapping.attack_object_id?;
+                    let family = mapping.capability_group.map_or(String::new(), |group| {
+                        families
+                            .get(&group)
+                            .cloned()
+                            .unwrap_or_else(|| group.to_uppercase())
+                    });
+
+                    return Some(ControlMappingRow {
+                        technique_id: technique_id.to_uppercase(),
+                        technique_name: mapping.attack_object_name.unwrap_or_default(),
+                        control_id: mapping.capability_id,
+                        control_name: mapping.capability_description,
+                        family,
+                        mapping_type: mapping.mapping_type,
+                    });
+                })
+                .collect(),
+        );
+    }
+}
+
+/// Index of the first column whose header is one of `names`.
+fn column_index(header: &[String], names: &[&str]) -> Option<usize> {
+    return header.iter().position(|column| {
+        names
+            .iter()
+            .any(|name| column.trim().eq_ignore_ascii_case(name))
+    });
+}
+
+/// Parse the CSV export of the CIS Controls v8 to ATT&CK mapping: one safeguard per line,
+/// with its "CIS Safeguard" number (4.1...), "Title" and mapped "Technique ID" (several
+/// separated by ';', ',' or spaces).
+pub fn parse_cis_mappings(csv: &str) -> Result<ControlMappingsTable, error::Error> {
+    let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+    let header = crate::csv_record(lines.next().unwrap_or_default());
+    let missing_column = |column: &str| {
+        return error::Error::Parse(format!("The CIS mapping has no '{}' column", column));
+    };
+    let safeguard_column = column_index(&header, &["CIS Safeguard", "Safeguard"])
+        .ok_or_else(|| missing_column("CIS Safeguard"))?;
+    let title_column = column_index(&header, &["Title", "Safeguard Title"])
+        .ok_or_else(|| missing_column("Title"))?;
+    let technique_column = column_index(
+        &header,
+        &["Technique ID", "ATT&CK Technique ID", "Technique"],
+    )
+    .ok_or_else(|| missing_column("Technique ID"))?;
+    let technique_name_column = column_index(&header, &["Technique Name", "ATT&CK Technique"]);
+
+    let mut mappings = ControlMappingsTable::default();
+
+    for line in lines {
+        let fields = crate::csv_record(line);
+        let field = |inx: usize| fields.get(inx).map_or("", |field| field.trim());
+        let safeguard = field(safeguard_column);
+
+        if safeguard.is_empty() {
+            continue;
+        }
+
+        let family = safeguard
+            .split('.')
+            .next()
+            .and_then(|control| control.parse::<usize>().ok())
+            .and_then(|control| {
+                CIS_CONTROLS
+                    .get(control.wrapping_sub(1))
+                    .map(|name| format!("{:02} {}", control, name))
+            })
+            .unwrap_or_default();
+
+        for technique_id in field(technique_column)
+            .split(|c: char| c == ';' || c == ',' || c.is_whitespace())
+            .filter(|id| id.to_uppercase().starts_with('T'))
+        {
+            mappings.0.push(ControlMappingRow {
+                technique_id: technique_id.to_uppercase(),
+                technique_name: technique_name_column
+                    .map_or(String::new(), |inx| field(inx).to_string()),
+                control_id: safeguard.to_string(),
+                control_name: field(title_column).to_string(),
+                family: family.clone(),
+                mapping_type: String::from("mitigates"),
+            });
+        }
+    }
+
+    return Ok(mappings);
+}
+
+/// Control of the bulk report, with the listed techniques it is mapped to.
+#[derive(Debug, Default, Serialize)]
+pub struct ControlReportRow {
+    pub control_id: String,
+    pub control_name: String,
+    pub family: String,
+    pub techniques: Vec<String>,
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for ControlReportRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.control_id))
+            .add_cell(comfy_table::Cell::new(self.control_name))
+            .add_cell(comfy_table::Cell::new(self.family))
+            .add_cell(comfy_table::Cell::new(self.techniques.len()))
+            .add_cell(comfy_table::Cell::new(self.techniques.join(", ")));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ControlReportTable(pub Vec<ControlReportRow>);
+
+impl ControlReportTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.0.len();
+    }
+}
+
+impl IntoIterator for ControlReportTable {
+    type Item = ControlReportRow;
+    type IntoIter = std::vec::IntoIter<ControlReportRow>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.0.into_iter();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for ControlReportTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table
+            .set_header(crate::theme::header(&[
+                "Control ID",
+                "Control",
+                "Family",
+                "Techniques",
+                "Technique IDs",
+            ]))
+            .add_rows(
+                self.into_iter()
+                    .map(|row| row.into())
+                    .collect::<Vec<comfy_table::Row>>(),
+            );
+
+        return table;
+    }
+}
+
+/// Controls addressing a list of techniques, for the compliance teams.
+#[derive(Debug, Default, Serialize)]
+pub struct ControlsMappingReport {
+    pub framework: String,
+    pub controls: ControlReportTable,
+    /// Listed techniques without any mapped control.
+    pub unmapped: Vec<String>,
+}
+
+impl ControlsMappingReport {
+    /// Controls mapped to the `technique_ids`, sorted from the one addressing the most
+    /// techniques.
+    pub fn build(
+        framework: Framework,
+        mappings: &ControlMappingsTable,
+        technique_ids: &[String],
+    ) -> Self {
+        let mut controls: BTreeMap<&str, ControlReportRow> = BTreeMap::new();
+        let mut unmapped: Vec<String> = Vec::new();
+
+        for technique_id in technique_ids {
+            let technique_id = technique_id.to_uppercase();
+            let mut mapped = false;
+
+            for mapping in mappings
+                .0
+                .iter()
+                .filter(|mapping| mapping.technique_id == technique_id)
+            {
+                let control =
+                    controls
+                        .entry(&mapping.control_id)
+                        .or_insert_with(|| ControlReportRow {
+                            control_id: mapping.control_id.clone(),
+                            control_name: mapping.control_name.clone(),
+                            family: mapping.family.clone(),
+                            ..Default::default()
+                        });
+
+                if !control.techniques.contains(&technique_id) {
+                    control.techniques.push(technique_id.clone());
+                }
+                mapped = true;
+            }
+
+            if !mapped {
+                unmapped.push(technique_id);
+            }
+        }
+
+        let mut rows: Vec<ControlReportRow> = controls.into_values().collect();
+        rows.sort_by(|a, b| {
+            b.techniques
+                .len()
+                .cmp(&a.techniques.len())
+                .then(a.control_id.cmp(&b.control_id))
+        });
+
+        return Self {
+            framework: framework.title().to_string(),
+            controls: ControlReportTable(rows),
+            unmapped,
+        };
+    }
+}
+
+/// Retrieve the techniques mapped to the controls of the framework from `source` (URL or
+/// file path), the default location of the framework if any otherwise.
+pub fn fetch_mappings(
+    framework: Framework,
+    source: Option<&str>,
+    web_client: &impl WebFetch,
+) -> Result<ControlMappingsTable, error::Error> {
+    let framework_name: &str = framework.into();
+    let source = match (source, framework.url()) {
+        (Some(source), _) => source.to_string(),
+        (None, Some(url)) => url,
+        (None, None) => {
+            return Err(error::Error::InvalidValue(format!(
+                "The {} mapping has no public download, run 'attack map sync {} --from <CSV>' \
+                 with its CSV export first",
+                framework.title(),
+                framework_name
+            )))
+        }
+    };
+    let content = if source.starts_with("http://") || source.starts_with("https://") {
+        web_client.fetch(&source)?
+    } else {
+        std::fs::read_to_string(&source)?
+    };
+
+    return framework.parse_mappings(&content);
+}
+
+fn mappings_path(framework: Framework) -> Result<PathBuf, error::Error> {
+    let framework_name: &str = framework.into();
+
+    return Ok(
+        storage::cache_path(MAPPINGS_CACHE_DIR_NAME)?.join(format!("{}.json", framework_name))
+    );
+}
+
+/// Fetch the mappings of the framework (see [`fetch_mappings`]) into the local cache.
+pub fn sync_mappings(
+    framework: Framework,
+    source: Option<&str>,
+    web_client: &impl WebFetch,
+) -> Result<ControlMappingsTable, error::Error> {
+    let mappings = fetch_mappings(framework, source, web_client)?;
+
+    if mappings.is_empty() {
+        return Err(error::Error::Parse(format!(
+            "No technique mapped to the {} controls",
+            framework.title()
+        )));
+    }
+
+    storage::save_json(&mappings_path(framework)?, &mappings)?;
+
+    return Ok(mappings);
+}
+
+/// Load the mappings of the framework from the local cache, syncing them from their
+/// default location the first time.
+pub fn mappings_or_fetch(
+    framework: Framework,
+    web_client: &impl WebFetch,
+) -> Result<ControlMappingsTable, error::Error> {
+    if let Some(mappings) = storage::load_json(&mappings_path(framework)?)? {
+        metrics::record_cache_hit();
+        return Ok(mappings);
+    }
+
+    metrics::record_cache_miss();
+
+    return sync_mappings(framework, None, web_client);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fakers::FakeHttpReqwest;
+
+    fn fetch_test_mappings() -> Result<ControlMappingsTable, error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default().set_success_response(
+            include_str!("json/nist_800_53_rev5_enterprise.json").to_string(),
+        );
+
+        return fetch_mappings(Framework::NIST80053, None, &fake_reqwest);
+    }
+
+    #[test]
+    fn test_fetch_mappings() -> Result<(), error::Error> {
+        let mappings = fetch_test_mappings()?;
+
+        // The non-mappable control is left out.
+        assert_eq!(mappings.len(), 4);
+
+        let lsass_controls = mappings.technique_controls("t1003.001");
+        assert_eq!(lsass_controls.len(), 2);
+        assert_eq!(lsass_controls.0[0].control_id, "AC-6");
+        assert_eq!(lsass_controls.0[0].family, "Access Control");
+        assert!(mappings.technique_controls("T1548").is_empty());
+
+        assert!(Framework::from_str("NIST-800-53").is_ok());
+        assert!(Framework::from_str("iso-27001").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_controls_mapping_report() -> Result<(), error::Error> {
+        let technique_ids = vec![
+            String::from("T1003.001"),
+            String::from("t1059.001"),
+            String::from("T1548"),
+        ];
+        let report = ControlsMappingReport::build(
+            Framework::NIST80053,
+            &fetch_test_mappings()?,
+            &technique_ids,
+        );
+
+        assert_eq!(report.controls.len(), 2);
+        assert_eq!(report.controls.0[0].control_id, "CM-6");
+        assert_eq!(report.controls.0[0].techniques, ["T1003.001", "T1059.001"]);
+        assert_eq!(report.controls.0[1].control_id, "AC-6");
+        assert_eq!(report.unmapped, ["T1548"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_json_response() {
+        let fake_reqwest =
+            FakeHttpReqwest::default().set_success_response(String::from("<html></html>"));
+
+        assert!(fetch_mappings(Framework::NIST80053, None, &fake_reqwest).is_err());
+        // Without any default location, the CIS mapping has to be provided.
+        assert!(fetch_mappings(Framework::CIS, None, &fake_reqwest).is_err());
+    }
+
+    #[test]
+    fn test_parse_cis_mappings() -> Result<(), error::Error> {
+        let mappings = parse_cis_mappings(
+            "CIS Control,CIS Safeguard,Asset Type,Title,Technique ID\n\
+             4,4.1,Applications,Establish and Maintain a Secure Configuration Process,\"T1059.001, T1003\"\n\
+             5,5.4,Users,Restrict Administrator Privileges to Dedicated Administrator Accounts,T1003.001\n\
+             18,18.1,N/A,Establish and Maintain a Penetration Testing Program,\n",
+        )?;
+
+        assert_eq!(mappings.len(), 3);
+        assert_eq!(mappings.0[0].technique_id, "T1059.001");
+        assert_eq!(mappings.0[0].control_id, "4.1");
+        assert_eq!(
+            mappings.0[0].family,
+            "04 Secure Configuration of Enterprise Assets and Software"
+        );
+        assert_eq!(
+            mappings.technique_controls("T1003.001").0[0].control_id,
+            "5.4"
+        );
+        assert!(parse_cis_mappings("Safeguard,Title\n4.1,Secure Configuration\n").is_err());
+
+        Ok(())
+    }
+}
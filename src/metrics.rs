@@ -0,0 +1,156 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+use crate::{error, throttle, WebFetch};
+
+static CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+static CACHE_MISSES: AtomicU64 = AtomicU64::new(0);
+static HTTP_REQUESTS: AtomicU64 = AtomicU64::new(0);
+static BYTES_DOWNLOADED: AtomicU64 = AtomicU64::new(0);
+static HTTP_MICROS: AtomicU64 = AtomicU64::new(0);
+
+pub fn record_cache_hit() {
+    CACHE_HITS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn record_cache_miss() {
+    CACHE_MISSES.fetch_add(1, Ordering::Relaxed);
+}
+
+fn record_request(bytes: usize, elapsed: Duration) {
+    HTTP_REQUESTS.fetch_add(1, Ordering::Relaxed);
+    BYTES_DOWNLOADED.fetch_add(bytes as u64, Ordering::Relaxed);
+    HTTP_MICROS.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+}
+
+/// Metrics of a single command run.
+#[derive(Debug, Serialize)]
+pub struct Metrics {
+    pub command: String,
+    pub timestamp: String,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    pub http_requests: u64,
+    pub bytes_downloaded: u64,
+    /// Time spent waiting for the HTTP responses.
+    pub http_ms: u64,
+    /// Time spent outside of the HTTP requests (scraping, parsing and rendering).
+    pub parse_ms: u64,
+    /// Time paused by throttled (HTTP 429) requests, included in the HTTP time.
+    pub throttled_ms: u64,
+    pub total_ms: u64,
+}
+
+impl Metrics {
+    /// Metrics recorded since the process started, `started` being the command start.
+    pub fn collect(command: &str, started: Instant) -> Self {
+        let total_ms = started.elapsed().as_millis() as u64;
+        let http_ms = HTTP_MICROS.load(Ordering::Relaxed) / 1000;
+
+        return Self {
+            command: command.to_string(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            cache_hits: CACHE_HITS.load(Ordering::Relaxed),
+            cache_misses: CACHE_MISSES.load(Ordering::Relaxed),
+            http_requests: HTTP_REQUESTS.load(Ordering::Relaxed),
+            bytes_downloaded: BYTES_DOWNLOADED.load(Ordering::Relaxed),
+            http_ms,
+            parse_ms: total_ms.saturating_sub(http_ms),
+            throttled_ms: throttle::throttled_time().as_millis() as u64,
+            total_ms,
+        };
+    }
+
+    /// Append the metrics as a JSON line to `path`.
+    pub fn append_to(&self, path: &Path) -> Result<(), error::Error> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(self)?)?;
+
+        return Ok(());
+    }
+}
+
+/// `WebFetch` wrapper counting the requests, downloaded bytes and time spent on them.
+pub struct MeteredFetch<W: WebFetch> {
+    inner: W,
+}
+
+impl<W: WebFetch> MeteredFetch<W> {
+    pub fn new(inner: W) -> Self {
+        return Self { inner };
+    }
+}
+
+impl<W: WebFetch> WebFetch for MeteredFetch<W> {
+    fn fetch(&self, url: &str) -> Result<String, error::Error> {
+        let started = Instant::now();
+        let response = self.inner.fetch(url)?;
+        record_request(response.len(), started.elapsed());
+
+        return Ok(response);
+    }
+
+    fn fetch_with_headers(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<String, error::Error> {
+        let started = Instant::now();
+        let response = self.inner.fetch_with_headers(url, headers)?;
+        record_request(response.len(), started.elapsed());
+
+        return Ok(response);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fakers::FakeHttpReqwest;
+
+    #[test]
+    fn test_metered_fetch() -> Result<(), error::Error> {
+        let started = Instant::now();
+        let before = Metrics::collect("test", started);
+        let metered_reqwest = MeteredFetch::new(
+            FakeHttpReqwest::default().set_success_response(String::from("0123456789")),
+        );
+
+        metered_reqwest.fetch("https://attack.mitre.org")?;
+        let after = Metrics::collect("test", started);
+
+        // Counters are process wide, other tests may fetch concurrently.
+        assert!(after.http_requests > before.http_requests);
+        assert!(after.bytes_downloaded >= before.bytes_downloaded + 10);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_append_metrics() -> Result<(), error::Error> {
+        let path = std::env::temp_dir().join("mitre_cli_metrics.jsonl");
+        let _ = std::fs::remove_file(&path);
+
+        Metrics::collect("attack list tactics", Instant::now()).append_to(&path)?;
+        Metrics::collect("attack list groups", Instant::now()).append_to(&path)?;
+
+        let lines = std::fs::read_to_string(&path)?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<serde_json::Value>, serde_json::Error>>()?;
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["command"], "attack list groups");
+
+        std::fs::remove_file(&path)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{error, WebFetch};
+
+/// Site the ATT&CK scrapers fetch from.
+pub const ATTACK_SITE_URL: &'static str = "https://attack.mitre.org/";
+/// Environment variable of the base URL of an ATT&CK website mirror.
+pub const BASE_URL_ENV: &'static str = "MITRE_CLI_BASE_URL";
+
+/// `WebFetch` wrapper fetching the ATT&CK pages from a static mirror of the website (e.g. in
+/// air-gapped sites). The scraped entities keep the attack.mitre.org URLs.
+pub struct MirrorFetch<W: WebFetch> {
+    inner: W,
+    base_url: Option<String>,
+}
+
+impl<W: WebFetch> MirrorFetch<W> {
+    /// Fetch from `base_url` instead of attack.mitre.org, when given.
+    pub fn new(inner: W, base_url: Option<&str>) -> Result<Self, error::Error> {
+        let base_url = match base_url.map(str::trim).filter(|url| !url.is_empty()) {
+            Some(url) if url.starts_with("http://") || url.starts_with("https://") => {
+                Some(format!("{}/", url.trim_end_matches('/')))
+            }
+            Some(url) => {
+                return Err(error::Error::InvalidValue(format!(
+                    "Invalid base URL '{}', expected an http:// or https:// URL",
+                    url
+                )))
+            }
+            None => None,
+        };
+
+        return Ok(Self { inner, base_url });
+    }
+
+    /// `url` with attack.mitre.org replaced by the mirror.
+    pub fn mirrored(&self, url: &str) -> String {
+        if let Some(base_url) = &self.base_url {
+            if let Some(path) = url.strip_prefix(ATTACK_SITE_URL) {
+                return format!("{}{}", base_url, path);
+            }
+            if url == ATTACK_SITE_URL.trim_end_matches('/') {
+                return base_url.clone();
+            }
+        }
+
+        return url.to_string();
+    }
+}
+
+impl<W: WebFetch> WebFetch for MirrorFetch<W> {
+    fn fetch(&self, url: &str) -> Result<String, error::Error> {
+        return self.inner.fetch(&self.mirrored(url));
+    }
+
+    fn fetch_with_headers(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<String, error::Error> {
+        return self.inner.fetch_with_headers(&self.mirrored(url), headers);
+    }
+
+    fn post_json(&self, url: &str, body: &str) -> Result<String, error::Error> {
+        return self.inner.post_json(url, body);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fakers::FakeHttpReqwest;
+
+    #[test]
+    fn test_mirrored_urls() -> Result<(), error::Error> {
+        let mirror = MirrorFetch::new(
+            FakeHttpReqwest::default(),
+            Some("https://attack.mirror.internal"),
+        )?;
+
+        assert_eq!(
+            mirror.mirrored("https://attack.mitre.org/techniques/T1548/002"),
+            "https://attack.mirror.internal/techniques/T1548/002"
+        );
+        assert_eq!(
+            mirror.mirrored("https://attack.mitre.org"),
+            "https://attack.mirror.internal/"
+        );
+        assert_eq!(
+            mirror.mirrored("https://services.nvd.nist.gov/rest/json/cves/2.0"),
+            "https://services.nvd.nist.gov/rest/json/cves/2.0"
+        );
+        assert_eq!(
+            MirrorFetch::new(FakeHttpReqwest::default(), None)?
+                .mirrored("https://attack.mitre.org/groups/"),
+            "https://attack.mitre.org/groups/"
+        );
+        assert!(
+            MirrorFetch::new(FakeHttpReqwest::default(), Some("attack.mirror.internal")).is_err()
+        );
+
+        Ok(())
+    }
+}
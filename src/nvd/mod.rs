@@ -0,0 +1,270 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error, WebFetch};
+
+const NVD_CVES_URL: &'static str = "https://services.nvd.nist.gov/rest/json/cves/2.0?cveId=";
+const NVD_API_KEY_HEADER: &'static str = "apiKey";
+/// Environment variable holding the NVD API key, requests without key are heavily rate limited.
+pub const NVD_API_KEY_ENV: &'static str = "NVD_API_KEY";
+
+lazy_static! {
+    static ref CVE_ID_RE: regex::Regex = regex::Regex::new(r"(?i)\bCVE-\d{4}-\d{4,}\b").unwrap();
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CvssData {
+    #[serde(rename = "baseScore")]
+    base_score: f64,
+    #[serde(rename = "baseSeverity")]
+    base_severity: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CvssMetric {
+    #[serde(rename = "cvssData")]
+    cvss_data: CvssData,
+    /// CVSS v2 metrics keep the severity outside of the CVSS data.
+    #[serde(rename = "baseSeverity")]
+    base_severity: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CveMetrics {
+    #[serde(rename = "cvssMetricV31", default)]
+    cvss_v31: Vec<CvssMetric>,
+    #[serde(rename = "cvssMetricV30", default)]
+    cvss_v30: Vec<CvssMetric>,
+    #[serde(rename = "cvssMetricV2", default)]
+    cvss_v2: Vec<CvssMetric>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CveDescription {
+    lang: String,
+    value: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Cve {
+    id: String,
+    published: String,
+    #[serde(default)]
+    descriptions: Vec<CveDescription>,
+    #[serde(default)]
+    metrics: CveMetrics,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Vulnerability {
+    cve: Cve,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CvesResponse {
+    vulnerabilities: Vec<Vulnerability>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CveRow {
+    pub id: String,
+    pub published: String,
+    pub score: Option<f64>,
+    pub severity: Option<String>,
+    pub description: String,
+}
+
+impl From<Cve> for CveRow {
+    fn from(cve: Cve) -> Self {
+        let metric = cve
+            .metrics
+            .cvss_v31
+            .into_iter()
+            .chain(cve.metrics.cvss_v30)
+            .chain(cve.metrics.cvss_v2)
+            .next();
+
+        return Self {
+            id: cve.id,
+            published: cve
+                .published
+                .split('T')
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            score: metric.as_ref().map(|metric| metric.cvss_data.base_score),
+            severity: metric
+                .and_then(|metric| metric.cvss_data.base_severity.or(metric.base_severity)),
+            description: cve
+                .descriptions
+                .into_iter()
+                .find(|description| description.lang == "en")
+                .map_or(String::new(), |description| description.value),
+        };
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Row> for CveRow {
+    fn into(self) -> comfy_table::Row {
+        let mut row = comfy_table::Row::new();
+        row.add_cell(comfy_table::Cell::new(self.id))
+            .add_cell(comfy_table::Cell::new(self.published))
+            .add_cell(comfy_table::Cell::new(
+                self.score
+                    .map_or(String::new(), |score| format!("{:.1}", score)),
+            ))
+            .add_cell(comfy_table::Cell::new(self.severity.unwrap_or_default()))
+            .add_cell(comfy_table::Cell::new(self.description));
+
+        return row;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CvesTable(pub Vec<CveRow>);
+
+impl CvesTable {
+    pub fn is_empty(&self) -> bool {
+        return self.0.is_empty();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.0.len();
+    }
+}
+
+impl IntoIterator for CvesTable {
+    type Item = CveRow;
+    type IntoIter = std::vec::IntoIter<CveRow>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.0.into_iter();
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Into<comfy_table::Table> for CvesTable {
+    fn into(self) -> comfy_table::Table {
+        let mut table = crate::theme::new_table();
+        table
+            .set_header(crate::theme::header(&[
+                "CVE",
+                "Published",
+                "CVSS",
+                "Severity",
+                "Description",
+            ]))
+            .add_rows(
+                self.into_iter()
+                    .map(|row| row.into())
+                    .collect::<Vec<comfy_table::Row>>(),
+            );
+
+        return table;
+    }
+}
+
+/// CVE IDs mentioned in `texts`, uppercased, deduplicated and sorted.
+pub fn extract_cve_ids<'a>(texts: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut cve_ids: BTreeSet<String> = BTreeSet::new();
+
+    for text in texts {
+        cve_ids.extend(
+            CVE_ID_RE
+                .find_iter(text)
+                .map(|cve_match| cve_match.as_str().to_uppercase()),
+        );
+    }
+
+    return cve_ids.into_iter().collect();
+}
+
+/// API key read from the `NVD_API_KEY` environment variable, if set.
+pub fn api_key_from_env() -> Option<String> {
+    return std::env::var(NVD_API_KEY_ENV)
+        .ok()
+        .filter(|api_key| !api_key.is_empty());
+}
+
+/// Retrieve the given CVE from the NVD API.
+pub fn fetch_cve(
+    cve_id: &str,
+    api_key: Option<&str>,
+    web_client: &impl WebFetch,
+) -> Result<CveRow, error::Error> {
+    let url = format!("{}{}", NVD_CVES_URL, cve_id.to_uppercase());
+    let fetched_response = match api_key {
+        Some(api_key) => web_client.fetch_with_headers(&url, &[(NVD_API_KEY_HEADER, api_key)])?,
+        None => web_client.fetch(&url)?,
+    };
+    let response: CvesResponse = serde_json::from_str(&fetched_response)?;
+
+    return match response.vulnerabilities.into_iter().next() {
+        Some(vulnerability) => Ok(vulnerability.cve.into()),
+        None => Err(error::Error::InvalidValue(format!(
+            "{} not found in the NVD",
+            cve_id.to_uppercase()
+        ))),
+    };
+}
+
+/// Retrieve the given CVEs from the NVD API.
+pub fn fetch_cves(
+    cve_ids: &[String],
+    api_key: Option<&str>,
+    web_client: &impl WebFetch,
+) -> Result<CvesTable, error::Error> {
+    return Ok(CvesTable(
+        cve_ids
+            .iter()
+            .map(|cve_id| fetch_cve(cve_id, api_key, web_client))
+            .collect::<Result<Vec<CveRow>, error::Error>>()?,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fakers::FakeHttpReqwest;
+
+    const TEST_CVE_ID: &'static str = "CVE-2012-0158";
+
+    #[test]
+    fn test_extract_cve_ids() {
+        let cve_ids = extract_cve_ids([
+            "Exploited Microsoft Word CVE-2012-0158 and cve-2014-6352.",
+            "Also used CVE-2012-0158 again, not CVE-12-1.",
+        ]);
+
+        assert_eq!(cve_ids, vec!["CVE-2012-0158", "CVE-2014-6352"]);
+    }
+
+    #[test]
+    fn test_fetch_cve() -> Result<(), error::Error> {
+        let fake_reqwest = FakeHttpReqwest::default()
+            .set_success_response(include_str!("json/cve_2012_0158.json").to_string());
+
+        let cve = fetch_cve(TEST_CVE_ID, Some("api-key"), &fake_reqwest)?;
+
+        assert_eq!(cve.id, TEST_CVE_ID);
+        assert_eq!(cve.published, "2012-04-10");
+        assert_eq!(cve.score, Some(9.3));
+        assert_eq!(cve.severity.as_deref(), Some("HIGH"));
+        assert!(cve.description.starts_with("The (1) ListView"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_cve() {
+        let fake_reqwest = FakeHttpReqwest::default()
+            .set_success_response(String::from(r#"{"vulnerabilities": []}"#));
+
+        assert!(matches!(
+            fetch_cve("CVE-2099-0001", None, &fake_reqwest),
+            Err(error::Error::InvalidValue(_))
+        ));
+    }
+}
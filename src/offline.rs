@@ -0,0 +1,111 @@
+use crate::{error, WebFetch};
+
+/// `WebFetch` wrapper forbidding any network access in offline mode, the commands then
+/// serve from the local cache (or the embedded dataset) or fail.
+pub struct OfflineFetch<W: WebFetch> {
+    inner: W,
+    offline: bool,
+}
+
+impl<W: WebFetch> OfflineFetch<W> {
+    pub fn new(inner: W, offline: bool) -> Self {
+        return Self { inner, offline };
+    }
+
+    fn forbid(&self, url: &str) -> Result<(), error::Error> {
+        if self.offline {
+            return Err(error::Error::General(format!(
+                "Not synced: {} is not in the local cache and --offline forbids network access, \
+                 run 'attack sync' (or 'attack fetch-dataset') while online first",
+                url
+            )));
+        }
+
+        return Ok(());
+    }
+}
+
+impl<W: WebFetch> WebFetch for OfflineFetch<W> {
+    fn fetch(&self, url: &str) -> Result<String, error::Error> {
+        self.forbid(url)?;
+
+        return self.inner.fetch(url);
+    }
+
+    fn fetch_with_headers(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<String, error::Error> {
+        self.forbid(url)?;
+
+        return self.inner.fetch_with_headers(url, headers);
+    }
+
+    fn post_json(&self, url: &str, body: &str) -> Result<String, error::Error> {
+        if self.offline {
+            return Err(error::Error::General(format!(
+                "--offline forbids network access, nothing was posted to {}",
+                url
+            )));
+        }
+
+        return self.inner.post_json(url, body);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attack::{
+            cache::EntityKind,
+            techniques::{self, Technique},
+        },
+        fakers::FakeHttpReqwest,
+    };
+
+    #[test]
+    fn test_offline_fetch_forbids_requests() -> Result<(), error::Error> {
+        let fake_reqwest =
+            || FakeHttpReqwest::default().set_success_response(String::from("<html></html>"));
+
+        assert!(OfflineFetch::new(fake_reqwest(), false)
+            .fetch("https://attack.mitre.org")
+            .is_ok());
+        assert!(matches!(
+            OfflineFetch::new(fake_reqwest(), true).fetch("https://attack.mitre.org"),
+            Err(error::Error::General(message)) if message.starts_with("Not synced")
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_offline_serves_from_cache() -> Result<(), error::Error> {
+        let cache = crate::attack::cache::test_cache("offline");
+        let offline_reqwest = OfflineFetch::new(FakeHttpReqwest::default(), true);
+        cache.save_entity(
+            EntityKind::TECHNIQUE,
+            "T1610",
+            &Technique {
+                id: String::from("T1610"),
+                ..Default::default()
+            },
+        )?;
+
+        let technique: Technique = cache.entity_or_fetch(EntityKind::TECHNIQUE, "T1610", || {
+            techniques::fetch_technique("T1610", &offline_reqwest)
+        })?;
+        assert_eq!(technique.id, "T1610");
+        assert!(cache
+            .entity_or_fetch::<Technique, _>(EntityKind::TECHNIQUE, "T1611", || {
+                techniques::fetch_technique("T1611", &offline_reqwest)
+            })
+            .is_err());
+
+        std::fs::remove_dir_all(cache.root())?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,300 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        RwLock,
+    },
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::error;
+
+const APP_DIR_NAME: &'static str = "mitre_cli";
+/// Directory of the releases keeping both the data and the cache (`~/.config/mitre_cli`).
+const LEGACY_DIR_NAME: &'static str = ".config/mitre_cli";
+/// Environment variable overriding both the data and the cache directories.
+pub const HOME_ENV: &'static str = "MITRE_CLI_HOME";
+
+/// Writes of the process, keeping the temporary files of concurrent writers apart.
+static WRITES: AtomicU64 = AtomicU64::new(0);
+
+/// Cache directory set by `--cache-dir`, taking precedence over the environment.
+static CACHE_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+fn home_dir() -> Result<PathBuf, error::Error> {
+    return directories::BaseDirs::new()
+        .map(|dirs| dirs.home_dir().to_path_buf())
+        .ok_or_else(|| {
+            error::Error::General(String::from("Unable to resolve the home directory"))
+        });
+}
+
+/// `$MITRE_CLI_HOME` when set and not empty.
+fn home_override() -> Option<PathBuf> {
+    return std::env::var_os(HOME_ENV)
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from);
+}
+
+fn project_dirs() -> Result<directories::ProjectDirs, error::Error> {
+    return directories::ProjectDirs::from_path(PathBuf::from(APP_DIR_NAME)).ok_or_else(|| {
+        error::Error::General(String::from(
+            "Unable to resolve the data and cache directories",
+        ))
+    });
+}
+
+/// Data directory of the platform: `$XDG_DATA_HOME/mitre_cli` (`~/.local/share/mitre_cli`),
+/// `~/Library/Application Support/mitre_cli` on macOS, `%APPDATA%\mitre_cli\data` on Windows.
+fn platform_data_dir() -> Result<PathBuf, error::Error> {
+    return Ok(project_dirs()?.data_dir().to_path_buf());
+}
+
+/// Cache directory of the platform: `$XDG_CACHE_HOME/mitre_cli` (`~/.cache/mitre_cli`),
+/// `~/Library/Caches/mitre_cli` on macOS, `%LOCALAPPDATA%\mitre_cli\cache` on Windows.
+fn platform_cache_dir() -> Result<PathBuf, error::Error> {
+    return Ok(project_dirs()?.cache_dir().to_path_buf());
+}
+
+/// Keep the cache in `dir` instead of the default directory (`--cache-dir`).
+pub fn set_cache_dir(dir: PathBuf) {
+    if let Ok(mut cache_dir) = CACHE_DIR.write() {
+        *cache_dir = Some(dir);
+    }
+}
+
+fn cache_dir_override() -> Option<PathBuf> {
+    return CACHE_DIR
+        .read()
+        .ok()
+        .and_then(|dir| dir.clone())
+        .or_else(home_override);
+}
+
+/// Path of the `name` entry of the directory of the data created by the user, such as the
+/// annotations: under `$MITRE_CLI_HOME`, or the data directory of the platform. See
+/// [`legacy_or`].
+pub fn data_path(name: &str) -> Result<PathBuf, error::Error> {
+    if let Some(home) = home_override() {
+        return Ok(home.join(name));
+    }
+
+    return legacy_or(platform_data_dir()?.join(name), name);
+}
+
+/// Path of the `name` entry of the directory of the data that can be fetched again, such as
+/// the ATT&CK cache: under `--cache-dir`, `$MITRE_CLI_HOME`, or the cache directory of the
+/// platform. See [`legacy_or`].
+pub fn cache_path(name: &str) -> Result<PathBuf, error::Error> {
+    if let Some(cache_dir) = cache_dir_override() {
+        return Ok(cache_dir.join(name));
+    }
+
+    return legacy_or(platform_cache_dir()?.join(name), name);
+}
+
+/// `path`, unless only the `~/.config/mitre_cli` layout has the `name` entry, which is then
+/// still read (e.g. when moving it to the platform directory failed).
+fn legacy_or(path: PathBuf, name: &str) -> Result<PathBuf, error::Error> {
+    if !path.exists() {
+        let legacy_path = home_dir()?.join(LEGACY_DIR_NAME).join(name);
+
+        if legacy_path.exists() {
+            return Ok(legacy_path);
+        }
+    }
+
+    return Ok(path);
+}
+
+/// Move the entries of the `~/.config/mitre_cli` layout to the platform data and cache
+/// directories, `entries` being the names of the cache (`true`) and data (`false`) entries.
+/// Entries already present at their new place, or whose directory is overridden, are left
+/// untouched. Returns the moved paths.
+pub fn migrate_legacy_layout(entries: &[(&str, bool)]) -> Result<Vec<PathBuf>, error::Error> {
+    let legacy_dir = home_dir()?.join(LEGACY_DIR_NAME);
+    if !legacy_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut moved: Vec<PathBuf> = Vec::new();
+
+    for (name, is_cache) in entries {
+        let target_dir = match is_cache {
+            true if cache_dir_override().is_none() => platform_cache_dir()?,
+            false if home_override().is_none() => platform_data_dir()?,
+            _ => continue,
+        };
+
+        if move_entry(&legacy_dir.join(name), &target_dir.join(name))? {
+            moved.push(target_dir.join(name));
+        }
+    }
+
+    return Ok(moved);
+}
+
+/// Rename `from` to `to` when the former exists and the latter does not.
+fn move_entry(from: &Path, to: &Path) -> Result<bool, error::Error> {
+    if !from.exists() || to.exists() {
+        return Ok(false);
+    }
+
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::rename(from, to).map_err(|err| {
+        error::Error::General(format!(
+            "Unable to move {} to {}: {}",
+            from.display(),
+            to.display(),
+            err
+        ))
+    })?;
+
+    return Ok(true);
+}
+
+/// Deserialize the JSON file at `path`, returning `None` if it does not exist.
+pub fn load_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, error::Error> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(path)?;
+
+    return Ok(Some(serde_json::from_str(&content)?));
+}
+
+/// Serialize `value` as JSON into `path`, creating the parent directories if needed. The file
+/// is replaced atomically, so concurrent writers (e.g. the sync workers) never interleave.
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<(), error::Error> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    write_atomic(path, serde_json::to_string_pretty(value)?)?;
+
+    return Ok(());
+}
+
+/// Replace the file at `path` with `content` through a temporary file of the same directory
+/// renamed over it, so readers never see a partially written file.
+pub fn write_atomic(path: &Path, content: impl AsRef<[u8]>) -> Result<(), error::Error> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| error::Error::InvalidValue(format!("{} is not a file", path.display())))?;
+    let tmp_path = path.with_file_name(format!(
+        ".{}.{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        WRITES.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    fs::write(&tmp_path, content)?;
+
+    if let Err(err) = fs::rename(&tmp_path, path) {
+        let _ = fs::remove_file(&tmp_path);
+
+        return Err(err.into());
+    }
+
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_file_loads_as_none() -> Result<(), error::Error> {
+        let path = std::env::temp_dir().join("mitre_cli_storage_missing.json");
+        let loaded: Option<Vec<String>> = load_json(&path)?;
+
+        assert!(loaded.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_and_load_json() -> Result<(), error::Error> {
+        let path = std::env::temp_dir()
+            .join("mitre_cli_storage_test")
+            .join("values.json");
+        let values = vec![String::from("T1548"), String::from("T1548.002")];
+
+        save_json(&path, &values)?;
+        let loaded: Option<Vec<String>> = load_json(&path)?;
+        fs::remove_file(&path)?;
+
+        assert_eq!(loaded, Some(values));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_file() -> Result<(), error::Error> {
+        let dir = std::env::temp_dir().join("mitre_cli_storage_atomic");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)?;
+        let path = dir.join("techniques.csv");
+
+        fs::write(&path, "Old")?;
+        write_atomic(&path, "ID,Name\n")?;
+
+        assert_eq!(fs::read_to_string(&path)?, "ID,Name\n");
+        assert_eq!(fs::read_dir(&dir)?.count(), 1);
+
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_move_entry() -> Result<(), error::Error> {
+        let dir = std::env::temp_dir().join("mitre_cli_storage_move");
+        let _ = fs::remove_dir_all(&dir);
+        let (legacy, target) = (dir.join("legacy/attack"), dir.join("cache/attack"));
+        save_json(&legacy.join("manifest.json"), &vec!["T1548"])?;
+
+        assert!(move_entry(&legacy, &target)?);
+        assert!(!legacy.exists());
+        assert!(target.join("manifest.json").exists());
+
+        save_json(&legacy.join("manifest.json"), &vec!["T1059"])?;
+        assert!(!move_entry(&legacy, &target)?);
+        assert_eq!(
+            load_json::<Vec<String>>(&target.join("manifest.json"))?,
+            Some(vec![String::from("T1548")])
+        );
+
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_concurrent_saves() -> Result<(), error::Error> {
+        let dir = std::env::temp_dir().join("mitre_cli_storage_concurrent");
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("T1059.json");
+
+        std::thread::scope(|scope| {
+            for worker in 0..8 {
+                let path = &path;
+                scope.spawn(move || save_json(path, &vec![worker; 1000]));
+            }
+        });
+
+        let saved: Vec<u32> = load_json(&path)?.unwrap();
+        assert!(saved.iter().all(|worker| *worker == saved[0]));
+        assert_eq!(fs::read_dir(&dir)?.count(), 1);
+
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+}
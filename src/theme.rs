@@ -0,0 +1,99 @@
+use std::sync::RwLock;
+
+use clap::ValueEnum;
+
+/// Styling of the table headers and borders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Theme {
+    /// Bold red headers
+    #[default]
+    RED,
+    /// Bold blue headers
+    BLUE,
+    /// Bold green headers
+    GREEN,
+    /// Bold headers without color
+    MONO,
+    /// ASCII borders, no color nor bold, for logs
+    PLAIN,
+}
+
+impl Theme {
+    fn header_color(&self) -> Option<comfy_table::Color> {
+        return match self {
+            Self::RED => Some(comfy_table::Color::Red),
+            Self::BLUE => Some(comfy_table::Color::Blue),
+            Self::GREEN => Some(comfy_table::Color::Green),
+            Self::MONO | Self::PLAIN => None,
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Style {
+    theme: Theme,
+    color: bool,
+}
+
+static STYLE: RwLock<Style> = RwLock::new(Style {
+    theme: Theme::RED,
+    color: true,
+});
+
+/// Select the theme of the tables built from now on, with or without colors.
+pub fn set_theme(theme: Theme, color: bool) {
+    if let Ok(mut style) = STYLE.write() {
+        *style = Style { theme, color };
+    }
+}
+
+/// Whether colors should be left out: --no-color, or a non-empty $NO_COLOR (no-color.org).
+pub fn no_color_requested(no_color: bool) -> bool {
+    return no_color || std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+}
+
+fn style() -> Style {
+    return STYLE.read().map_or(
+        Style {
+            theme: Theme::RED,
+            color: true,
+        },
+        |style| *style,
+    );
+}
+
+/// Empty table with the borders of the theme, wrapping the content to the terminal width.
+pub fn new_table() -> comfy_table::Table {
+    let preset = match style().theme {
+        Theme::PLAIN => comfy_table::presets::ASCII_FULL,
+        _ => comfy_table::presets::UTF8_FULL,
+    };
+
+    let mut table = comfy_table::Table::new();
+    table
+        .load_preset(preset)
+        .set_content_arrangement(comfy_table::ContentArrangement::Dynamic);
+
+    return table;
+}
+
+/// Centered header cell, bold and colored as the theme.
+pub fn header_cell(title: &str) -> comfy_table::Cell {
+    let style = style();
+    let mut cell = comfy_table::Cell::new(title).set_alignment(comfy_table::CellAlignment::Center);
+
+    if style.theme != Theme::PLAIN {
+        cell = cell.add_attribute(comfy_table::Attribute::Bold);
+    }
+
+    if let Some(color) = style.theme.header_color().filter(|_| style.color) {
+        cell = cell.fg(color);
+    }
+
+    return cell;
+}
+
+/// Header cells of the titles.
+pub fn header(titles: &[&str]) -> Vec<comfy_table::Cell> {
+    return titles.iter().map(|title| header_cell(title)).collect();
+}
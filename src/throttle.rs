@@ -0,0 +1,100 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+
+/// Wait used when a throttled response has no (valid) Retry-After header.
+pub const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(30);
+/// Throttled responses retried before giving up on the request.
+pub const MAX_THROTTLED_RETRIES: u32 = 5;
+
+static THROTTLED_MILLIS: AtomicU64 = AtomicU64::new(0);
+/// End of the last requested pause, shared by the concurrent sync workers.
+static RESUME_AT: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Wait requested by a Retry-After header, given either in seconds or as an HTTP date.
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let retry_at = DateTime::parse_from_rfc2822(value).ok()?;
+
+    return Some(
+        (retry_at.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or_default(),
+    );
+}
+
+/// Sleep for `wait`, accounting it in the process throttled time. The other threads
+/// requesting pages wait for the same pause through `wait_for_resume`.
+pub fn pause(wait: Duration) {
+    record_pause(wait);
+
+    if let Ok(mut resume_at) = RESUME_AT.lock() {
+        let until = Instant::now() + wait;
+        *resume_at = Some(resume_at.map_or(until, |resume_at| resume_at.max(until)));
+    }
+
+    std::thread::sleep(wait);
+}
+
+/// Sleep until the pause requested by another thread, if any, is over. Called before every
+/// request, so a throttled worker holds back the others too.
+pub fn wait_for_resume() {
+    let resume_at = RESUME_AT.lock().ok().and_then(|resume_at| *resume_at);
+
+    if let Some(wait) =
+        resume_at.and_then(|resume_at| resume_at.checked_duration_since(Instant::now()))
+    {
+        std::thread::sleep(wait);
+    }
+}
+
+/// Account `wait` in the process throttled time, for callers sleeping on their own.
+pub fn record_pause(wait: Duration) {
+    THROTTLED_MILLIS.fetch_add(wait.as_millis() as u64, Ordering::Relaxed);
+}
+
+/// Time spent waiting for throttled requests since the process started.
+pub fn throttled_time() -> Duration {
+    return Duration::from_millis(THROTTLED_MILLIS.load(Ordering::Relaxed));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_after_seconds() {
+        assert_eq!(
+            parse_retry_after(" 120 ", Utc::now()),
+            Some(Duration::from_secs(120))
+        );
+    }
+
+    #[test]
+    fn test_retry_after_http_date() {
+        let now = DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:27:00 GMT")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT", now),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:26:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+}